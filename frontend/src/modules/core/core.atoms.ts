@@ -9,7 +9,12 @@ import {
   refreshResourcesMutationOptions,
   resourcesQueryOptions,
 } from "@/modules/resources/resources.query";
-import type { AllResources, GameResourcePayload } from "@/modules/resources/resources.types";
+import {
+  type AllResources,
+  DEFAULT_ACCOUNT,
+  type GameFetchFailedPayload,
+  type GameResourcePayload,
+} from "@/modules/resources/resources.types";
 import { configQueryOptions } from "@/modules/settings/settings.query";
 import type { GamesConfig } from "@/modules/settings/settings.types";
 import "@formatjs/intl-durationformat/polyfill.js";
@@ -20,15 +25,17 @@ import { getLocale, isLocale, setLocale } from "@/paraglide/runtime";
 // =============================================================================
 
 interface AllDailyRewardStatus {
-  games?: Record<string, { info?: { is_signed?: boolean } }>;
+  games?: Record<string, Record<string, { info?: { is_signed?: boolean } }>>;
   lastChecked?: string;
+  stale?: boolean;
 }
 
 function extractClaimStatus(status: AllDailyRewardStatus): Map<GameId, boolean> {
   const map = new Map<GameId, boolean>();
   if (status.games) {
-    for (const [gameId, data] of Object.entries(status.games)) {
-      if (data.info?.is_signed != null) {
+    for (const [gameId, accounts] of Object.entries(status.games)) {
+      const data = accounts[DEFAULT_ACCOUNT];
+      if (data?.info?.is_signed != null) {
         map.set(gameId as GameId, data.info.is_signed);
       }
     }
@@ -184,18 +191,49 @@ export class CoreAtoms {
       }),
     );
 
-    // Listen for per-game resource updates (incremental)
+    // Listen for per-account resource updates (incremental)
     unlistenPromises.push(
       listen<GameResourcePayload>("game-resource-updated", (event) => {
-        const { gameId, data } = event.payload;
+        const { gameId, account, data, status } = event.payload;
         queryClient.setQueryData<AllResources>(["resources"], (old) => ({
           ...old,
-          games: { ...old?.games, [gameId]: data },
+          games: {
+            ...old?.games,
+            [gameId]: { ...old?.games?.[gameId], [account]: data },
+          },
+          fetchStatus: {
+            ...old?.fetchStatus,
+            [gameId]: { ...old?.fetchStatus?.[gameId], [account]: status },
+          },
         }));
         set(this.refreshTick);
       }),
     );
 
+    // Listen for per-account fetch failures (incremental)
+    unlistenPromises.push(
+      listen<GameFetchFailedPayload>("game-fetch-failed", (event) => {
+        const { gameId, account, error } = event.payload;
+        queryClient.setQueryData<AllResources>(["resources"], (old) => {
+          const previous = old?.fetchStatus?.[gameId]?.[account];
+          return {
+            ...old,
+            fetchStatus: {
+              ...old?.fetchStatus,
+              [gameId]: {
+                ...old?.fetchStatus?.[gameId],
+                [account]: {
+                  lastSuccess: previous?.lastSuccess ?? null,
+                  lastAttempt: new Date().toISOString(),
+                  error,
+                },
+              },
+            },
+          };
+        });
+      }),
+    );
+
     return () => {
       for (const p of unlistenPromises) {
         void p.then((fn) => fn()).catch(() => {});
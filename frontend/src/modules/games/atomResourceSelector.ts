@@ -1,11 +1,11 @@
 import { atom } from "jotai";
 import type { CoreAtoms } from "@/modules/core/core.atoms";
 import type { GameId, GameResourceTypeMap } from "@/modules/games/games.types";
-import type { GameResource } from "@/modules/resources/resources.types";
+import { DEFAULT_ACCOUNT, type GameResource } from "@/modules/resources/resources.types";
 
 /**
- * Creates a derived atom that selects a single resource by game and type,
- * narrowing `.data` via the provided type guard.
+ * Creates a derived atom that selects a single resource of a game's default
+ * account by type, narrowing `.data` via the provided type guard.
  *
  * Returns the narrowed data (`T`) or `null` if not found / guard fails.
  */
@@ -17,7 +17,9 @@ export function atomResourceSelector<G extends GameId, T>(
 ) {
   return atom<T | null>((get) => {
     const { data } = get(getCore().resourcesQuery);
-    const resource = data?.games?.[gameId]?.find((r: GameResource) => r.type === resourceType);
+    const resource = data?.games?.[gameId]?.[DEFAULT_ACCOUNT]?.find(
+      (r: GameResource) => r.type === resourceType,
+    );
     if (!resource || !guard(resource.data)) {
       return null;
     }
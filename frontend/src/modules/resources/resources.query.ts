@@ -1,6 +1,13 @@
 import { mutationOptions, queryOptions } from "@tanstack/react-query";
 import { invoke } from "@tauri-apps/api/core";
-import type { AllResources } from "@/modules/resources/resources.types";
+import type { GameId } from "@/modules/games/games.types";
+import type {
+  AlertLogEntry,
+  AllResources,
+  HistoryQuery,
+  HistorySeries,
+  OvercapSummary,
+} from "@/modules/resources/resources.types";
 
 /** Query options for fetching all resources from Tauri backend */
 export function resourcesQueryOptions() {
@@ -19,3 +26,51 @@ export function refreshResourcesMutationOptions() {
     mutationFn: async () => invoke<AllResources>("refresh_resources"),
   });
 }
+
+/** Mutation options for refreshing the resources of a single game */
+export function refreshGameMutationOptions() {
+  return mutationOptions({
+    mutationKey: ["refresh-game"],
+    mutationFn: async (gameId: GameId) => invoke<AllResources>("refresh_game", { gameId }),
+  });
+}
+
+/** Mutation options for handing a solved Geetest challenge to the account waiting on it */
+export function solveChallengeMutationOptions() {
+  return mutationOptions({
+    mutationKey: ["solve-challenge"],
+    mutationFn: async (params: {
+      gameId: GameId;
+      account: string;
+      challenge: string;
+      validate: string;
+    }) => invoke("solve_challenge", params),
+  });
+}
+
+/** Query options for fetching downsampled resource history */
+export function resourceHistoryQueryOptions(query: HistoryQuery) {
+  return queryOptions({
+    queryKey: ["resource-history", query],
+    queryFn: async () => invoke<HistorySeries[]>("get_resource_history", { query }),
+    retry: false,
+  });
+}
+
+/** Query options for fetching the overcap (wasted stamina) summary */
+export function overcapSummaryQueryOptions(days?: number) {
+  return queryOptions({
+    queryKey: ["overcap-summary", days],
+    queryFn: async () => invoke<OvercapSummary>("get_overcap_summary", { days }),
+    retry: false,
+  });
+}
+
+/** Query options for fetching recently sent notifications, newest first */
+export function recentAlertsQueryOptions(limit?: number) {
+  return queryOptions({
+    queryKey: ["recent-alerts", limit],
+    queryFn: async () => invoke<AlertLogEntry[]>("get_recent_alerts", { limit }),
+    retry: false,
+  });
+}
@@ -27,16 +27,138 @@ export interface GameResource {
   data: StaminaResource | CooldownResource | ExpeditionResource;
 }
 
-/** Payload for per-game resource update events */
+/** Name of the account described by a game's top-level config table */
+export const DEFAULT_ACCOUNT = "default";
+
+/** Category of a failed fetch */
+export type FetchErrorKind =
+  | "network"
+  | "timeout"
+  | "auth"
+  | "rate_limited"
+  | "captcha"
+  | "maintenance"
+  | "not_found"
+  | "protocol"
+  | "other";
+
+/** Parameters of a Geetest challenge, as the Geetest widget takes them */
+export interface GeetestChallenge {
+  gt: string;
+  challenge: string;
+}
+
+/** Why a fetch failed */
+export interface FetchError {
+  kind: FetchErrorKind;
+  message: string;
+  /** Challenge to solve before the account can be fetched again; only set for HoYoLab risk control */
+  challenge?: GeetestChallenge;
+}
+
+/** Outcome of an account's latest fetch */
+export interface FetchStatus {
+  lastSuccess: string | null; // ISO 8601 datetime
+  lastAttempt: string | null; // ISO 8601 datetime
+  /** Why the latest attempt failed; absent if it succeeded */
+  error?: FetchError;
+}
+
+/** Payload for per-account resource update events */
 export interface GameResourcePayload {
   gameId: GameId;
+  account: string;
   data: GameResource[];
+  status: FetchStatus;
 }
 
-/** All resources from all games (camelCase from Rust) */
+/** Payload for per-account fetch failure events */
+export interface GameFetchFailedPayload {
+  gameId: GameId;
+  account: string;
+  error: FetchError;
+}
+
+/** Payload for challenge-required events; the game is paused until the challenge is solved */
+export interface ChallengeRequiredPayload extends GeetestChallenge {
+  gameId: GameId;
+  account: string;
+  /** What the challenge refused, replayed once it is solved */
+  operation: "fetch" | "claim";
+}
+
+/** All resources from all games, keyed by game then account name (camelCase from Rust) */
 export interface AllResources {
-  games?: Partial<Record<GameId, GameResource[]>>;
+  games?: Partial<Record<GameId, Record<string, GameResource[]>>>;
+  /** When each account's resources were last fetched successfully */
+  fetchedAt?: Partial<Record<GameId, Record<string, string>>>; // ISO 8601 datetimes
+  /** Outcome of each account's latest fetch; absent for accounts never fetched */
+  fetchStatus?: Partial<Record<GameId, Record<string, FetchStatus>>>;
   lastUpdated?: string; // ISO 8601 datetime
+  /** True while showing data restored from the on-disk snapshot */
+  stale?: boolean;
+}
+
+/** Filter for the `get_resource_history` command; omitted fields match everything */
+export interface HistoryQuery {
+  gameId?: GameId;
+  account?: string;
+  type?: string;
+  from?: string; // ISO 8601 datetime
+  to?: string; // ISO 8601 datetime
+  maxPoints?: number;
+}
+
+/** One recorded sample of a resource */
+export interface HistoryPoint {
+  fetchedAt: string; // ISO 8601 datetime
+  data: GameResource["data"];
+}
+
+/** Time-ordered samples of one resource type of one account */
+export interface HistorySeries {
+  gameId: GameId;
+  account: string;
+  type: string;
+  points: HistoryPoint[];
+}
+
+/** Time at max and units wasted for one resource on one local day */
+export interface DailyOvercap {
+  date: string; // YYYY-MM-DD
+  fullSecs: number;
+  wastedUnits: number;
+}
+
+/** Overcap totals for one resource over the summary window */
+export interface ResourceOvercap {
+  gameId: GameId;
+  account: string;
+  type: string;
+  fullSecs: number;
+  wastedUnits: number;
+  daily: DailyOvercap[];
+}
+
+/** Result of the `get_overcap_summary` command */
+export interface OvercapSummary {
+  from: string; // ISO 8601 datetime
+  to: string; // ISO 8601 datetime
+  resources: ResourceOvercap[];
+}
+
+/** One notification sent to one channel, from the `get_recent_alerts` command */
+export interface AlertLogEntry {
+  sentAt: string; // ISO 8601 datetime
+  channel: string;
+  title: string;
+  body: string;
+  gameId?: GameId;
+  account?: string;
+  resource?: string;
+  rule?: string;
+  delivered: boolean;
+  error?: string;
 }
 
 /** Type guards */
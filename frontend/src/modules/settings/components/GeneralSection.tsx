@@ -52,6 +52,43 @@ export const GeneralSection: React.FC<GeneralSectionProps> = ({ config, onChange
       >
         {m.settings_general_autostart()}
       </Switch>
+      <Switch
+        isSelected={config.history_enabled}
+        onChange={(isSelected) =>
+          onChange({
+            ...config,
+            history_enabled: isSelected,
+          })
+        }
+      >
+        {m.settings_general_history_enabled()}
+      </Switch>
+      <NumberField
+        label={m.settings_general_history_retention()}
+        description={m.settings_general_history_retention_description()}
+        value={config.history_retention_days}
+        onChange={(value) =>
+          onChange({
+            ...config,
+            history_retention_days: value,
+          })
+        }
+        isDisabled={!config.history_enabled}
+        minValue={0}
+        maxValue={3650}
+        step={1}
+      />
+      <Switch
+        isSelected={config.overcap_weekly_digest}
+        onChange={(isSelected) =>
+          onChange({
+            ...config,
+            overcap_weekly_digest: isSelected,
+          })
+        }
+      >
+        {m.settings_general_overcap_weekly_digest()}
+      </Switch>
       <Select
         label={m.settings_general_language()}
         value={config.language ?? "auto"}
@@ -2,7 +2,8 @@ import type { GameId } from "@/modules/games/games.types";
 import type { ResourceLimits } from "@/modules/settings/components/NotificationResourceRow";
 import { NotificationSection } from "@/modules/settings/components/NotificationSection";
 import { Section } from "@/modules/settings/components/Section";
-import type { HoyolabGameConfig } from "@/modules/settings/settings.types";
+import type { GameRole, HoyolabGameConfig } from "@/modules/settings/settings.types";
+import { Select, SelectItem } from "@/modules/ui/components/Select";
 import { Switch } from "@/modules/ui/components/Switch";
 import { TextField } from "@/modules/ui/components/TextField";
 import * as m from "@/paraglide/messages";
@@ -14,6 +15,8 @@ interface HoyolabGameSectionProps {
   resourceTypes: readonly string[];
   config: HoyolabGameConfig | undefined;
   resourceLimits?: Partial<Record<string, ResourceLimits>>;
+  /** Roles of the HoYoLab login to pick the UID from, across all games */
+  roles?: GameRole[];
   onChange: (config: HoyolabGameConfig) => void;
 }
 
@@ -24,11 +27,15 @@ export const HoyolabGameSection: React.FC<HoyolabGameSectionProps> = ({
   resourceTypes,
   config,
   resourceLimits,
+  roles,
   onChange,
 }) => {
   const enabled = config?.enabled ?? false;
   const uid = config?.uid ?? "";
   const autoClaimDailyRewards = config?.auto_claim_daily_rewards ?? false;
+  // Roles on servers without a config region (e.g. Bilibili) can't be queried
+  const gameRoles =
+    roles?.filter((role) => role.gameId === gameId && role.region !== null) ?? [];
 
   return (
     <Section title={title} description={description}>
@@ -47,6 +54,36 @@ export const HoyolabGameSection: React.FC<HoyolabGameSectionProps> = ({
       </Switch>
       {enabled && (
         <>
+          {gameRoles.length > 0 && (
+            <Select
+              label={m.settings_game_role()}
+              placeholder={m.settings_game_role_placeholder()}
+              value={gameRoles.some((role) => role.uid === uid) ? uid : null}
+              onChange={(value) => {
+                const role = gameRoles.find((r) => r.uid === value);
+                if (role) {
+                  onChange({
+                    ...config,
+                    enabled,
+                    uid: role.uid,
+                    region: role.region ?? undefined,
+                    auto_claim_daily_rewards: autoClaimDailyRewards,
+                  });
+                }
+              }}
+            >
+              {gameRoles.map((role) => (
+                <SelectItem key={role.uid} id={role.uid} textValue={role.nickname}>
+                  {m.settings_game_role_option({
+                    nickname: role.nickname,
+                    level: role.level,
+                    server: role.serverName,
+                    uid: role.uid,
+                  })}
+                </SelectItem>
+              ))}
+            </Select>
+          )}
           <TextField
             label={m.settings_game_uid()}
             value={uid}
@@ -5,6 +5,7 @@ import { atomWithMutation, atomWithQuery } from "jotai-tanstack-query";
 import type { CoreAtoms } from "@/modules/core/core.atoms";
 import { queryClient } from "@/modules/core/core.queryClient";
 import {
+  gameRolesQueryOptions,
   saveAndApplyMutationOptions,
   secretsQueryOptions,
 } from "@/modules/settings/settings.query";
@@ -32,6 +33,9 @@ export class SettingsAtoms {
   /** Fetch secrets from backend */
   readonly secretsQuery = atomWithQuery(() => secretsQueryOptions());
 
+  /** Game roles bound to the default HoYoLab login, for UID pickers */
+  readonly gameRolesQuery = atomWithQuery(() => gameRolesQueryOptions());
+
   // ---------------------------------------------------------------------------
   // Edited State (local form state)
   // ---------------------------------------------------------------------------
@@ -144,6 +148,8 @@ export class SettingsAtoms {
       const { mutateAsync: doSaveAndApply } = get(this.saveAndApplyMutation);
       const result = await doSaveAndApply({ config, secrets });
       queryClient.setQueryData(["config"], config);
+      // Saved secrets may belong to another login
+      void queryClient.invalidateQueries({ queryKey: ["game-roles"] });
       set(this.markAsSaved);
 
       // Sync frontend locale from backend's effective locale
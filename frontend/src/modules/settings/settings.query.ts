@@ -1,6 +1,11 @@
 import { mutationOptions, queryOptions } from "@tanstack/react-query";
 import { invoke } from "@tauri-apps/api/core";
-import type { AppConfig, SaveResult, SecretsConfig } from "@/modules/settings/settings.types";
+import type {
+  AppConfig,
+  GameRole,
+  SaveResult,
+  SecretsConfig,
+} from "@/modules/settings/settings.types";
 
 /** Query options for fetching config from backend */
 export function configQueryOptions() {
@@ -20,6 +25,16 @@ export function secretsQueryOptions() {
   });
 }
 
+/** Query options for listing the game roles bound to the default HoYoLab login */
+export function gameRolesQueryOptions() {
+  return queryOptions({
+    queryKey: ["game-roles"],
+    queryFn: async () => invoke<GameRole[]>("list_game_roles"),
+    staleTime: Number.POSITIVE_INFINITY,
+    retry: false,
+  });
+}
+
 /** Mutation options for saving config + secrets and applying changes in one call */
 export function saveAndApplyMutationOptions() {
   return mutationOptions({
@@ -4,6 +4,7 @@ import type {
   WuwaResourceType,
   ZzzResourceType,
 } from "@/modules/games/games.constants";
+import type { GameId } from "@/modules/games/games.types";
 
 // =============================================================================
 // Configuration Types (matching Rust AppConfig - snake_case)
@@ -16,6 +17,12 @@ export interface GeneralConfig {
   log_level: string;
   language: string | null;
   autostart: boolean;
+  history_enabled: boolean;
+  history_retention_days: number;
+  history_compact_after_days: number;
+  history_compact_interval_mins: number;
+  overcap_weekly_digest: boolean;
+  metrics_textfile?: string | null;
 }
 
 /** Per-resource notification configuration */
@@ -24,6 +31,32 @@ export interface ResourceNotificationConfig {
   notify_minutes_before_full?: number | null;
   notify_at_value?: number | null;
   cooldown_minutes: number;
+  channels?: string[];
+  template?: string | null;
+  /** Sent even during quiet hours */
+  critical?: boolean;
+  /** Separate rules; when set, they replace the threshold fields above */
+  rules?: NotificationRule[];
+}
+
+/** One of several notification rules of a resource */
+export interface NotificationRule {
+  id: string;
+  notify_minutes_before_full?: number | null;
+  notify_at_value?: number | null;
+  cooldown_minutes?: number;
+  channels?: string[];
+  template?: string | null;
+}
+
+export type Weekday = "mon" | "tue" | "wed" | "thu" | "fri" | "sat" | "sun";
+
+/** Local-time window ("HH:MM") during which notifications are deferred */
+export interface QuietHours {
+  start: string;
+  end: string;
+  /** Days the window starts on; every day when omitted */
+  days?: Weekday[];
 }
 
 /** Common configuration for HoYoLab games */
@@ -35,21 +68,41 @@ export interface HoyolabGameConfig {
   auto_claim_daily_rewards: boolean;
   auto_claim_time?: string;
   notifications?: Partial<Record<string, ResourceNotificationConfig>>;
+  /** Replaces the global quiet hours for this game */
+  quiet_hours?: QuietHours[];
+  /** Replaces general.poll_interval_secs for this game */
+  poll_interval_secs?: number;
+  /** Replace the [adaptive_polling] bounds for this game */
+  min_poll_interval_secs?: number;
+  max_poll_interval_secs?: number;
 }
 
+/** Named account of a game; `credentials` names a login in secrets.toml */
+export type AccountConfig<T> = Omit<
+  T,
+  | "accounts"
+  | "quiet_hours"
+  | "poll_interval_secs"
+  | "min_poll_interval_secs"
+  | "max_poll_interval_secs"
+> & { credentials?: string };
+
 /** Genshin Impact configuration */
 export interface GenshinConfig extends HoyolabGameConfig {
   notifications?: Partial<Record<GenshinResourceType, ResourceNotificationConfig>>;
+  accounts?: Record<string, AccountConfig<GenshinConfig>>;
 }
 
 /** Honkai: Star Rail configuration */
 export interface HsrConfig extends HoyolabGameConfig {
   notifications?: Partial<Record<HsrResourceType, ResourceNotificationConfig>>;
+  accounts?: Record<string, AccountConfig<HsrConfig>>;
 }
 
 /** Zenless Zone Zero configuration */
 export interface ZzzConfig extends HoyolabGameConfig {
   notifications?: Partial<Record<ZzzResourceType, ResourceNotificationConfig>>;
+  accounts?: Record<string, AccountConfig<ZzzConfig>>;
 }
 
 /** Wuthering Waves configuration */
@@ -59,6 +112,11 @@ export interface WuwaConfig {
   region?: string;
   tracked_resources?: string[];
   notifications?: Partial<Record<WuwaResourceType, ResourceNotificationConfig>>;
+  quiet_hours?: QuietHours[];
+  poll_interval_secs?: number;
+  min_poll_interval_secs?: number;
+  max_poll_interval_secs?: number;
+  accounts?: Record<string, AccountConfig<WuwaConfig>>;
 }
 
 /** Per-game configuration */
@@ -69,13 +127,132 @@ export interface GamesConfig {
   wuthering_waves?: WuwaConfig;
 }
 
+/** Local HTTP API settings (served on 127.0.0.1 only) */
+export interface HttpApiConfig {
+  enabled: boolean;
+  port: number;
+}
+
+/** MQTT publishing with Home Assistant discovery */
+export interface MqttConfig {
+  enabled: boolean;
+  host: string;
+  port: number;
+  tls?: boolean;
+  client_id?: string;
+  base_topic?: string;
+  discovery?: boolean;
+  discovery_prefix?: string;
+}
+
+/** Poll intervals driven by predicted notification thresholds */
+export interface AdaptivePollingConfig {
+  enabled: boolean;
+  min_interval_secs: number;
+  max_interval_secs: number;
+  /** Seconds before a predicted crossing to poll */
+  lead_secs: number;
+}
+
+/** Request throttling per API provider */
+export interface RateLimitsConfig {
+  hoyolab_requests_per_minute: number;
+  hoyolab_burst: number;
+  kuro_requests_per_minute: number;
+  kuro_burst: number;
+  /** Seconds every request waits after a provider reports a rate limit */
+  cooldown_secs: number;
+}
+
+/** Grouping of resource notifications that fire close together */
+export interface NotificationBatchConfig {
+  enabled: boolean;
+  /** Minutes to collect for after the first one; 0 groups one check only */
+  window_minutes: number;
+}
+
+/** Webhook payload shape */
+export type WebhookPreset = "discord" | "slack" | "json";
+
+/** Extra notification channel, tagged by `type` */
+export interface WebhookChannelConfig {
+  type: "webhook";
+  preset?: WebhookPreset;
+  template?: string | null;
+}
+
+/** Push priority for full/ready and approaching notifications */
+export interface PushPriority {
+  full: number;
+  approaching: number;
+}
+
+export interface NtfyChannelConfig {
+  type: "ntfy";
+  server?: string;
+  topic: string;
+  priority?: PushPriority;
+  /** Extra tags per game, keyed by game config key (e.g. "genshin_impact") */
+  tags?: Record<string, string[]>;
+}
+
+export interface GotifyChannelConfig {
+  type: "gotify";
+  server: string;
+  priority?: PushPriority;
+}
+
+export type SmtpSecurity = "starttls" | "tls" | "none";
+
+export interface EmailChannelConfig {
+  type: "email";
+  host: string;
+  port?: number;
+  security?: SmtpSecurity;
+  from: string;
+  to: string[];
+  alerts?: boolean;
+  /** Local time of the daily digest ("HH:MM") */
+  digest_time?: string | null;
+}
+
+export type HookEvent =
+  | "resource_approaching"
+  | "resource_full"
+  | "claim_succeeded"
+  | "claim_failed"
+  | "auth_expired";
+
+export interface HookChannelConfig {
+  type: "hook";
+  command: string;
+  args?: string[];
+  timeout_secs?: number;
+  /** Events to run on; every event when omitted */
+  events?: HookEvent[];
+}
+
+export type NotificationChannelConfig =
+  | WebhookChannelConfig
+  | NtfyChannelConfig
+  | GotifyChannelConfig
+  | EmailChannelConfig
+  | HookChannelConfig;
+
 /** Config keys for HoYoLab games only */
 export type HoyolabConfigKey = Exclude<keyof GamesConfig, "wuthering_waves">;
 
 /** Main application configuration (config.toml) */
 export interface AppConfig {
   general: GeneralConfig;
+  adaptive_polling?: AdaptivePollingConfig;
+  rate_limits?: RateLimitsConfig;
   games: GamesConfig;
+  http_api: HttpApiConfig;
+  mqtt?: MqttConfig;
+  notification_batch?: NotificationBatchConfig;
+  notification_channels?: Record<string, NotificationChannelConfig>;
+  quiet_hours?: QuietHours[];
 }
 
 // =============================================================================
@@ -94,10 +271,34 @@ export interface KuroSecrets {
   oauth_code: string;
 }
 
+/** Local HTTP API bearer token */
+export interface HttpApiSecrets {
+  token: string;
+}
+
+/** MQTT broker login */
+export interface MqttSecrets {
+  username: string;
+  password: string;
+}
+
+/** Notification channel URL (e.g. a webhook URL) */
+export interface NotificationChannelSecrets {
+  url?: string;
+  token?: string;
+  username?: string;
+  password?: string;
+}
+
 /** Secrets configuration (secrets.toml) */
 export interface SecretsConfig {
   hoyolab: HoyolabSecrets;
   kuro: KuroSecrets;
+  hoyolab_accounts?: Record<string, HoyolabSecrets>;
+  kuro_accounts?: Record<string, KuroSecrets>;
+  http_api?: HttpApiSecrets;
+  mqtt?: MqttSecrets;
+  notification_channels?: Record<string, NotificationChannelSecrets>;
 }
 
 // =============================================================================
@@ -108,3 +309,17 @@ export interface SecretsConfig {
 export interface SaveResult {
   effective_locale: string;
 }
+
+/** A game account bound to a HoYoLab login, returned by the list_game_roles command */
+export interface GameRole {
+  gameId: GameId;
+  uid: string;
+  nickname: string;
+  level: number;
+  /** API name of the server, e.g. "os_usa" */
+  server: string;
+  /** Display name of the server */
+  serverName: string;
+  /** Config region of the server; null for servers Storekeeper can't query */
+  region: string | null;
+}
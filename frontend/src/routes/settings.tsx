@@ -7,7 +7,11 @@ import { Button as AriaButton, TooltipTrigger } from "react-aria-components";
 import { atoms } from "@/modules/atoms";
 import { GenshinResource, HsrResource, ZzzResource } from "@/modules/games/games.constants";
 import { GameId } from "@/modules/games/games.types";
-import { type AllResources, isStaminaResource } from "@/modules/resources/resources.types";
+import {
+  type AllResources,
+  DEFAULT_ACCOUNT,
+  isStaminaResource,
+} from "@/modules/resources/resources.types";
 import { GeneralSection } from "@/modules/settings/components/GeneralSection";
 import { HoyolabGameSection } from "@/modules/settings/components/HoyolabGameSection";
 import { HoyolabSecretsSection } from "@/modules/settings/components/HoyolabSecretsSection";
@@ -25,7 +29,7 @@ function getResourceLimitsForGame(
   resources: AllResources | undefined,
   gameId: GameId,
 ): Partial<Record<string, ResourceLimits>> | undefined {
-  const gameResources = resources?.games?.[gameId];
+  const gameResources = resources?.games?.[gameId]?.[DEFAULT_ACCOUNT];
   if (!gameResources) {
     return undefined;
   }
@@ -106,6 +110,7 @@ const SettingsPage: React.FC = () => {
 
   // Resource data for computing input limits
   const { data: resources } = useAtomValue(atoms.core.resourcesQuery);
+  const { data: gameRoles } = useAtomValue(atoms.settings.gameRolesQuery);
   const resourceLimits = useMemo(
     () => ({
       GENSHIN_IMPACT: getResourceLimitsForGame(resources, GameId.GenshinImpact),
@@ -187,6 +192,7 @@ const SettingsPage: React.FC = () => {
             resourceTypes={game.resourceTypes}
             config={config.games[game.configKey]}
             resourceLimits={resourceLimits[game.gameId]}
+            roles={gameRoles}
             onChange={(value) =>
               updateConfig("games", {
                 ...config.games,
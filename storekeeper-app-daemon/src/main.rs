@@ -0,0 +1,82 @@
+//! Storekeeper - Gacha Game Stamina Tracker (headless daemon)
+//!
+//! Runs the same polling, scheduled daily reward claims, notification checks
+//! and history recording as the desktop app, without a webview or tray.
+//! Reads the same `config.toml` and `secrets.toml`; events and notifications
+//! are written to the log.
+
+use anyhow::Result;
+use std::sync::Arc;
+use storekeeper_runtime::events::LogEventSink;
+use storekeeper_runtime::i18n;
+use storekeeper_runtime::notification::LogNotifier;
+use storekeeper_runtime::notification::Notifier;
+use storekeeper_runtime::state::AppState;
+use tokio_util::sync::CancellationToken;
+use tracing_subscriber::EnvFilter;
+
+/// Initializes the tracing subscriber for logging.
+///
+/// Uses `RUST_LOG` environment variable if set, otherwise defaults to "info".
+fn init_tracing() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+
+    tracing_subscriber::fmt().with_env_filter(filter).init();
+}
+
+/// Builds the notifiers the daemon delivers notifications through.
+fn notifiers() -> Vec<Box<dyn Notifier>> {
+    vec![Box::new(LogNotifier)]
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    init_tracing();
+    tracing::info!("Starting storekeeper daemon");
+
+    let state = AppState::with_config(Arc::new(LogEventSink), notifiers());
+
+    let language = {
+        let inner = state.inner.read().await;
+        inner.config.general.language.clone()
+    };
+    i18n::init_from_config(language.as_deref());
+
+    let cancel_token = CancellationToken::new();
+    let tasks = tokio::spawn(storekeeper_runtime::run_background_tasks(
+        state,
+        cancel_token.clone(),
+    ));
+
+    wait_for_shutdown_signal().await?;
+    tracing::info!("Shutdown requested, stopping background tasks...");
+    cancel_token.cancel();
+    tasks.await?;
+
+    tracing::info!("Storekeeper daemon stopped");
+    Ok(())
+}
+
+/// Waits for Ctrl+C (SIGINT) or, on Unix, SIGTERM.
+///
+/// # Errors
+///
+/// Returns an error if the signal handlers cannot be installed.
+async fn wait_for_shutdown_signal() -> Result<()> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::SignalKind;
+        use tokio::signal::unix::signal;
+
+        let mut terminate = signal(SignalKind::terminate())?;
+        tokio::select! {
+            result = tokio::signal::ctrl_c() => result?,
+            _ = terminate.recv() => {}
+        }
+    }
+
+    #[cfg(not(unix))]
+    tokio::signal::ctrl_c().await?;
+
+    Ok(())
+}
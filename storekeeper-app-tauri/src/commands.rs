@@ -10,25 +10,35 @@
 
 use crate::error::CommandError;
 use crate::error::ErrorCode;
-use crate::events::AppEvent;
-use crate::i18n;
-use crate::notification;
-use crate::polling;
-use crate::retry_helpers::retry_with_backoff;
-use crate::state::AllDailyRewardStatus;
-use crate::state::AllResources;
-use crate::state::AppState;
 use jiff::Timestamp;
 use serde::Serialize;
+use storekeeper_core::AccountKey;
 use storekeeper_core::AppConfig;
+use storekeeper_core::DEFAULT_ACCOUNT;
 use storekeeper_core::GameId;
 use storekeeper_core::SecretsConfig;
+use storekeeper_runtime::challenge;
+use storekeeper_runtime::challenge::GeetestSolution;
+use storekeeper_runtime::config_diff;
+use storekeeper_runtime::history::HistoryQuery;
+use storekeeper_runtime::history::HistorySeries;
+use storekeeper_runtime::i18n;
+use storekeeper_runtime::notification;
+use storekeeper_runtime::notification::AlertLogEntry;
+use storekeeper_runtime::notification::Notification;
+use storekeeper_runtime::notification::Urgency;
+use storekeeper_runtime::overcap;
+use storekeeper_runtime::overcap::OvercapSummary;
+use storekeeper_runtime::polling;
+use storekeeper_runtime::roles;
+use storekeeper_runtime::roles::GameRole;
+use storekeeper_runtime::state::AllDailyRewardStatus;
+use storekeeper_runtime::state::AllResources;
+use storekeeper_runtime::state::AppState;
 use tauri::AppHandle;
-use tauri::Emitter;
 use tauri::Manager;
 use tauri::State;
 use tauri_plugin_autostart::ManagerExt;
-use tauri_plugin_notification::NotificationExt;
 
 /// Gets all cached resources.
 #[tauri::command]
@@ -38,8 +48,20 @@ pub async fn get_all_resources(state: State<'_, AppState>) -> Result<AllResource
 
 /// Refreshes resources from all configured games.
 #[tauri::command]
-pub async fn refresh_resources(app_handle: AppHandle) -> Result<AllResources, CommandError> {
-    polling::refresh_now(&app_handle)
+pub async fn refresh_resources(state: State<'_, AppState>) -> Result<AllResources, CommandError> {
+    polling::refresh_now(&state)
+        .await
+        .map_err(CommandError::internal)
+}
+
+/// Refreshes resources from a single game, without waiting for refreshes of
+/// other games.
+#[tauri::command]
+pub async fn refresh_game(
+    state: State<'_, AppState>,
+    game_id: GameId,
+) -> Result<AllResources, CommandError> {
+    polling::refresh_game(&state, game_id)
         .await
         .map_err(CommandError::internal)
 }
@@ -56,6 +78,21 @@ pub async fn get_secrets() -> Result<SecretsConfig, CommandError> {
     Ok(SecretsConfig::load()?)
 }
 
+/// Lists the Genshin Impact, Honkai: Star Rail and Zenless Zone Zero roles
+/// bound to a HoYoLab login of the saved secrets, for settings to pick UIDs
+/// and regions from.
+///
+/// `credentials` names the login, defaulting to the top-level `[hoyolab]`
+/// one.
+#[tauri::command]
+pub async fn list_game_roles(
+    credentials: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<GameRole>, CommandError> {
+    let credentials = credentials.unwrap_or_else(|| DEFAULT_ACCOUNT.to_string());
+    Ok(roles::discover_game_roles(&state, &credentials).await?)
+}
+
 /// Result returned by `save_and_apply`.
 #[derive(Serialize)]
 pub struct SaveResult {
@@ -90,7 +127,7 @@ pub async fn save_and_apply(
     tracing::info!("Configuration and secrets saved to disk");
 
     // Compute diff in-memory (no disk re-read)
-    let diff = crate::config_diff::compute(&old_config, &config, &old_secrets, &secrets);
+    let diff = config_diff::compute(&old_config, &config, &old_secrets, &secrets);
 
     if diff.is_empty() {
         tracing::info!("Config unchanged, nothing to apply");
@@ -102,6 +139,9 @@ pub async fn save_and_apply(
     tracing::info!(
         locale_changed = diff.locale_changed,
         autostart_changed = diff.autostart_changed,
+        http_api_changed = diff.http_api_changed,
+        mqtt_changed = diff.mqtt_changed,
+        notification_channels_changed = diff.notification_channels_changed,
         needs_registry_rebuild = diff.needs_registry_rebuild,
         games_to_refresh = ?diff.games_to_refresh,
         games_to_reset_notifications = ?diff.games_to_reset_notifications,
@@ -116,6 +156,14 @@ pub async fn save_and_apply(
     // Wake the scheduler so it re-evaluates auto-claim config immediately
     state.wake_scheduler();
 
+    if diff.http_api_changed {
+        state.restart_http_api();
+    }
+
+    if diff.mqtt_changed {
+        state.restart_mqtt();
+    }
+
     // Update locale if changed
     if diff.locale_changed {
         let language = {
@@ -126,7 +174,12 @@ pub async fn save_and_apply(
         if let Err(e) = i18n::set_locale(effective_locale) {
             tracing::warn!(error = %e, "Failed to update i18n locale");
         }
-        if let Err(e) = crate::tray::build_tray_menu(&app_handle) {
+    }
+
+    // Rebuild the tray menu for the new locale or set of games
+    if diff.locale_changed || diff.needs_registry_rebuild {
+        let games = state.configured_games().await;
+        if let Err(e) = crate::tray::build_tray_menu(&app_handle, &games) {
             tracing::warn!(error = %e, "Failed to rebuild tray menu");
         }
     }
@@ -150,15 +203,14 @@ pub async fn save_and_apply(
 
     // Reset notification cooldowns for affected games only
     if !diff.games_to_reset_notifications.is_empty() {
-        let mut inner = state.inner.write().await;
-        for game_id in &diff.games_to_reset_notifications {
-            inner.notification_tracker.clear_for_game(*game_id);
-        }
+        state
+            .reset_notifications(&diff.games_to_reset_notifications)
+            .await;
     }
 
     // Selective refresh: only fetch games that actually changed
     if !diff.games_to_refresh.is_empty()
-        && let Err(e) = polling::refresh_games(&app_handle, &diff.games_to_refresh).await
+        && let Err(e) = polling::refresh_games(&state, &diff.games_to_refresh).await
     {
         tracing::warn!(error = %e, "Failed to refresh games after config change");
     }
@@ -226,61 +278,124 @@ pub async fn refresh_daily_reward_status(
     Ok(status)
 }
 
-/// Claims daily reward for a specific game with retry on network failures.
+/// Builds the account key for a command targeting an optional named account.
+///
+/// Omitting the account targets the game's default account.
+fn account_key(game_id: GameId, account: Option<String>) -> AccountKey {
+    AccountKey::new(
+        game_id,
+        account.unwrap_or_else(|| DEFAULT_ACCOUNT.to_string()),
+    )
+}
+
+/// Claims daily reward for one account of a game with retry on network
+/// failures.
 #[tauri::command]
 pub async fn claim_daily_reward_for_game(
     game_id: GameId,
-    app_handle: AppHandle,
+    account: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<serde_json::Value, CommandError> {
-    tracing::info!(game_id = ?game_id, "Manual daily reward claim requested for specific game");
-    let result = retry_with_backoff(|| state.claim_daily_reward_for_game(game_id)).await?;
-
-    // Refresh status for this game after claiming
-    if let Ok(game_status) = state.get_daily_reward_status_for_game(game_id).await {
-        let mut current_status = state.get_daily_reward_status().await;
-        current_status.games.insert(game_id, game_status);
-        current_status.last_checked = Some(Timestamp::now());
-        state.set_daily_reward_status(current_status).await;
-    }
-
-    // Notify frontend so atoms re-fetch claim status
-    if let Err(e) = app_handle.emit(AppEvent::DailyRewardClaimed.as_str(), &result) {
-        tracing::warn!(error = %e, "Failed to emit DailyRewardClaimed event");
-    }
+    let key = account_key(game_id, account);
+    tracing::info!(account = %key, "Manual daily reward claim requested for specific account");
+    Ok(state.claim_daily_reward_now(&key).await?)
+}
 
-    Ok(result)
+/// Hands the solution to a Geetest challenge to the account of a game waiting
+/// on it, replaying the refused fetch or claim and resuming the game.
+#[tauri::command]
+pub async fn solve_challenge(
+    game_id: GameId,
+    account: Option<String>,
+    challenge: String,
+    validate: String,
+    state: State<'_, AppState>,
+) -> Result<(), CommandError> {
+    let key = account_key(game_id, account);
+    tracing::info!(account = %key, "Geetest challenge solution submitted");
+    let solution = GeetestSolution {
+        challenge,
+        validate,
+    };
+    Ok(challenge::solve_challenge(&state, &key, solution).await?)
 }
 
-/// Gets the daily reward status for a specific game.
+/// Gets the daily reward status for one account of a game.
 #[tauri::command]
 pub async fn get_daily_reward_status_for_game(
     game_id: GameId,
+    account: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<serde_json::Value, CommandError> {
-    Ok(state.get_daily_reward_status_for_game(game_id).await?)
+    let key = account_key(game_id, account);
+    Ok(state.get_daily_reward_status_for_account(&key).await?)
+}
+
+// ============================================================================
+// History & Analytics Commands
+// ============================================================================
+
+/// Queries recorded resource history, downsampled for charting.
+///
+/// Returns one series per matching account and resource type.
+#[tauri::command]
+pub async fn get_resource_history(
+    query: HistoryQuery,
+    state: State<'_, AppState>,
+) -> Result<Vec<HistorySeries>, CommandError> {
+    let Some(history) = state.history() else {
+        return Ok(Vec::new());
+    };
+    // Reads the whole history file, so keep it off the async runtime
+    let series = tokio::task::spawn_blocking(move || history.query(&query))
+        .await
+        .map_err(anyhow::Error::from)??;
+    Ok(series)
+}
+
+/// Summarizes time spent at max and stamina wasted over the last `days`
+/// local days (default 7, at most 56).
+#[tauri::command]
+pub async fn get_overcap_summary(
+    days: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<OvercapSummary, CommandError> {
+    let days = days
+        .unwrap_or(overcap::DIGEST_DAYS)
+        .clamp(1, overcap::MAX_SUMMARY_DAYS);
+    let now = Timestamp::now();
+    Ok(state.overcap().map_or_else(
+        || OvercapSummary {
+            from: now,
+            to: now,
+            resources: Vec::new(),
+        },
+        |overcap| overcap.summarize(now, days),
+    ))
 }
 
 // ============================================================================
 // Notification Commands
 // ============================================================================
 
-/// Sends a preview notification for a specific game resource using cached data.
+/// Sends a preview notification for a specific account resource using cached
+/// data.
 #[tauri::command]
 pub async fn send_preview_notification(
     game_id: GameId,
+    account: Option<String>,
     resource_type: String,
-    app_handle: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<(), CommandError> {
+    let key = account_key(game_id, account);
     let resources = state.get_resources().await;
-    let game_name = notification::game_display_name(game_id);
+    let game_name = notification::account_display_name(&key);
     let resource_name = notification::resource_display_name(&resource_type);
 
     // Try to find cached resource data and build a real notification body
     let body = resources
         .games
-        .get(&game_id)
+        .get(&key)
         .and_then(|v| v.as_array())
         .and_then(|arr| {
             arr.iter().find(|obj| {
@@ -305,18 +420,32 @@ pub async fn send_preview_notification(
         ],
     );
 
-    app_handle
-        .notification()
-        .builder()
-        .title(&title)
-        .body(&body)
-        .show()
+    state
+        .notify(&Notification {
+            title,
+            body,
+            game_id: Some(game_id),
+            urgency: Urgency::Normal,
+            event: None,
+        })
+        .await
         .map_err(|e| CommandError {
             code: ErrorCode::NotificationError,
-            message: e.to_string(),
+            message: format!("{e:#}"),
         })
 }
 
+/// Returns recently sent notifications, newest first, for the recent alerts
+/// view.
+#[tauri::command]
+pub async fn get_recent_alerts(
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<AlertLogEntry>, CommandError> {
+    let limit = limit.unwrap_or(notification::ALERT_LOG_CAPACITY);
+    Ok(state.recent_alerts(limit).await)
+}
+
 // ============================================================================
 // Locale Commands
 // ============================================================================
@@ -60,6 +60,7 @@ impl From<storekeeper_core::Error> for CommandError {
             | storekeeper_core::Error::InvalidRegion(_)
             | storekeeper_core::Error::UnknownUidRegion(_) => ErrorCode::ConfigInvalid,
             storekeeper_core::Error::ConfigReadFailed(_) => ErrorCode::IoError,
+            storekeeper_core::Error::UnknownServerRegion(_) => ErrorCode::Internal,
         };
         Self {
             code,
@@ -1,26 +1,22 @@
 //! Storekeeper Tauri Application Library
 //!
 //! This module provides the main entry point and setup for the Tauri
-//! application.
+//! application. Polling, scheduling and notifications live in
+//! `storekeeper-runtime`; this crate adds the webview, tray, Tauri commands
+//! and the Tauri-backed event sink and notifier.
 
-mod clients;
 mod commands;
-mod config_diff;
-mod daily_reward_registry;
 mod error;
-mod events;
-pub mod i18n;
-mod notification;
-mod polling;
-mod provider_batch;
-mod registry;
-mod retry_helpers;
-mod scheduled_claim;
-mod state;
+mod sink;
 mod tray;
 
 use anyhow::Context;
 use anyhow::Result;
+use sink::OsNotifier;
+use sink::TauriEventSink;
+use std::sync::Arc;
+use storekeeper_runtime::i18n;
+use storekeeper_runtime::state;
 use tauri::Manager;
 use tauri::RunEvent;
 use tauri_plugin_autostart::ManagerExt;
@@ -64,28 +60,28 @@ pub fn run() -> Result<()> {
         .plugin(tauri_plugin_autostart::Builder::new().build())
         .plugin(tauri_plugin_notification::init())
         .setup(|app| {
-            // Initialize application state with config and game clients
-            let app_state = state::AppState::with_config();
+            // Initialize application state with config and game clients,
+            // publishing events to the webview and notifying via OS toasts
+            let app_state = state::AppState::with_config(
+                Arc::new(TauriEventSink::new(app.handle().clone())),
+                vec![Box::new(OsNotifier::new(app.handle().clone()))],
+            );
 
             // Read config values needed for setup
-            let (language, should_autostart) = tauri::async_runtime::block_on(async {
+            let (language, should_autostart, games) = tauri::async_runtime::block_on(async {
+                let games = app_state.configured_games().await;
                 let inner = app_state.inner.read().await;
                 (
                     inner.config.general.language.clone(),
                     inner.config.general.autostart,
+                    games,
                 )
             });
 
             // Initialize i18n with resolved locale (auto-detect if no override)
-            let effective_locale = i18n::resolve_locale(language.as_deref());
-            if let Err(e) = i18n::init(effective_locale) {
-                tracing::warn!(error = %e, "Failed to initialize i18n, falling back to defaults");
-                if let Err(e) = i18n::init("en") {
-                    tracing::error!(error = %e, "Failed to initialize i18n fallback locale");
-                }
-            }
+            i18n::init_from_config(language.as_deref());
 
-            app.manage(app_state);
+            app.manage(app_state.clone());
 
             // Sync autostart state from config
             let autolaunch = app.autolaunch();
@@ -102,37 +98,42 @@ pub fn run() -> Result<()> {
             let cancel_token = CancellationToken::new();
             app.manage(cancel_token.clone());
 
-            // Start background polling for resources
-            polling::start_polling(app.handle().clone(), cancel_token.clone());
-
-            // Start scheduled daily reward claims
-            scheduled_claim::start_scheduled_claims(app.handle().clone(), cancel_token.clone());
-
-            // Start notification checker
-            notification::start_notification_checker(app.handle().clone(), cancel_token.clone());
+            // Start polling, scheduled claims, notification checks and
+            // history compaction
+            tauri::async_runtime::spawn(storekeeper_runtime::run_background_tasks(
+                app_state,
+                cancel_token.clone(),
+            ));
 
             // Set up Ctrl+C handler to trigger graceful shutdown
             setup_ctrlc_handler(app.handle().clone(), cancel_token);
 
             // Set up system tray
-            tray::setup_tray(app)?;
+            tray::setup_tray(app, &games)?;
 
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
             commands::get_all_resources,
             commands::refresh_resources,
+            commands::refresh_game,
             commands::get_config,
             commands::get_secrets,
             commands::save_and_apply,
+            commands::list_game_roles,
             commands::open_config_folder,
             // Notification commands
             commands::send_preview_notification,
+            commands::get_recent_alerts,
             // Daily reward commands
             commands::get_daily_reward_status,
             commands::refresh_daily_reward_status,
             commands::claim_daily_reward_for_game,
             commands::get_daily_reward_status_for_game,
+            commands::solve_challenge,
+            // History commands
+            commands::get_resource_history,
+            commands::get_overcap_summary,
             // Locale commands
             commands::get_supported_locales,
             commands::get_effective_locale,
@@ -0,0 +1,65 @@
+//! Tauri implementations of the runtime's event sink and notifier.
+
+use std::future::Future;
+use std::pin::Pin;
+use storekeeper_runtime::events::AppEvent;
+use storekeeper_runtime::events::EventSink;
+use storekeeper_runtime::notification::Notification;
+use storekeeper_runtime::notification::Notifier;
+use tauri::AppHandle;
+use tauri::Emitter;
+use tauri_plugin_notification::NotificationExt;
+
+type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Forwards runtime events to the webview as Tauri events.
+pub struct TauriEventSink {
+    app_handle: AppHandle,
+}
+
+impl TauriEventSink {
+    /// Creates a sink emitting through the given app handle.
+    #[must_use]
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self { app_handle }
+    }
+}
+
+impl EventSink for TauriEventSink {
+    fn emit(&self, event: AppEvent, payload: &serde_json::Value) {
+        if let Err(e) = self.app_handle.emit(event.as_str(), payload) {
+            tracing::warn!(event = event.as_str(), error = %e, "Failed to emit event");
+        }
+    }
+}
+
+/// Shows notifications as OS toasts via the notification plugin.
+pub struct OsNotifier {
+    app_handle: AppHandle,
+}
+
+impl OsNotifier {
+    /// Creates a notifier showing toasts through the given app handle.
+    #[must_use]
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self { app_handle }
+    }
+}
+
+impl Notifier for OsNotifier {
+    fn name(&self) -> &'static str {
+        "os"
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            self.app_handle
+                .notification()
+                .builder()
+                .title(&notification.title)
+                .body(&notification.body)
+                .show()?;
+            Ok(())
+        })
+    }
+}
@@ -1,39 +1,68 @@
 //! System tray setup and event handling.
 
-use crate::i18n;
 use anyhow::Context;
 use anyhow::Result;
+use std::collections::HashSet;
+use storekeeper_core::GameId;
+use storekeeper_runtime::i18n;
+use storekeeper_runtime::notification::game_display_name;
+use storekeeper_runtime::polling;
+use storekeeper_runtime::state::AppState;
 use tauri::App;
 use tauri::AppHandle;
 use tauri::Manager;
-use tauri::menu::Menu;
+use tauri::menu::MenuBuilder;
 use tauri::menu::MenuItem;
 use tauri::tray::MouseButton;
 use tauri::tray::MouseButtonState;
 use tauri::tray::TrayIconEvent;
 use tokio_util::sync::CancellationToken;
 
-/// Builds (or rebuilds) the tray menu with localized strings.
+/// Menu item ID prefix of the per-game refresh items, followed by the game's
+/// config key.
+const REFRESH_GAME_PREFIX: &str = "refresh:";
+
+/// Builds (or rebuilds) the tray menu with localized strings, with a refresh
+/// item for each of `games`.
 ///
-/// Can be called at startup and again after locale changes.
+/// Can be called at startup and again after locale or game changes.
 ///
 /// # Errors
 ///
 /// Returns an error if the menu items or menu cannot be created,
 /// or if the tray icon is not found.
-pub fn build_tray_menu(app: &AppHandle) -> Result<()> {
+pub fn build_tray_menu(app: &AppHandle, games: &HashSet<GameId>) -> Result<()> {
     let refresh_label = i18n::t("tray_refresh_now");
     let open_config_label = i18n::t("tray_open_config_folder");
     let quit_label = i18n::t("tray_quit");
 
     let refresh = MenuItem::with_id(app, "refresh", &refresh_label, true, None::<&str>)
         .context("failed to create 'Refresh Now' menu item")?;
+    let refresh_games = GameId::all()
+        .iter()
+        .filter(|game_id| games.contains(game_id))
+        .map(|game_id| {
+            let label = i18n::t_args(
+                "tray_refresh_game",
+                &[("game_name", i18n::Value::from(game_display_name(*game_id)))],
+            );
+            let id = format!("{REFRESH_GAME_PREFIX}{}", game_id.config_key());
+            MenuItem::with_id(app, id, &label, true, None::<&str>)
+                .with_context(|| format!("failed to create '{label}' menu item"))
+        })
+        .collect::<Result<Vec<_>>>()?;
     let open_config = MenuItem::with_id(app, "open_config", &open_config_label, true, None::<&str>)
         .context("failed to create 'Open Config Folder' menu item")?;
     let quit = MenuItem::with_id(app, "quit", &quit_label, true, None::<&str>)
         .context("failed to create 'Quit' menu item")?;
 
-    let menu = Menu::with_items(app, &[&refresh, &open_config, &quit])
+    let mut menu = MenuBuilder::new(app).item(&refresh);
+    for item in &refresh_games {
+        menu = menu.item(item);
+    }
+    let menu = menu
+        .items(&[&open_config, &quit])
+        .build()
         .context("failed to create tray menu")?;
 
     let tray = app
@@ -51,9 +80,9 @@ pub fn build_tray_menu(app: &AppHandle) -> Result<()> {
 /// # Errors
 ///
 /// Returns an error if the tray icon or menu cannot be created.
-pub fn setup_tray(app: &App) -> Result<()> {
+pub fn setup_tray(app: &App, games: &HashSet<GameId>) -> Result<()> {
     // Build initial menu using the app handle
-    build_tray_menu(app.handle())?;
+    build_tray_menu(app.handle(), games)?;
 
     // Get the tray icon and attach event handlers
     let tray = app
@@ -63,9 +92,9 @@ pub fn setup_tray(app: &App) -> Result<()> {
     tray.on_menu_event(|app, event| {
         match event.id.as_ref() {
             "refresh" => {
-                let app_handle = app.clone();
+                let state = app.state::<AppState>().inner().clone();
                 tauri::async_runtime::spawn(async move {
-                    if let Err(e) = crate::polling::refresh_now(&app_handle).await {
+                    if let Err(e) = polling::refresh_now(&state).await {
                         tracing::error!("Refresh failed: {e}");
                     }
                 });
@@ -86,7 +115,16 @@ pub fn setup_tray(app: &App) -> Result<()> {
 
                 app.exit(0);
             }
-            _ => {}
+            id => {
+                if let Some(game_id) = refresh_game_id(id) {
+                    let state = app.state::<AppState>().inner().clone();
+                    tauri::async_runtime::spawn(async move {
+                        if let Err(e) = polling::refresh_game(&state, game_id).await {
+                            tracing::error!(game = %game_id, "Refresh failed: {e}");
+                        }
+                    });
+                }
+            }
         }
     });
 
@@ -133,3 +171,12 @@ pub fn setup_tray(app: &App) -> Result<()> {
 
     Ok(())
 }
+
+/// Returns the game refreshed by a per-game refresh menu item.
+fn refresh_game_id(menu_id: &str) -> Option<GameId> {
+    let config_key = menu_id.strip_prefix(REFRESH_GAME_PREFIX)?;
+    GameId::all()
+        .iter()
+        .copied()
+        .find(|game_id| game_id.config_key() == config_key)
+}
@@ -0,0 +1,80 @@
+//! `storekeeper check-auth`: verify the configured credentials.
+
+use crate::Selection;
+use crate::error::CliError;
+use crate::failure::Exit;
+use crate::failure::Failure;
+use crate::failure::FailureKind;
+use crate::output;
+use crate::output::Table;
+use serde::Serialize;
+use storekeeper_core::AccountKey;
+use storekeeper_runtime::challenge::Challenges;
+use storekeeper_runtime::clients::RateLimiters;
+use storekeeper_runtime::clients::create_registry;
+use storekeeper_runtime::notification::account_display_name;
+
+/// Auth check outcome for one account, as printed by `--json`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountAuth {
+    #[serde(flatten)]
+    key: AccountKey,
+    authenticated: bool,
+    error: Option<Failure>,
+}
+
+#[derive(Debug, Serialize)]
+struct CheckAuthOutput {
+    accounts: Vec<AccountAuth>,
+}
+
+/// Checks the credentials of every selected account sequentially.
+///
+/// # Errors
+///
+/// Returns an error if the configuration cannot be loaded or no account is
+/// selected.
+pub async fn run(selection: &Selection, json: bool) -> Result<Exit, CliError> {
+    let (config, secrets) = crate::load_config()?;
+    let registry = create_registry(
+        &config,
+        &secrets,
+        &RateLimiters::new(&config.rate_limits),
+        &Challenges::default(),
+    );
+
+    let mut accounts = Vec::new();
+    for (key, client) in selection.select(registry.clients())? {
+        let error = match client.is_authenticated_dyn().await {
+            Ok(true) => None,
+            Ok(false) => Some(Failure {
+                kind: FailureKind::Auth,
+                message: "credentials were rejected".to_string(),
+            }),
+            Err(e) => Some(Failure::from_error(e.as_ref())),
+        };
+        accounts.push(AccountAuth {
+            key: key.clone(),
+            authenticated: error.is_none(),
+            error,
+        });
+    }
+
+    let exit = Exit::from_failures(accounts.iter().filter_map(|a| a.error.as_ref()));
+
+    if json {
+        output::print_json(&CheckAuthOutput { accounts })?;
+    } else {
+        let mut table = Table::new(&["ACCOUNT", "AUTH"]);
+        for account in &accounts {
+            let detail = account
+                .error
+                .as_ref()
+                .map_or_else(|| "ok".to_string(), ToString::to_string);
+            table.push(vec![account_display_name(&account.key), detail]);
+        }
+        println!("{}", table.render());
+    }
+    Ok(exit)
+}
@@ -0,0 +1,97 @@
+//! `storekeeper claim`: claim today's daily reward.
+
+use crate::Selection;
+use crate::error::CliError;
+use crate::failure::Exit;
+use crate::failure::Failure;
+use crate::output;
+use crate::output::Table;
+use serde::Serialize;
+use storekeeper_core::AccountKey;
+use storekeeper_core::ClaimResult;
+use storekeeper_runtime::challenge::Challenges;
+use storekeeper_runtime::clients::RateLimiters;
+use storekeeper_runtime::clients::create_daily_reward_registry;
+use storekeeper_runtime::notification::account_display_name;
+
+/// Claim outcome for one account, as printed by `--json`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountClaim {
+    #[serde(flatten)]
+    key: AccountKey,
+    /// Claim result as returned by the client; `null` if the claim failed.
+    result: Option<serde_json::Value>,
+    error: Option<Failure>,
+}
+
+#[derive(Debug, Serialize)]
+struct ClaimOutput {
+    claims: Vec<AccountClaim>,
+}
+
+/// Claims the daily reward for every selected account sequentially.
+///
+/// A reward that was already claimed today is not a failure.
+///
+/// # Errors
+///
+/// Returns an error if the configuration cannot be loaded or no account with
+/// daily rewards is selected.
+pub async fn run(selection: &Selection, json: bool) -> Result<Exit, CliError> {
+    let (config, secrets) = crate::load_config()?;
+    let registry = create_daily_reward_registry(
+        &config,
+        &secrets,
+        &RateLimiters::new(&config.rate_limits),
+        &Challenges::default(),
+    );
+
+    let mut claims = Vec::new();
+    for (key, client) in selection.select(registry.clients())? {
+        let (result, error) = match client.claim_daily_reward_json().await {
+            Ok(result) => (Some(result), None),
+            Err(e) => (None, Some(Failure::from_error(e.as_ref()))),
+        };
+        claims.push(AccountClaim {
+            key: key.clone(),
+            result,
+            error,
+        });
+    }
+
+    let exit = Exit::from_failures(claims.iter().filter_map(|c| c.error.as_ref()));
+
+    if json {
+        output::print_json(&ClaimOutput { claims })?;
+    } else {
+        let mut table = Table::new(&["ACCOUNT", "RESULT"]);
+        for claim in &claims {
+            table.push(vec![account_display_name(&claim.key), describe(claim)]);
+        }
+        println!("{}", table.render());
+    }
+    Ok(exit)
+}
+
+fn describe(claim: &AccountClaim) -> String {
+    if let Some(failure) = &claim.error {
+        return failure.to_string();
+    }
+    let Some(result) = claim
+        .result
+        .clone()
+        .and_then(|value| serde_json::from_value::<ClaimResult>(value).ok())
+    else {
+        return "-".to_string();
+    };
+
+    let reward = result
+        .reward
+        .map(|reward| format!("{} x{}", reward.name, reward.amount));
+    match (result.success, reward) {
+        (true, Some(reward)) => format!("claimed {reward}"),
+        (true, None) => "claimed".to_string(),
+        (false, _) => result.message.unwrap_or_else(|| "not claimed".to_string()),
+    }
+}
@@ -0,0 +1,58 @@
+//! Command-level errors that abort a command before any per-account result.
+
+use crate::failure::Exit;
+use serde::Serialize;
+use thiserror::Error;
+
+/// Error that prevents a command from running at all.
+#[derive(Debug, Error)]
+pub enum CliError {
+    /// `config.toml` or `secrets.toml` could not be loaded.
+    #[error(transparent)]
+    Config(#[from] storekeeper_core::Error),
+
+    /// No enabled account with credentials matches the `--game`/`--account`
+    /// selection.
+    #[error("no enabled accounts with credentials match the selection")]
+    NoAccounts,
+
+    /// Unexpected failure (e.g. writing output).
+    #[error(transparent)]
+    Other(#[from] anyhow::Error),
+}
+
+impl CliError {
+    /// Returns the exit status for this error.
+    pub fn exit(&self) -> Exit {
+        match self {
+            Self::Config(_) | Self::NoAccounts => Exit::Config,
+            Self::Other(_) => Exit::Failure,
+        }
+    }
+
+    /// Returns the JSON form of this error.
+    pub fn to_json(&self) -> ErrorOutput {
+        ErrorOutput {
+            error: ErrorBody {
+                kind: match self {
+                    Self::Config(_) => "config",
+                    Self::NoAccounts => "no_accounts",
+                    Self::Other(_) => "other",
+                },
+                message: format!("{self:#}"),
+            },
+        }
+    }
+}
+
+/// JSON document printed when a command fails as a whole.
+#[derive(Debug, Serialize)]
+pub struct ErrorOutput {
+    error: ErrorBody,
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+    kind: &'static str,
+    message: String,
+}
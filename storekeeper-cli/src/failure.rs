@@ -0,0 +1,196 @@
+//! Failure classification and process exit codes.
+//!
+//! Exit codes are part of the CLI's scripting contract and must stay stable:
+//!
+//! | Code | Meaning                                                   |
+//! | ---- | --------------------------------------------------------- |
+//! | 0    | Success                                                   |
+//! | 1    | Any other failure (unexpected API response, ...)          |
+//! | 2    | Invalid command-line usage                                |
+//! | 3    | Configuration missing, unparsable or invalid              |
+//! | 4    | Authentication failed for at least one account            |
+//! | 5    | Network failure (and no authentication failure)           |
+
+use serde::Serialize;
+use std::process::ExitCode;
+use storekeeper_runtime::error_kind::ErrorKind;
+use storekeeper_runtime::error_kind::classify;
+
+/// Category of a failed account operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    /// Anything that is neither an auth nor a network failure.
+    Other,
+    /// The API could not be reached or asked us to back off.
+    Network,
+    /// Credentials were rejected.
+    Auth,
+}
+
+impl FailureKind {
+    /// Classifies an error by walking its source chain for typed client
+    /// errors.
+    pub fn classify(error: &(dyn std::error::Error + 'static)) -> Self {
+        match classify(error) {
+            ErrorKind::Auth => Self::Auth,
+            ErrorKind::Network
+            | ErrorKind::Timeout
+            | ErrorKind::RateLimited
+            | ErrorKind::Maintenance => Self::Network,
+            ErrorKind::Captcha | ErrorKind::NotFound | ErrorKind::Protocol | ErrorKind::Other => {
+                Self::Other
+            }
+        }
+    }
+}
+
+/// A failed account operation, as reported in JSON output.
+#[derive(Debug, Clone, Serialize)]
+pub struct Failure {
+    /// Failure category.
+    pub kind: FailureKind,
+    /// Human-readable error message.
+    pub message: String,
+}
+
+impl Failure {
+    /// Builds a failure from a client error.
+    pub fn from_error(error: &(dyn std::error::Error + 'static)) -> Self {
+        Self {
+            kind: FailureKind::classify(error),
+            message: error.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.kind {
+            FailureKind::Auth => "auth failed",
+            FailureKind::Network => "network error",
+            FailureKind::Other => "error",
+        };
+        write!(f, "{kind}: {}", self.message)
+    }
+}
+
+/// Process exit status of a command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exit {
+    /// Everything succeeded.
+    Success,
+    /// At least one operation failed for another reason.
+    Failure,
+    /// The configuration could not be used.
+    Config,
+    /// At least one account failed to authenticate.
+    Auth,
+    /// At least one account could not reach the API.
+    Network,
+}
+
+impl Exit {
+    /// Picks the exit status for a set of per-account failures.
+    ///
+    /// Auth failures take precedence over network failures, which take
+    /// precedence over anything else, so scripts can react to the most
+    /// actionable problem.
+    pub fn from_failures<'a>(failures: impl IntoIterator<Item = &'a Failure>) -> Self {
+        match failures.into_iter().map(|f| f.kind).max() {
+            None => Self::Success,
+            Some(FailureKind::Auth) => Self::Auth,
+            Some(FailureKind::Network) => Self::Network,
+            Some(FailureKind::Other) => Self::Failure,
+        }
+    }
+}
+
+impl From<Exit> for ExitCode {
+    fn from(exit: Exit) -> Self {
+        Self::from(match exit {
+            Exit::Success => 0,
+            Exit::Failure => 1,
+            Exit::Config => 3,
+            Exit::Auth => 4,
+            Exit::Network => 5,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use storekeeper_client_core::ClientError;
+
+    fn failure(kind: FailureKind) -> Failure {
+        Failure {
+            kind,
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn hoyolab_not_logged_in_is_auth() {
+        let err =
+            storekeeper_client_hoyolab::Error::Client(ClientError::api_error(-100, "Please login"));
+        assert_eq!(FailureKind::classify(&err), FailureKind::Auth);
+    }
+
+    #[test]
+    fn hoyolab_rate_limit_is_network() {
+        let err = storekeeper_client_hoyolab::Error::RateLimited {
+            retry_after_secs: 30,
+        };
+        assert_eq!(FailureKind::classify(&err), FailureKind::Network);
+    }
+
+    #[test]
+    fn kuro_retry_requested_is_network() {
+        let err = storekeeper_client_kuro::Error::RetryRequested;
+        assert_eq!(FailureKind::classify(&err), FailureKind::Network);
+    }
+
+    #[test]
+    fn unknown_api_error_is_other() {
+        let err = storekeeper_client_hoyolab::Error::Client(ClientError::api_error(
+            -1002,
+            "invalid request",
+        ));
+        assert_eq!(FailureKind::classify(&err), FailureKind::Other);
+    }
+
+    #[test]
+    fn classification_follows_source_chain() {
+        let err = anyhow::Error::new(ClientError::auth_failed("expired"))
+            .context("failed to claim daily reward");
+        assert_eq!(FailureKind::classify(err.as_ref()), FailureKind::Auth);
+    }
+
+    #[test]
+    fn untyped_error_is_other() {
+        let err = std::io::Error::other("boom");
+        assert_eq!(FailureKind::classify(&err), FailureKind::Other);
+    }
+
+    #[test]
+    fn exit_prefers_auth_over_network() {
+        let failures = [
+            failure(FailureKind::Network),
+            failure(FailureKind::Auth),
+            failure(FailureKind::Other),
+        ];
+        assert_eq!(Exit::from_failures(&failures), Exit::Auth);
+    }
+
+    #[test]
+    fn exit_prefers_network_over_other() {
+        let failures = [failure(FailureKind::Other), failure(FailureKind::Network)];
+        assert_eq!(Exit::from_failures(&failures), Exit::Network);
+    }
+
+    #[test]
+    fn exit_without_failures_is_success() {
+        assert_eq!(Exit::from_failures(&[]), Exit::Success);
+    }
+}
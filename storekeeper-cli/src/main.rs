@@ -0,0 +1,225 @@
+//! Storekeeper - Gacha Game Stamina Tracker (command-line interface)
+//!
+//! One-shot commands for scripting: show resources, claim daily rewards,
+//! check credentials and validate the configuration. Reads the same
+//! `config.toml` and `secrets.toml` as the desktop app. Every command accepts
+//! `--json` for stable machine-readable output; exit codes are documented in
+//! [`failure`].
+
+mod check_auth;
+mod claim;
+mod error;
+mod failure;
+mod output;
+mod status;
+mod validate;
+
+use clap::Args;
+use clap::Parser;
+use clap::Subcommand;
+use clap::ValueEnum;
+use error::CliError;
+use failure::Exit;
+use std::process::ExitCode;
+use storekeeper_core::AccountKey;
+use storekeeper_core::AppConfig;
+use storekeeper_core::GameId;
+use storekeeper_core::SecretsConfig;
+use storekeeper_runtime::i18n;
+use tracing_subscriber::EnvFilter;
+
+/// Track stamina resources and daily rewards for gacha games.
+#[derive(Debug, Parser)]
+#[command(name = "storekeeper", version)]
+struct Cli {
+    /// Print machine-readable JSON instead of tables.
+    #[arg(long, global = true)]
+    json: bool,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Fetch and show current resources and daily reward status.
+    Status(Selection),
+    /// Claim today's daily reward (HoYoLab games).
+    Claim(Selection),
+    /// Check whether the configured credentials are accepted.
+    CheckAuth(Selection),
+    /// Inspect the configuration files.
+    #[command(subcommand)]
+    Config(ConfigCommand),
+}
+
+#[derive(Debug, Subcommand)]
+enum ConfigCommand {
+    /// Check config.toml and secrets.toml for errors.
+    Validate,
+}
+
+/// Game selectable on the command line.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum GameArg {
+    /// Genshin Impact.
+    Genshin,
+    /// Honkai: Star Rail.
+    Hsr,
+    /// Zenless Zone Zero.
+    Zzz,
+    /// Wuthering Waves.
+    Wuwa,
+}
+
+impl From<GameArg> for GameId {
+    fn from(game: GameArg) -> Self {
+        match game {
+            GameArg::Genshin => Self::GenshinImpact,
+            GameArg::Hsr => Self::HonkaiStarRail,
+            GameArg::Zzz => Self::ZenlessZoneZero,
+            GameArg::Wuwa => Self::WutheringWaves,
+        }
+    }
+}
+
+/// Restricts a command to some accounts.
+#[derive(Debug, Clone, Default, Args)]
+struct Selection {
+    /// Only include accounts of this game.
+    #[arg(long, value_enum)]
+    game: Option<GameArg>,
+
+    /// Only include the account with this name ("default" for the top-level
+    /// game table).
+    #[arg(long)]
+    account: Option<String>,
+}
+
+impl Selection {
+    /// Returns true if the account is selected.
+    fn matches(&self, key: &AccountKey) -> bool {
+        self.game
+            .is_none_or(|game| key.game_id == GameId::from(game))
+            && self
+                .account
+                .as_deref()
+                .is_none_or(|account| key.account == account)
+    }
+
+    /// Keeps the selected registry clients, ordered by game then account.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CliError::NoAccounts`] if nothing is selected.
+    fn select<'a, C: ?Sized>(
+        &self,
+        clients: impl Iterator<Item = (&'a AccountKey, &'a C)>,
+    ) -> Result<Vec<(&'a AccountKey, &'a C)>, CliError> {
+        let mut selected: Vec<_> = clients.filter(|(key, _)| self.matches(key)).collect();
+        if selected.is_empty() {
+            return Err(CliError::NoAccounts);
+        }
+        selected.sort_by_key(|(key, _)| *key);
+        Ok(selected)
+    }
+}
+
+/// Initializes logging to stderr so stdout only carries command output.
+///
+/// Uses `RUST_LOG` environment variable if set, otherwise defaults to "warn".
+fn init_tracing() {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("warn"));
+
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .init();
+}
+
+/// Loads the configuration and secrets, and initializes i18n from the
+/// configured language.
+///
+/// # Errors
+///
+/// Returns an error if either file cannot be read or parsed.
+fn load_config() -> Result<(AppConfig, SecretsConfig), CliError> {
+    let config = AppConfig::load()?;
+    let secrets = SecretsConfig::load()?;
+    i18n::init_from_config(config.general.language.as_deref());
+    Ok((config, secrets))
+}
+
+async fn run(cli: &Cli) -> Result<Exit, CliError> {
+    match &cli.command {
+        Command::Status(selection) => status::run(selection, cli.json).await,
+        Command::Claim(selection) => claim::run(selection, cli.json).await,
+        Command::CheckAuth(selection) => check_auth::run(selection, cli.json).await,
+        Command::Config(ConfigCommand::Validate) => validate::run(cli.json),
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+    init_tracing();
+
+    let exit = match run(&cli).await {
+        Ok(exit) => exit,
+        Err(e) => {
+            if cli.json {
+                if let Err(print_err) = output::print_json(&e.to_json()) {
+                    eprintln!("error: {print_err:#}");
+                }
+            } else {
+                eprintln!("error: {e:#}");
+            }
+            e.exit()
+        }
+    };
+    exit.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use storekeeper_core::DEFAULT_ACCOUNT;
+
+    #[test]
+    fn cli_definition_is_valid() {
+        use clap::CommandFactory;
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn empty_selection_matches_everything() {
+        let selection = Selection::default();
+        assert!(selection.matches(&AccountKey::default_for(GameId::WutheringWaves)));
+        assert!(selection.matches(&AccountKey::new(GameId::GenshinImpact, "alt")));
+    }
+
+    #[test]
+    fn selection_filters_by_game_and_account() {
+        let selection = Selection {
+            game: Some(GameArg::Genshin),
+            account: Some(DEFAULT_ACCOUNT.to_string()),
+        };
+        assert!(selection.matches(&AccountKey::default_for(GameId::GenshinImpact)));
+        assert!(!selection.matches(&AccountKey::new(GameId::GenshinImpact, "alt")));
+        assert!(!selection.matches(&AccountKey::default_for(GameId::HonkaiStarRail)));
+    }
+
+    #[test]
+    fn json_flag_is_global() {
+        let cli = Cli::try_parse_from(["storekeeper", "claim", "--game", "hsr", "--json"])
+            .expect("valid command line");
+        assert!(cli.json);
+        assert!(matches!(
+            cli.command,
+            Command::Claim(Selection {
+                game: Some(GameArg::Hsr),
+                account: None,
+            })
+        ));
+    }
+}
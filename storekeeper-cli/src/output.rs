@@ -0,0 +1,94 @@
+//! Plain-text tables and JSON output.
+
+use serde::Serialize;
+
+/// A plain-text table with left-aligned, space-padded columns.
+pub struct Table {
+    headers: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    /// Creates an empty table with the given column headers.
+    pub fn new(headers: &[&'static str]) -> Self {
+        Self {
+            headers: headers.to_vec(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Appends a row. Missing cells render empty; extra cells are ignored.
+    pub fn push(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    /// Renders the table, one line per row, without a trailing newline.
+    pub fn render(&self) -> String {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.chars().count()).collect();
+        for row in &self.rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.chars().count());
+            }
+        }
+
+        let header_cells: Vec<&str> = self.headers.clone();
+        std::iter::once(render_row(&widths, &header_cells))
+            .chain(self.rows.iter().map(|row| {
+                let cells: Vec<&str> = row.iter().map(String::as_str).collect();
+                render_row(&widths, &cells)
+            }))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn render_row(widths: &[usize], cells: &[&str]) -> String {
+    let mut line = widths
+        .iter()
+        .enumerate()
+        .map(|(i, &width)| {
+            let cell = cells.get(i).copied().unwrap_or_default();
+            format!("{cell:<width$}")
+        })
+        .collect::<Vec<_>>()
+        .join("  ");
+    line.truncate(line.trim_end().len());
+    line
+}
+
+/// Prints a value as pretty-printed JSON to stdout.
+///
+/// # Errors
+///
+/// Returns an error if the value cannot be serialized.
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns_are_padded_to_widest_cell() {
+        let mut table = Table::new(&["GAME", "STATUS"]);
+        table.push(vec!["Genshin Impact".to_string(), "ok".to_string()]);
+        table.push(vec!["HSR".to_string(), "auth failed".to_string()]);
+
+        assert_eq!(
+            table.render(),
+            "GAME            STATUS\n\
+             Genshin Impact  ok\n\
+             HSR             auth failed"
+        );
+    }
+
+    #[test]
+    fn missing_cells_render_empty() {
+        let mut table = Table::new(&["A", "B", "C"]);
+        table.push(vec!["x".to_string()]);
+
+        assert_eq!(table.render(), "A  B  C\nx");
+    }
+}
@@ -0,0 +1,151 @@
+//! `storekeeper status`: fetch resources and daily reward status.
+
+use crate::Selection;
+use crate::error::CliError;
+use crate::failure::Exit;
+use crate::failure::Failure;
+use crate::output;
+use crate::output::Table;
+use jiff::Timestamp;
+use serde::Serialize;
+use std::collections::HashMap;
+use storekeeper_core::AccountKey;
+use storekeeper_core::DailyRewardStatus;
+use storekeeper_runtime::challenge::Challenges;
+use storekeeper_runtime::clients::RateLimiters;
+use storekeeper_runtime::clients::create_daily_reward_registry;
+use storekeeper_runtime::clients::create_registry;
+use storekeeper_runtime::i18n;
+use storekeeper_runtime::notification::account_display_name;
+use storekeeper_runtime::notification::build_notification_body;
+use storekeeper_runtime::notification::extract_resource_info;
+use storekeeper_runtime::notification::resource_display_name;
+
+/// Status of one account, as printed by `--json`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AccountStatus {
+    #[serde(flatten)]
+    key: AccountKey,
+    /// Resources as returned by the game client; `null` if the fetch failed.
+    resources: Option<serde_json::Value>,
+    resources_error: Option<Failure>,
+    /// Daily reward status; `null` for games without daily rewards or if the
+    /// fetch failed.
+    daily_reward: Option<serde_json::Value>,
+    daily_reward_error: Option<Failure>,
+}
+
+#[derive(Debug, Serialize)]
+struct StatusOutput {
+    accounts: Vec<AccountStatus>,
+}
+
+/// Fetches every selected account sequentially and prints the result.
+///
+/// # Errors
+///
+/// Returns an error if the configuration cannot be loaded or no account is
+/// selected.
+pub async fn run(selection: &Selection, json: bool) -> Result<Exit, CliError> {
+    let (config, secrets) = crate::load_config()?;
+    let rate_limiters = RateLimiters::new(&config.rate_limits);
+    let challenges = Challenges::default();
+    let registry = create_registry(&config, &secrets, &rate_limiters, &challenges);
+    let daily_rewards =
+        create_daily_reward_registry(&config, &secrets, &rate_limiters, &challenges);
+    let reward_clients: HashMap<_, _> = daily_rewards.clients().collect();
+
+    let mut accounts = Vec::new();
+    for (key, client) in selection.select(registry.clients())? {
+        let (resources, resources_error) = match client.fetch_resources_json().await {
+            Ok(resources) => (Some(resources), None),
+            Err(e) => (None, Some(Failure::from_error(e.as_ref()))),
+        };
+        let (daily_reward, daily_reward_error) = match reward_clients.get(key) {
+            Some(reward_client) => match reward_client.get_reward_status_json().await {
+                Ok(status) => (Some(status), None),
+                Err(e) => (None, Some(Failure::from_error(e.as_ref()))),
+            },
+            None => (None, None),
+        };
+        accounts.push(AccountStatus {
+            key: key.clone(),
+            resources,
+            resources_error,
+            daily_reward,
+            daily_reward_error,
+        });
+    }
+
+    let exit = Exit::from_failures(
+        accounts
+            .iter()
+            .flat_map(|a| a.resources_error.iter().chain(a.daily_reward_error.iter())),
+    );
+
+    if json {
+        output::print_json(&StatusOutput { accounts })?;
+    } else {
+        println!("{}", render(&accounts, Timestamp::now()).render());
+    }
+    Ok(exit)
+}
+
+fn render(accounts: &[AccountStatus], now: Timestamp) -> Table {
+    let mut table = Table::new(&["ACCOUNT", "RESOURCE", "STATUS"]);
+    for status in accounts {
+        let mut rows = resource_rows(status, now);
+        if let Some(reward) = reward_row(status) {
+            rows.push(reward);
+        }
+        for (i, [resource, detail]) in rows.into_iter().enumerate() {
+            let account = if i == 0 {
+                account_display_name(&status.key)
+            } else {
+                String::new()
+            };
+            table.push(vec![account, resource, detail]);
+        }
+    }
+    table
+}
+
+fn resource_rows(status: &AccountStatus, now: Timestamp) -> Vec<[String; 2]> {
+    if let Some(failure) = &status.resources_error {
+        return vec![["-".to_string(), failure.to_string()]];
+    }
+    status
+        .resources
+        .as_ref()
+        .and_then(serde_json::Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|resource| {
+            let type_tag = resource.get("type")?.as_str()?;
+            let detail = resource
+                .get("data")
+                .and_then(|data| extract_resource_info(type_tag, data))
+                .map_or_else(
+                    || "-".to_string(),
+                    |info| build_notification_body(&info, now),
+                );
+            Some([resource_display_name(type_tag), detail])
+        })
+        .collect()
+}
+
+fn reward_row(status: &AccountStatus) -> Option<[String; 2]> {
+    let detail = if let Some(failure) = &status.daily_reward_error {
+        failure.to_string()
+    } else {
+        let reward: DailyRewardStatus =
+            serde_json::from_value(status.daily_reward.clone()?).ok()?;
+        if reward.info.is_signed {
+            i18n::t("daily_claimed")
+        } else {
+            i18n::t("daily_unclaimed")
+        }
+    };
+    Some(["Daily reward".to_string(), detail])
+}
@@ -0,0 +1,616 @@
+//! `storekeeper config validate`: check the configuration files.
+//!
+//! Beyond parsing, reports enabled accounts that the app would silently skip
+//! when building its clients (empty UID, undetectable region, missing
+//! credentials) and notification channels missing their URL.
+
+use crate::error::CliError;
+use crate::failure::Exit;
+use crate::output;
+use serde::Serialize;
+use std::collections::HashSet;
+use storekeeper_core::AccountView;
+use storekeeper_core::ApiProvider;
+use storekeeper_core::AppConfig;
+use storekeeper_core::DEFAULT_ACCOUNT;
+use storekeeper_core::GameId;
+use storekeeper_core::NotificationChannelConfig;
+use storekeeper_core::NotificationChannelSecrets;
+use storekeeper_core::Region;
+use storekeeper_core::SecretsConfig;
+use storekeeper_core::config::secrets::KuroSecrets;
+
+/// How serious a configuration issue is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Severity {
+    /// The affected account will not be tracked.
+    Error,
+    /// Works, but may not behave as intended.
+    Warning,
+}
+
+/// A single configuration problem.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+struct Issue {
+    severity: Severity,
+    /// File or TOML table the issue refers to (e.g. `games.genshin_impact`).
+    location: String,
+    message: String,
+}
+
+impl Issue {
+    fn error(location: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            location: location.into(),
+            message: message.into(),
+        }
+    }
+
+    fn warning(location: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            location: location.into(),
+            message: message.into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ValidateOutput {
+    valid: bool,
+    issues: Vec<Issue>,
+}
+
+/// Validates `config.toml` and `secrets.toml` and prints the issues found.
+///
+/// Exits with the config status if any error-level issue was found; warnings
+/// alone do not fail validation.
+///
+/// # Errors
+///
+/// Returns an error if the output cannot be written.
+pub fn run(json: bool) -> Result<Exit, CliError> {
+    let issues = match (AppConfig::load(), SecretsConfig::load()) {
+        (Ok(config), Ok(secrets)) => check(&config, &secrets),
+        (config, secrets) => [
+            config
+                .err()
+                .map(|e| Issue::error("config.toml", e.to_string())),
+            secrets
+                .err()
+                .map(|e| Issue::error("secrets.toml", e.to_string())),
+        ]
+        .into_iter()
+        .flatten()
+        .collect(),
+    };
+    let valid = issues.iter().all(|i| i.severity != Severity::Error);
+
+    if json {
+        output::print_json(&ValidateOutput { valid, issues })?;
+    } else if issues.is_empty() {
+        println!("Configuration is valid");
+    } else {
+        for issue in &issues {
+            let severity = match issue.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+            };
+            println!("{severity}: {}: {}", issue.location, issue.message);
+        }
+    }
+
+    Ok(if valid { Exit::Success } else { Exit::Config })
+}
+
+/// Checks every enabled account for problems that would keep it from being
+/// tracked.
+fn check(config: &AppConfig, secrets: &SecretsConfig) -> Vec<Issue> {
+    let accounts: Vec<_> = config
+        .games
+        .all_accounts()
+        .into_iter()
+        .filter(|account| account.enabled)
+        .collect();
+    let mut issues = if accounts.is_empty() {
+        vec![Issue::warning("config.toml", "no games are enabled")]
+    } else {
+        accounts
+            .iter()
+            .flat_map(|account| {
+                let mut issues = check_account(account, secrets);
+                issues.extend(check_notification_rules(config, account));
+                issues
+            })
+            .collect()
+    };
+    issues.extend(check_notification_channels(config, secrets));
+    issues.extend(check_mqtt(config, secrets));
+    issues
+}
+
+/// Reports MQTT settings that would keep the client from connecting or
+/// publishing where expected.
+fn check_mqtt(config: &AppConfig, secrets: &SecretsConfig) -> Vec<Issue> {
+    let mqtt = &config.mqtt;
+    if !mqtt.enabled {
+        return Vec::new();
+    }
+    let mut issues = Vec::new();
+    if mqtt.host.trim().is_empty() {
+        issues.push(Issue::error("mqtt", "host must be set"));
+    }
+    for (field, topic) in [
+        ("base_topic", &mqtt.base_topic),
+        ("discovery_prefix", &mqtt.discovery_prefix),
+    ] {
+        if topic.is_empty() || topic.contains(['+', '#']) {
+            issues.push(Issue::error(
+                "mqtt",
+                format!("{field} must be a non-empty topic without wildcards"),
+            ));
+        }
+    }
+    if secrets.mqtt.login().is_none() && !secrets.mqtt.password.is_empty() {
+        issues.push(Issue::warning(
+            "secrets.toml",
+            "[mqtt] password is ignored without a username",
+        ));
+    }
+    issues
+}
+
+/// Reports configured notification channels that would be skipped or
+/// misconfigured.
+fn check_notification_channels(config: &AppConfig, secrets: &SecretsConfig) -> Vec<Issue> {
+    let mut issues = Vec::new();
+    for (name, channel) in &config.notification_channels {
+        let channel_secrets = secrets.notification_channels.get(name);
+        let missing_secret = match channel {
+            NotificationChannelConfig::Webhook(_) => channel_secrets
+                .and_then(NotificationChannelSecrets::url)
+                .is_none()
+                .then_some("url"),
+            NotificationChannelConfig::Gotify(_) => channel_secrets
+                .and_then(NotificationChannelSecrets::token)
+                .is_none()
+                .then_some("token"),
+            NotificationChannelConfig::Email(email) => {
+                let location = format!("notification_channels.{name}");
+                if email.to.is_empty() {
+                    issues.push(Issue::error(
+                        &location,
+                        "to must list at least one recipient",
+                    ));
+                }
+                if !email.alerts && email.digest_time.is_none() {
+                    issues.push(Issue::warning(
+                        &location,
+                        "alerts are disabled and no digest_time is set, so nothing is sent",
+                    ));
+                }
+                None
+            }
+            NotificationChannelConfig::Ntfy(ntfy) => {
+                issues.extend(
+                    ntfy.tags
+                        .keys()
+                        .filter(|key| !GameId::all().iter().any(|game| game.config_key() == *key))
+                        .map(|key| {
+                            Issue::warning(
+                                format!("notification_channels.{name}"),
+                                format!("tags for unknown game \"{key}\" are never used"),
+                            )
+                        }),
+                );
+                None
+            }
+            NotificationChannelConfig::Hook(hook) => {
+                let location = format!("notification_channels.{name}");
+                if hook.command.trim().is_empty() {
+                    issues.push(Issue::error(&location, "command is empty"));
+                }
+                if hook.events.is_empty() {
+                    issues.push(Issue::warning(
+                        &location,
+                        "events is empty, so the hook never runs",
+                    ));
+                }
+                None
+            }
+        };
+        if let Some(secret) = missing_secret {
+            issues.push(Issue::error(
+                "secrets.toml",
+                format!("{secret} must be set in [notification_channels.{name}]"),
+            ));
+        }
+    }
+    issues
+}
+
+fn check_account(account: &AccountView<'_>, secrets: &SecretsConfig) -> Vec<Issue> {
+    let location = account_location(account);
+    let mut issues = Vec::new();
+
+    if account.uid.is_empty() {
+        issues.push(Issue::error(&location, "uid is empty"));
+    } else if account.region.is_none() && detect_region(account.game_id, account.uid).is_err() {
+        issues.push(Issue::error(
+            &location,
+            format!(
+                "cannot detect the region from uid \"{}\"; set region explicitly",
+                account.uid
+            ),
+        ));
+    }
+
+    issues.extend(check_credentials(account, secrets));
+    issues
+}
+
+/// Reports notification rules of an account that share an id, and so a
+/// cooldown.
+fn check_notification_rules(config: &AppConfig, account: &AccountView<'_>) -> Vec<Issue> {
+    let location = account_location(account);
+    let notifications = config.games.notification_configs(&account.key());
+    let mut resources: Vec<_> = notifications.iter().collect();
+    resources.sort_by_key(|(resource, _)| resource.as_str());
+
+    let mut issues = Vec::new();
+    for (resource, notification) in resources {
+        let mut seen = HashSet::new();
+        for rule in &notification.rules {
+            if !seen.insert(rule.id.as_str()) {
+                issues.push(Issue::error(
+                    format!("{location}.notifications.{resource}"),
+                    format!("rule id \"{}\" is used more than once", rule.id),
+                ));
+            }
+        }
+    }
+    issues
+}
+
+fn check_credentials(account: &AccountView<'_>, secrets: &SecretsConfig) -> Option<Issue> {
+    let credentials = account.credentials;
+    let is_default = credentials == DEFAULT_ACCOUNT;
+    match account.game_id.api_provider() {
+        ApiProvider::HoYoLab => {
+            let table = secrets_table("hoyolab", credentials);
+            match secrets.hoyolab_login(credentials) {
+                None => Some(Issue::error(
+                    "secrets.toml",
+                    format!("{table} is missing (used by {})", account_location(account)),
+                )),
+                Some(login) if !login.is_configured() => Some(Issue::error(
+                    "secrets.toml",
+                    format!("ltuid_v2 and ltoken_v2 must be set in {table}"),
+                )),
+                Some(_) => None,
+            }
+        }
+        ApiProvider::Kuro => {
+            let has_override = secrets
+                .kuro_login(credentials)
+                .is_some_and(KuroSecrets::has_override);
+            if has_override {
+                None
+            } else if is_default {
+                Some(Issue::warning(
+                    "secrets.toml",
+                    "no oauth_code in [kuro]; relying on the Kuro launcher cache",
+                ))
+            } else {
+                Some(Issue::error(
+                    "secrets.toml",
+                    format!(
+                        "oauth_code must be set in {}",
+                        secrets_table("kuro", credentials)
+                    ),
+                ))
+            }
+        }
+    }
+}
+
+fn detect_region(game_id: GameId, uid: &str) -> storekeeper_core::Result<Region> {
+    match game_id {
+        GameId::GenshinImpact => Region::from_genshin_uid(uid),
+        GameId::HonkaiStarRail => Region::from_hsr_uid(uid),
+        GameId::ZenlessZoneZero => Region::from_zzz_uid(uid),
+        GameId::WutheringWaves => Region::from_wuwa_uid(uid),
+    }
+}
+
+/// Returns the `config.toml` table an account is defined in.
+fn account_location(account: &AccountView<'_>) -> String {
+    let section = account.game_id.config_key();
+    if account.name == DEFAULT_ACCOUNT {
+        format!("games.{section}")
+    } else {
+        format!("games.{section}.accounts.{}", account.name)
+    }
+}
+
+/// Returns the `secrets.toml` table holding a login (e.g. `[hoyolab]`).
+fn secrets_table(provider: &str, credentials: &str) -> String {
+    if credentials == DEFAULT_ACCOUNT {
+        format!("[{provider}]")
+    } else {
+        format!("[{provider}_accounts.{credentials}]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(config: &str, secrets: &str) -> (AppConfig, SecretsConfig) {
+        (
+            toml::from_str(config).expect("valid config"),
+            toml::from_str(secrets).expect("valid secrets"),
+        )
+    }
+
+    #[test]
+    fn no_enabled_games_is_a_warning() {
+        let (config, secrets) = parse("", "");
+        assert_eq!(
+            check(&config, &secrets),
+            vec![Issue::warning("config.toml", "no games are enabled")]
+        );
+    }
+
+    #[test]
+    fn configured_hoyolab_account_has_no_issues() {
+        let (config, secrets) = parse(
+            r#"
+            [games.genshin_impact]
+            enabled = true
+            uid = "800000001"
+            "#,
+            r#"
+            [hoyolab]
+            ltuid_v2 = "1"
+            ltoken_v2 = "token"
+            "#,
+        );
+        assert!(check(&config, &secrets).is_empty());
+    }
+
+    #[test]
+    fn duplicate_notification_rule_ids_are_errors() {
+        let (config, secrets) = parse(
+            r#"
+            [games.genshin_impact]
+            enabled = true
+            uid = "800000001"
+
+            [[games.genshin_impact.notifications.resin.rules]]
+            id = "soon"
+            notify_minutes_before_full = 60
+
+            [[games.genshin_impact.notifications.resin.rules]]
+            id = "soon"
+            notify_at_value = 120
+            "#,
+            r#"
+            [hoyolab]
+            ltuid_v2 = "1"
+            ltoken_v2 = "token"
+            "#,
+        );
+        assert_eq!(
+            check(&config, &secrets),
+            vec![Issue::error(
+                "games.genshin_impact.notifications.resin",
+                "rule id \"soon\" is used more than once"
+            )]
+        );
+    }
+
+    #[test]
+    fn empty_uid_and_missing_cookie_are_errors() {
+        let (config, secrets) = parse(
+            r#"
+            [games.honkai_star_rail]
+            enabled = true
+            uid = ""
+            "#,
+            "",
+        );
+        assert_eq!(
+            check(&config, &secrets),
+            vec![
+                Issue::error("games.honkai_star_rail", "uid is empty"),
+                Issue::error(
+                    "secrets.toml",
+                    "ltuid_v2 and ltoken_v2 must be set in [hoyolab]"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn named_account_requires_its_login() {
+        let (config, secrets) = parse(
+            r#"
+            [games.genshin_impact]
+            enabled = true
+            uid = "800000001"
+
+            [games.genshin_impact.accounts.alt]
+            uid = "800000002"
+            "#,
+            r#"
+            [hoyolab]
+            ltuid_v2 = "1"
+            ltoken_v2 = "token"
+            "#,
+        );
+        assert_eq!(
+            check(&config, &secrets),
+            vec![Issue::error(
+                "secrets.toml",
+                "[hoyolab_accounts.alt] is missing (used by games.genshin_impact.accounts.alt)"
+            )]
+        );
+    }
+
+    #[test]
+    fn notification_channel_without_url_is_an_error() {
+        let (config, secrets) = parse(
+            r#"
+            [notification_channels.phone]
+            type = "webhook"
+            preset = "discord"
+            "#,
+            "",
+        );
+        assert_eq!(
+            check(&config, &secrets),
+            vec![
+                Issue::warning("config.toml", "no games are enabled"),
+                Issue::error(
+                    "secrets.toml",
+                    "url must be set in [notification_channels.phone]"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn push_channels_check_token_and_tags() {
+        let (config, secrets) = parse(
+            r#"
+            [notification_channels.gotify]
+            type = "gotify"
+            server = "https://gotify.example.com"
+
+            [notification_channels.ntfy]
+            type = "ntfy"
+            topic = "storekeeper"
+            tags = { genshin_impact = ["crystal_ball"], genshin = ["typo"] }
+            "#,
+            "",
+        );
+        assert_eq!(
+            check(&config, &secrets),
+            vec![
+                Issue::warning("config.toml", "no games are enabled"),
+                Issue::error(
+                    "secrets.toml",
+                    "token must be set in [notification_channels.gotify]"
+                ),
+                Issue::warning(
+                    "notification_channels.ntfy",
+                    "tags for unknown game \"genshin\" are never used"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn email_channel_needs_recipients() {
+        let (config, secrets) = parse(
+            r#"
+            [notification_channels.email]
+            type = "email"
+            host = "smtp.example.com"
+            from = "storekeeper@example.com"
+            to = []
+            alerts = false
+            "#,
+            "",
+        );
+        assert_eq!(
+            check(&config, &secrets),
+            vec![
+                Issue::warning("config.toml", "no games are enabled"),
+                Issue::error(
+                    "notification_channels.email",
+                    "to must list at least one recipient"
+                ),
+                Issue::warning(
+                    "notification_channels.email",
+                    "alerts are disabled and no digest_time is set, so nothing is sent"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn hook_channel_needs_command_and_events() {
+        let (config, secrets) = parse(
+            r#"
+            [notification_channels.script]
+            type = "hook"
+            command = ""
+            events = []
+            "#,
+            "",
+        );
+        assert_eq!(
+            check(&config, &secrets),
+            vec![
+                Issue::warning("config.toml", "no games are enabled"),
+                Issue::error("notification_channels.script", "command is empty"),
+                Issue::warning(
+                    "notification_channels.script",
+                    "events is empty, so the hook never runs"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn mqtt_topics_and_login_are_checked() {
+        let (config, secrets) = parse(
+            r#"
+            [mqtt]
+            enabled = true
+            base_topic = "home/#"
+            "#,
+            r#"
+            [mqtt]
+            password = "hunter2"
+            "#,
+        );
+        assert_eq!(
+            check(&config, &secrets),
+            vec![
+                Issue::warning("config.toml", "no games are enabled"),
+                Issue::error(
+                    "mqtt",
+                    "base_topic must be a non-empty topic without wildcards"
+                ),
+                Issue::warning(
+                    "secrets.toml",
+                    "[mqtt] password is ignored without a username"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn default_kuro_login_may_use_launcher_cache() {
+        let (config, secrets) = parse(
+            r#"
+            [games.wuthering_waves]
+            enabled = true
+            uid = "500000001"
+            "#,
+            "",
+        );
+        let issues = check(&config, &secrets);
+        assert_eq!(issues.len(), 1);
+        assert!(
+            issues
+                .iter()
+                .all(|issue| issue.severity == Severity::Warning)
+        );
+    }
+}
@@ -1,7 +1,94 @@
 //! Base error types for API clients.
 
+use serde::Deserialize;
+use serde::Serialize;
 use thiserror::Error;
 
+/// Category of a failed API operation, shared by every API provider.
+///
+/// Each client maps its provider's error codes onto these, so callers can
+/// decide whether to retry, notify or prompt the user without knowing the
+/// provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// The API could not be reached.
+    Network,
+    /// The API did not answer in time.
+    Timeout,
+    /// Credentials are missing, invalid or expired.
+    Auth,
+    /// The API asked us to back off.
+    RateLimited,
+    /// The API wants a captcha solved before answering.
+    Captcha,
+    /// The API is down for maintenance.
+    Maintenance,
+    /// The account has no role on the requested game or server.
+    NotFound,
+    /// The API answered with something we don't understand.
+    Protocol,
+    /// Anything else, such as invalid configuration.
+    Other,
+}
+
+impl ErrorKind {
+    /// Returns the kind of a failed HTTP response, if its status has a known
+    /// meaning.
+    #[must_use]
+    pub const fn from_http_status(status: u16) -> Option<Self> {
+        match status {
+            401 | 403 => Some(Self::Auth),
+            404 => Some(Self::NotFound),
+            408 | 504 => Some(Self::Timeout),
+            429 => Some(Self::RateLimited),
+            503 => Some(Self::Maintenance),
+            500..=599 => Some(Self::Network),
+            _ => None,
+        }
+    }
+
+    /// Returns the kind of a failed `reqwest` request.
+    #[must_use]
+    pub fn from_reqwest(error: &reqwest::Error) -> Self {
+        if error.is_timeout() {
+            Self::Timeout
+        } else if error.is_decode() {
+            Self::Protocol
+        } else if let Some(kind) = error
+            .status()
+            .and_then(|status| Self::from_http_status(status.as_u16()))
+        {
+            kind
+        } else {
+            Self::Network
+        }
+    }
+
+    /// Returns whether an operation failing this way is worth retrying
+    /// shortly.
+    #[must_use]
+    pub const fn is_transient(self) -> bool {
+        matches!(self, Self::Network | Self::Timeout)
+    }
+
+    /// Returns the kind's `snake_case` name, as used in metrics labels.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Network => "network",
+            Self::Timeout => "timeout",
+            Self::Auth => "auth",
+            Self::RateLimited => "rate_limited",
+            Self::Captcha => "captcha",
+            Self::Maintenance => "maintenance",
+            Self::NotFound => "not_found",
+            Self::Protocol => "protocol",
+            Self::Other => "other",
+        }
+    }
+}
+
 /// Base error type shared by all API clients.
 ///
 /// Client implementations should create their own error enum that wraps
@@ -64,6 +151,26 @@ impl ClientError {
             _ => None,
         }
     }
+
+    /// Returns the error's kind.
+    ///
+    /// API error codes are read as HTTP statuses; clients with their own
+    /// code tables consult those first.
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::HttpRequest(e) => ErrorKind::from_reqwest(e),
+            Self::Deserialize(_) => ErrorKind::Protocol,
+            // Middleware errors (retries exhausted) are reported with code 0.
+            Self::ApiError { code: 0, .. } => ErrorKind::Network,
+            Self::ApiError { code, .. } => u16::try_from(*code)
+                .ok()
+                .and_then(ErrorKind::from_http_status)
+                .unwrap_or(ErrorKind::Protocol),
+            Self::AuthenticationFailed(_) => ErrorKind::Auth,
+            Self::InvalidConfig(_) => ErrorKind::Other,
+        }
+    }
 }
 
 impl From<reqwest_middleware::Error> for ClientError {
@@ -77,3 +184,51 @@ impl From<reqwest_middleware::Error> for ClientError {
 
 /// Result type alias using the base `ClientError` type.
 pub type Result<T> = std::result::Result<T, ClientError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn api_error_codes_read_as_http_statuses() {
+        assert_eq!(
+            ClientError::api_error(429, "").kind(),
+            ErrorKind::RateLimited
+        );
+        assert_eq!(
+            ClientError::api_error(503, "").kind(),
+            ErrorKind::Maintenance
+        );
+        assert_eq!(ClientError::api_error(502, "").kind(), ErrorKind::Network);
+        assert_eq!(
+            ClientError::api_error(0, "timed out").kind(),
+            ErrorKind::Network
+        );
+        assert_eq!(
+            ClientError::api_error(-1002, "").kind(),
+            ErrorKind::Protocol
+        );
+    }
+
+    #[test]
+    fn only_network_failures_are_transient() {
+        assert!(ErrorKind::Network.is_transient());
+        assert!(ErrorKind::Timeout.is_transient());
+        assert!(!ErrorKind::RateLimited.is_transient());
+        assert!(!ErrorKind::Auth.is_transient());
+    }
+
+    #[test]
+    fn kind_serializes_as_snake_case_name() {
+        for kind in [
+            ErrorKind::RateLimited,
+            ErrorKind::NotFound,
+            ErrorKind::Captcha,
+        ] {
+            assert_eq!(
+                serde_json::to_value(kind).expect("should serialize"),
+                serde_json::json!(kind.as_str())
+            );
+        }
+    }
+}
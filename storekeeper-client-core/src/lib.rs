@@ -3,10 +3,12 @@
 //! This crate provides common types and utilities for building game API
 //! clients, including:
 //!
-//! - Base error types that can be extended by specific clients
+//! - Base error types that can be extended by specific clients, and the error
+//!   kinds every client maps its errors onto
 //! - API response traits for handling different response formats
 //! - HTTP client builder with common configuration options
 //! - Retry utilities with exponential backoff and jitter
+//! - Token-bucket rate limiting shared by the clients of one API provider
 //!
 //! # Example
 //!
@@ -25,13 +27,17 @@
 
 pub mod client;
 pub mod error;
+pub mod rate_limit;
 pub mod response;
 pub mod retry;
 
 pub use client::DEFAULT_USER_AGENT;
 pub use client::HttpClientBuilder;
 pub use error::ClientError;
+pub use error::ErrorKind;
 pub use error::Result;
+pub use rate_limit::RateLimit;
+pub use rate_limit::RateLimiter;
 pub use reqwest_middleware::ClientWithMiddleware;
 pub use response::ApiResponse;
 pub use response::HoyolabApiResponse;
@@ -0,0 +1,131 @@
+//! Token-bucket rate limiting shared by the clients of one API provider.
+
+use governor::DefaultDirectRateLimiter;
+use governor::Quota;
+use std::num::NonZeroU32;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Request rate allowed against one API provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    /// Requests allowed per minute on average.
+    pub requests_per_minute: u32,
+    /// Requests allowed back to back before the rate applies.
+    pub burst: u32,
+    /// How long every request waits after the provider reports a rate limit.
+    pub cooldown: Duration,
+}
+
+impl RateLimit {
+    /// Creates a rate limit; zero rates are raised to one request.
+    #[must_use = "this returns a new RateLimit and does not modify self"]
+    pub const fn new(requests_per_minute: u32, burst: u32, cooldown: Duration) -> Self {
+        Self {
+            requests_per_minute,
+            burst,
+            cooldown,
+        }
+    }
+
+    fn quota(&self) -> Quota {
+        let per_minute = NonZeroU32::new(self.requests_per_minute).unwrap_or(NonZeroU32::MIN);
+        let burst = NonZeroU32::new(self.burst).unwrap_or(NonZeroU32::MIN);
+        Quota::per_minute(per_minute).allow_burst(burst)
+    }
+}
+
+/// Token-bucket rate limiter.
+///
+/// Clones share one bucket, so every client handed a clone draws from the
+/// same budget, whichever account it belongs to.
+#[derive(Clone)]
+pub struct RateLimiter {
+    limit: RateLimit,
+    bucket: Arc<DefaultDirectRateLimiter>,
+    paused_until: Arc<Mutex<Option<Instant>>>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter with a fresh bucket.
+    #[must_use = "this returns a new RateLimiter"]
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            bucket: Arc::new(DefaultDirectRateLimiter::direct(limit.quota())),
+            paused_until: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Waits until a request may be sent: past any cooldown, then until the
+    /// bucket has a token.
+    pub async fn acquire(&self) {
+        let paused_until = *self.lock_paused_until();
+        if let Some(until) = paused_until {
+            tokio::time::sleep_until(until).await;
+        }
+        self.bucket.until_ready().await;
+    }
+
+    /// Returns how long [`Self::cool_down`] holds back requests.
+    #[must_use]
+    pub fn cooldown(&self) -> Duration {
+        self.limit.cooldown
+    }
+
+    /// Holds back every request for the configured cooldown, after the
+    /// provider reported that it is being called too often.
+    pub fn cool_down(&self) {
+        let until = Instant::now() + self.limit.cooldown;
+        let mut paused_until = self.lock_paused_until();
+        if paused_until.is_none_or(|current| current < until) {
+            *paused_until = Some(until);
+        }
+    }
+
+    fn lock_paused_until(&self) -> MutexGuard<'_, Option<Instant>> {
+        self.paused_until
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl std::fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("limit", &self.limit)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn burst_is_spent_before_waiting() {
+        let limiter = RateLimiter::new(RateLimit::new(1, 2, Duration::ZERO));
+
+        limiter.acquire().await;
+        limiter.clone().acquire().await;
+
+        let third = tokio::time::timeout(Duration::from_millis(50), limiter.acquire()).await;
+        assert!(third.is_err(), "third request should wait for a token");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn cooldown_holds_back_every_clone() {
+        let limiter = RateLimiter::new(RateLimit::new(60, 5, Duration::from_secs(30)));
+        let other = limiter.clone();
+        let started = Instant::now();
+
+        limiter.cool_down();
+        other.acquire().await;
+
+        assert!(started.elapsed() >= Duration::from_secs(30));
+    }
+}
@@ -0,0 +1,158 @@
+//! Geetest challenges raised by HoYoLab's risk control.
+//!
+//! When HoYoLab suspects automation, it refuses a request until a Geetest
+//! captcha is solved. The client then fails with
+//! [`Error::ChallengeRequired`](crate::Error::ChallengeRequired), carrying the
+//! challenge's parameters. Once a user has solved it in a browser, the solution
+//! goes to [`Challenges::solve`] and the next attempt of the refused request is
+//! sent with it.
+
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+
+/// Retcodes HoYoLab refuses a request with until a Geetest captcha is solved.
+pub const CHALLENGE_RETCODES: [i32; 2] = [1034, 10035];
+
+/// Parameters of a Geetest challenge, as the Geetest widget takes them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GeetestChallenge {
+    /// Geetest captcha ID.
+    pub gt: String,
+    /// Challenge to solve.
+    pub challenge: String,
+}
+
+/// Solution to a [`GeetestChallenge`], as the Geetest widget returns it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GeetestSolution {
+    /// The solved challenge.
+    pub challenge: String,
+    /// Validation token of the solution.
+    pub validate: String,
+}
+
+impl GeetestSolution {
+    /// Returns the security code HoYoLab expects along with the solution.
+    #[must_use]
+    pub fn seccode(&self) -> String {
+        format!("{}|jordan", self.validate)
+    }
+
+    /// Returns the headers that send a request along with the solution.
+    pub(crate) fn headers(&self) -> [(&'static str, String); 3] {
+        [
+            ("x-rpc-challenge", self.challenge.clone()),
+            ("x-rpc-validate", self.validate.clone()),
+            ("x-rpc-seccode", self.seccode()),
+        ]
+    }
+}
+
+/// How a solution is handed back to HoYoLab.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Replay {
+    /// Sent along with the refused request only.
+    Headers,
+    /// Verified with HoYoLab first, then sent along with the refused request.
+    Verify,
+}
+
+/// A request HoYoLab refused: the cookie it was sent with and its URL.
+pub(crate) type RequestKey = (String, String);
+
+/// Geetest challenges awaiting a solution, and solutions awaiting the retry
+/// of the request they unblock.
+///
+/// Clones share their state, so one instance can be handed to every HoYoLab
+/// client and solutions accepted from anywhere.
+#[derive(Debug, Clone, Default)]
+pub struct Challenges {
+    inner: Arc<Mutex<ChallengesInner>>,
+}
+
+#[derive(Debug, Default)]
+struct ChallengesInner {
+    /// Refused requests by the ID of the challenge they raised.
+    raised: HashMap<String, (RequestKey, Replay)>,
+    /// Solutions by the request they unblock.
+    solved: HashMap<RequestKey, (GeetestSolution, Replay)>,
+}
+
+impl Challenges {
+    /// Records that `request` was refused until `challenge` is solved.
+    pub(crate) fn raise(&self, request: RequestKey, challenge: &GeetestChallenge, replay: Replay) {
+        self.lock()
+            .raised
+            .insert(challenge.challenge.clone(), (request, replay));
+    }
+
+    /// Stores the solution to a raised challenge, to be sent with the next
+    /// attempt of the request that raised it.
+    ///
+    /// Returns `false` if no request raised the solved challenge.
+    #[must_use]
+    pub fn solve(&self, solution: GeetestSolution) -> bool {
+        let mut inner = self.lock();
+        let Some((request, replay)) = inner.raised.remove(&solution.challenge) else {
+            return false;
+        };
+        inner.solved.insert(request, (solution, replay));
+        true
+    }
+
+    /// Removes and returns the solution unblocking `request`, if any.
+    pub(crate) fn take_solution(&self, request: &RequestKey) -> Option<(GeetestSolution, Replay)> {
+        self.lock().solved.remove(request)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ChallengesInner> {
+        self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn challenge(id: &str) -> GeetestChallenge {
+        GeetestChallenge {
+            gt: "gt".to_string(),
+            challenge: id.to_string(),
+        }
+    }
+
+    fn solution(id: &str) -> GeetestSolution {
+        GeetestSolution {
+            challenge: id.to_string(),
+            validate: "validate".to_string(),
+        }
+    }
+
+    #[test]
+    fn solution_unblocks_the_request_that_raised_it_once() {
+        let challenges = Challenges::default();
+        let request = ("cookie".to_string(), "https://example.com/sign".to_string());
+        challenges.raise(request.clone(), &challenge("c1"), Replay::Headers);
+
+        assert!(!challenges.solve(solution("unknown")));
+        assert!(challenges.clone().solve(solution("c1")));
+
+        let other = ("other".to_string(), request.1.clone());
+        assert_eq!(challenges.take_solution(&other), None);
+        assert_eq!(
+            challenges.take_solution(&request),
+            Some((solution("c1"), Replay::Headers))
+        );
+        assert_eq!(challenges.take_solution(&request), None);
+    }
+
+    #[test]
+    fn seccode_is_validate_with_suffix() {
+        assert_eq!(solution("c1").seccode(), "validate|jordan");
+    }
+}
@@ -1,29 +1,52 @@
 //! HoYoLab HTTP client implementation.
 
+use crate::challenge::CHALLENGE_RETCODES;
+use crate::challenge::Challenges;
+use crate::challenge::GeetestChallenge;
+use crate::challenge::GeetestSolution;
+use crate::challenge::Replay;
+use crate::challenge::RequestKey;
+use crate::ds::canonical_query;
+use crate::ds::generate_dynamic_secret_chinese;
 use crate::ds::generate_dynamic_secret_overseas;
 use crate::error::Error;
 use crate::error::Result;
+use crate::platform::Platform;
 use reqwest::Method;
+use reqwest::StatusCode;
+use reqwest::header::CONTENT_TYPE;
 use reqwest::header::COOKIE;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
+use std::time::Duration;
 use storekeeper_client_core::ApiResponse;
 use storekeeper_client_core::ClientError;
 use storekeeper_client_core::ClientWithMiddleware;
+use storekeeper_client_core::ErrorKind;
 use storekeeper_client_core::HoyolabApiResponse;
 use storekeeper_client_core::HttpClientBuilder;
+use storekeeper_client_core::RateLimit;
+use storekeeper_client_core::RateLimiter;
+use storekeeper_client_core::RetryConfig;
 use storekeeper_client_core::retry::DEFAULT_MAX_RETRIES;
 
 /// HoYoLab API client.
 #[derive(Debug, Clone)]
 pub struct HoyolabClient {
     client: ClientWithMiddleware,
+    platform: Platform,
     cookie: String,
     auth_check_url: String,
+    verification_url: String,
+    rate_limiter: RateLimiter,
+    challenges: Challenges,
 }
 
-const HOYOLAB_AUTH_CHECK_URL: &str =
-    "https://bbs-api-os.hoyolab.com/community/user/wapi/getUserFullInfo";
+/// Rate limit of a client not given a shared limiter.
+const DEFAULT_RATE_LIMIT: RateLimit = RateLimit::new(30, 5, Duration::from_secs(30));
+
+/// Retcode HoYoLab answers with when it is called too often.
+const VISITS_TOO_FREQUENTLY_RETCODE: i32 = -110;
 
 impl HoyolabClient {
     /// Creates a new HoYoLab client with the given credentials.
@@ -32,7 +55,35 @@ impl HoyolabClient {
     ///
     /// Returns an error if the HTTP client cannot be created.
     pub fn new(ltuid: impl Into<String>, ltoken: impl Into<String>) -> Result<Self> {
-        Self::with_auth_check_url(ltuid, ltoken, HOYOLAB_AUTH_CHECK_URL)
+        Self::with_auth_check_url(ltuid, ltoken, Platform::Hoyolab.auth_check_url())
+    }
+
+    /// Creates a new client for Miyoushe, the platform serving mainland
+    /// China servers, with the given credentials.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be created.
+    #[expect(
+        clippy::similar_names,
+        reason = "parameters are named after the cookies they fill"
+    )]
+    pub fn miyoushe(
+        ltuid: impl Into<String>,
+        ltoken: impl Into<String>,
+        ltmid: impl Into<String>,
+    ) -> Result<Self> {
+        let cookie = format!(
+            "ltuid_v2={}; ltoken_v2={}; ltmid_v2={}",
+            ltuid.into(),
+            ltoken.into(),
+            ltmid.into()
+        );
+        Self::build(
+            Platform::Miyoushe,
+            cookie,
+            Platform::Miyoushe.auth_check_url(),
+        )
     }
 
     /// Creates a new HoYoLab client with a custom auth-check URL.
@@ -46,25 +97,81 @@ impl HoyolabClient {
         ltuid: impl Into<String>,
         ltoken: impl Into<String>,
         auth_check_url: impl Into<String>,
+    ) -> Result<Self> {
+        let ltuid = ltuid.into();
+        let ltoken = ltoken.into();
+        let cookie = format!("ltuid_v2={ltuid}; ltoken_v2={ltoken}");
+        Self::build(Platform::Hoyolab, cookie, auth_check_url)
+    }
+
+    /// Creates a client for `platform` that logs in with `cookie`.
+    fn build(
+        platform: Platform,
+        cookie: String,
+        auth_check_url: impl Into<String>,
     ) -> Result<Self> {
         let client = HttpClientBuilder::new()
-            .header_static("x-rpc-app_version", "1.5.0")
+            .header_static("x-rpc-app_version", platform.app_version())
             .header_static("x-rpc-client_type", "5")
-            .header_static("x-rpc-language", "en-us")
+            .header_static("x-rpc-language", platform.language())
             .build_with_retry(DEFAULT_MAX_RETRIES)
             .map_err(Error::Client)?;
 
-        let ltuid = ltuid.into();
-        let ltoken = ltoken.into();
-        let cookie = format!("ltuid_v2={ltuid}; ltoken_v2={ltoken}");
-
         Ok(Self {
             client,
+            platform,
             cookie,
             auth_check_url: auth_check_url.into(),
+            verification_url: platform.verification_url().to_string(),
+            rate_limiter: RateLimiter::new(DEFAULT_RATE_LIMIT),
+            challenges: Challenges::default(),
         })
     }
 
+    /// Returns the platform the client sends its requests to.
+    #[must_use]
+    pub const fn platform(&self) -> Platform {
+        self.platform
+    }
+
+    /// Replaces the client's own rate limiter, typically with one shared by
+    /// every HoYoLab client so that several accounts draw from one budget.
+    #[must_use]
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// Replaces the client's own challenge store, typically with one shared
+    /// by every HoYoLab client so that solutions can be handed in from one
+    /// place.
+    #[must_use]
+    pub fn with_challenges(mut self, challenges: Challenges) -> Self {
+        self.challenges = challenges;
+        self
+    }
+
+    /// Replaces the base URL of the Geetest verification endpoints.
+    ///
+    /// This is primarily useful for testing.
+    #[must_use]
+    pub fn with_verification_url(mut self, verification_url: impl Into<String>) -> Self {
+        self.verification_url = verification_url.into();
+        self
+    }
+
+    /// Records that HoYoLab refused a request to `url` until `challenge` is
+    /// solved, with the solution to be sent along with the next attempt only.
+    pub(crate) fn raise_challenge(&self, url: &str, challenge: &GeetestChallenge) {
+        self.challenges
+            .raise(self.request_key(url), challenge, Replay::Headers);
+    }
+
+    /// Identifies a request to `url` made by this client's login.
+    fn request_key(&self, url: &str) -> RequestKey {
+        (self.cookie.clone(), url.to_string())
+    }
+
     /// Makes an authenticated GET request to the HoYoLab API.
     ///
     /// # Errors
@@ -84,7 +191,7 @@ impl HoyolabClient {
         // Try to fetch user info to verify credentials
         match self.get::<serde_json::Value>(&self.auth_check_url).await {
             Ok(_) => Ok(true),
-            Err(Error::Client(ClientError::ApiError { code: -100, .. })) => Ok(false), /* Not logged in */
+            Err(e) if e.kind() == ErrorKind::Auth => Ok(false),
             Err(e) => Err(e),
         }
     }
@@ -104,9 +211,21 @@ impl HoyolabClient {
     /// This is useful for endpoints like daily rewards that require additional
     /// headers such as `x-rpc-signgame`.
     ///
+    /// Every request waits for the rate limiter. When HoYoLab answers that it
+    /// is called too often, all requests sharing the limiter pause for its
+    /// cooldown and this one is retried.
+    ///
+    /// When risk control refuses the request, the Geetest challenge that
+    /// unblocks it is fetched and recorded; once solved through
+    /// [`Challenges::solve`], the next call is verified and sent with the
+    /// solution.
+    ///
     /// # Errors
     ///
-    /// Returns an error if the request fails or the response cannot be parsed.
+    /// Returns an error if the request fails or the response cannot be parsed,
+    /// [`Error::RateLimited`] if HoYoLab still rate limits it after the
+    /// retries, or [`Error::ChallengeRequired`] if a challenge must be solved
+    /// first.
     pub async fn request_with_headers<T: DeserializeOwned, B: Serialize>(
         &self,
         method: Method,
@@ -114,7 +233,116 @@ impl HoyolabClient {
         body: Option<&B>,
         extra_headers: &[(&str, &str)],
     ) -> Result<T> {
-        let ds = generate_dynamic_secret_overseas();
+        self.request_optional_with_headers(method, url, body, extra_headers)
+            .await?
+            .ok_or_else(|| Error::Client(ClientError::api_error(0, "Response data is null")))
+    }
+
+    /// Same as [`Self::request_with_headers`], but for endpoints that may
+    /// succeed with `"data": null`, which is returned as `None`.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Self::request_with_headers`].
+    pub(crate) async fn request_optional_with_headers<T: DeserializeOwned, B: Serialize>(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&B>,
+        extra_headers: &[(&str, &str)],
+    ) -> Result<Option<T>> {
+        storekeeper_client_core::retry::retry_with_backoff(
+            &RetryConfig::default(),
+            || self.request_once(method.clone(), url, body, extra_headers),
+            |err| matches!(err, Error::RateLimited { .. }),
+        )
+        .await
+    }
+
+    /// Performs a single request without retries, sending along the solution
+    /// to a challenge it raised before, if one was stored.
+    async fn request_once<T: DeserializeOwned, B: Serialize>(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&B>,
+        extra_headers: &[(&str, &str)],
+    ) -> Result<Option<T>> {
+        let request_key = self.request_key(url);
+        let solution = self.challenges.take_solution(&request_key);
+        let solution_headers = match &solution {
+            Some((solution, replay)) => {
+                if *replay == Replay::Verify {
+                    self.verify_challenge(solution).await?;
+                }
+                tracing::info!(url = %url, "Replaying HoYoLab request with a solved challenge");
+                solution.headers().to_vec()
+            }
+            None => Vec::new(),
+        };
+        let headers: Vec<(&str, &str)> = extra_headers
+            .iter()
+            .copied()
+            .chain(
+                solution_headers
+                    .iter()
+                    .map(|(name, value)| (*name, value.as_str())),
+            )
+            .collect();
+
+        let api_response: HoyolabApiResponse<T> = self.send(method, url, body, &headers).await?;
+
+        if CHALLENGE_RETCODES.contains(&api_response.retcode) {
+            let challenge = self.create_challenge().await?;
+            tracing::warn!(
+                url = %url,
+                retcode = api_response.retcode,
+                "HoYoLab requires a Geetest challenge to be solved"
+            );
+            self.challenges
+                .raise(request_key, &challenge, Replay::Verify);
+            return Err(Error::ChallengeRequired(challenge));
+        }
+
+        if !api_response.is_success() {
+            tracing::warn!(
+                retcode = api_response.retcode,
+                message = %api_response.message,
+                url = %url,
+                "HoYoLab API error response"
+            );
+            return Err(Error::Client(ClientError::api_error(
+                api_response.retcode,
+                api_response.message,
+            )));
+        }
+
+        tracing::debug!(url = %url, "HoYoLab API request successful");
+
+        Ok(api_response.data)
+    }
+
+    /// Sends a single rate-limited request and parses the HoYoLab envelope.
+    async fn send<T: DeserializeOwned, B: Serialize>(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&B>,
+        extra_headers: &[(&str, &str)],
+    ) -> Result<HoyolabApiResponse<T>> {
+        let body = body
+            .map(serde_json::to_string)
+            .transpose()
+            .map_err(ClientError::from)?;
+        self.rate_limiter.acquire().await;
+        // Miyoushe signs the exact body and query sent, overseas only the time
+        let ds = match self.platform {
+            Platform::Hoyolab => generate_dynamic_secret_overseas(),
+            Platform::Miyoushe => generate_dynamic_secret_chinese(
+                body.as_deref().unwrap_or_default(),
+                &canonical_query(url),
+            ),
+        };
 
         tracing::debug!(url = %url, method = %method, "HoYoLab API request");
 
@@ -128,7 +356,7 @@ impl HoyolabClient {
 
         // Add body for POST requests if provided
         if let Some(body) = body {
-            request = request.json(body);
+            request = request.header(CONTENT_TYPE, "application/json").body(body);
         }
 
         let response = request.send().await?;
@@ -141,6 +369,9 @@ impl HoyolabClient {
             let body_preview: String = body.chars().take(300).collect();
             let message = format!("HTTP {status} from HoYoLab API: {}", body_preview.trim());
             tracing::warn!(url = %url, status = %status, body_preview = %body_preview, "HoYoLab HTTP error");
+            if status == StatusCode::TOO_MANY_REQUESTS {
+                self.rate_limiter.cool_down();
+            }
             return Err(Error::Client(ClientError::api_error(
                 i32::from(status.as_u16()),
                 message,
@@ -149,18 +380,44 @@ impl HoyolabClient {
 
         let api_response: HoyolabApiResponse<T> = response.json().await?;
 
-        if !api_response.is_success() {
+        if api_response.retcode == VISITS_TOO_FREQUENTLY_RETCODE {
+            self.rate_limiter.cool_down();
+            let cooldown = self.rate_limiter.cooldown();
             tracing::warn!(
-                retcode = api_response.retcode,
-                message = %api_response.message,
                 url = %url,
-                "HoYoLab API error response"
+                cooldown_secs = cooldown.as_secs(),
+                "HoYoLab rate limited the request, pausing requests"
             );
+            return Err(Error::RateLimited {
+                retry_after_secs: cooldown.as_secs(),
+            });
         }
 
-        tracing::debug!(url = %url, "HoYoLab API request successful");
+        Ok(api_response)
+    }
 
-        api_response.into_result().map_err(Error::Client)
+    /// Asks HoYoLab for the Geetest challenge that unblocks this login.
+    async fn create_challenge(&self) -> Result<GeetestChallenge> {
+        let url = format!("{}/createVerification?is_high=true", self.verification_url);
+        self.send::<GeetestChallenge, ()>(Method::GET, &url, None, &[])
+            .await?
+            .into_result()
+            .map_err(Error::Client)
+    }
+
+    /// Hands a solved challenge back to HoYoLab.
+    async fn verify_challenge(&self, solution: &GeetestSolution) -> Result<()> {
+        let url = format!("{}/verifyVerification", self.verification_url);
+        let body = serde_json::json!({
+            "geetest_challenge": solution.challenge,
+            "geetest_validate": solution.validate,
+            "geetest_seccode": solution.seccode(),
+        });
+        self.send::<serde_json::Value, _>(Method::POST, &url, Some(&body), &[])
+            .await?
+            .into_result()
+            .map_err(Error::Client)?;
+        Ok(())
     }
 }
 
@@ -420,6 +677,45 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn miyoushe_client_sends_cn_headers_and_cn_ds() {
+        let server = TestServer::spawn(Arc::new(|_| {
+            json_response(200, r#"{"retcode":0,"message":"OK","data":{"ok":true}}"#)
+        }))
+        .await;
+
+        let client = HoyolabClient::miyoushe("123", "456", "789").expect("create client");
+        assert_eq!(client.platform(), Platform::Miyoushe);
+        let url = format!("{}/note?server=cn_gf01&role_id=1", server.base_url);
+        let body = serde_json::json!({ "hello": "world" });
+        let _: serde_json::Value = client
+            .request_with_headers(Method::POST, &url, Some(&body), &[])
+            .await
+            .expect("request should succeed");
+
+        let requests = server.requests().await;
+        let request = requests.first().expect("expected a single request");
+        assert_eq!(
+            request.headers.get("cookie"),
+            Some(&"ltuid_v2=123; ltoken_v2=456; ltmid_v2=789".to_string())
+        );
+        assert_eq!(
+            request.headers.get("x-rpc-language"),
+            Some(&"zh-cn".to_string())
+        );
+        assert_eq!(request.body, r#"{"hello":"world"}"#);
+        let random = request
+            .headers
+            .get("ds")
+            .and_then(|ds| ds.split(',').nth(1))
+            .and_then(|random| random.parse::<u32>().ok());
+        assert!(
+            random.is_some_and(|random| (100_001..=200_000).contains(&random)),
+            "DS should use the CN format, got: {:?}",
+            request.headers.get("ds")
+        );
+    }
+
     #[tokio::test]
     async fn non_success_http_status_maps_to_api_error() {
         let server = TestServer::spawn(Arc::new(|_| text_response(429, "rate limited"))).await;
@@ -466,6 +762,37 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn rate_limited_retcode_cools_down_and_retries() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let server_calls = Arc::clone(&calls);
+        let server = TestServer::spawn(Arc::new(move |_| {
+            if server_calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) == 0 {
+                json_response(
+                    200,
+                    r#"{"retcode":-110,"message":"visits too frequently","data":null}"#,
+                )
+            } else {
+                json_response(200, r#"{"retcode":0,"message":"OK","data":{"ok":true}}"#)
+            }
+        }))
+        .await;
+
+        let auth_url = format!("{}/auth", server.base_url);
+        let client = HoyolabClient::with_auth_check_url("123", "456", auth_url)
+            .expect("create client")
+            .with_rate_limiter(RateLimiter::new(RateLimit::new(
+                600,
+                5,
+                Duration::from_millis(10),
+            )));
+        let url = format!("{}/limited", server.base_url);
+
+        let result: serde_json::Value = client.get(&url).await.expect("retry should succeed");
+        assert_eq!(result, serde_json::json!({"ok": true}));
+        assert_eq!(server.requests().await.len(), 2);
+    }
+
     #[tokio::test]
     async fn check_auth_returns_false_for_not_logged_in() {
         let server = TestServer::spawn(Arc::new(|request| {
@@ -519,4 +846,68 @@ mod tests {
             "Unexpected API error should be returned, got: {result:?}"
         );
     }
+
+    #[tokio::test]
+    async fn challenge_is_raised_then_verified_and_replayed_once_solved() {
+        let solved = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let server_solved = Arc::clone(&solved);
+        let server = TestServer::spawn(Arc::new(move |request| {
+            if request.target.starts_with("/verification/createVerification") {
+                json_response(
+                    200,
+                    r#"{"retcode":0,"message":"OK","data":{"gt":"gt1","challenge":"c1","new_captcha":1,"success":1}}"#,
+                )
+            } else if request.target.starts_with("/verification/verifyVerification") {
+                server_solved.store(true, std::sync::atomic::Ordering::SeqCst);
+                json_response(200, r#"{"retcode":0,"message":"OK","data":{"challenge":"c1"}}"#)
+            } else if server_solved.load(std::sync::atomic::Ordering::SeqCst) {
+                json_response(200, r#"{"retcode":0,"message":"OK","data":{"ok":true}}"#)
+            } else {
+                json_response(200, r#"{"retcode":1034,"message":"risk","data":null}"#)
+            }
+        }))
+        .await;
+
+        let auth_url = format!("{}/auth", server.base_url);
+        let challenges = Challenges::default();
+        let client = HoyolabClient::with_auth_check_url("123", "456", auth_url)
+            .expect("create client")
+            .with_challenges(challenges.clone())
+            .with_verification_url(format!("{}/verification", server.base_url));
+        let url = format!("{}/dailyNote", server.base_url);
+
+        let result = client.get::<serde_json::Value>(&url).await;
+        assert!(
+            matches!(
+                &result,
+                Err(Error::ChallengeRequired(challenge))
+                    if challenge.gt == "gt1" && challenge.challenge == "c1"
+            ),
+            "risk retcode should raise the created challenge, got: {result:?}"
+        );
+
+        assert!(challenges.solve(GeetestSolution {
+            challenge: "c1".to_string(),
+            validate: "v1".to_string(),
+        }));
+        let result: serde_json::Value = client.get(&url).await.expect("replay should succeed");
+        assert_eq!(result, serde_json::json!({"ok": true}));
+
+        let requests = server.requests().await;
+        let verify = requests
+            .iter()
+            .find(|request| request.target.ends_with("/verifyVerification"))
+            .expect("solution should be verified");
+        assert!(verify.body.contains("\"geetest_seccode\":\"v1|jordan\""));
+        let replay = requests.last().expect("replayed request");
+        assert_eq!(replay.target, "/dailyNote");
+        assert_eq!(
+            replay.headers.get("x-rpc-challenge"),
+            Some(&"c1".to_string())
+        );
+        assert_eq!(
+            replay.headers.get("x-rpc-validate"),
+            Some(&"v1".to_string())
+        );
+    }
 }
@@ -1,8 +1,10 @@
 //! Generic HoYoLab daily reward client.
 //!
 //! Provides a config-driven `DailyRewardClient` implementation that works for
-//! all HoYoLab games (Genshin Impact, Honkai: Star Rail, Zenless Zone Zero).
+//! all HoYoLab games (Genshin Impact, Honkai: Star Rail, Zenless Zone Zero),
+//! on HoYoLab and on Miyoushe for mainland China servers.
 
+use crate::challenge::GeetestChallenge;
 use crate::client::HoyolabClient;
 use crate::error::Error;
 use crate::error::Result;
@@ -56,6 +58,30 @@ pub const ZZZ_DAILY_REWARD: HoyolabDailyRewardConfig = HoyolabDailyRewardConfig
     game_id: GameId::ZenlessZoneZero,
 };
 
+/// Genshin Impact daily reward configuration on Miyoushe.
+pub const GENSHIN_DAILY_REWARD_CN: HoyolabDailyRewardConfig = HoyolabDailyRewardConfig {
+    reward_url: "https://api-takumi.mihoyo.com/event/luna",
+    act_id: "e202311201442471",
+    sign_game: "hk4e",
+    game_id: GameId::GenshinImpact,
+};
+
+/// Honkai: Star Rail daily reward configuration on Miyoushe.
+pub const HSR_DAILY_REWARD_CN: HoyolabDailyRewardConfig = HoyolabDailyRewardConfig {
+    reward_url: "https://api-takumi.mihoyo.com/event/luna",
+    act_id: "e202304121516551",
+    sign_game: "hkrpg",
+    game_id: GameId::HonkaiStarRail,
+};
+
+/// Zenless Zone Zero daily reward configuration on Miyoushe.
+pub const ZZZ_DAILY_REWARD_CN: HoyolabDailyRewardConfig = HoyolabDailyRewardConfig {
+    reward_url: "https://act-nap-api.mihoyo.com/event/luna/zzz",
+    act_id: "e202406242138391",
+    sign_game: "zzz",
+    game_id: GameId::ZenlessZoneZero,
+};
+
 // ============================================================================
 // Response Structures
 // ============================================================================
@@ -73,6 +99,40 @@ struct RewardHomeResponse {
     awards: Vec<RewardItem>,
 }
 
+/// API response for a claim (`/sign` endpoint).
+///
+/// When risk control suspects the claim, it succeeds with a risk code and a
+/// Geetest challenge instead of claiming; Genshin Impact nests them in
+/// `gt_result`.
+#[derive(Debug, Default, Deserialize)]
+struct SignResponse {
+    #[serde(flatten)]
+    risk: SignRisk,
+    gt_result: Option<SignRisk>,
+}
+
+/// Risk control verdict on a claim.
+#[derive(Debug, Default, Deserialize)]
+struct SignRisk {
+    #[serde(default)]
+    risk_code: i32,
+    #[serde(default)]
+    gt: String,
+    #[serde(default)]
+    challenge: String,
+}
+
+impl SignResponse {
+    /// Returns the challenge to solve before the claim goes through, if any.
+    fn into_challenge(self) -> Option<GeetestChallenge> {
+        let risk = self.gt_result.unwrap_or(self.risk);
+        (risk.risk_code != 0).then_some(GeetestChallenge {
+            gt: risk.gt,
+            challenge: risk.challenge,
+        })
+    }
+}
+
 /// Individual reward item in the monthly rewards list.
 #[derive(Debug, Deserialize)]
 struct RewardItem {
@@ -90,6 +150,8 @@ struct RewardItem {
 pub struct HoyolabDailyRewardClient {
     client: HoyolabClient,
     config: &'static HoyolabDailyRewardConfig,
+    /// Query parameters naming the game account, which Miyoushe requires.
+    role_query: String,
 }
 
 impl HoyolabDailyRewardClient {
@@ -97,14 +159,33 @@ impl HoyolabDailyRewardClient {
     /// config.
     #[must_use]
     pub fn new(client: HoyolabClient, config: &'static HoyolabDailyRewardConfig) -> Self {
-        Self { client, config }
+        Self {
+            client,
+            config,
+            role_query: String::new(),
+        }
+    }
+
+    /// Names the game account to claim for, by UID and API region (e.g.
+    /// `cn_gf01`).
+    ///
+    /// Miyoushe claims per game account rather than per login, so this is
+    /// required there; HoYoLab ignores it.
+    #[must_use]
+    pub fn with_role(mut self, uid: &str, region: &str) -> Self {
+        self.role_query = format!("&region={region}&uid={uid}");
+        self
     }
 
     /// Builds a daily reward URL with the given endpoint.
     fn reward_url(&self, endpoint: &str) -> String {
         format!(
-            "{}/{}?act_id={}&lang=en-us",
-            self.config.reward_url, endpoint, self.config.act_id
+            "{}/{}?act_id={}&lang={}{}",
+            self.config.reward_url,
+            endpoint,
+            self.config.act_id,
+            self.client.platform().language(),
+            self.role_query
         )
     }
 
@@ -112,7 +193,7 @@ impl HoyolabDailyRewardClient {
     fn reward_headers(&self) -> [(&'static str, &'static str); 2] {
         [
             ("x-rpc-signgame", self.config.sign_game),
-            ("referer", "https://act.hoyolab.com/"),
+            ("referer", self.client.platform().act_referer()),
         ]
     }
 }
@@ -199,10 +280,19 @@ impl DailyRewardClient for HoyolabDailyRewardClient {
         let url = self.reward_url("sign");
         let headers = self.reward_headers();
 
-        let _ = self
+        // The sign endpoint may answer a plain claim with `"data": null`
+        let response = self
             .client
-            .request_with_headers::<serde_json::Value, ()>(Method::POST, &url, None, &headers)
+            .request_optional_with_headers::<SignResponse, ()>(Method::POST, &url, None, &headers)
             .await?;
+        if let Some(challenge) = response.and_then(SignResponse::into_challenge) {
+            tracing::warn!(
+                game = game,
+                "Daily reward claim requires a Geetest challenge"
+            );
+            self.client.raise_challenge(&url, &challenge);
+            return Err(Error::ChallengeRequired(challenge));
+        }
 
         // Fetch updated status to get reward details
         let status = self.get_reward_status().await?;
@@ -239,6 +329,7 @@ mod tests {
     struct TestRequest {
         method: String,
         target: String,
+        headers: HashMap<String, String>,
     }
 
     #[derive(Debug, Clone)]
@@ -371,7 +462,11 @@ mod tests {
             }
         }
 
-        Some(TestRequest { method, target })
+        Some(TestRequest {
+            method,
+            target,
+            headers,
+        })
     }
 
     fn find_header_end(bytes: &[u8]) -> Option<usize> {
@@ -541,6 +636,35 @@ mod tests {
         assert_eq!(sign_calls, 1, "sign endpoint should be called exactly once");
     }
 
+    #[tokio::test]
+    async fn claim_daily_reward_accepts_sign_reply_without_data() {
+        let server = TestServer::spawn(Arc::new(|request| {
+            if request.target.starts_with("/event/luna/test/info") {
+                ok(r#"{"retcode":0,"message":"OK","data":{"is_sign":false,"total_sign_day":0}}"#)
+            } else if request.target.starts_with("/event/luna/test/home") {
+                ok(
+                    r#"{"retcode":0,"message":"OK","data":{"awards":[{"name":"Primogems","cnt":60,"icon":"primogem.png"}]}}"#,
+                )
+            } else {
+                ok(r#"{"retcode":0,"message":"OK","data":null}"#)
+            }
+        }))
+        .await;
+
+        let config = test_config(&server.base_url);
+        let client = test_client(&server, config);
+        let claim = client
+            .claim_daily_reward()
+            .await
+            .expect("a sign reply without data should still claim");
+
+        assert!(claim.success);
+        assert_eq!(
+            claim.reward.as_ref().map(|reward| reward.name.as_str()),
+            Some("Primogems")
+        );
+    }
+
     #[tokio::test]
     async fn reward_status_selects_signed_day_reward_by_index() {
         let server = TestServer::spawn(Arc::new(|request| {
@@ -572,4 +696,96 @@ mod tests {
             "signed day 2 should map to index 1"
         );
     }
+
+    #[tokio::test]
+    async fn miyoushe_requests_name_the_role_in_chinese() {
+        let server = TestServer::spawn(Arc::new(|_| {
+            ok(r#"{"retcode":0,"message":"OK","data":{"is_sign":true,"total_sign_day":3}}"#)
+        }))
+        .await;
+
+        let config = test_config(&server.base_url);
+        let hoyolab = HoyolabClient::miyoushe("uid", "token", "mid").expect("create client");
+        let client =
+            HoyolabDailyRewardClient::new(hoyolab, config).with_role("100000001", "cn_gf01");
+        client.get_reward_info().await.expect("info should load");
+
+        let requests = server.requests().await;
+        let request = requests.first().expect("info request");
+        assert_eq!(
+            request.target,
+            "/event/luna/test/info?act_id=act123&lang=zh-cn&region=cn_gf01&uid=100000001"
+        );
+        assert_eq!(
+            request.headers.get("referer"),
+            Some(&"https://act.mihoyo.com/".to_string())
+        );
+        assert_eq!(
+            request.headers.get("cookie"),
+            Some(&"ltuid_v2=uid; ltoken_v2=token; ltmid_v2=mid".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn claim_raises_sign_challenge_and_replays_with_solution() {
+        let server = TestServer::spawn(Arc::new(|request| {
+            if request.target.starts_with("/event/luna/test/info") {
+                ok(r#"{"retcode":0,"message":"OK","data":{"is_sign":false,"total_sign_day":0}}"#)
+            } else if request.target.starts_with("/event/luna/test/home") {
+                ok(
+                    r#"{"retcode":0,"message":"OK","data":{"awards":[{"name":"Mora","cnt":5000,"icon":"mora.png"}]}}"#,
+                )
+            } else if request.headers.contains_key("x-rpc-validate") {
+                ok(r#"{"retcode":0,"message":"OK","data":{"code":"ok","risk_code":0}}"#)
+            } else {
+                ok(
+                    r#"{"retcode":0,"message":"OK","data":{"code":"","gt_result":{"risk_code":375,"gt":"gt1","challenge":"c1","success":1,"is_risk":true}}}"#,
+                )
+            }
+        }))
+        .await;
+
+        let config = test_config(&server.base_url);
+        let auth_url = format!("{}/auth", server.base_url);
+        let challenges = crate::Challenges::default();
+        let hoyolab = HoyolabClient::with_auth_check_url("uid", "token", auth_url)
+            .expect("create client")
+            .with_challenges(challenges.clone());
+        let client = HoyolabDailyRewardClient::new(hoyolab, config);
+
+        let result = client.claim_daily_reward().await;
+        assert!(
+            matches!(
+                &result,
+                Err(Error::ChallengeRequired(challenge))
+                    if challenge.gt == "gt1" && challenge.challenge == "c1"
+            ),
+            "risky sign should raise its challenge, got: {result:?}"
+        );
+
+        assert!(challenges.solve(crate::GeetestSolution {
+            challenge: "c1".to_string(),
+            validate: "v1".to_string(),
+        }));
+        client
+            .claim_daily_reward()
+            .await
+            .expect("replayed claim should succeed");
+
+        let requests = server.requests().await;
+        let signs: Vec<_> = requests
+            .iter()
+            .filter(|request| request.target.starts_with("/event/luna/test/sign"))
+            .collect();
+        assert_eq!(signs.len(), 2);
+        let replay = signs.last().expect("replayed sign");
+        assert_eq!(
+            replay.headers.get("x-rpc-challenge"),
+            Some(&"c1".to_string())
+        );
+        assert_eq!(
+            replay.headers.get("x-rpc-seccode"),
+            Some(&"v1|jordan".to_string())
+        );
+    }
 }
@@ -12,8 +12,6 @@ use std::fmt::Write;
 const SALT_OVERSEAS: &str = "6s25p5ox5y14umn1p61aqyyvbvvl3lrt";
 
 /// Salt for Chinese HoYoLab API (miyoushe).
-///
-/// Currently unused — reserved for future Chinese server (miyoushe) support.
 const SALT_CHINESE: &str = "xV8v4Qu54lUKrEYFZkJhB8cuOh9Asafs";
 
 /// Generates a DS header for overseas (global) HoYoLab API.
@@ -38,7 +36,8 @@ pub fn generate_dynamic_secret_overseas() -> String {
 /// - random: Random integer between 100001 and 200000
 /// - hash: MD5("salt={salt}&t={t}&r={r}&b={body}&q={query}")
 ///
-/// Currently unused — reserved for future Chinese server (miyoushe) support.
+/// `body` is the JSON body exactly as sent (empty for GET requests) and
+/// `query` the request's query string, as [`canonical_query`] builds it.
 #[must_use]
 pub fn generate_dynamic_secret_chinese(body: &str, query: &str) -> String {
     let timestamp = jiff::Timestamp::now().as_second();
@@ -48,6 +47,24 @@ pub fn generate_dynamic_secret_chinese(body: &str, query: &str) -> String {
     format!("{timestamp},{random},{hash}")
 }
 
+/// Returns the query string of `url` with its parameters sorted by name, as
+/// the Chinese DS hashes it.
+///
+/// Returns an empty string if `url` has no query or cannot be parsed.
+#[must_use]
+pub fn canonical_query(url: &str) -> String {
+    let Ok(url) = reqwest::Url::parse(url) else {
+        return String::new();
+    };
+    let mut params: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+    params.sort();
+    params
+        .iter()
+        .map(|(name, value)| format!("{name}={value}"))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
 /// Generates a random string of lowercase ASCII letters.
 fn generate_random_lowercase_string(len: usize) -> String {
     let mut rng = rand::rng();
@@ -292,6 +309,18 @@ mod tests {
         assert_eq!(hash2.len(), 32);
     }
 
+    #[test]
+    fn test_canonical_query_sorts_params() {
+        assert_eq!(
+            canonical_query(
+                "https://api-takumi-record.mihoyo.com/game_record/app/genshin/api/dailyNote?server=cn_gf01&role_id=100000001"
+            ),
+            "role_id=100000001&server=cn_gf01"
+        );
+        assert_eq!(canonical_query("https://example.com/path"), "");
+        assert_eq!(canonical_query("not a url"), "");
+    }
+
     // =========================================================================
     // Helper function tests
     // =========================================================================
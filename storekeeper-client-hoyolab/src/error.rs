@@ -1,9 +1,41 @@
 //! Error types for the HoYoLab API client.
 
+use crate::challenge::GeetestChallenge;
 // Re-export base error for convenience
 pub use storekeeper_client_core::ClientError;
+pub use storekeeper_client_core::ErrorKind;
 use thiserror::Error;
 
+/// HoYoLab retcodes with a known meaning.
+const RETCODE_KINDS: [(i32, ErrorKind); 9] = [
+    // Not logged in.
+    (-100, ErrorKind::Auth),
+    // Invalid or expired cookie.
+    (10001, ErrorKind::Auth),
+    // Cookie has no HoYoLab account bound to it.
+    (10103, ErrorKind::Auth),
+    // Visits too frequently.
+    (-110, ErrorKind::RateLimited),
+    // Too many accounts looked up with one cookie today.
+    (10101, ErrorKind::RateLimited),
+    // Risk control wants a Geetest captcha solved.
+    (1034, ErrorKind::Captcha),
+    (10035, ErrorKind::Captcha),
+    // Account not found.
+    (1009, ErrorKind::NotFound),
+    // UID isn't bound to the cookie's account.
+    (10104, ErrorKind::NotFound),
+];
+
+/// Returns the kind of a HoYoLab retcode, if it has a known meaning.
+#[must_use]
+pub fn retcode_kind(retcode: i32) -> Option<ErrorKind> {
+    RETCODE_KINDS
+        .iter()
+        .find(|(code, _)| *code == retcode)
+        .map(|(_, kind)| *kind)
+}
+
 /// Error type for HoYoLab API operations.
 #[derive(Error, Debug)]
 pub enum Error {
@@ -17,6 +49,26 @@ pub enum Error {
         /// Seconds to wait before retrying.
         retry_after_secs: u64,
     },
+
+    /// Risk control refused the request until a Geetest challenge is solved.
+    #[error("HoYoLab requires a Geetest challenge to be solved")]
+    ChallengeRequired(GeetestChallenge),
+}
+
+impl Error {
+    /// Returns the error's kind, reading API error codes as HoYoLab retcodes
+    /// first.
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Client(client) => client
+                .api_error_code()
+                .and_then(retcode_kind)
+                .unwrap_or_else(|| client.kind()),
+            Self::RateLimited { .. } => ErrorKind::RateLimited,
+            Self::ChallengeRequired(_) => ErrorKind::Captcha,
+        }
+    }
 }
 
 /// Result type alias using the HoYoLab Error type.
@@ -40,3 +92,26 @@ impl From<reqwest_middleware::Error> for Error {
         Self::Client(ClientError::from(err))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retcodes_map_onto_error_kinds() {
+        let kind = |code| Error::Client(ClientError::api_error(code, "")).kind();
+        assert_eq!(kind(-100), ErrorKind::Auth);
+        assert_eq!(kind(10001), ErrorKind::Auth);
+        assert_eq!(kind(1034), ErrorKind::Captcha);
+        assert_eq!(kind(10104), ErrorKind::NotFound);
+        assert_eq!(kind(429), ErrorKind::RateLimited);
+        assert_eq!(kind(-1002), ErrorKind::Protocol);
+        assert_eq!(
+            Error::RateLimited {
+                retry_after_secs: 30
+            }
+            .kind(),
+            ErrorKind::RateLimited
+        );
+    }
+}
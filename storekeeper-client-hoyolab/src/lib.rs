@@ -1,20 +1,33 @@
 //! HoYoLab API client for Storekeeper.
 //!
 //! This crate provides a shared HTTP client for interacting with the HoYoLab
-//! API, used by Genshin Impact, Honkai: Star Rail, and Zenless Zone Zero.
+//! API, used by Genshin Impact, Honkai: Star Rail, and Zenless Zone Zero, and
+//! with its mainland China counterpart, Miyoushe.
 
+pub mod challenge;
 pub mod client;
 pub mod daily_reward;
 pub mod ds;
 pub mod error;
+pub mod platform;
+pub mod roles;
 
+pub use challenge::Challenges;
+pub use challenge::GeetestChallenge;
+pub use challenge::GeetestSolution;
 pub use client::HoyolabClient;
 pub use daily_reward::GENSHIN_DAILY_REWARD;
+pub use daily_reward::GENSHIN_DAILY_REWARD_CN;
 pub use daily_reward::HSR_DAILY_REWARD;
+pub use daily_reward::HSR_DAILY_REWARD_CN;
 pub use daily_reward::HoyolabDailyRewardClient;
 pub use daily_reward::HoyolabDailyRewardConfig;
 pub use daily_reward::ZZZ_DAILY_REWARD;
+pub use daily_reward::ZZZ_DAILY_REWARD_CN;
 pub use error::Error;
 pub use error::Result;
+pub use platform::Platform;
 // Re-export reqwest::Method for use in game crates
 pub use reqwest::Method;
+pub use roles::GameRole;
+pub use roles::fetch_game_roles;
@@ -0,0 +1,92 @@
+//! The two platforms serving the HoYoLab APIs.
+//!
+//! Overseas servers are served by HoYoLab (`hoyolab.com`), while mainland
+//! China servers are served by Miyoushe (`mihoyo.com`). They differ in their
+//! endpoints, login cookies, client headers and DS salt, so each
+//! [`HoyolabClient`](crate::HoyolabClient) targets one of them.
+
+use storekeeper_core::Region;
+
+/// Platform serving the HoYoLab APIs for a game server.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Platform {
+    /// HoYoLab, serving overseas servers.
+    #[default]
+    Hoyolab,
+    /// Miyoushe (miHoYo BBS), serving mainland China servers.
+    Miyoushe,
+}
+
+impl Platform {
+    /// Returns the platform serving accounts of `region`.
+    #[must_use]
+    pub const fn from_region(region: Region) -> Self {
+        match region {
+            Region::China => Self::Miyoushe,
+            _ => Self::Hoyolab,
+        }
+    }
+
+    /// Returns the app version the platform's APIs expect.
+    pub(crate) const fn app_version(self) -> &'static str {
+        match self {
+            Self::Hoyolab => "1.5.0",
+            Self::Miyoushe => "2.71.1",
+        }
+    }
+
+    /// Returns the language the platform's APIs answer in.
+    pub(crate) const fn language(self) -> &'static str {
+        match self {
+            Self::Hoyolab => "en-us",
+            Self::Miyoushe => "zh-cn",
+        }
+    }
+
+    /// Returns the URL used to check whether a login is still valid.
+    pub(crate) const fn auth_check_url(self) -> &'static str {
+        match self {
+            Self::Hoyolab => "https://bbs-api-os.hoyolab.com/community/user/wapi/getUserFullInfo",
+            Self::Miyoushe => "https://bbs-api.miyoushe.com/user/wapi/getUserFullInfo",
+        }
+    }
+
+    /// Returns the URL listing the game roles bound to a login.
+    pub(crate) const fn game_roles_url(self) -> &'static str {
+        match self {
+            Self::Hoyolab => {
+                "https://api-account-os.hoyolab.com/account/binding/api/getUserGameRolesByCookie"
+            }
+            Self::Miyoushe => "https://api-takumi.mihoyo.com/binding/api/getUserGameRolesByCookie",
+        }
+    }
+
+    /// Returns the base URL of the endpoints that create and verify Geetest
+    /// challenges.
+    pub(crate) const fn verification_url(self) -> &'static str {
+        match self {
+            Self::Hoyolab => "https://bbs-api-os.hoyolab.com/game_record/app/card/wapi",
+            Self::Miyoushe => "https://api-takumi-record.mihoyo.com/game_record/app/card/wapi",
+        }
+    }
+
+    /// Returns the page daily reward requests are sent from.
+    pub(crate) const fn act_referer(self) -> &'static str {
+        match self {
+            Self::Hoyolab => "https://act.hoyolab.com/",
+            Self::Miyoushe => "https://act.mihoyo.com/",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_china_is_served_by_miyoushe() {
+        assert_eq!(Platform::from_region(Region::China), Platform::Miyoushe);
+        assert_eq!(Platform::from_region(Region::America), Platform::Hoyolab);
+        assert_eq!(Platform::from_region(Region::Asia), Platform::Hoyolab);
+    }
+}
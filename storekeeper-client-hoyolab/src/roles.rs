@@ -0,0 +1,170 @@
+//! Game roles bound to a HoYoLab login.
+//!
+//! Lists the Genshin Impact, Honkai: Star Rail and Zenless Zone Zero accounts
+//! of a login along with the server each is on, so UIDs and regions don't
+//! have to be entered or guessed.
+
+use crate::client::HoyolabClient;
+use crate::error::Result;
+use serde::Deserialize;
+use serde::Serialize;
+use storekeeper_core::GameId;
+use storekeeper_core::Region;
+
+/// A game account bound to a HoYoLab login.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameRole {
+    /// The game the account plays.
+    pub game_id: GameId,
+    /// In-game UID.
+    pub uid: String,
+    /// In-game nickname.
+    pub nickname: String,
+    /// Adventure rank, trailblaze level or inter-knot level.
+    pub level: u32,
+    /// API name of the account's server (e.g. `os_usa`).
+    pub server: String,
+    /// Display name of the account's server.
+    pub server_name: String,
+    /// Region of the account's server, if Storekeeper can query it (not for
+    /// Bilibili servers).
+    pub region: Option<Region>,
+}
+
+/// API response for the roles of a login (`getUserGameRolesByCookie`).
+#[derive(Debug, Deserialize)]
+struct GameRolesResponse {
+    list: Vec<RawGameRole>,
+}
+
+/// A role as the API returns it.
+#[derive(Debug, Deserialize)]
+struct RawGameRole {
+    game_biz: String,
+    region: String,
+    game_uid: String,
+    nickname: String,
+    level: u32,
+    region_name: String,
+}
+
+impl RawGameRole {
+    /// Converts the role, or returns `None` for games Storekeeper doesn't
+    /// track.
+    fn into_role(self) -> Option<GameRole> {
+        let game_id = match self.game_biz.split('_').next() {
+            Some("hk4e") => GameId::GenshinImpact,
+            Some("hkrpg") => GameId::HonkaiStarRail,
+            Some("nap") => GameId::ZenlessZoneZero,
+            _ => return None,
+        };
+        Some(GameRole {
+            game_id,
+            region: Region::from_hoyolab_server(&self.region).ok(),
+            uid: self.game_uid,
+            nickname: self.nickname,
+            level: self.level,
+            server: self.region,
+            server_name: self.region_name,
+        })
+    }
+}
+
+/// Fetches the Genshin Impact, Honkai: Star Rail and Zenless Zone Zero roles
+/// bound to the client's login.
+///
+/// # Errors
+///
+/// Returns an error if the request fails, e.g. because the login belongs to
+/// the other platform.
+pub async fn fetch_game_roles(client: &HoyolabClient) -> Result<Vec<GameRole>> {
+    fetch_game_roles_from(client, client.platform().game_roles_url()).await
+}
+
+async fn fetch_game_roles_from(client: &HoyolabClient, url: &str) -> Result<Vec<GameRole>> {
+    let response: GameRolesResponse = client.get(url).await?;
+    let roles: Vec<GameRole> = response
+        .list
+        .into_iter()
+        .filter_map(RawGameRole::into_role)
+        .collect();
+    tracing::debug!(count = roles.len(), "Fetched HoYoLab game roles");
+    Ok(roles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
+    use tokio::net::TcpListener;
+
+    /// Serves a single request with `body` and returns the server's URL.
+    async fn serve_once(body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind test server");
+        let addr = listener.local_addr().expect("get local addr");
+        tokio::spawn(async move {
+            if let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0_u8; 4096];
+                #[expect(
+                    clippy::let_underscore_must_use,
+                    reason = "the request itself is irrelevant to the test"
+                )]
+                let _ = stream.read(&mut buf).await;
+                let reply = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                #[expect(
+                    clippy::let_underscore_must_use,
+                    reason = "best-effort response write to a test client that may have disconnected"
+                )]
+                let _ = stream.write_all(reply.as_bytes()).await;
+            }
+        });
+        format!("http://{addr}/getUserGameRolesByCookie")
+    }
+
+    #[tokio::test]
+    async fn roles_of_tracked_games_are_listed_with_their_region() {
+        let url = serve_once(
+            r#"{"retcode":0,"message":"OK","data":{"list":[
+                {"game_biz":"hk4e_global","region":"os_euro","game_uid":"700000001","nickname":"Lumine","level":60,"region_name":"Europe Server","is_chosen":true,"is_official":true},
+                {"game_biz":"bh3_global","region":"overseas01","game_uid":"1","nickname":"Kiana","level":80,"region_name":"Asia","is_chosen":false,"is_official":true},
+                {"game_biz":"nap_global","region":"prod_gf_jp","game_uid":"1300000001","nickname":"Belle","level":45,"region_name":"TW, HK, MO","is_chosen":false,"is_official":true},
+                {"game_biz":"hkrpg_global","region":"prod_official_new","game_uid":"600000001","nickname":"Stelle","level":70,"region_name":"New","is_chosen":false,"is_official":true}
+            ]}}"#,
+        )
+        .await;
+        let client = HoyolabClient::new("uid", "token").expect("create client");
+
+        let roles = fetch_game_roles_from(&client, &url)
+            .await
+            .expect("roles should load");
+
+        let summary: Vec<_> = roles
+            .iter()
+            .map(|role| (role.game_id, role.uid.as_str(), role.level, role.region))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (GameId::GenshinImpact, "700000001", 60, Some(Region::Europe)),
+                (
+                    GameId::ZenlessZoneZero,
+                    "1300000001",
+                    45,
+                    Some(Region::Japan)
+                ),
+                (GameId::HonkaiStarRail, "600000001", 70, None),
+            ]
+        );
+        assert_eq!(
+            roles.first().map(|role| role.server_name.as_str()),
+            Some("Europe Server")
+        );
+    }
+}
@@ -3,17 +3,22 @@
 use crate::error::Error;
 use crate::error::Result;
 use reqwest::Method;
+use reqwest::StatusCode;
 use reqwest::header::ACCEPT;
 use reqwest::header::CONTENT_TYPE;
 use reqwest::header::ORIGIN;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
+use std::time::Duration;
 use std::time::Instant;
 use storekeeper_client_core::ApiResponse;
 use storekeeper_client_core::ClientError;
 use storekeeper_client_core::ClientWithMiddleware;
+use storekeeper_client_core::ErrorKind;
 use storekeeper_client_core::HttpClientBuilder;
 use storekeeper_client_core::KuroApiResponse;
+use storekeeper_client_core::RateLimit;
+use storekeeper_client_core::RateLimiter;
 use storekeeper_client_core::RetryConfig;
 use storekeeper_client_core::retry::DEFAULT_MAX_DELAY_MS;
 use storekeeper_client_core::retry::DEFAULT_MAX_RETRIES;
@@ -39,12 +44,18 @@ pub struct KuroClient {
     client: ClientWithMiddleware,
     oauth_code: String,
     base_url: String,
+    rate_limiter: RateLimiter,
 }
 
 #[cfg(not(test))]
 const KURO_API_DEFAULT_BASE_DELAY_MS: u64 = 1500;
 #[cfg(test)]
 const KURO_API_DEFAULT_BASE_DELAY_MS: u64 = 1;
+/// Rate limit of a client not given a shared limiter.
+#[cfg(not(test))]
+const DEFAULT_RATE_LIMIT: RateLimit = RateLimit::new(20, 4, Duration::from_secs(30));
+#[cfg(test)]
+const DEFAULT_RATE_LIMIT: RateLimit = RateLimit::new(6000, 100, Duration::from_millis(1));
 const PREFLIGHT_ACCESS_CONTROL_METHOD: &str = "POST";
 const PREFLIGHT_ACCESS_CONTROL_HEADERS: &str = "content-type";
 
@@ -88,6 +99,32 @@ impl KuroClient {
             client,
             oauth_code: oauth_code.into(),
             base_url: normalized_base_url,
+            rate_limiter: RateLimiter::new(DEFAULT_RATE_LIMIT),
+        })
+    }
+
+    /// Replaces the client's own rate limiter, typically with one shared by
+    /// every Kuro client so that several accounts draw from one budget.
+    #[must_use]
+    pub fn with_rate_limiter(mut self, rate_limiter: RateLimiter) -> Self {
+        self.rate_limiter = rate_limiter;
+        self
+    }
+
+    /// Checks the status of a response, pausing every request sharing the
+    /// rate limiter if the API reports that it is called too often.
+    fn check_rate_limited(&self, status: StatusCode) -> Result<()> {
+        if status != StatusCode::TOO_MANY_REQUESTS {
+            return Ok(());
+        }
+        self.rate_limiter.cool_down();
+        let cooldown = self.rate_limiter.cooldown();
+        tracing::warn!(
+            cooldown_secs = cooldown.as_secs(),
+            "Kuro API rate limited the request, pausing requests"
+        );
+        Err(Error::RateLimited {
+            retry_after_secs: cooldown.as_secs(),
         })
     }
 
@@ -101,6 +138,7 @@ impl KuroClient {
     /// Returns an error if the preflight request fails.
     async fn send_preflight(&self, url: &str) -> Result<()> {
         tracing::debug!(url = %url, "Sending CORS preflight OPTIONS request");
+        self.rate_limiter.acquire().await;
         let started = Instant::now();
 
         let response = self
@@ -124,6 +162,7 @@ impl KuroClient {
             "Preflight response received"
         );
 
+        self.check_rate_limited(status)?;
         if !status.is_success() {
             tracing::warn!(status = %status, "Preflight request failed");
             return Err(Error::Client(ClientError::api_error(
@@ -156,6 +195,7 @@ impl KuroClient {
             region = %region,
             "Kuro API POST request to queryRole"
         );
+        self.rate_limiter.acquire().await;
         let post_started = Instant::now();
 
         // Make the POST request
@@ -167,6 +207,7 @@ impl KuroClient {
             elapsed_ms = post_started.elapsed().as_millis(),
             "Kuro API response received"
         );
+        self.check_rate_limited(status)?;
 
         let api_response: KuroApiResponse<serde_json::Value> = response.json().await?;
 
@@ -212,8 +253,10 @@ impl KuroClient {
     ///
     /// This method first sends a CORS preflight OPTIONS request, then makes the
     /// actual POST request to fetch the role data. If the server returns
-    /// code 1005 (retry requested), the request will be retried up to 3
-    /// times with exponential backoff and jitter.
+    /// code 1005 (retry requested) or rate limits the request, the request
+    /// will be retried up to 3 times with exponential backoff and jitter.
+    ///
+    /// Both requests wait for the rate limiter first.
     ///
     /// HTTP-level failures (5xx, timeouts) are automatically retried by the
     /// middleware.
@@ -230,7 +273,7 @@ impl KuroClient {
         storekeeper_client_core::retry::retry_with_backoff(
             &retry_config,
             || self.query_role_once(uid, region),
-            |err| matches!(err, Error::RetryRequested),
+            |err| err.kind() == ErrorKind::RateLimited,
         )
         .await
     }
@@ -2,8 +2,26 @@
 
 // Re-export base error for convenience
 pub use storekeeper_client_core::ClientError;
+pub use storekeeper_client_core::ErrorKind;
 use thiserror::Error;
 
+/// Kuro API codes with a known meaning.
+///
+/// Code 1005 (retry requested) surfaces as [`Error::RetryRequested`].
+const CODE_KINDS: [(i32, ErrorKind); 1] = [
+    // Login expired.
+    (220, ErrorKind::Auth),
+];
+
+/// Returns the kind of a Kuro API code, if it has a known meaning.
+#[must_use]
+pub fn code_kind(code: i32) -> Option<ErrorKind> {
+    CODE_KINDS
+        .iter()
+        .find(|(known, _)| *known == code)
+        .map(|(_, kind)| *kind)
+}
+
 /// Error type for Kuro Games API operations.
 #[derive(Error, Debug)]
 pub enum Error {
@@ -15,11 +33,33 @@ pub enum Error {
     #[error("Server requested retry (code 1005)")]
     RetryRequested,
 
+    /// Rate limit exceeded.
+    #[error("Rate limit exceeded, retry after {retry_after_secs} seconds")]
+    RateLimited {
+        /// Seconds to wait before retrying.
+        retry_after_secs: u64,
+    },
+
     /// Failed to parse nested JSON data.
     #[error("Failed to parse nested data: {0}")]
     NestedDataParseFailed(String),
 }
 
+impl Error {
+    /// Returns the error's kind, reading API error codes as Kuro codes first.
+    #[must_use]
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Self::Client(client) => client
+                .api_error_code()
+                .and_then(code_kind)
+                .unwrap_or_else(|| client.kind()),
+            Self::RetryRequested | Self::RateLimited { .. } => ErrorKind::RateLimited,
+            Self::NestedDataParseFailed(_) => ErrorKind::Protocol,
+        }
+    }
+}
+
 /// Result type alias using the Kuro Error type.
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -41,3 +81,21 @@ impl From<reqwest_middleware::Error> for Error {
         Self::Client(ClientError::from(err))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_map_onto_error_kinds() {
+        let kind = |code| Error::Client(ClientError::api_error(code, "")).kind();
+        assert_eq!(kind(220), ErrorKind::Auth);
+        assert_eq!(kind(429), ErrorKind::RateLimited);
+        assert_eq!(kind(1234), ErrorKind::Protocol);
+        assert_eq!(Error::RetryRequested.kind(), ErrorKind::RateLimited);
+        assert_eq!(
+            Error::NestedDataParseFailed(String::new()).kind(),
+            ErrorKind::Protocol
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! Account identifiers for games with multiple configured accounts.
+
+use crate::game_id::GameId;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Name of the implicit account described by a game's top-level config table.
+///
+/// The same name refers to the top-level `[hoyolab]` / `[kuro]` credentials in
+/// `secrets.toml`.
+pub const DEFAULT_ACCOUNT: &str = "default";
+
+/// Identifies one configured account of one game.
+///
+/// Runtime state (cached resources, client registries, notification cooldowns)
+/// is keyed by this pair so that several accounts of the same game can be
+/// tracked side by side.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountKey {
+    /// The game this account belongs to.
+    pub game_id: GameId,
+    /// The account name as written in `config.toml`.
+    pub account: String,
+}
+
+impl AccountKey {
+    /// Creates a key for a named account of a game.
+    #[must_use]
+    pub fn new(game_id: GameId, account: impl Into<String>) -> Self {
+        Self {
+            game_id,
+            account: account.into(),
+        }
+    }
+
+    /// Creates a key for the default account of a game.
+    #[must_use]
+    pub fn default_for(game_id: GameId) -> Self {
+        Self::new(game_id, DEFAULT_ACCOUNT)
+    }
+
+    /// Returns true if this key refers to the game's default account.
+    #[must_use]
+    pub fn is_default(&self) -> bool {
+        self.account == DEFAULT_ACCOUNT
+    }
+}
+
+impl std::fmt::Display for AccountKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.game_id.as_str(), self.account)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_key_is_default() {
+        let key = AccountKey::default_for(GameId::GenshinImpact);
+        assert!(key.is_default());
+        assert_eq!(key.account, DEFAULT_ACCOUNT);
+    }
+
+    #[test]
+    fn named_key_is_not_default() {
+        let key = AccountKey::new(GameId::HonkaiStarRail, "alt");
+        assert!(!key.is_default());
+    }
+
+    #[test]
+    fn display_includes_game_and_account() {
+        let key = AccountKey::new(GameId::WutheringWaves, "alt");
+        assert_eq!(key.to_string(), "WUTHERING_WAVES/alt");
+    }
+
+    #[test]
+    fn serializes_camel_case() {
+        let key = AccountKey::new(GameId::ZenlessZoneZero, "main");
+        let json = serde_json::to_value(&key).expect("should serialize");
+        assert_eq!(
+            json.get("gameId").and_then(serde_json::Value::as_str),
+            Some("ZENLESS_ZONE_ZERO")
+        );
+        assert_eq!(
+            json.get("account").and_then(serde_json::Value::as_str),
+            Some("main")
+        );
+    }
+}
@@ -0,0 +1,306 @@
+//! Game-agnostic views over the accounts configured for each game.
+
+use super::GamesConfig;
+use super::claim_time::ClaimTime;
+use super::games::GenshinConfig;
+use super::games::HsrConfig;
+use super::games::WuwaConfig;
+use super::games::ZzzConfig;
+use crate::account::AccountKey;
+use crate::account::DEFAULT_ACCOUNT;
+use crate::game_id::GameId;
+use crate::region::Region;
+
+/// Borrowed view of one configured account of a game.
+///
+/// Flattens the default (top-level) account and the named `accounts.<name>`
+/// entries into a single shape so callers don't need per-game matches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountView<'a> {
+    /// The game this account belongs to.
+    pub game_id: GameId,
+    /// The account name (`"default"` for the top-level account).
+    pub name: &'a str,
+    /// Whether both the game and this account are enabled.
+    pub enabled: bool,
+    /// Player UID.
+    pub uid: &'a str,
+    /// Optional region override.
+    pub region: Option<Region>,
+    /// Name of the credentials entry in `secrets.toml` used by this account.
+    pub credentials: &'a str,
+    /// Whether daily rewards should be auto-claimed for this account.
+    pub auto_claim_daily_rewards: bool,
+    /// Optional auto-claim time.
+    pub auto_claim_time: Option<ClaimTime>,
+}
+
+impl AccountView<'_> {
+    /// Returns the owned key identifying this account.
+    #[must_use]
+    pub fn key(&self) -> AccountKey {
+        AccountKey::new(self.game_id, self.name)
+    }
+}
+
+/// Resolves the credentials name for a named account.
+fn credentials_for<'a>(name: &'a str, credentials: Option<&'a String>) -> &'a str {
+    credentials.map_or(name, String::as_str)
+}
+
+/// Returns `false` (and logs) for named accounts that shadow the default one.
+fn is_valid_account_name(game_id: GameId, name: &str) -> bool {
+    if name == DEFAULT_ACCOUNT {
+        tracing::warn!(
+            game_id = ?game_id,
+            "Ignoring named account \"{DEFAULT_ACCOUNT}\"; it is reserved for the top-level game config"
+        );
+        return false;
+    }
+    true
+}
+
+macro_rules! hoyolab_account_views {
+    ($($cfg:ty => $game_id:expr;)*) => {$(
+        impl $cfg {
+            /// Returns views over the default account and every named account.
+            #[must_use]
+            pub fn account_views(&self) -> Vec<AccountView<'_>> {
+                let mut views = vec![AccountView {
+                    game_id: $game_id,
+                    name: DEFAULT_ACCOUNT,
+                    enabled: self.enabled,
+                    uid: &self.uid,
+                    region: self.region,
+                    credentials: DEFAULT_ACCOUNT,
+                    auto_claim_daily_rewards: self.auto_claim_daily_rewards,
+                    auto_claim_time: self.auto_claim_time,
+                }];
+                views.extend(
+                    self.accounts
+                        .iter()
+                        .filter(|(name, _)| is_valid_account_name($game_id, name))
+                        .map(|(name, account)| AccountView {
+                            game_id: $game_id,
+                            name,
+                            enabled: self.enabled && account.enabled,
+                            uid: &account.uid,
+                            region: account.region,
+                            credentials: credentials_for(name, account.credentials.as_ref()),
+                            auto_claim_daily_rewards: account.auto_claim_daily_rewards,
+                            auto_claim_time: account.auto_claim_time,
+                        }),
+                );
+                views
+            }
+        }
+    )*};
+}
+
+hoyolab_account_views! {
+    GenshinConfig => GameId::GenshinImpact;
+    HsrConfig => GameId::HonkaiStarRail;
+    ZzzConfig => GameId::ZenlessZoneZero;
+}
+
+impl WuwaConfig {
+    /// Returns views over the default account and every named account.
+    #[must_use]
+    pub fn account_views(&self) -> Vec<AccountView<'_>> {
+        let game_id = GameId::WutheringWaves;
+        let mut views = vec![AccountView {
+            game_id,
+            name: DEFAULT_ACCOUNT,
+            enabled: self.enabled,
+            uid: &self.uid,
+            region: self.region,
+            credentials: DEFAULT_ACCOUNT,
+            auto_claim_daily_rewards: false,
+            auto_claim_time: None,
+        }];
+        views.extend(
+            self.accounts
+                .iter()
+                .filter(|(name, _)| is_valid_account_name(game_id, name))
+                .map(|(name, account)| AccountView {
+                    game_id,
+                    name,
+                    enabled: self.enabled && account.enabled,
+                    uid: &account.uid,
+                    region: account.region,
+                    credentials: credentials_for(name, account.credentials.as_ref()),
+                    auto_claim_daily_rewards: false,
+                    auto_claim_time: None,
+                }),
+        );
+        views
+    }
+}
+
+impl GamesConfig {
+    /// Returns all accounts configured for a game, default account first.
+    ///
+    /// Returns an empty list if the game has no config section.
+    #[must_use]
+    pub fn accounts(&self, game_id: GameId) -> Vec<AccountView<'_>> {
+        match game_id {
+            GameId::GenshinImpact => self
+                .genshin_impact
+                .as_ref()
+                .map(GenshinConfig::account_views),
+            GameId::HonkaiStarRail => self.honkai_star_rail.as_ref().map(HsrConfig::account_views),
+            GameId::ZenlessZoneZero => self
+                .zenless_zone_zero
+                .as_ref()
+                .map(ZzzConfig::account_views),
+            GameId::WutheringWaves => self.wuthering_waves.as_ref().map(WuwaConfig::account_views),
+        }
+        .unwrap_or_default()
+    }
+
+    /// Returns all accounts across all games, in `GameId::all()` order.
+    #[must_use]
+    pub fn all_accounts(&self) -> Vec<AccountView<'_>> {
+        GameId::all()
+            .iter()
+            .flat_map(|&game_id| self.accounts(game_id))
+            .collect()
+    }
+
+    /// Returns the view for a single account, if configured.
+    #[must_use]
+    pub fn account(&self, key: &AccountKey) -> Option<AccountView<'_>> {
+        self.accounts(key.game_id)
+            .into_iter()
+            .find(|view| view.name == key.account)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AppConfig;
+
+    fn parse(toml_str: &str) -> AppConfig {
+        toml::from_str(toml_str).expect("should parse config")
+    }
+
+    #[test]
+    fn single_table_config_has_only_default_account() {
+        let config = parse(
+            r#"
+            [games.genshin_impact]
+            uid = "800000001"
+            "#,
+        );
+        let accounts = config.games.accounts(GameId::GenshinImpact);
+        assert_eq!(accounts.len(), 1);
+        let default = accounts.first().expect("should have default account");
+        assert_eq!(default.name, DEFAULT_ACCOUNT);
+        assert_eq!(default.uid, "800000001");
+        assert_eq!(default.credentials, DEFAULT_ACCOUNT);
+    }
+
+    #[test]
+    fn named_accounts_follow_default() {
+        let config = parse(
+            r#"
+            [games.honkai_star_rail]
+            uid = "600000001"
+
+            [games.honkai_star_rail.accounts.alt]
+            uid = "600000002"
+            auto_claim_daily_rewards = true
+
+            [games.honkai_star_rail.accounts.shared]
+            uid = "600000003"
+            credentials = "default"
+            "#,
+        );
+        let accounts = config.games.accounts(GameId::HonkaiStarRail);
+        let names: Vec<_> = accounts.iter().map(|a| a.name).collect();
+        assert_eq!(names, ["default", "alt", "shared"]);
+
+        let alt = config
+            .games
+            .account(&AccountKey::new(GameId::HonkaiStarRail, "alt"))
+            .expect("alt account should exist");
+        assert_eq!(alt.uid, "600000002");
+        assert_eq!(alt.credentials, "alt", "credentials default to the name");
+        assert!(alt.auto_claim_daily_rewards);
+
+        let shared = config
+            .games
+            .account(&AccountKey::new(GameId::HonkaiStarRail, "shared"))
+            .expect("shared account should exist");
+        assert_eq!(shared.credentials, DEFAULT_ACCOUNT);
+    }
+
+    #[test]
+    fn disabled_game_disables_named_accounts() {
+        let config = parse(
+            r#"
+            [games.wuthering_waves]
+            enabled = false
+            uid = "500000001"
+
+            [games.wuthering_waves.accounts.alt]
+            uid = "500000002"
+            "#,
+        );
+        assert!(
+            config
+                .games
+                .accounts(GameId::WutheringWaves)
+                .iter()
+                .all(|a| !a.enabled)
+        );
+    }
+
+    #[test]
+    fn named_default_account_is_ignored() {
+        let config = parse(
+            r#"
+            [games.zenless_zone_zero]
+            uid = "1000000001"
+
+            [games.zenless_zone_zero.accounts.default]
+            uid = "1000000002"
+            "#,
+        );
+        let accounts = config.games.accounts(GameId::ZenlessZoneZero);
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(
+            accounts.first().map(|a| a.uid),
+            Some("1000000001"),
+            "top-level config must win over a named \"default\" account"
+        );
+    }
+
+    #[test]
+    fn missing_game_has_no_accounts() {
+        let config = AppConfig::default();
+        assert!(config.games.accounts(GameId::GenshinImpact).is_empty());
+        assert!(config.games.all_accounts().is_empty());
+    }
+
+    #[test]
+    fn accounts_roundtrip_through_toml() {
+        let config = parse(
+            r#"
+            [games.genshin_impact]
+            uid = "800000001"
+
+            [games.genshin_impact.accounts.alt]
+            uid = "800000002"
+            credentials = "second"
+
+            [games.genshin_impact.accounts.alt.notifications.resin]
+            notify_minutes_before_full = 30
+            "#,
+        );
+        let serialized = toml::to_string_pretty(&config).expect("should serialize");
+        let reparsed: AppConfig = toml::from_str(&serialized).expect("should reparse");
+        assert_eq!(reparsed, config);
+    }
+}
@@ -4,6 +4,7 @@ use super::claim_time::ClaimTime;
 use super::claim_time::claim_time_serde;
 use super::default_true;
 use super::notification::ResourceNotificationConfig;
+use super::quiet_hours::QuietHours;
 use crate::region::Region;
 use crate::resource_types::GenshinResourceType;
 use crate::resource_types::HsrResourceType;
@@ -13,6 +14,7 @@ use serde::Deserialize;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use serde::de::Deserializer;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::hash::Hash;
 
@@ -49,12 +51,79 @@ pub struct GenshinConfig {
     /// Per-resource notification settings.
     #[serde(default, deserialize_with = "deserialize_genshin_notifications")]
     pub notifications: HashMap<GenshinResourceType, ResourceNotificationConfig>,
+
+    /// Quiet hours for every account of this game, replacing the global
+    /// `[[quiet_hours]]` when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet_hours: Option<Vec<QuietHours>>,
+
+    /// Time between polls of this game, replacing `[general]
+    /// poll_interval_secs` when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poll_interval_secs: Option<u64>,
+
+    /// Shortest time between adaptive polls of this game, replacing
+    /// `[adaptive_polling] min_interval_secs` when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_poll_interval_secs: Option<u64>,
+
+    /// Longest time between adaptive polls of this game, replacing
+    /// `[adaptive_polling] max_interval_secs` when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_poll_interval_secs: Option<u64>,
+
+    /// Additional named accounts for this game.
+    ///
+    /// The top-level fields above describe the `"default"` account; each entry
+    /// here is tracked alongside it with its own UID, credentials, tracked
+    /// resources and notifications.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub accounts: BTreeMap<String, GenshinAccountConfig>,
 }
 
 fn default_genshin_resources() -> Vec<GenshinResourceType> {
     GenshinResourceType::all().to_vec()
 }
 
+/// An additional Genshin Impact account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GenshinAccountConfig {
+    /// Whether this account is enabled.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Player UID.
+    pub uid: String,
+
+    /// Optional region override.
+    pub region: Option<Region>,
+
+    /// Name of the `[hoyolab_accounts.<name>]` entry in `secrets.toml` to
+    /// authenticate with. Defaults to the account's own name; `"default"`
+    /// selects the top-level `[hoyolab]` credentials.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<String>,
+
+    /// Resources to track.
+    #[serde(
+        default = "default_genshin_resources",
+        deserialize_with = "deserialize_genshin_tracked_resources"
+    )]
+    pub tracked_resources: Vec<GenshinResourceType>,
+
+    /// Whether to auto-claim daily rewards for this account.
+    #[serde(default)]
+    pub auto_claim_daily_rewards: bool,
+
+    /// Optional time to auto-claim daily rewards in HH:MM format (UTC+8).
+    #[serde(default, with = "claim_time_serde")]
+    pub auto_claim_time: Option<ClaimTime>,
+
+    /// Per-resource notification settings.
+    #[serde(default, deserialize_with = "deserialize_genshin_notifications")]
+    pub notifications: HashMap<GenshinResourceType, ResourceNotificationConfig>,
+}
+
 /// Honkai: Star Rail specific configuration.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct HsrConfig {
@@ -88,12 +157,79 @@ pub struct HsrConfig {
     /// Per-resource notification settings.
     #[serde(default, deserialize_with = "deserialize_hsr_notifications")]
     pub notifications: HashMap<HsrResourceType, ResourceNotificationConfig>,
+
+    /// Quiet hours for every account of this game, replacing the global
+    /// `[[quiet_hours]]` when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet_hours: Option<Vec<QuietHours>>,
+
+    /// Time between polls of this game, replacing `[general]
+    /// poll_interval_secs` when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poll_interval_secs: Option<u64>,
+
+    /// Shortest time between adaptive polls of this game, replacing
+    /// `[adaptive_polling] min_interval_secs` when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_poll_interval_secs: Option<u64>,
+
+    /// Longest time between adaptive polls of this game, replacing
+    /// `[adaptive_polling] max_interval_secs` when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_poll_interval_secs: Option<u64>,
+
+    /// Additional named accounts for this game.
+    ///
+    /// The top-level fields above describe the `"default"` account; each entry
+    /// here is tracked alongside it with its own UID, credentials, tracked
+    /// resources and notifications.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub accounts: BTreeMap<String, HsrAccountConfig>,
 }
 
 fn default_hsr_resources() -> Vec<HsrResourceType> {
     HsrResourceType::all().to_vec()
 }
 
+/// An additional Honkai: Star Rail account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HsrAccountConfig {
+    /// Whether this account is enabled.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Player UID.
+    pub uid: String,
+
+    /// Optional region override.
+    pub region: Option<Region>,
+
+    /// Name of the `[hoyolab_accounts.<name>]` entry in `secrets.toml` to
+    /// authenticate with. Defaults to the account's own name; `"default"`
+    /// selects the top-level `[hoyolab]` credentials.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<String>,
+
+    /// Resources to track.
+    #[serde(
+        default = "default_hsr_resources",
+        deserialize_with = "deserialize_hsr_tracked_resources"
+    )]
+    pub tracked_resources: Vec<HsrResourceType>,
+
+    /// Whether to auto-claim daily rewards for this account.
+    #[serde(default)]
+    pub auto_claim_daily_rewards: bool,
+
+    /// Optional time to auto-claim daily rewards in HH:MM format (UTC+8).
+    #[serde(default, with = "claim_time_serde")]
+    pub auto_claim_time: Option<ClaimTime>,
+
+    /// Per-resource notification settings.
+    #[serde(default, deserialize_with = "deserialize_hsr_notifications")]
+    pub notifications: HashMap<HsrResourceType, ResourceNotificationConfig>,
+}
+
 /// Zenless Zone Zero specific configuration.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ZzzConfig {
@@ -127,12 +263,79 @@ pub struct ZzzConfig {
     /// Per-resource notification settings.
     #[serde(default, deserialize_with = "deserialize_zzz_notifications")]
     pub notifications: HashMap<ZzzResourceType, ResourceNotificationConfig>,
+
+    /// Quiet hours for every account of this game, replacing the global
+    /// `[[quiet_hours]]` when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet_hours: Option<Vec<QuietHours>>,
+
+    /// Time between polls of this game, replacing `[general]
+    /// poll_interval_secs` when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poll_interval_secs: Option<u64>,
+
+    /// Shortest time between adaptive polls of this game, replacing
+    /// `[adaptive_polling] min_interval_secs` when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_poll_interval_secs: Option<u64>,
+
+    /// Longest time between adaptive polls of this game, replacing
+    /// `[adaptive_polling] max_interval_secs` when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_poll_interval_secs: Option<u64>,
+
+    /// Additional named accounts for this game.
+    ///
+    /// The top-level fields above describe the `"default"` account; each entry
+    /// here is tracked alongside it with its own UID, credentials, tracked
+    /// resources and notifications.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub accounts: BTreeMap<String, ZzzAccountConfig>,
 }
 
 fn default_zzz_resources() -> Vec<ZzzResourceType> {
     ZzzResourceType::all().to_vec()
 }
 
+/// An additional Zenless Zone Zero account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZzzAccountConfig {
+    /// Whether this account is enabled.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Player UID.
+    pub uid: String,
+
+    /// Optional region override.
+    pub region: Option<Region>,
+
+    /// Name of the `[hoyolab_accounts.<name>]` entry in `secrets.toml` to
+    /// authenticate with. Defaults to the account's own name; `"default"`
+    /// selects the top-level `[hoyolab]` credentials.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<String>,
+
+    /// Resources to track.
+    #[serde(
+        default = "default_zzz_resources",
+        deserialize_with = "deserialize_zzz_tracked_resources"
+    )]
+    pub tracked_resources: Vec<ZzzResourceType>,
+
+    /// Whether to auto-claim daily rewards for this account.
+    #[serde(default)]
+    pub auto_claim_daily_rewards: bool,
+
+    /// Optional time to auto-claim daily rewards in HH:MM format (UTC+8).
+    #[serde(default, with = "claim_time_serde")]
+    pub auto_claim_time: Option<ClaimTime>,
+
+    /// Per-resource notification settings.
+    #[serde(default, deserialize_with = "deserialize_zzz_notifications")]
+    pub notifications: HashMap<ZzzResourceType, ResourceNotificationConfig>,
+}
+
 /// Wuthering Waves specific configuration.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WuwaConfig {
@@ -156,12 +359,71 @@ pub struct WuwaConfig {
     /// Per-resource notification settings.
     #[serde(default, deserialize_with = "deserialize_wuwa_notifications")]
     pub notifications: HashMap<WuwaResourceType, ResourceNotificationConfig>,
+
+    /// Quiet hours for every account of this game, replacing the global
+    /// `[[quiet_hours]]` when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quiet_hours: Option<Vec<QuietHours>>,
+
+    /// Time between polls of this game, replacing `[general]
+    /// poll_interval_secs` when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub poll_interval_secs: Option<u64>,
+
+    /// Shortest time between adaptive polls of this game, replacing
+    /// `[adaptive_polling] min_interval_secs` when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_poll_interval_secs: Option<u64>,
+
+    /// Longest time between adaptive polls of this game, replacing
+    /// `[adaptive_polling] max_interval_secs` when set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_poll_interval_secs: Option<u64>,
+
+    /// Additional named accounts for this game.
+    ///
+    /// The top-level fields above describe the `"default"` account; each entry
+    /// here is tracked alongside it with its own UID, credentials, tracked
+    /// resources and notifications.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub accounts: BTreeMap<String, WuwaAccountConfig>,
 }
 
 fn default_wuwa_resources() -> Vec<WuwaResourceType> {
     WuwaResourceType::all().to_vec()
 }
 
+/// An additional Wuthering Waves account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WuwaAccountConfig {
+    /// Whether this account is enabled.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// UID.
+    pub uid: String,
+
+    /// Optional region override.
+    pub region: Option<Region>,
+
+    /// Name of the `[kuro_accounts.<name>]` entry in `secrets.toml` to
+    /// authenticate with. Defaults to the account's own name; `"default"`
+    /// selects the top-level `[kuro]` credentials (or the launcher cache).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credentials: Option<String>,
+
+    /// Resources to track.
+    #[serde(
+        default = "default_wuwa_resources",
+        deserialize_with = "deserialize_wuwa_tracked_resources"
+    )]
+    pub tracked_resources: Vec<WuwaResourceType>,
+
+    /// Per-resource notification settings.
+    #[serde(default, deserialize_with = "deserialize_wuwa_notifications")]
+    pub notifications: HashMap<WuwaResourceType, ResourceNotificationConfig>,
+}
+
 fn parse_resource_key<T: DeserializeOwned>(key: &str) -> Option<T> {
     serde_json::from_value::<T>(serde_json::Value::String(key.to_string())).ok()
 }
@@ -4,29 +4,60 @@
 //! - `config.toml`: Non-sensitive settings that can be synced across machines
 //! - `secrets.toml`: Sensitive credentials that must be set manually
 
+pub mod accounts;
 pub mod claim_time;
 pub mod games;
 pub mod notification;
+pub mod quiet_hours;
 pub mod secrets;
 
+use crate::account::AccountKey;
+use crate::account::DEFAULT_ACCOUNT;
 use crate::error::Error;
 use crate::error::Result;
+use crate::game_id::GameId;
 use crate::resource_types::GenshinResourceType;
 use crate::resource_types::HsrResourceType;
 use crate::resource_types::WuwaResourceType;
 use crate::resource_types::ZzzResourceType;
+pub use accounts::AccountView;
 use camino::Utf8Path;
 use camino::Utf8PathBuf;
 // Re-exports: keep the same public surface as the original single-file module.
 pub use claim_time::{ClaimTime, DEFAULT_AUTO_CLAIM_TIME, next_claim_datetime_utc};
+pub use games::GenshinAccountConfig;
 pub use games::GenshinConfig;
+pub use games::HsrAccountConfig;
 pub use games::HsrConfig;
+pub use games::WuwaAccountConfig;
 pub use games::WuwaConfig;
+pub use games::ZzzAccountConfig;
 pub use games::ZzzConfig;
+pub use notification::DEFAULT_RULE_ID;
+pub use notification::EmailChannelConfig;
+pub use notification::GotifyChannelConfig;
+pub use notification::HookChannelConfig;
+pub use notification::HookEvent;
+pub use notification::NotificationBatchConfig;
+pub use notification::NotificationChannelConfig;
+pub use notification::NotificationRule;
+pub use notification::NtfyChannelConfig;
+pub use notification::PushPriority;
 pub use notification::ResourceNotificationConfig;
+pub use notification::SmtpSecurity;
+pub use notification::WebhookChannelConfig;
+pub use notification::WebhookPreset;
+pub use quiet_hours::QuietHours;
+pub use quiet_hours::Weekday;
+pub use quiet_hours::in_quiet_hours;
+pub use secrets::HttpApiSecrets;
+pub use secrets::MqttSecrets;
+pub use secrets::NotificationChannelSecrets;
 pub use secrets::SecretsConfig;
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 // ============================================================================
@@ -45,6 +76,22 @@ fn default_log_level() -> String {
     "info".to_string()
 }
 
+fn default_history_retention_days() -> u32 {
+    90
+}
+
+fn default_history_compact_after_days() -> u32 {
+    7
+}
+
+fn default_history_compact_interval_mins() -> u32 {
+    60
+}
+
+fn default_http_api_port() -> u16 {
+    DEFAULT_HTTP_API_PORT
+}
+
 /// Converts an OS path obtained from `dirs` into a UTF-8 path.
 ///
 /// This is the boundary where we leave `std::path` behind; everything past it
@@ -59,6 +106,240 @@ fn to_utf8_path(path: PathBuf) -> Result<Utf8PathBuf> {
 // AppConfig
 // ============================================================================
 
+/// Template written to `config.toml` when no config file exists.
+const DEFAULT_CONFIG_CONTENT: &str = r#"# Storekeeper Configuration
+# This file contains non-sensitive application settings.
+#
+# For sensitive credentials (API tokens, cookies), see secrets.toml
+
+[general]
+# Polling interval in seconds (default: 300 = 5 minutes)
+poll_interval_secs = 300
+
+# Start the app minimized to system tray (default: true)
+start_minimized = true
+
+# Log level: error, warn, info, debug, trace (default: info)
+log_level = "info"
+
+# Automatically start the app when the system boots (default: false)
+autostart = false
+
+# Record fetched resources in a local history file (default: true)
+history_enabled = true
+
+# Days of resource history to keep; 0 keeps everything (default: 90)
+history_retention_days = 90
+
+# History older than this many days is thinned to one sample per resource per
+# history_compact_interval_mins (defaults: 7 days, 60 minutes)
+history_compact_after_days = 7
+history_compact_interval_mins = 60
+
+# Send a weekly summary of stamina wasted while resources were full
+# (default: false)
+overcap_weekly_digest = false
+
+# Write Prometheus metrics to this file after every refresh, for
+# node_exporter's textfile collector (default: not written). The same metrics
+# are served at /metrics by the HTTP API below.
+# metrics_textfile = "/var/lib/node_exporter/textfile_collector/storekeeper.prom"
+
+# Adaptive polling (default: disabled): instead of every poll_interval_secs,
+# poll each game lead_secs before its next resource is predicted to cross a
+# notification threshold, at most every min_interval_secs and at least every
+# max_interval_secs. A game can set its own min_poll_interval_secs and
+# max_poll_interval_secs. Games on the same API are fetched together.
+[adaptive_polling]
+enabled = false
+min_interval_secs = 60
+max_interval_secs = 1800
+lead_secs = 120
+
+# Requests allowed per API provider, shared by all of its accounts. After the
+# API answers that it is called too often, every request to it waits
+# cooldown_secs before trying again.
+[rate_limits]
+hoyolab_requests_per_minute = 30
+hoyolab_burst = 5
+kuro_requests_per_minute = 20
+kuro_burst = 4
+cooldown_secs = 30
+
+# Local HTTP API on http://127.0.0.1:<port> for scripts and dashboards.
+# Requests need `Authorization: Bearer <token>` with the [http_api] token
+# from secrets.toml; the server stays off without one (default: disabled)
+[http_api]
+enabled = false
+port = 7870
+
+# Publish resources to an MQTT broker, with Home Assistant discovery so each
+# resource shows up as sensors and "Refresh" / "Claim daily reward" buttons.
+# The broker login goes in secrets.toml under [mqtt] (default: disabled)
+[mqtt]
+enabled = false
+host = "localhost"
+port = 1883
+# tls = false
+# base_topic = "storekeeper"
+# discovery = true
+# discovery_prefix = "homeassistant"
+
+# Group resource notifications that fire together into one notification per
+# channel (default: disabled). With window_minutes = 0 only those due in the
+# same check are grouped; otherwise the first one waits that long for others.
+[notification_batch]
+enabled = false
+window_minutes = 0
+
+# Extra notification channels (optional). Notifications go to the built-in
+# channel ("os" on the desktop, "log" in the daemon) and every channel here,
+# unless a resource lists `channels`. Webhook URLs, push tokens and SMTP
+# logins go in secrets.toml under the same [notification_channels.<name>] table.
+#
+# [notification_channels.phone]
+# type = "webhook"
+# preset = "discord"  # "discord", "slack" or "json"
+# template = "{title}: {body}"  # Optional message text
+#
+# [notification_channels.ntfy]
+# type = "ntfy"
+# server = "https://ntfy.sh"  # Default
+# topic = "my-storekeeper-alerts"
+# priority = { full = 4, approaching = 3 }  # ntfy priorities 1-5 (default)
+# tags = { genshin_impact = ["crystal_ball"], wuthering_waves = ["ocean"] }
+#
+# [notification_channels.gotify]
+# type = "gotify"
+# server = "https://gotify.example.com"
+# priority = { full = 8, approaching = 5 }  # Gotify priorities 0-10 (default)
+#
+# [notification_channels.email]
+# type = "email"
+# host = "smtp.example.com"
+# port = 587  # Default
+# security = "starttls"  # "starttls" (default), "tls" or "none"
+# from = "Storekeeper <storekeeper@example.com>"
+# to = ["me@example.com"]
+# alerts = true  # Email resource notifications as they fire (default)
+# digest_time = "08:00"  # Optional daily digest, in local time
+#
+# [notification_channels.script]
+# type = "hook"
+# command = "/usr/local/bin/on-storekeeper"  # Gets the event as JSON on stdin
+# args = []
+# timeout_secs = 10  # Default
+# events = ["resource_full", "claim_failed", "auth_expired"]  # Default: all
+
+# Quiet hours (optional): resource notifications that fire inside a window are
+# held back and sent as one summary when it ends. Times are local; a window
+# ending before it starts spans midnight. `days` lists the days it starts on
+# (default: every day). Add [[games.<game>.quiet_hours]] to give a game its own
+# windows instead, or set `quiet_hours = []` in a game to never hold it back.
+#
+# [[quiet_hours]]
+# start = "23:00"
+# end = "07:30"
+# days = ["mon", "tue", "wed", "thu", "fri"]
+
+# =============================================================================
+# GAME CONFIGURATION
+# =============================================================================
+# Enable only the games you play. Each game requires:
+# 1. enabled = true
+# 2. Your UID/Player ID
+# 3. Credentials in secrets.toml
+#
+# HoYoLab games (Genshin, HSR, ZZZ) support auto-claiming daily rewards:
+#   auto_claim_daily_rewards = true/false
+#   auto_claim_time = "HH:MM"  # Optional, in UTC+8. Defaults to "00:00" (midnight)
+#
+# Per-resource notifications (optional):
+#   [games.<game>.notifications.<resource_type>]
+#   enabled = true
+#   notify_minutes_before_full = 60  # Start notifying 60 min before full
+#   # notify_at_value = 180          # OR: notify when value reaches 180 (stamina resources only)
+#   cooldown_minutes = 10            # Minutes between repeated notifications
+#   # channels = ["os", "phone"]     # Only these channels (default: all)
+#   # critical = true                # Notify during quiet hours too
+#   # template = "{current}/{max}, full at {local_time}"  # Custom body
+#
+# Several rules per resource (each with its own id, cooldown, channels and
+# template) replace the threshold above:
+#   [[games.<game>.notifications.<resource_type>.rules]]
+#   id = "at_120"
+#   notify_at_value = 120
+#   [[games.<game>.notifications.<resource_type>.rules]]
+#   id = "while_full"                # No threshold: notify once full
+#   cooldown_minutes = 120           # ...and every 2 hours while it stays full
+#
+# Poll interval (optional, seconds), replacing [general] poll_interval_secs:
+#   poll_interval_secs = 900
+#
+# Adaptive polling bounds (optional, seconds), replacing [adaptive_polling]'s:
+#   min_poll_interval_secs = 300
+#   max_poll_interval_secs = 3600
+#
+# Multiple accounts (optional): the top-level game table is the "default"
+# account. Add more with [games.<game>.accounts.<name>]; each takes its own
+# uid, region, tracked_resources, notifications and (HoYoLab) auto-claim
+# settings. `credentials` selects the login from secrets.toml and defaults to
+# the account name ("default" = the top-level [hoyolab]/[kuro] credentials):
+#   [games.genshin_impact.accounts.alt]
+#   uid = "800000002"
+#   credentials = "alt"  # -> [hoyolab_accounts.alt] in secrets.toml
+
+# Genshin Impact
+[games.genshin_impact]
+enabled = false
+uid = ""
+# region = "os_usa"  # Optional: auto-detected from UID
+# auto_claim_daily_rewards = false
+# auto_claim_time = "00:00"  # Optional: HH:MM in UTC+8 (China Standard Time)
+#
+# [games.genshin_impact.notifications.resin]
+# enabled = true
+# notify_minutes_before_full = 60
+# cooldown_minutes = 10
+
+# Honkai: Star Rail
+[games.honkai_star_rail]
+enabled = false
+uid = ""
+# region = "prod_official_usa"  # Optional: auto-detected from UID
+# auto_claim_daily_rewards = false
+# auto_claim_time = "00:00"  # Optional: HH:MM in UTC+8 (China Standard Time)
+#
+# [games.honkai_star_rail.notifications.trailblaze_power]
+# enabled = true
+# notify_minutes_before_full = 30
+# cooldown_minutes = 15
+
+# Zenless Zone Zero
+[games.zenless_zone_zero]
+enabled = false
+uid = ""
+# region = "prod_gf_us"  # Optional: auto-detected from UID
+# auto_claim_daily_rewards = false
+# auto_claim_time = "00:00"  # Optional: HH:MM in UTC+8 (China Standard Time)
+#
+# [games.zenless_zone_zero.notifications.battery]
+# enabled = true
+# notify_minutes_before_full = 30
+# cooldown_minutes = 15
+
+# Wuthering Waves
+[games.wuthering_waves]
+enabled = false
+uid = ""
+# region = "na"  # Optional: auto-detected from player ID
+#
+# [games.wuthering_waves.notifications.waveplates]
+# enabled = true
+# notify_minutes_before_full = 30
+# cooldown_minutes = 15
+"#;
+
 /// Main application configuration loaded from `config.toml`.
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct AppConfig {
@@ -66,9 +347,37 @@ pub struct AppConfig {
     #[serde(default)]
     pub general: GeneralConfig,
 
+    /// Poll intervals driven by predicted notification thresholds.
+    #[serde(default)]
+    pub adaptive_polling: AdaptivePollingConfig,
+
+    /// Request rates allowed per API provider.
+    #[serde(default)]
+    pub rate_limits: RateLimitsConfig,
+
     /// Per-game configuration.
     #[serde(default)]
     pub games: GamesConfig,
+
+    /// Local HTTP API settings.
+    #[serde(default)]
+    pub http_api: HttpApiConfig,
+
+    /// MQTT publishing and Home Assistant discovery settings.
+    #[serde(default)]
+    pub mqtt: MqttConfig,
+
+    /// Grouping of resource notifications that fire close together.
+    #[serde(default)]
+    pub notification_batch: NotificationBatchConfig,
+
+    /// Extra notification channels, keyed by the name resources route to.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub notification_channels: BTreeMap<String, NotificationChannelConfig>,
+
+    /// Local-time windows during which resource notifications are deferred.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub quiet_hours: Vec<QuietHours>,
 }
 
 impl AppConfig {
@@ -183,93 +492,35 @@ impl AppConfig {
 
     /// Returns the default config file content with helpful comments.
     fn default_config_content() -> &'static str {
-        r#"# Storekeeper Configuration
-# This file contains non-sensitive application settings.
-#
-# For sensitive credentials (API tokens, cookies), see secrets.toml
-
-[general]
-# Polling interval in seconds (default: 300 = 5 minutes)
-poll_interval_secs = 300
-
-# Start the app minimized to system tray (default: true)
-start_minimized = true
-
-# Log level: error, warn, info, debug, trace (default: info)
-log_level = "info"
-
-# Automatically start the app when the system boots (default: false)
-autostart = false
-
-# =============================================================================
-# GAME CONFIGURATION
-# =============================================================================
-# Enable only the games you play. Each game requires:
-# 1. enabled = true
-# 2. Your UID/Player ID
-# 3. Credentials in secrets.toml
-#
-# HoYoLab games (Genshin, HSR, ZZZ) support auto-claiming daily rewards:
-#   auto_claim_daily_rewards = true/false
-#   auto_claim_time = "HH:MM"  # Optional, in UTC+8. Defaults to "00:00" (midnight)
-#
-# Per-resource notifications (optional):
-#   [games.<game>.notifications.<resource_type>]
-#   enabled = true
-#   notify_minutes_before_full = 60  # Start notifying 60 min before full
-#   # notify_at_value = 180          # OR: notify when value reaches 180 (stamina resources only)
-#   cooldown_minutes = 10            # Minutes between repeated notifications
-
-# Genshin Impact
-[games.genshin_impact]
-enabled = false
-uid = ""
-# region = "os_usa"  # Optional: auto-detected from UID
-# auto_claim_daily_rewards = false
-# auto_claim_time = "00:00"  # Optional: HH:MM in UTC+8 (China Standard Time)
-#
-# [games.genshin_impact.notifications.resin]
-# enabled = true
-# notify_minutes_before_full = 60
-# cooldown_minutes = 10
+        DEFAULT_CONFIG_CONTENT
+    }
 
-# Honkai: Star Rail
-[games.honkai_star_rail]
-enabled = false
-uid = ""
-# region = "prod_official_usa"  # Optional: auto-detected from UID
-# auto_claim_daily_rewards = false
-# auto_claim_time = "00:00"  # Optional: HH:MM in UTC+8 (China Standard Time)
-#
-# [games.honkai_star_rail.notifications.trailblaze_power]
-# enabled = true
-# notify_minutes_before_full = 30
-# cooldown_minutes = 15
+    /// Returns the shortest and longest time between adaptive polls of a
+    /// game, in seconds: its own bounds where set, otherwise the global ones.
+    ///
+    /// The longest is never below the shortest.
+    #[must_use]
+    pub fn adaptive_poll_bounds(&self, game_id: GameId) -> (u64, u64) {
+        let (min, max) = self.games.poll_interval_bounds(game_id);
+        let min = min.unwrap_or(self.adaptive_polling.min_interval_secs);
+        let max = max.unwrap_or(self.adaptive_polling.max_interval_secs);
+        (min, max.max(min))
+    }
 
-# Zenless Zone Zero
-[games.zenless_zone_zero]
-enabled = false
-uid = ""
-# region = "prod_gf_us"  # Optional: auto-detected from UID
-# auto_claim_daily_rewards = false
-# auto_claim_time = "00:00"  # Optional: HH:MM in UTC+8 (China Standard Time)
-#
-# [games.zenless_zone_zero.notifications.battery]
-# enabled = true
-# notify_minutes_before_full = 30
-# cooldown_minutes = 15
+    /// Returns the poll interval of a game in seconds: its own if set,
+    /// otherwise `[general] poll_interval_secs`.
+    #[must_use]
+    pub fn poll_interval_for(&self, game_id: GameId) -> u64 {
+        self.games
+            .poll_interval(game_id)
+            .unwrap_or(self.general.poll_interval_secs)
+    }
 
-# Wuthering Waves
-[games.wuthering_waves]
-enabled = false
-uid = ""
-# region = "na"  # Optional: auto-detected from player ID
-#
-# [games.wuthering_waves.notifications.waveplates]
-# enabled = true
-# notify_minutes_before_full = 30
-# cooldown_minutes = 15
-"#
+    /// Returns the quiet hours of a game: its own if set, otherwise the
+    /// global ones.
+    #[must_use]
+    pub fn quiet_hours_for(&self, game_id: GameId) -> &[QuietHours] {
+        self.games.quiet_hours(game_id).unwrap_or(&self.quiet_hours)
     }
 }
 
@@ -279,6 +530,10 @@ uid = ""
 
 /// General application settings.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[expect(
+    clippy::struct_excessive_bools,
+    reason = "independent on/off settings mirrored 1:1 in config.toml"
+)]
 pub struct GeneralConfig {
     /// Polling interval in seconds.
     #[serde(default = "default_poll_interval")]
@@ -300,6 +555,33 @@ pub struct GeneralConfig {
     /// Whether to automatically start the app at system login.
     #[serde(default)]
     pub autostart: bool,
+
+    /// Whether to record fetched resources in the local history store.
+    #[serde(default = "default_true")]
+    pub history_enabled: bool,
+
+    /// Days of resource history to keep. `0` keeps history forever.
+    #[serde(default = "default_history_retention_days")]
+    pub history_retention_days: u32,
+
+    /// Age in days after which history samples are thinned out.
+    #[serde(default = "default_history_compact_after_days")]
+    pub history_compact_after_days: u32,
+
+    /// Bucket size in minutes for thinned-out history: compacted samples keep
+    /// one entry per resource per bucket.
+    #[serde(default = "default_history_compact_interval_mins")]
+    pub history_compact_interval_mins: u32,
+
+    /// Whether to send a weekly notification summarizing stamina wasted while
+    /// resources sat at max.
+    #[serde(default)]
+    pub overcap_weekly_digest: bool,
+
+    /// File to write Prometheus metrics to after every refresh, for
+    /// node_exporter's textfile collector. When `None`, no file is written.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metrics_textfile: Option<String>,
 }
 
 impl Default for GeneralConfig {
@@ -310,6 +592,242 @@ impl Default for GeneralConfig {
             log_level: default_log_level(),
             language: None,
             autostart: false,
+            history_enabled: true,
+            history_retention_days: default_history_retention_days(),
+            history_compact_after_days: default_history_compact_after_days(),
+            history_compact_interval_mins: default_history_compact_interval_mins(),
+            overcap_weekly_digest: false,
+            metrics_textfile: None,
+        }
+    }
+}
+
+// ============================================================================
+// AdaptivePollingConfig
+// ============================================================================
+
+/// Adaptive polling settings.
+///
+/// When enabled, each game is polled shortly before its next predicted
+/// notification threshold crossing instead of every `poll_interval_secs`,
+/// but never more often than `min_interval_secs` or less often than
+/// `max_interval_secs`. Games can override both bounds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdaptivePollingConfig {
+    /// Whether to poll adaptively.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Shortest time between polls of a game, in seconds.
+    #[serde(default = "default_adaptive_min_interval")]
+    pub min_interval_secs: u64,
+
+    /// Longest time between polls of a game, in seconds.
+    #[serde(default = "default_adaptive_max_interval")]
+    pub max_interval_secs: u64,
+
+    /// How long before a predicted crossing to poll, in seconds.
+    #[serde(default = "default_adaptive_lead")]
+    pub lead_secs: u64,
+}
+
+fn default_adaptive_min_interval() -> u64 {
+    60
+}
+
+fn default_adaptive_max_interval() -> u64 {
+    1800
+}
+
+fn default_adaptive_lead() -> u64 {
+    120
+}
+
+impl Default for AdaptivePollingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            min_interval_secs: default_adaptive_min_interval(),
+            max_interval_secs: default_adaptive_max_interval(),
+            lead_secs: default_adaptive_lead(),
+        }
+    }
+}
+
+// ============================================================================
+// RateLimitsConfig
+// ============================================================================
+
+/// Request rates allowed per API provider.
+///
+/// Each provider has one token bucket shared by the clients of all its
+/// accounts: `*_burst` requests may go out back to back, then
+/// `*_requests_per_minute` on average.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimitsConfig {
+    /// Average HoYoLab requests per minute.
+    #[serde(default = "default_hoyolab_requests_per_minute")]
+    pub hoyolab_requests_per_minute: u32,
+
+    /// HoYoLab requests allowed back to back.
+    #[serde(default = "default_hoyolab_burst")]
+    pub hoyolab_burst: u32,
+
+    /// Average Kuro requests per minute.
+    #[serde(default = "default_kuro_requests_per_minute")]
+    pub kuro_requests_per_minute: u32,
+
+    /// Kuro requests allowed back to back.
+    #[serde(default = "default_kuro_burst")]
+    pub kuro_burst: u32,
+
+    /// How long all requests to a provider wait after it reports a rate
+    /// limit, in seconds.
+    #[serde(default = "default_rate_limit_cooldown")]
+    pub cooldown_secs: u64,
+}
+
+fn default_hoyolab_requests_per_minute() -> u32 {
+    30
+}
+
+fn default_hoyolab_burst() -> u32 {
+    5
+}
+
+fn default_kuro_requests_per_minute() -> u32 {
+    20
+}
+
+fn default_kuro_burst() -> u32 {
+    4
+}
+
+fn default_rate_limit_cooldown() -> u64 {
+    30
+}
+
+impl Default for RateLimitsConfig {
+    fn default() -> Self {
+        Self {
+            hoyolab_requests_per_minute: default_hoyolab_requests_per_minute(),
+            hoyolab_burst: default_hoyolab_burst(),
+            kuro_requests_per_minute: default_kuro_requests_per_minute(),
+            kuro_burst: default_kuro_burst(),
+            cooldown_secs: default_rate_limit_cooldown(),
+        }
+    }
+}
+
+// ============================================================================
+// HttpApiConfig
+// ============================================================================
+
+/// Default port of the local HTTP API.
+pub const DEFAULT_HTTP_API_PORT: u16 = 7870;
+
+/// Local HTTP API settings.
+///
+/// The server only ever binds to 127.0.0.1. Its bearer token lives in
+/// `secrets.toml` ([`HttpApiSecrets`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpApiConfig {
+    /// Whether to run the local HTTP API.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Port to listen on.
+    #[serde(default = "default_http_api_port")]
+    pub port: u16,
+}
+
+impl Default for HttpApiConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_HTTP_API_PORT,
+        }
+    }
+}
+
+// ============================================================================
+// MqttConfig
+// ============================================================================
+
+/// Default port of an MQTT broker.
+pub const DEFAULT_MQTT_PORT: u16 = 1883;
+
+/// MQTT publishing settings.
+///
+/// Resources are published under `<base_topic>/<game>/<account>/<resource>`,
+/// and Home Assistant discovery configs under `<discovery_prefix>`. The broker
+/// login lives in `secrets.toml` ([`MqttSecrets`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MqttConfig {
+    /// Whether to publish to an MQTT broker.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Broker host name.
+    #[serde(default = "default_mqtt_host")]
+    pub host: String,
+
+    /// Broker port.
+    #[serde(default = "default_mqtt_port")]
+    pub port: u16,
+
+    /// Whether to connect over TLS.
+    #[serde(default)]
+    pub tls: bool,
+
+    /// Client ID; also names the Home Assistant devices.
+    #[serde(default = "default_mqtt_client_id")]
+    pub client_id: String,
+
+    /// Topic prefix for resource states, availability and commands.
+    #[serde(default = "default_mqtt_base_topic")]
+    pub base_topic: String,
+
+    /// Whether to publish Home Assistant discovery configs.
+    #[serde(default = "default_true")]
+    pub discovery: bool,
+
+    /// Home Assistant discovery topic prefix.
+    #[serde(default = "default_mqtt_discovery_prefix")]
+    pub discovery_prefix: String,
+}
+
+fn default_mqtt_host() -> String {
+    "localhost".to_string()
+}
+
+fn default_mqtt_port() -> u16 {
+    DEFAULT_MQTT_PORT
+}
+
+fn default_mqtt_client_id() -> String {
+    "storekeeper".to_string()
+}
+
+fn default_mqtt_base_topic() -> String {
+    "storekeeper".to_string()
+}
+
+fn default_mqtt_discovery_prefix() -> String {
+    "homeassistant".to_string()
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: default_mqtt_host(),
+            port: DEFAULT_MQTT_PORT,
+            tls: false,
+            client_id: default_mqtt_client_id(),
+            base_topic: default_mqtt_base_topic(),
+            discovery: true,
+            discovery_prefix: default_mqtt_discovery_prefix(),
         }
     }
 }
@@ -336,7 +854,7 @@ pub struct GamesConfig {
 
 impl GamesConfig {
     fn genshin_notification_for<'a>(
-        cfg: &'a GenshinConfig,
+        notifications: &'a HashMap<GenshinResourceType, ResourceNotificationConfig>,
         resource_type: &str,
     ) -> Option<&'a ResourceNotificationConfig> {
         let resource = match resource_type {
@@ -346,148 +864,227 @@ impl GamesConfig {
             "expeditions" => GenshinResourceType::Expeditions,
             _ => return None,
         };
-        cfg.notifications.get(&resource)
+        notifications.get(&resource)
     }
 
     fn hsr_notification_for<'a>(
-        cfg: &'a HsrConfig,
+        notifications: &'a HashMap<HsrResourceType, ResourceNotificationConfig>,
         resource_type: &str,
     ) -> Option<&'a ResourceNotificationConfig> {
         let resource = match resource_type {
             "trailblaze_power" => HsrResourceType::TrailblazePower,
             _ => return None,
         };
-        cfg.notifications.get(&resource)
+        notifications.get(&resource)
     }
 
     fn zzz_notification_for<'a>(
-        cfg: &'a ZzzConfig,
+        notifications: &'a HashMap<ZzzResourceType, ResourceNotificationConfig>,
         resource_type: &str,
     ) -> Option<&'a ResourceNotificationConfig> {
         let resource = match resource_type {
             "battery" => ZzzResourceType::Battery,
             _ => return None,
         };
-        cfg.notifications.get(&resource)
+        notifications.get(&resource)
     }
 
     fn wuwa_notification_for<'a>(
-        cfg: &'a WuwaConfig,
+        notifications: &'a HashMap<WuwaResourceType, ResourceNotificationConfig>,
         resource_type: &str,
     ) -> Option<&'a ResourceNotificationConfig> {
         let resource = match resource_type {
             "waveplates" => WuwaResourceType::Waveplates,
             _ => return None,
         };
-        cfg.notifications.get(&resource)
+        notifications.get(&resource)
+    }
+
+    /// Selects the notification map of the default account or a named
+    /// account.
+    fn account_notifications<'a, K, A>(
+        default: &'a HashMap<K, ResourceNotificationConfig>,
+        accounts: &'a BTreeMap<String, A>,
+        account: &str,
+        notifications_of: impl FnOnce(&'a A) -> &'a HashMap<K, ResourceNotificationConfig>,
+    ) -> Option<&'a HashMap<K, ResourceNotificationConfig>> {
+        if account == DEFAULT_ACCOUNT {
+            Some(default)
+        } else {
+            accounts.get(account).map(notifications_of)
+        }
     }
 
     /// Converts a typed notification map to string-keyed map for the
     /// notification system.
     fn stringify_notification_map<K: AsRef<str>>(
-        notifications: &std::collections::HashMap<K, ResourceNotificationConfig>,
-    ) -> std::collections::HashMap<String, ResourceNotificationConfig> {
+        notifications: &HashMap<K, ResourceNotificationConfig>,
+    ) -> HashMap<String, ResourceNotificationConfig> {
         notifications
             .iter()
             .map(|(k, v)| (k.as_ref().to_string(), v.clone()))
             .collect()
     }
 
-    /// Notification configs for a game, with string keys for the notification
-    /// system.
+    /// Notification configs for an account, with string keys for the
+    /// notification system.
     ///
     /// Converts typed resource keys to strings via `AsRef<str>`.
     #[must_use]
     pub fn notification_configs(
         &self,
-        game_id: crate::GameId,
-    ) -> std::collections::HashMap<String, ResourceNotificationConfig> {
-        use crate::GameId;
-
-        match game_id {
-            GameId::GenshinImpact => self
-                .genshin_impact
-                .as_ref()
-                .map(|c| Self::stringify_notification_map(&c.notifications))
-                .unwrap_or_default(),
-            GameId::HonkaiStarRail => self
-                .honkai_star_rail
-                .as_ref()
-                .map(|c| Self::stringify_notification_map(&c.notifications))
-                .unwrap_or_default(),
-            GameId::ZenlessZoneZero => self
-                .zenless_zone_zero
-                .as_ref()
-                .map(|c| Self::stringify_notification_map(&c.notifications))
-                .unwrap_or_default(),
-            GameId::WutheringWaves => self
-                .wuthering_waves
-                .as_ref()
-                .map(|c| Self::stringify_notification_map(&c.notifications))
-                .unwrap_or_default(),
+        key: &AccountKey,
+    ) -> HashMap<String, ResourceNotificationConfig> {
+        let account = key.account.as_str();
+        match key.game_id {
+            GameId::GenshinImpact => self.genshin_impact.as_ref().and_then(|c| {
+                Self::account_notifications(&c.notifications, &c.accounts, account, |a| {
+                    &a.notifications
+                })
+                .map(Self::stringify_notification_map)
+            }),
+            GameId::HonkaiStarRail => self.honkai_star_rail.as_ref().and_then(|c| {
+                Self::account_notifications(&c.notifications, &c.accounts, account, |a| {
+                    &a.notifications
+                })
+                .map(Self::stringify_notification_map)
+            }),
+            GameId::ZenlessZoneZero => self.zenless_zone_zero.as_ref().and_then(|c| {
+                Self::account_notifications(&c.notifications, &c.accounts, account, |a| {
+                    &a.notifications
+                })
+                .map(Self::stringify_notification_map)
+            }),
+            GameId::WutheringWaves => self.wuthering_waves.as_ref().and_then(|c| {
+                Self::account_notifications(&c.notifications, &c.accounts, account, |a| {
+                    &a.notifications
+                })
+                .map(Self::stringify_notification_map)
+            }),
         }
+        .unwrap_or_default()
     }
 
-    /// Returns true if a game has any notification configs defined.
+    /// Returns true if an account has any notification configs defined.
     #[must_use]
-    pub fn has_notification_configs(&self, game_id: crate::GameId) -> bool {
-        use crate::GameId;
-
-        match game_id {
-            GameId::GenshinImpact => self
-                .genshin_impact
-                .as_ref()
-                .is_some_and(|c| !c.notifications.is_empty()),
-            GameId::HonkaiStarRail => self
-                .honkai_star_rail
-                .as_ref()
-                .is_some_and(|c| !c.notifications.is_empty()),
-            GameId::ZenlessZoneZero => self
-                .zenless_zone_zero
-                .as_ref()
-                .is_some_and(|c| !c.notifications.is_empty()),
-            GameId::WutheringWaves => self
-                .wuthering_waves
-                .as_ref()
-                .is_some_and(|c| !c.notifications.is_empty()),
+    pub fn has_notification_configs(&self, key: &AccountKey) -> bool {
+        let account = key.account.as_str();
+        match key.game_id {
+            GameId::GenshinImpact => self.genshin_impact.as_ref().is_some_and(|c| {
+                Self::account_notifications(&c.notifications, &c.accounts, account, |a| {
+                    &a.notifications
+                })
+                .is_some_and(|n| !n.is_empty())
+            }),
+            GameId::HonkaiStarRail => self.honkai_star_rail.as_ref().is_some_and(|c| {
+                Self::account_notifications(&c.notifications, &c.accounts, account, |a| {
+                    &a.notifications
+                })
+                .is_some_and(|n| !n.is_empty())
+            }),
+            GameId::ZenlessZoneZero => self.zenless_zone_zero.as_ref().is_some_and(|c| {
+                Self::account_notifications(&c.notifications, &c.accounts, account, |a| {
+                    &a.notifications
+                })
+                .is_some_and(|n| !n.is_empty())
+            }),
+            GameId::WutheringWaves => self.wuthering_waves.as_ref().is_some_and(|c| {
+                Self::account_notifications(&c.notifications, &c.accounts, account, |a| {
+                    &a.notifications
+                })
+                .is_some_and(|n| !n.is_empty())
+            }),
         }
     }
 
-    /// Gets notification config for a game/resource pair without allocating a
-    /// map.
+    /// Gets notification config for an account/resource pair without
+    /// allocating a map.
     #[must_use]
     pub fn notification_config(
         &self,
-        game_id: crate::GameId,
+        key: &AccountKey,
         resource_type: &str,
     ) -> Option<&ResourceNotificationConfig> {
-        use crate::GameId;
+        let account = key.account.as_str();
+        match key.game_id {
+            GameId::GenshinImpact => self.genshin_impact.as_ref().and_then(|c| {
+                Self::account_notifications(&c.notifications, &c.accounts, account, |a| {
+                    &a.notifications
+                })
+                .and_then(|n| Self::genshin_notification_for(n, resource_type))
+            }),
+            GameId::HonkaiStarRail => self.honkai_star_rail.as_ref().and_then(|c| {
+                Self::account_notifications(&c.notifications, &c.accounts, account, |a| {
+                    &a.notifications
+                })
+                .and_then(|n| Self::hsr_notification_for(n, resource_type))
+            }),
+            GameId::ZenlessZoneZero => self.zenless_zone_zero.as_ref().and_then(|c| {
+                Self::account_notifications(&c.notifications, &c.accounts, account, |a| {
+                    &a.notifications
+                })
+                .and_then(|n| Self::zzz_notification_for(n, resource_type))
+            }),
+            GameId::WutheringWaves => self.wuthering_waves.as_ref().and_then(|c| {
+                Self::account_notifications(&c.notifications, &c.accounts, account, |a| {
+                    &a.notifications
+                })
+                .and_then(|n| Self::wuwa_notification_for(n, resource_type))
+            }),
+        }
+    }
 
+    /// Poll interval set for a game, in seconds, if it overrides the global
+    /// one.
+    #[must_use]
+    pub fn poll_interval(&self, game_id: GameId) -> Option<u64> {
         match game_id {
+            GameId::GenshinImpact => self.genshin_impact.as_ref()?.poll_interval_secs,
+            GameId::HonkaiStarRail => self.honkai_star_rail.as_ref()?.poll_interval_secs,
+            GameId::ZenlessZoneZero => self.zenless_zone_zero.as_ref()?.poll_interval_secs,
+            GameId::WutheringWaves => self.wuthering_waves.as_ref()?.poll_interval_secs,
+        }
+    }
+
+    /// Adaptive poll interval bounds set for a game, as `(min, max)` seconds,
+    /// each `None` unless it overrides the global one.
+    #[must_use]
+    pub fn poll_interval_bounds(&self, game_id: GameId) -> (Option<u64>, Option<u64>) {
+        let bounds = match game_id {
             GameId::GenshinImpact => self
                 .genshin_impact
                 .as_ref()
-                .and_then(|c| Self::genshin_notification_for(c, resource_type)),
+                .map(|c| (c.min_poll_interval_secs, c.max_poll_interval_secs)),
             GameId::HonkaiStarRail => self
                 .honkai_star_rail
                 .as_ref()
-                .and_then(|c| Self::hsr_notification_for(c, resource_type)),
+                .map(|c| (c.min_poll_interval_secs, c.max_poll_interval_secs)),
             GameId::ZenlessZoneZero => self
                 .zenless_zone_zero
                 .as_ref()
-                .and_then(|c| Self::zzz_notification_for(c, resource_type)),
+                .map(|c| (c.min_poll_interval_secs, c.max_poll_interval_secs)),
             GameId::WutheringWaves => self
                 .wuthering_waves
                 .as_ref()
-                .and_then(|c| Self::wuwa_notification_for(c, resource_type)),
+                .map(|c| (c.min_poll_interval_secs, c.max_poll_interval_secs)),
+        };
+        bounds.unwrap_or_default()
+    }
+
+    /// Quiet hours set for a game, if it overrides the global ones.
+    #[must_use]
+    pub fn quiet_hours(&self, game_id: GameId) -> Option<&[QuietHours]> {
+        match game_id {
+            GameId::GenshinImpact => self.genshin_impact.as_ref()?.quiet_hours.as_deref(),
+            GameId::HonkaiStarRail => self.honkai_star_rail.as_ref()?.quiet_hours.as_deref(),
+            GameId::ZenlessZoneZero => self.zenless_zone_zero.as_ref()?.quiet_hours.as_deref(),
+            GameId::WutheringWaves => self.wuthering_waves.as_ref()?.quiet_hours.as_deref(),
         }
     }
 
     /// Whether a game is enabled in config.
     #[must_use]
-    pub fn is_enabled(&self, game_id: crate::GameId) -> bool {
-        use crate::GameId;
-
+    pub fn is_enabled(&self, game_id: GameId) -> bool {
         match game_id {
             GameId::GenshinImpact => self.genshin_impact.as_ref().is_some_and(|c| c.enabled),
             GameId::HonkaiStarRail => self.honkai_star_rail.as_ref().is_some_and(|c| c.enabled),
@@ -496,48 +1093,20 @@ impl GamesConfig {
         }
     }
 
-    /// Whether auto-claim is enabled for a game.
+    /// Whether auto-claim is enabled for an account.
     ///
     /// Wuthering Waves does not support daily rewards, so always returns
     /// `false`.
     #[must_use]
-    pub fn auto_claim_enabled(&self, game_id: crate::GameId) -> bool {
-        use crate::GameId;
-
-        match game_id {
-            GameId::GenshinImpact => self
-                .genshin_impact
-                .as_ref()
-                .is_some_and(|c| c.enabled && c.auto_claim_daily_rewards),
-            GameId::HonkaiStarRail => self
-                .honkai_star_rail
-                .as_ref()
-                .is_some_and(|c| c.enabled && c.auto_claim_daily_rewards),
-            GameId::ZenlessZoneZero => self
-                .zenless_zone_zero
-                .as_ref()
-                .is_some_and(|c| c.enabled && c.auto_claim_daily_rewards),
-            GameId::WutheringWaves => false,
-        }
+    pub fn auto_claim_enabled(&self, key: &AccountKey) -> bool {
+        self.account(key)
+            .is_some_and(|a| a.enabled && a.auto_claim_daily_rewards)
     }
 
-    /// Auto-claim time for a game.
+    /// Auto-claim time for an account.
     #[must_use]
-    pub fn auto_claim_time(&self, game_id: crate::GameId) -> Option<ClaimTime> {
-        use crate::GameId;
-
-        match game_id {
-            GameId::GenshinImpact => self.genshin_impact.as_ref().and_then(|c| c.auto_claim_time),
-            GameId::HonkaiStarRail => self
-                .honkai_star_rail
-                .as_ref()
-                .and_then(|c| c.auto_claim_time),
-            GameId::ZenlessZoneZero => self
-                .zenless_zone_zero
-                .as_ref()
-                .and_then(|c| c.auto_claim_time),
-            GameId::WutheringWaves => None,
-        }
+    pub fn auto_claim_time(&self, key: &AccountKey) -> Option<ClaimTime> {
+        self.account(key).and_then(|a| a.auto_claim_time)
     }
 }
 
@@ -594,6 +1163,119 @@ mod tests {
         assert_eq!(config.general.poll_interval_secs, 300);
     }
 
+    #[test]
+    fn general_config_history_defaults_when_omitted() {
+        let config: AppConfig = toml::from_str("[general]").expect("should parse config");
+        assert!(config.general.history_enabled);
+        assert_eq!(config.general.history_retention_days, 90);
+        assert_eq!(config.general.history_compact_after_days, 7);
+        assert_eq!(config.general.history_compact_interval_mins, 60);
+        assert_eq!(config.general, GeneralConfig::default());
+    }
+
+    #[test]
+    fn default_config_content_parses_to_defaults() {
+        let config: AppConfig =
+            toml::from_str(AppConfig::default_config_content()).expect("should parse template");
+        assert_eq!(config.general, GeneralConfig::default());
+        assert_eq!(config.http_api, HttpApiConfig::default());
+        assert_eq!(config.adaptive_polling, AdaptivePollingConfig::default());
+        assert_eq!(config.rate_limits, RateLimitsConfig::default());
+    }
+
+    #[test]
+    fn game_poll_bounds_override_global_ones() {
+        let config: AppConfig = toml::from_str(
+            r#"
+            [adaptive_polling]
+            enabled = true
+            min_interval_secs = 120
+
+            [games.genshin_impact]
+            uid = "800000001"
+            max_poll_interval_secs = 600
+
+            [games.wuthering_waves]
+            uid = "500000001"
+            min_poll_interval_secs = 3600
+            "#,
+        )
+        .expect("should parse config");
+
+        assert_eq!(
+            config.adaptive_poll_bounds(GameId::GenshinImpact),
+            (120, 600)
+        );
+        // A minimum above the maximum raises the maximum with it.
+        assert_eq!(
+            config.adaptive_poll_bounds(GameId::WutheringWaves),
+            (3600, 3600)
+        );
+        assert_eq!(
+            config.adaptive_poll_bounds(GameId::HonkaiStarRail),
+            (120, 1800)
+        );
+    }
+
+    #[test]
+    fn game_poll_interval_overrides_global_one() {
+        let config: AppConfig = toml::from_str(
+            r#"
+            [general]
+            poll_interval_secs = 300
+
+            [games.wuthering_waves]
+            uid = "500000001"
+            poll_interval_secs = 900
+            "#,
+        )
+        .expect("should parse config");
+
+        assert_eq!(config.poll_interval_for(GameId::WutheringWaves), 900);
+        assert_eq!(config.poll_interval_for(GameId::GenshinImpact), 300);
+    }
+
+    #[test]
+    fn http_api_is_disabled_when_omitted() {
+        let config: AppConfig = toml::from_str("[http_api]").expect("should parse config");
+        assert!(!config.http_api.enabled);
+        assert_eq!(config.http_api.port, DEFAULT_HTTP_API_PORT);
+    }
+
+    #[test]
+    fn game_quiet_hours_replace_global_ones() {
+        let config: AppConfig = toml::from_str(
+            r#"
+            [[quiet_hours]]
+            start = "23:00"
+            end = "07:00"
+
+            [games.genshin_impact]
+            uid = "800000001"
+            quiet_hours = []
+
+            [games.wuthering_waves]
+            uid = "500000001"
+            "#,
+        )
+        .expect("should parse config");
+
+        assert!(config.quiet_hours_for(GameId::GenshinImpact).is_empty());
+        assert_eq!(config.quiet_hours_for(GameId::WutheringWaves).len(), 1);
+        assert_eq!(config.quiet_hours_for(GameId::HonkaiStarRail).len(), 1);
+    }
+
+    #[test]
+    fn mqtt_defaults_when_omitted() {
+        let config: AppConfig =
+            toml::from_str("[mqtt]\nenabled = true").expect("should parse config");
+        assert!(config.mqtt.enabled);
+        assert_eq!(config.mqtt.port, DEFAULT_MQTT_PORT);
+        assert_eq!(config.mqtt.base_topic, "storekeeper");
+        assert!(config.mqtt.discovery);
+        assert_eq!(config.mqtt.discovery_prefix, "homeassistant");
+    }
+
     #[test]
     fn app_config_save_load_roundtrip_through_temp_dir() {
         let dir = unique_temp_dir("config");
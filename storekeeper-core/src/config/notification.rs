@@ -1,8 +1,11 @@
 //! Notification configuration for tracked resources.
 
 use super::default_true;
+use jiff::civil::Time;
 use serde::Deserialize;
 use serde::Serialize;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
 
 /// Notification configuration for a specific resource.
 ///
@@ -16,6 +19,14 @@ use serde::Serialize;
 /// If both are `None`, notifications fire only when the resource is full/ready.
 /// If both are `Some` (e.g. manual config edit), `notify_at_value` takes
 /// priority.
+///
+/// `channels` routes the notification to specific channels by name; when empty
+/// it goes to every channel. `critical` notifications are sent even during
+/// quiet hours.
+///
+/// A non-empty `rules` list replaces the single threshold, cooldown, channels
+/// and template set on the table itself, so one resource can notify at several
+/// points (see [`NotificationRule`]).
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ResourceNotificationConfig {
     /// Whether notifications are enabled for this resource.
@@ -34,12 +45,340 @@ pub struct ResourceNotificationConfig {
     /// Minutes between repeated notifications.
     #[serde(default = "default_notification_cooldown")]
     pub cooldown_minutes: u32,
+
+    /// Names of the channels to deliver to: the built-in `"os"` (desktop) or
+    /// `"log"` (daemon) channel, or a `[notification_channels]` entry. Empty
+    /// delivers to every channel.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<String>,
+
+    /// Notification body template in the i18n message format. `None` uses
+    /// the default body.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+
+    /// Whether to notify during quiet hours instead of deferring to their end.
+    #[serde(default)]
+    pub critical: bool,
+
+    /// Separate notification rules, each with its own id. Empty uses the
+    /// threshold set on this table.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<NotificationRule>,
+}
+
+impl ResourceNotificationConfig {
+    /// Returns the rules to check: `rules`, or a single rule with id
+    /// [`DEFAULT_RULE_ID`] built from this table's own settings.
+    #[must_use]
+    pub fn effective_rules(&self) -> Cow<'_, [NotificationRule]> {
+        if self.rules.is_empty() {
+            Cow::Owned(vec![NotificationRule {
+                id: DEFAULT_RULE_ID.to_string(),
+                notify_minutes_before_full: self.notify_minutes_before_full,
+                notify_at_value: self.notify_at_value,
+                cooldown_minutes: self.cooldown_minutes,
+                channels: self.channels.clone(),
+                template: self.template.clone(),
+            }])
+        } else {
+            Cow::Borrowed(&self.rules)
+        }
+    }
+}
+
+/// Id of the rule made from a resource's single-table settings.
+pub const DEFAULT_RULE_ID: &str = "default";
+
+/// One notification rule of a resource, under
+/// `[[games.<game>.notifications.<resource>.rules]]`.
+///
+/// Thresholds work as on [`ResourceNotificationConfig`]: with neither set the
+/// rule fires once the resource is full, so a long cooldown gives a periodic
+/// reminder while it stays full. Each rule keeps its own cooldown, keyed by
+/// `id`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationRule {
+    /// Identifies the rule's cooldown; unique within the resource.
+    pub id: String,
+
+    /// Minutes before full to start notifying.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notify_minutes_before_full: Option<u32>,
+
+    /// Resource value at which to notify (stamina resources only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notify_at_value: Option<u64>,
+
+    /// Minutes between repeated notifications; 0 notifies once per window.
+    #[serde(default = "default_notification_cooldown")]
+    pub cooldown_minutes: u32,
+
+    /// Names of the channels to deliver to. Empty delivers to every channel.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub channels: Vec<String>,
+
+    /// Notification body template in the i18n message format, with
+    /// `{game_name}`, `{resource_name}`, `{current}`, `{max}`, `{duration}`,
+    /// `{local_time}` and `{body}` (the default body) placeholders. `None`
+    /// uses the default body.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
 }
 
 fn default_notification_cooldown() -> u32 {
     30
 }
 
+/// Grouping of resource notifications that fire close together, under
+/// `[notification_batch]`.
+///
+/// When enabled, resource notifications are collected and sent as a single
+/// notification per channel, listing each resource. With `window_minutes` at
+/// 0 only notifications of the same check are grouped; otherwise the first
+/// one waits that long for others to join it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationBatchConfig {
+    /// Whether to group resource notifications.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Minutes to collect notifications for after the first one is due.
+    #[serde(default)]
+    pub window_minutes: u32,
+}
+
+/// A named notification channel, configured under
+/// `[notification_channels.<name>]`.
+///
+/// Credentials and URLs live in the matching `[notification_channels.<name>]`
+/// table of `secrets.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NotificationChannelConfig {
+    /// JSON `POST` to a webhook URL.
+    Webhook(WebhookChannelConfig),
+    /// Push to a topic on an ntfy server.
+    Ntfy(NtfyChannelConfig),
+    /// Push to a Gotify server.
+    Gotify(GotifyChannelConfig),
+    /// Email over SMTP, with an optional daily digest.
+    Email(EmailChannelConfig),
+    /// Runs a local executable with the event as JSON on stdin.
+    Hook(HookChannelConfig),
+}
+
+/// Settings of a webhook notification channel.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookChannelConfig {
+    /// Shape of the JSON payload.
+    #[serde(default)]
+    pub preset: WebhookPreset,
+
+    /// Message text template in the i18n message format, with `{title}` and
+    /// `{body}` placeholders. `None` uses the preset's default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+/// JSON payload shape of a webhook channel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookPreset {
+    /// Discord incoming webhook: `{"content": "<message>"}`.
+    Discord,
+    /// Slack incoming webhook: `{"text": "<message>"}`.
+    Slack,
+    /// `{"title": ..., "body": ..., "message": "<message>"}`.
+    #[default]
+    Json,
+}
+
+/// Settings of an ntfy notification channel.
+///
+/// An access token, if the topic needs one, goes in `secrets.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NtfyChannelConfig {
+    /// Base URL of the ntfy server.
+    #[serde(default = "default_ntfy_server")]
+    pub server: String,
+
+    /// Topic to publish to.
+    pub topic: String,
+
+    /// ntfy priorities (1-5) to publish with.
+    #[serde(default = "default_ntfy_priority")]
+    pub priority: PushPriority,
+
+    /// Extra tags (emoji shortcodes or labels) per game, keyed by the game's
+    /// table name (e.g. `genshin_impact`).
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, Vec<String>>,
+}
+
+fn default_ntfy_server() -> String {
+    "https://ntfy.sh".to_string()
+}
+
+fn default_ntfy_priority() -> PushPriority {
+    PushPriority {
+        full: 4,
+        approaching: 3,
+    }
+}
+
+/// Settings of a Gotify notification channel.
+///
+/// The application token goes in `secrets.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GotifyChannelConfig {
+    /// Base URL of the Gotify server.
+    pub server: String,
+
+    /// Gotify priorities (0-10) to send with.
+    #[serde(default = "default_gotify_priority")]
+    pub priority: PushPriority,
+}
+
+fn default_gotify_priority() -> PushPriority {
+    PushPriority {
+        full: 8,
+        approaching: 5,
+    }
+}
+
+/// Settings of an email notification channel.
+///
+/// The SMTP username and password go in `secrets.toml`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmailChannelConfig {
+    /// SMTP server host name.
+    pub host: String,
+
+    /// SMTP server port.
+    #[serde(default = "default_smtp_port")]
+    pub port: u16,
+
+    /// How the connection to the SMTP server is secured.
+    #[serde(default)]
+    pub security: SmtpSecurity,
+
+    /// Sender address (e.g. `"Storekeeper <storekeeper@example.com>"`).
+    pub from: String,
+
+    /// Recipient addresses.
+    pub to: Vec<String>,
+
+    /// Whether resource notifications are emailed as they fire. Disable to
+    /// only receive the daily digest.
+    #[serde(default = "default_true")]
+    pub alerts: bool,
+
+    /// Local time of day to email a digest of every tracked resource and
+    /// daily reward. `None` disables the digest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest_time: Option<Time>,
+}
+
+fn default_smtp_port() -> u16 {
+    587
+}
+
+/// Connection security of an SMTP channel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// Upgrade a plain connection with `STARTTLS` (usually port 587).
+    #[default]
+    #[serde(rename = "starttls")]
+    StartTls,
+    /// Implicit TLS from the start (usually port 465).
+    Tls,
+    /// No encryption; only for local relays and testing.
+    None,
+}
+
+/// Settings of a hook channel, which runs an executable for each event.
+///
+/// The event is passed as JSON on stdin and summarized in `STOREKEEPER_*`
+/// environment variables.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HookChannelConfig {
+    /// Path of the executable to run.
+    pub command: String,
+
+    /// Arguments passed to the executable.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+
+    /// Seconds to wait for the executable before killing it.
+    #[serde(default = "default_hook_timeout_secs")]
+    pub timeout_secs: u64,
+
+    /// Events to run on. Defaults to every event.
+    #[serde(default = "default_hook_events")]
+    pub events: Vec<HookEvent>,
+}
+
+fn default_hook_timeout_secs() -> u64 {
+    10
+}
+
+fn default_hook_events() -> Vec<HookEvent> {
+    HookEvent::all().to_vec()
+}
+
+/// Event a hook channel can run on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    /// A resource entered its notification threshold.
+    ResourceApproaching,
+    /// A resource is full or ready.
+    ResourceFull,
+    /// A daily reward was claimed.
+    ClaimSucceeded,
+    /// A daily reward claim failed.
+    ClaimFailed,
+    /// An account's login was rejected.
+    AuthExpired,
+}
+
+impl HookEvent {
+    /// Returns every event.
+    #[must_use]
+    pub const fn all() -> &'static [Self] {
+        &[
+            Self::ResourceApproaching,
+            Self::ResourceFull,
+            Self::ClaimSucceeded,
+            Self::ClaimFailed,
+            Self::AuthExpired,
+        ]
+    }
+
+    /// Returns the event name used in config and hook payloads.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::ResourceApproaching => "resource_approaching",
+            Self::ResourceFull => "resource_full",
+            Self::ClaimSucceeded => "claim_succeeded",
+            Self::ClaimFailed => "claim_failed",
+            Self::AuthExpired => "auth_expired",
+        }
+    }
+}
+
+/// Priority a push channel sends each kind of resource notification with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PushPriority {
+    /// Priority when a resource is full or ready.
+    pub full: u8,
+    /// Priority when a resource is approaching its threshold.
+    pub approaching: u8,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -102,6 +441,210 @@ mod tests {
         assert_eq!(roundtripped.notify_minutes_before_full, None);
     }
 
+    #[test]
+    fn test_resource_notification_config_channels_default_to_all() {
+        let config: ResourceNotificationConfig =
+            toml::from_str("enabled = true").expect("should parse config");
+        assert!(config.channels.is_empty());
+        assert!(!config.critical);
+
+        let serialized = toml::to_string(&config).expect("should serialize");
+        assert!(
+            !serialized.contains("channels"),
+            "serialized should skip empty channels, got: {serialized}"
+        );
+    }
+
+    #[test]
+    fn test_webhook_channel_config_parses_preset() {
+        let config: NotificationChannelConfig = toml::from_str(
+            r#"
+            type = "webhook"
+            preset = "discord"
+            "#,
+        )
+        .expect("should parse channel config");
+        assert_eq!(
+            config,
+            NotificationChannelConfig::Webhook(WebhookChannelConfig {
+                preset: WebhookPreset::Discord,
+                template: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_ntfy_channel_config_defaults() {
+        let config: NotificationChannelConfig = toml::from_str(
+            r#"
+            type = "ntfy"
+            topic = "storekeeper"
+            tags = { genshin_impact = ["crystal_ball"] }
+            "#,
+        )
+        .expect("should parse channel config");
+        assert_eq!(
+            config,
+            NotificationChannelConfig::Ntfy(NtfyChannelConfig {
+                server: "https://ntfy.sh".to_string(),
+                topic: "storekeeper".to_string(),
+                priority: PushPriority {
+                    full: 4,
+                    approaching: 3,
+                },
+                tags: BTreeMap::from([(
+                    "genshin_impact".to_string(),
+                    vec!["crystal_ball".to_string()]
+                )]),
+            })
+        );
+    }
+
+    #[test]
+    fn test_gotify_channel_config_custom_priority() {
+        let config: NotificationChannelConfig = toml::from_str(
+            r#"
+            type = "gotify"
+            server = "https://gotify.example.com"
+            priority = { full = 10, approaching = 2 }
+            "#,
+        )
+        .expect("should parse channel config");
+        assert_eq!(
+            config,
+            NotificationChannelConfig::Gotify(GotifyChannelConfig {
+                server: "https://gotify.example.com".to_string(),
+                priority: PushPriority {
+                    full: 10,
+                    approaching: 2,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn test_email_channel_config_defaults() {
+        let config: NotificationChannelConfig = toml::from_str(
+            r#"
+            type = "email"
+            host = "smtp.example.com"
+            from = "storekeeper@example.com"
+            to = ["me@example.com"]
+            digest_time = "08:30"
+            "#,
+        )
+        .expect("should parse channel config");
+        assert_eq!(
+            config,
+            NotificationChannelConfig::Email(EmailChannelConfig {
+                host: "smtp.example.com".to_string(),
+                port: 587,
+                security: SmtpSecurity::StartTls,
+                from: "storekeeper@example.com".to_string(),
+                to: vec!["me@example.com".to_string()],
+                alerts: true,
+                digest_time: Some(Time::constant(8, 30, 0, 0)),
+            })
+        );
+    }
+
+    #[test]
+    fn test_hook_channel_config_defaults_to_every_event() {
+        let config: NotificationChannelConfig = toml::from_str(
+            r#"
+            type = "hook"
+            command = "/usr/local/bin/on-storekeeper"
+            "#,
+        )
+        .expect("should parse channel config");
+        assert_eq!(
+            config,
+            NotificationChannelConfig::Hook(HookChannelConfig {
+                command: "/usr/local/bin/on-storekeeper".to_string(),
+                args: Vec::new(),
+                timeout_secs: 10,
+                events: HookEvent::all().to_vec(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_hook_event_names_match_serde() {
+        for event in HookEvent::all() {
+            assert_eq!(
+                serde_json::to_value(event).expect("should serialize"),
+                event.as_str()
+            );
+        }
+    }
+
+    #[test]
+    fn test_single_table_is_one_default_rule() {
+        let config: ResourceNotificationConfig = toml::from_str(
+            r#"
+            notify_minutes_before_full = 60
+            cooldown_minutes = 10
+            channels = ["phone"]
+            "#,
+        )
+        .expect("should parse config");
+
+        assert_eq!(
+            config.effective_rules().as_ref(),
+            [NotificationRule {
+                id: DEFAULT_RULE_ID.to_string(),
+                notify_minutes_before_full: Some(60),
+                notify_at_value: None,
+                cooldown_minutes: 10,
+                channels: vec!["phone".to_string()],
+                template: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_rules_replace_single_table_threshold() {
+        let config: ResourceNotificationConfig = toml::from_str(
+            r#"
+            notify_minutes_before_full = 60
+
+            [[rules]]
+            id = "at_120"
+            notify_at_value = 120
+
+            [[rules]]
+            id = "while_full"
+            cooldown_minutes = 120
+            template = "{resource_name} is still full"
+            "#,
+        )
+        .expect("should parse rules");
+
+        let rules = config.effective_rules();
+        let ids: Vec<&str> = rules.iter().map(|rule| rule.id.as_str()).collect();
+        assert_eq!(ids, ["at_120", "while_full"]);
+        assert!(matches!(rules, Cow::Borrowed(_)));
+        assert_eq!(
+            rules.first().and_then(|rule| rule.notify_at_value),
+            Some(120)
+        );
+        assert_eq!(rules.last().map(|rule| rule.cooldown_minutes), Some(120));
+    }
+
+    #[test]
+    fn test_notification_batch_defaults_to_disabled() {
+        let config: NotificationBatchConfig =
+            toml::from_str("enabled = true").expect("should parse batch config");
+        assert_eq!(
+            config,
+            NotificationBatchConfig {
+                enabled: true,
+                window_minutes: 0,
+            }
+        );
+        assert!(!NotificationBatchConfig::default().enabled);
+    }
+
     #[test]
     fn test_resource_notification_config_both_none_defaults() {
         let toml_str = r"
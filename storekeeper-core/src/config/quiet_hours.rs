@@ -0,0 +1,154 @@
+//! Quiet hours: local-time windows during which notifications are held back.
+
+use jiff::Zoned;
+use jiff::civil::Date;
+use jiff::civil::Time;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// A daily window, in local time, during which notifications are deferred.
+///
+/// A window whose `end` is before its `start` spans midnight, and belongs to
+/// the day it starts on. A window whose `start` and `end` are equal is empty.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuietHours {
+    /// Local time the window starts.
+    pub start: Time,
+
+    /// Local time the window ends.
+    pub end: Time,
+
+    /// Days the window starts on. Empty means every day.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub days: Vec<Weekday>,
+}
+
+impl QuietHours {
+    /// Returns whether `now` falls inside the window.
+    #[must_use]
+    pub fn contains(&self, now: &Zoned) -> bool {
+        let time = now.time();
+        if self.start <= self.end {
+            self.start <= time && time < self.end && self.starts_on(now.date())
+        } else if time >= self.start {
+            self.starts_on(now.date())
+        } else {
+            time < self.end && now.date().yesterday().is_ok_and(|day| self.starts_on(day))
+        }
+    }
+
+    fn starts_on(&self, date: Date) -> bool {
+        self.days.is_empty() || self.days.iter().any(|day| day.to_jiff() == date.weekday())
+    }
+}
+
+/// Day of the week, written as its three-letter English abbreviation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Weekday {
+    /// Monday.
+    Mon,
+    /// Tuesday.
+    Tue,
+    /// Wednesday.
+    Wed,
+    /// Thursday.
+    Thu,
+    /// Friday.
+    Fri,
+    /// Saturday.
+    Sat,
+    /// Sunday.
+    Sun,
+}
+
+impl Weekday {
+    /// Converts to jiff's weekday.
+    #[must_use]
+    pub const fn to_jiff(self) -> jiff::civil::Weekday {
+        match self {
+            Self::Mon => jiff::civil::Weekday::Monday,
+            Self::Tue => jiff::civil::Weekday::Tuesday,
+            Self::Wed => jiff::civil::Weekday::Wednesday,
+            Self::Thu => jiff::civil::Weekday::Thursday,
+            Self::Fri => jiff::civil::Weekday::Friday,
+            Self::Sat => jiff::civil::Weekday::Saturday,
+            Self::Sun => jiff::civil::Weekday::Sunday,
+        }
+    }
+}
+
+/// Returns whether `now` falls inside any of `windows`.
+#[must_use]
+pub fn in_quiet_hours(windows: &[QuietHours], now: &Zoned) -> bool {
+    windows.iter().any(|window| window.contains(now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::tz::TimeZone;
+
+    /// 2026-03-02 is a Monday.
+    fn at(day: i8, hour: i8, minute: i8) -> Zoned {
+        jiff::civil::date(2026, 3, day)
+            .at(hour, minute, 0, 0)
+            .to_zoned(TimeZone::UTC)
+            .expect("valid time")
+    }
+
+    fn window(start: i8, end: i8, days: Vec<Weekday>) -> QuietHours {
+        QuietHours {
+            start: Time::constant(start, 0, 0, 0),
+            end: Time::constant(end, 0, 0, 0),
+            days,
+        }
+    }
+
+    #[test]
+    fn parses_times_and_days() {
+        let window: QuietHours = toml::from_str(
+            r#"
+            start = "23:00"
+            end = "07:30"
+            days = ["mon", "fri"]
+            "#,
+        )
+        .expect("should parse quiet hours");
+        assert_eq!(
+            window,
+            QuietHours {
+                start: Time::constant(23, 0, 0, 0),
+                end: Time::constant(7, 30, 0, 0),
+                days: vec![Weekday::Mon, Weekday::Fri],
+            }
+        );
+    }
+
+    #[test]
+    fn daytime_window_is_half_open() {
+        let lunch = window(12, 13, Vec::new());
+        assert!(!lunch.contains(&at(2, 11, 59)));
+        assert!(lunch.contains(&at(2, 12, 0)));
+        assert!(!lunch.contains(&at(2, 13, 0)));
+    }
+
+    #[test]
+    fn overnight_window_belongs_to_its_start_day() {
+        let weeknights = window(23, 7, vec![Weekday::Mon, Weekday::Tue]);
+        assert!(weeknights.contains(&at(2, 23, 30)));
+        assert!(weeknights.contains(&at(3, 6, 59)));
+        assert!(!weeknights.contains(&at(3, 7, 0)));
+        // Sunday night runs into Monday morning but is not covered.
+        assert!(!weeknights.contains(&at(1, 23, 30)));
+        assert!(!weeknights.contains(&at(2, 3, 0)));
+        assert!(weeknights.contains(&at(3, 23, 0)));
+        assert!(weeknights.contains(&at(4, 3, 0)));
+        assert!(!weeknights.contains(&at(4, 23, 0)));
+    }
+
+    #[test]
+    fn equal_start_and_end_is_empty() {
+        assert!(!in_quiet_hours(&[window(8, 8, Vec::new())], &at(2, 8, 0)));
+    }
+}
@@ -1,11 +1,13 @@
 //! Secrets configuration for sensitive credentials.
 
+use crate::account::DEFAULT_ACCOUNT;
 use crate::error::Error;
 use crate::error::Result;
 use camino::Utf8Path;
 use camino::Utf8PathBuf;
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::BTreeMap;
 
 /// Secrets configuration loaded from `secrets.toml`.
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -17,9 +19,58 @@ pub struct SecretsConfig {
     /// Kuro Games authentication.
     #[serde(default)]
     pub kuro: KuroSecrets,
+
+    /// Additional named `HoYoLab` logins, referenced by game accounts via
+    /// `credentials = "<name>"`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub hoyolab_accounts: BTreeMap<String, HoyolabSecrets>,
+
+    /// Additional named Kuro Games logins, referenced by game accounts via
+    /// `credentials = "<name>"`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub kuro_accounts: BTreeMap<String, KuroSecrets>,
+
+    /// Local HTTP API authentication.
+    #[serde(default, skip_serializing_if = "HttpApiSecrets::is_unset")]
+    pub http_api: HttpApiSecrets,
+
+    /// MQTT broker login.
+    #[serde(default, skip_serializing_if = "MqttSecrets::is_unset")]
+    pub mqtt: MqttSecrets,
+
+    /// Notification channel URLs and credentials, keyed by the channel names
+    /// in `config.toml`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub notification_channels: BTreeMap<String, NotificationChannelSecrets>,
 }
 
 impl SecretsConfig {
+    /// Returns the `HoYoLab` login with the given name.
+    ///
+    /// `"default"` refers to the top-level `[hoyolab]` table; any other name
+    /// is looked up in `[hoyolab_accounts]`.
+    #[must_use]
+    pub fn hoyolab_login(&self, name: &str) -> Option<&HoyolabSecrets> {
+        if name == DEFAULT_ACCOUNT {
+            Some(&self.hoyolab)
+        } else {
+            self.hoyolab_accounts.get(name)
+        }
+    }
+
+    /// Returns the Kuro Games login with the given name.
+    ///
+    /// `"default"` refers to the top-level `[kuro]` table; any other name is
+    /// looked up in `[kuro_accounts]`.
+    #[must_use]
+    pub fn kuro_login(&self, name: &str) -> Option<&KuroSecrets> {
+        if name == DEFAULT_ACCOUNT {
+            Some(&self.kuro)
+        } else {
+            self.kuro_accounts.get(name)
+        }
+    }
+
     /// Loads secrets from the default secrets file location.
     ///
     /// # Errors
@@ -130,6 +181,8 @@ impl SecretsConfig {
 # 1. Go to https://www.hoyolab.com and log in
 # 2. Open browser Developer Tools (F12) > Application > Cookies
 # 3. Find cookies named "ltuid_v2", "ltoken_v2", and "ltmid_v2"
+#
+# For mainland China servers, take them from https://www.miyoushe.com instead.
 
 [hoyolab]
 # Required: v2 authentication cookies
@@ -137,6 +190,14 @@ ltuid_v2 = ""
 ltoken_v2 = ""
 ltmid_v2 = ""
 
+# Additional HoYoLab logins (optional). Game accounts in config.toml select a
+# login with `credentials = "<name>"` (defaulting to the account's own name).
+#
+# [hoyolab_accounts.alt]
+# ltuid_v2 = ""
+# ltoken_v2 = ""
+# ltmid_v2 = ""
+
 # =============================================================================
 # Kuro Games Authentication (for Wuthering Waves)
 # =============================================================================
@@ -151,6 +212,47 @@ ltmid_v2 = ""
 [kuro]
 # Optional: uncomment and set to override automatic detection
 # oauth_code = ""
+
+# Additional Kuro logins (optional). Named accounts cannot use the launcher
+# cache, so oauth_code is required here.
+#
+# [kuro_accounts.alt]
+# oauth_code = ""
+
+# =============================================================================
+# Local HTTP API (optional)
+# =============================================================================
+# Bearer token required by the local HTTP API ([http_api] in config.toml).
+# Use a long random string; the server does not start without one.
+#
+# [http_api]
+# token = ""
+
+# =============================================================================
+# MQTT (optional)
+# =============================================================================
+# Broker login for [mqtt] in config.toml, if the broker requires one.
+#
+# [mqtt]
+# username = ""
+# password = ""
+
+# =============================================================================
+# Notification Channels (optional)
+# =============================================================================
+# Webhook URLs, push tokens and SMTP logins for [notification_channels.<name>]
+# in config.toml. Gotify needs an application token; ntfy only needs one for
+# protected topics.
+#
+# [notification_channels.phone]
+# url = "https://discord.com/api/webhooks/..."
+#
+# [notification_channels.gotify]
+# token = ""
+#
+# [notification_channels.email]
+# username = ""
+# password = ""
 "#
     }
 }
@@ -191,6 +293,12 @@ impl HoyolabSecrets {
     pub fn ltoken(&self) -> &str {
         &self.ltoken_v2
     }
+
+    /// Returns the ltmid value, which Miyoushe requests also send.
+    #[must_use]
+    pub fn ltmid(&self) -> &str {
+        &self.ltmid_v2
+    }
 }
 
 /// Kuro Games authentication secrets.
@@ -225,3 +333,170 @@ impl KuroSecrets {
         }
     }
 }
+
+/// Local HTTP API authentication secrets.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpApiSecrets {
+    /// Bearer token clients must send in the `Authorization` header.
+    #[serde(default)]
+    pub token: String,
+}
+
+impl HttpApiSecrets {
+    /// Returns the bearer token, if one is set.
+    #[must_use]
+    pub fn token(&self) -> Option<&str> {
+        let token = self.token.trim();
+        (!token.is_empty()).then_some(token)
+    }
+
+    fn is_unset(&self) -> bool {
+        self.token.is_empty()
+    }
+}
+
+/// MQTT broker login.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MqttSecrets {
+    /// Broker username.
+    #[serde(default)]
+    pub username: String,
+
+    /// Broker password.
+    #[serde(default)]
+    pub password: String,
+}
+
+impl MqttSecrets {
+    /// Returns the username and password, if a username is set.
+    #[must_use]
+    pub fn login(&self) -> Option<(&str, &str)> {
+        let username = self.username.trim();
+        (!username.is_empty()).then_some((username, self.password.as_str()))
+    }
+
+    fn is_unset(&self) -> bool {
+        self.username.is_empty() && self.password.is_empty()
+    }
+}
+
+/// Secrets of a notification channel.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationChannelSecrets {
+    /// Endpoint to deliver to (e.g. a webhook URL).
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub url: String,
+
+    /// Access token (ntfy access token or Gotify application token).
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub token: String,
+
+    /// SMTP username.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub username: String,
+
+    /// SMTP password.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub password: String,
+}
+
+impl NotificationChannelSecrets {
+    /// Returns the endpoint URL, if one is set.
+    #[must_use]
+    pub fn url(&self) -> Option<&str> {
+        let url = self.url.trim();
+        (!url.is_empty()).then_some(url)
+    }
+
+    /// Returns the access token, if one is set.
+    #[must_use]
+    pub fn token(&self) -> Option<&str> {
+        let token = self.token.trim();
+        (!token.is_empty()).then_some(token)
+    }
+
+    /// Returns the SMTP username and password, if a username is set.
+    #[must_use]
+    pub fn smtp_login(&self) -> Option<(&str, &str)> {
+        let username = self.username.trim();
+        (!username.is_empty()).then_some((username, self.password.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_table_secrets_have_no_named_logins() {
+        let toml_str = r#"
+            [hoyolab]
+            ltuid_v2 = "1"
+            ltoken_v2 = "token"
+        "#;
+
+        let secrets: SecretsConfig = toml::from_str(toml_str).expect("should parse secrets");
+        assert!(secrets.hoyolab_accounts.is_empty());
+        assert!(secrets.kuro_accounts.is_empty());
+        assert_eq!(
+            secrets
+                .hoyolab_login(DEFAULT_ACCOUNT)
+                .map(HoyolabSecrets::ltuid),
+            Some("1")
+        );
+    }
+
+    #[test]
+    fn named_logins_are_resolved_by_name() {
+        let toml_str = r#"
+            [hoyolab]
+            ltuid_v2 = "1"
+            ltoken_v2 = "token"
+
+            [hoyolab_accounts.alt]
+            ltuid_v2 = "2"
+            ltoken_v2 = "alt-token"
+
+            [kuro_accounts.alt]
+            oauth_code = "code"
+        "#;
+
+        let secrets: SecretsConfig = toml::from_str(toml_str).expect("should parse secrets");
+        assert_eq!(
+            secrets.hoyolab_login("alt").map(HoyolabSecrets::ltuid),
+            Some("2")
+        );
+        assert!(secrets.hoyolab_login("missing").is_none());
+        assert_eq!(
+            secrets
+                .kuro_login("alt")
+                .and_then(KuroSecrets::oauth_code_override),
+            Some("code")
+        );
+        assert!(
+            secrets
+                .kuro_login(DEFAULT_ACCOUNT)
+                .is_some_and(|k| !k.has_override())
+        );
+    }
+
+    #[test]
+    fn empty_named_logins_are_not_serialized() {
+        let serialized =
+            toml::to_string_pretty(&SecretsConfig::default()).expect("should serialize");
+        assert!(!serialized.contains("hoyolab_accounts"));
+        assert!(!serialized.contains("kuro_accounts"));
+        assert!(!serialized.contains("http_api"));
+    }
+
+    #[test]
+    fn blank_http_api_token_is_unset() {
+        let secrets: SecretsConfig =
+            toml::from_str("[http_api]\ntoken = \"  \"").expect("should parse secrets");
+        assert_eq!(secrets.http_api.token(), None);
+
+        let secrets: SecretsConfig =
+            toml::from_str("[http_api]\ntoken = \"s3cret\"").expect("should parse secrets");
+        assert_eq!(secrets.http_api.token(), Some("s3cret"));
+    }
+}
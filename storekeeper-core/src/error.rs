@@ -43,6 +43,10 @@ pub enum Error {
     /// Failed to determine region from UID.
     #[error("Could not determine region from UID: {0}")]
     UnknownUidRegion(String),
+
+    /// Failed to determine region from a HoYoLab server name.
+    #[error("Unknown HoYoLab server: {0}")]
+    UnknownServerRegion(String),
 }
 
 /// Result type alias using the core Error type.
@@ -21,7 +21,7 @@ pub enum ApiProvider {
 /// This enum provides a type-safe way to identify games throughout the
 /// application, replacing string-based identification with compile-time checked
 /// values.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum GameId {
     /// Genshin Impact
@@ -72,6 +72,17 @@ impl GameId {
         }
     }
 
+    /// Returns the name of this game's table under `[games]` in `config.toml`.
+    #[must_use]
+    pub const fn config_key(&self) -> &'static str {
+        match self {
+            Self::GenshinImpact => "genshin_impact",
+            Self::HonkaiStarRail => "honkai_star_rail",
+            Self::ZenlessZoneZero => "zenless_zone_zero",
+            Self::WutheringWaves => "wuthering_waves",
+        }
+    }
+
     /// Returns the human-readable display name for this game.
     ///
     /// For internal logging only — user-facing strings should use i18n
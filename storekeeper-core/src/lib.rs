@@ -3,6 +3,7 @@
 //! This crate provides the foundational types and abstractions used across
 //! all game implementations and the main application.
 
+pub mod account;
 pub mod config;
 pub mod daily_reward;
 pub mod error;
@@ -14,17 +15,47 @@ pub mod resource;
 pub mod resource_types;
 pub mod serde_utils;
 
+pub use account::AccountKey;
+pub use account::DEFAULT_ACCOUNT;
+pub use config::AccountView;
+pub use config::AdaptivePollingConfig;
 pub use config::AppConfig;
 pub use config::ClaimTime;
 pub use config::DEFAULT_AUTO_CLAIM_TIME;
+pub use config::DEFAULT_RULE_ID;
+pub use config::EmailChannelConfig;
 pub use config::GamesConfig;
+pub use config::GenshinAccountConfig;
 pub use config::GenshinConfig;
+pub use config::GotifyChannelConfig;
+pub use config::HookChannelConfig;
+pub use config::HookEvent;
+pub use config::HsrAccountConfig;
 pub use config::HsrConfig;
+pub use config::HttpApiConfig;
+pub use config::HttpApiSecrets;
+pub use config::MqttConfig;
+pub use config::MqttSecrets;
+pub use config::NotificationBatchConfig;
+pub use config::NotificationChannelConfig;
+pub use config::NotificationChannelSecrets;
+pub use config::NotificationRule;
+pub use config::NtfyChannelConfig;
+pub use config::PushPriority;
+pub use config::QuietHours;
+pub use config::RateLimitsConfig;
 pub use config::ResourceNotificationConfig;
 pub use config::SecretsConfig;
+pub use config::SmtpSecurity;
+pub use config::WebhookChannelConfig;
+pub use config::WebhookPreset;
+pub use config::Weekday;
+pub use config::WuwaAccountConfig;
 pub use config::WuwaConfig;
+pub use config::ZzzAccountConfig;
 pub use config::ZzzConfig;
 pub use config::ensure_configs_exist;
+pub use config::in_quiet_hours;
 pub use config::next_claim_datetime_utc;
 pub use daily_reward::ClaimResult;
 pub use daily_reward::DailyReward;
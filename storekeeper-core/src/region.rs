@@ -91,6 +91,28 @@ impl Region {
         }
     }
 
+    /// Parses region from a HoYoLab API server name (e.g. `os_usa` or
+    /// `prod_gf_jp`), as returned for game roles.
+    ///
+    /// Inverse of [`Self::genshin_region`], [`Self::hsr_region`] and
+    /// [`Self::zzz_region`]. The Bilibili servers of mainland China (`cn_qd01`,
+    /// `prod_qd_cn`) are rejected, as no region maps back to them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server name is unknown.
+    pub fn from_hoyolab_server(server: &str) -> Result<Self> {
+        match server {
+            "cn_gf01" | "prod_gf_cn" => Ok(Self::China),
+            "os_usa" | "prod_official_usa" | "prod_gf_us" => Ok(Self::America),
+            "os_euro" | "prod_official_eur" | "prod_gf_eu" => Ok(Self::Europe),
+            "os_asia" | "prod_official_asia" | "prod_gf_sg" => Ok(Self::Asia),
+            "os_cht" | "prod_official_cht" => Ok(Self::Cht),
+            "prod_gf_jp" => Ok(Self::Japan),
+            _ => Err(Error::UnknownServerRegion(server.to_string())),
+        }
+    }
+
     /// Parses region from a Genshin Impact UID.
     ///
     /// # Errors
@@ -227,6 +249,76 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_hoyolab_server_parsing() {
+        assert_eq!(
+            Region::from_hoyolab_server("os_euro").expect("known server"),
+            Region::Europe
+        );
+        assert_eq!(
+            Region::from_hoyolab_server("prod_gf_jp").expect("known server"),
+            Region::Japan
+        );
+        assert_eq!(
+            Region::from_hoyolab_server("prod_gf_cn").expect("known server"),
+            Region::China
+        );
+        for region in [Region::China, Region::America, Region::Europe, Region::Cht] {
+            assert_eq!(
+                Region::from_hoyolab_server(region.genshin_region()).ok(),
+                Some(region)
+            );
+            assert_eq!(
+                Region::from_hoyolab_server(region.hsr_region()).ok(),
+                Some(region)
+            );
+        }
+        assert!(matches!(
+            Region::from_hoyolab_server("unknown"),
+            Err(Error::UnknownServerRegion(_))
+        ));
+    }
+
+    #[test]
+    fn test_hoyolab_servers_round_trip() {
+        let round_trip = |to_server: fn(Region) -> &'static str, servers: &[&str]| {
+            for &server in servers {
+                let region = Region::from_hoyolab_server(server).expect("known server");
+                assert_eq!(to_server(region), server);
+            }
+        };
+        round_trip(
+            Region::genshin_region,
+            &["cn_gf01", "os_usa", "os_euro", "os_asia", "os_cht"],
+        );
+        round_trip(
+            Region::hsr_region,
+            &[
+                "prod_gf_cn",
+                "prod_official_usa",
+                "prod_official_eur",
+                "prod_official_asia",
+                "prod_official_cht",
+            ],
+        );
+        round_trip(
+            Region::zzz_region,
+            &[
+                "prod_gf_cn",
+                "prod_gf_us",
+                "prod_gf_eu",
+                "prod_gf_sg",
+                "prod_gf_jp",
+            ],
+        );
+        for server in ["cn_qd01", "prod_qd_cn"] {
+            assert!(matches!(
+                Region::from_hoyolab_server(server),
+                Err(Error::UnknownServerRegion(_))
+            ));
+        }
+    }
+
     #[test]
     fn test_wuwa_uid_parsing() {
         assert_eq!(
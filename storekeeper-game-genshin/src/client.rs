@@ -8,6 +8,7 @@ use jiff::Timestamp;
 use serde::Deserialize;
 use serde::Deserializer;
 use storekeeper_client_hoyolab::HoyolabClient;
+use storekeeper_client_hoyolab::Platform;
 use storekeeper_core::CooldownResource;
 use storekeeper_core::ExpeditionResource;
 use storekeeper_core::GameClient;
@@ -154,8 +155,16 @@ impl GenshinClient {
     /// Fetches the daily note data from the API.
     async fn fetch_daily_note(&self) -> Result<DailyNoteResponse> {
         tracing::debug!(uid = %self.uid, region = ?self.region, "Fetching Genshin daily note");
+        let base_url = match self.hoyolab.platform() {
+            Platform::Hoyolab => {
+                "https://sg-public-api.hoyolab.com/event/game_record/genshin/api/dailyNote"
+            }
+            Platform::Miyoushe => {
+                "https://api-takumi-record.mihoyo.com/game_record/app/genshin/api/dailyNote"
+            }
+        };
         let url = format!(
-            "https://sg-public-api.hoyolab.com/event/game_record/genshin/api/dailyNote?server={}&role_id={}",
+            "{base_url}?server={}&role_id={}",
             self.region.genshin_region(),
             self.uid
         );
@@ -6,6 +6,7 @@ use crate::resource::HsrResource;
 use jiff::Timestamp;
 use serde::Deserialize;
 use storekeeper_client_hoyolab::HoyolabClient;
+use storekeeper_client_hoyolab::Platform;
 use storekeeper_core::GameClient;
 use storekeeper_core::GameId;
 use storekeeper_core::Region;
@@ -46,8 +47,14 @@ impl HsrClient {
     /// Fetches the note data from the API.
     async fn fetch_note(&self) -> Result<NoteResponse> {
         tracing::debug!(uid = %self.uid, region = ?self.region, "Fetching HSR note");
+        let base_url = match self.hoyolab.platform() {
+            Platform::Hoyolab => "https://bbs-api-os.hoyolab.com/game_record/hkrpg/api/note",
+            Platform::Miyoushe => {
+                "https://api-takumi-record.mihoyo.com/game_record/app/hkrpg/api/note"
+            }
+        };
         let url = format!(
-            "https://bbs-api-os.hoyolab.com/game_record/hkrpg/api/note?server={}&role_id={}",
+            "{base_url}?server={}&role_id={}",
             self.region.hsr_region(),
             self.uid
         );
@@ -51,11 +51,18 @@ impl WuwaClient {
         region: Region,
     ) -> Result<Self> {
         let kuro = KuroClient::new(oauth_code)?;
-        Ok(Self {
+        Ok(Self::with_kuro(kuro, uid, region))
+    }
+
+    /// Creates a new WuWa client using an existing Kuro client, e.g. one
+    /// sharing a rate limiter with other accounts.
+    #[must_use]
+    pub fn with_kuro(kuro: KuroClient, uid: impl Into<String>, region: Region) -> Self {
+        Self {
             kuro,
             uid: uid.into(),
             region,
-        })
+        }
     }
 
     /// Fetches the role data from the API.
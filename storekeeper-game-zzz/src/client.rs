@@ -6,6 +6,7 @@ use crate::resource::ZzzResource;
 use jiff::Timestamp;
 use serde::Deserialize;
 use storekeeper_client_hoyolab::HoyolabClient;
+use storekeeper_client_hoyolab::Platform;
 use storekeeper_core::GameClient;
 use storekeeper_core::GameId;
 use storekeeper_core::Region;
@@ -56,8 +57,16 @@ impl ZzzClient {
     /// Fetches the note data from the API.
     async fn fetch_note(&self) -> Result<NoteResponse> {
         tracing::debug!(uid = %self.uid, region = ?self.region, "Fetching ZZZ note");
+        let base_url = match self.hoyolab.platform() {
+            Platform::Hoyolab => {
+                "https://sg-public-api.hoyolab.com/event/game_record_zzz/api/zzz/note"
+            }
+            Platform::Miyoushe => {
+                "https://api-takumi-record.mihoyo.com/event/game_record_zzz/api/zzz/note"
+            }
+        };
         let url = format!(
-            "https://sg-public-api.hoyolab.com/event/game_record_zzz/api/zzz/note?server={}&role_id={}",
+            "{base_url}?server={}&role_id={}",
             self.region.zzz_region(),
             self.uid
         );
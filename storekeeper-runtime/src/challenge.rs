@@ -0,0 +1,165 @@
+//! Geetest challenges blocking HoYoLab accounts.
+//!
+//! When HoYoLab's risk control refuses a resource fetch or a daily reward
+//! claim, the account's game is paused: scheduled polls and claims leave it
+//! out, so retrying doesn't provoke more challenges. An
+//! [`AppEvent::ChallengeRequired`](crate::events::AppEvent::ChallengeRequired)
+//! event carries the challenge to the frontend, which has it solved in a
+//! webview and hands the solution to [`solve_challenge`]. The refused request
+//! is then replayed with the solution and the game resumes.
+
+use crate::polling;
+use crate::state::AppState;
+use anyhow::Context as _;
+use serde::Serialize;
+pub use storekeeper_client_hoyolab::Challenges;
+pub use storekeeper_client_hoyolab::GeetestChallenge;
+pub use storekeeper_client_hoyolab::GeetestSolution;
+use storekeeper_core::AccountKey;
+use storekeeper_core::GameId;
+
+/// What a challenge refused, replayed once it is solved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockedOperation {
+    /// Fetching the account's resources.
+    Fetch,
+    /// Claiming the account's daily reward.
+    Claim,
+}
+
+/// A challenge an account is waiting on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingChallenge {
+    /// The challenge to solve.
+    #[serde(flatten)]
+    pub challenge: GeetestChallenge,
+    /// What the challenge refused.
+    pub operation: BlockedOperation,
+}
+
+/// Payload for challenge-required events.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChallengeRequiredPayload<'a> {
+    /// The game that is paused until the challenge is solved.
+    pub game_id: GameId,
+    /// The account whose request was refused.
+    pub account: &'a str,
+    /// The challenge to solve.
+    #[serde(flatten)]
+    pub pending: &'a PendingChallenge,
+}
+
+/// Finds the challenge HoYoLab raised in an error's source chain, if any.
+#[must_use]
+pub fn find_challenge<'a>(
+    error: &'a (dyn std::error::Error + 'static),
+) -> Option<&'a GeetestChallenge> {
+    std::iter::successors(Some(error), |e| e.source()).find_map(|e| {
+        match e.downcast_ref::<storekeeper_client_hoyolab::Error>() {
+            Some(storekeeper_client_hoyolab::Error::ChallengeRequired(challenge)) => {
+                Some(challenge)
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Finds the challenge in an `anyhow` error like [`find_challenge`],
+/// including one built from a type-erased client error.
+#[must_use]
+pub fn find_challenge_anyhow(error: &anyhow::Error) -> Option<&GeetestChallenge> {
+    error
+        .downcast_ref::<Box<dyn std::error::Error + Send + Sync>>()
+        .map_or_else(
+            || find_challenge(error.as_ref()),
+            |boxed| find_challenge(boxed.as_ref()),
+        )
+}
+
+/// Hands the solution to an account's pending challenge to its HoYoLab client
+/// and replays the refused fetch or claim with it, resuming the game unless
+/// another of its accounts still waits on a challenge.
+///
+/// # Errors
+///
+/// Returns an error if the account has no pending challenge matching the
+/// solution, or if the replay fails.
+pub async fn solve_challenge(
+    state: &AppState,
+    key: &AccountKey,
+    solution: GeetestSolution,
+) -> anyhow::Result<()> {
+    let pending = state
+        .take_challenge(key, &solution.challenge)
+        .await
+        .with_context(|| format!("no pending challenge {} for {key}", solution.challenge))?;
+    anyhow::ensure!(
+        state.challenges().solve(solution),
+        "challenge for {key} is no longer awaiting a solution"
+    );
+    tracing::info!(account = %key, operation = ?pending.operation, "Replaying request with solved challenge");
+
+    match pending.operation {
+        // Only this account: its game may stay paused by another account
+        BlockedOperation::Fetch => {
+            polling::refresh_account(state, key)
+                .await
+                .map_err(anyhow::Error::msg)?;
+        }
+        BlockedOperation::Claim => {
+            state.claim_daily_reward_now(key).await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use storekeeper_client_core::ClientError;
+
+    fn challenge() -> GeetestChallenge {
+        GeetestChallenge {
+            gt: "gt1".to_string(),
+            challenge: "c1".to_string(),
+        }
+    }
+
+    #[test]
+    fn challenge_is_found_behind_anyhow_boxing() {
+        let boxed: Box<dyn std::error::Error + Send + Sync> = Box::new(
+            storekeeper_client_hoyolab::Error::ChallengeRequired(challenge()),
+        );
+        let err = anyhow::anyhow!(boxed).context("failed to claim daily reward");
+        assert_eq!(find_challenge_anyhow(&err), Some(&challenge()));
+
+        let other = storekeeper_client_hoyolab::Error::Client(ClientError::api_error(1034, ""));
+        assert_eq!(find_challenge(&other), None);
+    }
+
+    #[test]
+    fn payload_flattens_the_challenge() {
+        let pending = PendingChallenge {
+            challenge: challenge(),
+            operation: BlockedOperation::Claim,
+        };
+        let payload = ChallengeRequiredPayload {
+            game_id: GameId::GenshinImpact,
+            account: "main",
+            pending: &pending,
+        };
+        assert_eq!(
+            serde_json::to_value(&payload).expect("should serialize"),
+            serde_json::json!({
+                "gameId": "GENSHIN_IMPACT",
+                "account": "main",
+                "gt": "gt1",
+                "challenge": "c1",
+                "operation": "claim",
+            })
+        );
+    }
+}
@@ -0,0 +1,462 @@
+//! Game client initialization for the registry.
+
+use crate::daily_reward_registry::DailyRewardRegistry;
+use crate::registry::GameClientRegistry;
+use std::collections::HashMap;
+use std::time::Duration;
+use storekeeper_client_core::RateLimit;
+use storekeeper_client_core::RateLimiter;
+use storekeeper_client_hoyolab::Challenges;
+use storekeeper_client_hoyolab::GENSHIN_DAILY_REWARD;
+use storekeeper_client_hoyolab::GENSHIN_DAILY_REWARD_CN;
+use storekeeper_client_hoyolab::HSR_DAILY_REWARD;
+use storekeeper_client_hoyolab::HSR_DAILY_REWARD_CN;
+use storekeeper_client_hoyolab::HoyolabClient;
+use storekeeper_client_hoyolab::HoyolabDailyRewardClient;
+use storekeeper_client_hoyolab::HoyolabDailyRewardConfig;
+use storekeeper_client_hoyolab::Platform;
+use storekeeper_client_hoyolab::ZZZ_DAILY_REWARD;
+use storekeeper_client_hoyolab::ZZZ_DAILY_REWARD_CN;
+use storekeeper_client_kuro::KuroClient;
+use storekeeper_client_kuro::load_oauth_from_cache;
+use storekeeper_core::AccountView;
+use storekeeper_core::AppConfig;
+use storekeeper_core::DEFAULT_ACCOUNT;
+use storekeeper_core::DynDailyRewardClient;
+use storekeeper_core::DynGameClient;
+use storekeeper_core::GameId;
+use storekeeper_core::RateLimitsConfig;
+use storekeeper_core::Region;
+use storekeeper_core::SecretsConfig;
+use storekeeper_game_genshin::GenshinClient;
+use storekeeper_game_hsr::HsrClient;
+use storekeeper_game_wuwa::WuwaClient;
+use storekeeper_game_zzz::ZzzClient;
+
+type RegionDetector = fn(&str) -> std::result::Result<Region, storekeeper_core::Error>;
+type HoyolabGameFactory = fn(HoyolabClient, &str, Region) -> Box<dyn DynGameClient>;
+type ApiRegion = fn(Region) -> &'static str;
+
+struct EnabledHoyolabGame<'a> {
+    account: AccountView<'a>,
+    detect_region: RegionDetector,
+    create_client: HoyolabGameFactory,
+    game_name: &'static str,
+}
+
+struct DailyRewardSpec<'a> {
+    account: AccountView<'a>,
+    config: &'static HoyolabDailyRewardConfig,
+    cn_config: &'static HoyolabDailyRewardConfig,
+    detect_region: RegionDetector,
+    api_region: ApiRegion,
+    game_name: &'static str,
+}
+
+/// Returns the account's configured region, or the one its UID implies.
+fn resolve_region(account: &AccountView<'_>, detect_region: RegionDetector) -> Option<Region> {
+    account.region.or_else(|| detect_region(account.uid).ok())
+}
+
+/// Rate limiters shared by every client of each API provider.
+///
+/// Both registries built from one config should get the same limiters, so
+/// resource fetches, daily reward checks and auth checks draw from one
+/// budget per provider.
+#[derive(Debug, Clone)]
+pub struct RateLimiters {
+    pub(crate) hoyolab: RateLimiter,
+    kuro: RateLimiter,
+}
+
+impl RateLimiters {
+    /// Creates fresh limiters from the configured rates.
+    #[must_use]
+    pub fn new(config: &RateLimitsConfig) -> Self {
+        let cooldown = Duration::from_secs(config.cooldown_secs);
+        Self {
+            hoyolab: RateLimiter::new(RateLimit::new(
+                config.hoyolab_requests_per_minute,
+                config.hoyolab_burst,
+                cooldown,
+            )),
+            kuro: RateLimiter::new(RateLimit::new(
+                config.kuro_requests_per_minute,
+                config.kuro_burst,
+                cooldown,
+            )),
+        }
+    }
+}
+
+impl Default for RateLimiters {
+    fn default() -> Self {
+        Self::new(&RateLimitsConfig::default())
+    }
+}
+
+/// Lazily created `HoYoLab` clients, one per credentials entry and platform.
+///
+/// Accounts that share a login share a single `HoyolabClient` instance to
+/// avoid redundant HTTP client allocations.
+struct HoyolabClients<'a> {
+    secrets: &'a SecretsConfig,
+    rate_limiter: &'a RateLimiter,
+    challenges: &'a Challenges,
+    clients: HashMap<(String, Platform), Option<HoyolabClient>>,
+}
+
+impl<'a> HoyolabClients<'a> {
+    fn new(
+        secrets: &'a SecretsConfig,
+        rate_limiters: &'a RateLimiters,
+        challenges: &'a Challenges,
+    ) -> Self {
+        Self {
+            secrets,
+            rate_limiter: &rate_limiters.hoyolab,
+            challenges,
+            clients: HashMap::new(),
+        }
+    }
+
+    /// Returns the client for a credentials entry on `platform`, creating it
+    /// on first use.
+    ///
+    /// Returns `None` if the credentials are missing or the client could not
+    /// be created.
+    fn get(&mut self, credentials: &str, platform: Platform) -> Option<&HoyolabClient> {
+        let (secrets, rate_limiter, challenges) =
+            (self.secrets, self.rate_limiter, self.challenges);
+        self.clients
+            .entry((credentials.to_owned(), platform))
+            .or_insert_with(|| {
+                create_hoyolab_client(secrets, rate_limiter, challenges, credentials, platform)
+            })
+            .as_ref()
+    }
+}
+
+/// Creates a `HoYoLab` client for a credentials entry on `platform`.
+///
+/// Returns `None` if the credentials are missing or the client could not be
+/// created.
+pub(crate) fn create_hoyolab_client(
+    secrets: &SecretsConfig,
+    rate_limiter: &RateLimiter,
+    challenges: &Challenges,
+    credentials: &str,
+    platform: Platform,
+) -> Option<HoyolabClient> {
+    let Some(login) = secrets
+        .hoyolab_login(credentials)
+        .filter(|login| login.is_configured())
+    else {
+        tracing::debug!(
+            credentials = %credentials,
+            "HoYoLab credentials not configured, skipping accounts using them"
+        );
+        return None;
+    };
+
+    let client = match platform {
+        Platform::Hoyolab => HoyolabClient::new(login.ltuid(), login.ltoken()),
+        Platform::Miyoushe => HoyolabClient::miyoushe(login.ltuid(), login.ltoken(), login.ltmid()),
+    };
+    match client {
+        Ok(client) => Some(
+            client
+                .with_rate_limiter(rate_limiter.clone())
+                .with_challenges(challenges.clone()),
+        ),
+        Err(e) => {
+            tracing::warn!(credentials = %credentials, "Failed to create HoYoLab client: {e}");
+            None
+        }
+    }
+}
+
+fn enabled_accounts(config: &AppConfig, game_id: GameId) -> impl Iterator<Item = AccountView<'_>> {
+    config
+        .games
+        .accounts(game_id)
+        .into_iter()
+        .filter(|account| account.enabled)
+}
+
+fn enabled_hoyolab_games(config: &AppConfig) -> Vec<EnabledHoyolabGame<'_>> {
+    let games: [(GameId, RegionDetector, HoyolabGameFactory, &'static str); 3] = [
+        (
+            GameId::GenshinImpact,
+            Region::from_genshin_uid,
+            |h, uid, region| Box::new(GenshinClient::new(h, uid, region)),
+            "Genshin Impact",
+        ),
+        (
+            GameId::HonkaiStarRail,
+            Region::from_hsr_uid,
+            |h, uid, region| Box::new(HsrClient::new(h, uid, region)),
+            "Honkai: Star Rail",
+        ),
+        (
+            GameId::ZenlessZoneZero,
+            Region::from_zzz_uid,
+            |h, uid, region| Box::new(ZzzClient::new(h, uid, region)),
+            "Zenless Zone Zero",
+        ),
+    ];
+
+    games
+        .into_iter()
+        .flat_map(|(game_id, detect_region, create_client, game_name)| {
+            enabled_accounts(config, game_id).map(move |account| EnabledHoyolabGame {
+                account,
+                detect_region,
+                create_client,
+                game_name,
+            })
+        })
+        .collect()
+}
+
+fn daily_reward_specs(config: &AppConfig) -> Vec<DailyRewardSpec<'_>> {
+    let games: [(
+        GameId,
+        &'static HoyolabDailyRewardConfig,
+        &'static HoyolabDailyRewardConfig,
+        RegionDetector,
+        ApiRegion,
+        &'static str,
+    ); 3] = [
+        (
+            GameId::GenshinImpact,
+            &GENSHIN_DAILY_REWARD,
+            &GENSHIN_DAILY_REWARD_CN,
+            Region::from_genshin_uid,
+            Region::genshin_region,
+            "Genshin Impact",
+        ),
+        (
+            GameId::HonkaiStarRail,
+            &HSR_DAILY_REWARD,
+            &HSR_DAILY_REWARD_CN,
+            Region::from_hsr_uid,
+            Region::hsr_region,
+            "Honkai: Star Rail",
+        ),
+        (
+            GameId::ZenlessZoneZero,
+            &ZZZ_DAILY_REWARD,
+            &ZZZ_DAILY_REWARD_CN,
+            Region::from_zzz_uid,
+            Region::zzz_region,
+            "Zenless Zone Zero",
+        ),
+    ];
+
+    games
+        .into_iter()
+        .flat_map(
+            |(game_id, reward_config, cn_config, detect_region, api_region, game_name)| {
+                enabled_accounts(config, game_id).map(move |account| DailyRewardSpec {
+                    account,
+                    config: reward_config,
+                    cn_config,
+                    detect_region,
+                    api_region,
+                    game_name,
+                })
+            },
+        )
+        .collect()
+}
+
+/// Registers a HoYoLab-based game client in its resolved region.
+fn register_hoyolab_game(
+    registry: &mut GameClientRegistry,
+    hoyolab: &HoyolabClient,
+    game: &EnabledHoyolabGame<'_>,
+    region: Region,
+) {
+    let account = &game.account;
+    let client = (game.create_client)(hoyolab.clone(), account.uid, region);
+    tracing::info!(
+        uid = %account.uid,
+        region = ?region,
+        account = %account.name,
+        "{} client registered",
+        game.game_name
+    );
+    registry.register(account.name, client);
+}
+
+/// Creates the daily reward client of an account, on Miyoushe for mainland
+/// China servers and on HoYoLab otherwise.
+fn create_daily_reward_client(
+    hoyolab_clients: &mut HoyolabClients<'_>,
+    spec: &DailyRewardSpec<'_>,
+) -> Option<HoyolabDailyRewardClient> {
+    let region = resolve_region(&spec.account, spec.detect_region);
+    let platform = region.map_or(Platform::Hoyolab, Platform::from_region);
+    let hoyolab = hoyolab_clients
+        .get(spec.account.credentials, platform)?
+        .clone();
+    Some(match (platform, region) {
+        (Platform::Miyoushe, Some(region)) => {
+            HoyolabDailyRewardClient::new(hoyolab, spec.cn_config)
+                .with_role(spec.account.uid, (spec.api_region)(region))
+        }
+        _ => HoyolabDailyRewardClient::new(hoyolab, spec.config),
+    })
+}
+
+/// Resolves the Kuro OAuth code for a Wuthering Waves account.
+///
+/// The default login falls back to the Kuro launcher cache, which only ever
+/// holds the account currently signed in to the launcher. Named logins must
+/// set `oauth_code` explicitly.
+fn kuro_oauth_code(secrets: &SecretsConfig, credentials: &str) -> Option<String> {
+    let override_code = secrets
+        .kuro_login(credentials)
+        .and_then(|kuro| kuro.oauth_code_override())
+        .map(String::from);
+
+    if credentials != DEFAULT_ACCOUNT {
+        return override_code;
+    }
+
+    override_code.or_else(|| match load_oauth_from_cache() {
+        Ok(code) => code,
+        Err(e) => {
+            tracing::warn!("Failed to load Kuro OAuth code from cache: {e}");
+            None
+        }
+    })
+}
+
+/// Registers a Wuthering Waves client for one account if an OAuth code and
+/// region are available.
+fn register_wuwa_account(
+    registry: &mut GameClientRegistry,
+    secrets: &SecretsConfig,
+    rate_limiter: &RateLimiter,
+    account: &AccountView<'_>,
+) {
+    let Some(oauth_code) = kuro_oauth_code(secrets, account.credentials) else {
+        if account.credentials == DEFAULT_ACCOUNT {
+            tracing::warn!(
+                "Wuthering Waves is enabled but no OAuth code available. \
+                     Set oauth_code in secrets.toml or ensure the Kuro launcher cache exists."
+            );
+        } else {
+            tracing::warn!(
+                account = %account.name,
+                "Wuthering Waves account is enabled but no OAuth code available. \
+                     Set oauth_code in [kuro_accounts.{}] in secrets.toml.",
+                account.credentials
+            );
+        }
+        return;
+    };
+
+    let region = account
+        .region
+        .or_else(|| Region::from_wuwa_uid(account.uid).ok());
+    if let Some(region) = region
+        && let Ok(kuro) = KuroClient::new(&oauth_code)
+    {
+        let client = WuwaClient::with_kuro(
+            kuro.with_rate_limiter(rate_limiter.clone()),
+            account.uid,
+            region,
+        );
+        tracing::info!(
+            uid = %account.uid,
+            region = ?region,
+            account = %account.name,
+            "Wuthering Waves client registered"
+        );
+        registry.register(account.name, Box::new(client) as Box<dyn DynGameClient>);
+    }
+}
+
+/// Creates a `GameClientRegistry` from configuration and secrets.
+///
+/// One client is registered per enabled account. HoYoLab-based accounts that
+/// share a login share a single `HoyolabClient` instance per platform, and
+/// all clients of a provider share its rate limiter. Accounts on mainland
+/// China servers go through Miyoushe. HoYoLab clients record the Geetest
+/// challenges they raise in `challenges`.
+#[must_use]
+pub fn create_registry(
+    config: &AppConfig,
+    secrets: &SecretsConfig,
+    rate_limiters: &RateLimiters,
+    challenges: &Challenges,
+) -> GameClientRegistry {
+    tracing::info!("Creating game client registry from configuration");
+    let mut registry = GameClientRegistry::new();
+
+    // Initialize HoYoLab-based clients for accounts with configured credentials
+    let mut hoyolab_clients = HoyolabClients::new(secrets, rate_limiters, challenges);
+    for game in enabled_hoyolab_games(config) {
+        let Some(region) = resolve_region(&game.account, game.detect_region) else {
+            continue;
+        };
+        let platform = Platform::from_region(region);
+        if let Some(hoyolab) = hoyolab_clients.get(game.account.credentials, platform) {
+            register_hoyolab_game(&mut registry, hoyolab, &game, region);
+        }
+    }
+
+    // Initialize Kuro-based clients (Wuthering Waves)
+    for account in enabled_accounts(config, GameId::WutheringWaves) {
+        register_wuwa_account(&mut registry, secrets, &rate_limiters.kuro, &account);
+    }
+
+    tracing::info!(
+        client_count = registry.len(),
+        "Game client registry creation complete"
+    );
+
+    registry
+}
+
+/// Creates a `DailyRewardRegistry` from configuration and secrets.
+///
+/// Daily reward clients for accounts sharing a login share a single
+/// `HoyolabClient` per platform and differ only by their endpoint
+/// configuration. Accounts on mainland China servers claim on Miyoushe.
+#[must_use]
+pub fn create_daily_reward_registry(
+    config: &AppConfig,
+    secrets: &SecretsConfig,
+    rate_limiters: &RateLimiters,
+    challenges: &Challenges,
+) -> DailyRewardRegistry {
+    tracing::info!("Creating daily reward registry from configuration");
+    let mut registry = DailyRewardRegistry::new();
+
+    let mut hoyolab_clients = HoyolabClients::new(secrets, rate_limiters, challenges);
+    for spec in daily_reward_specs(config) {
+        let Some(client) = create_daily_reward_client(&mut hoyolab_clients, &spec) else {
+            continue;
+        };
+        tracing::info!(
+            account = %spec.account.name,
+            "{} daily reward client registered",
+            spec.game_name
+        );
+        registry.register(
+            spec.account.name,
+            Box::new(client) as Box<dyn DynDailyRewardClient>,
+        );
+    }
+
+    tracing::info!(
+        client_count = registry.len(),
+        "Daily reward registry creation complete"
+    );
+
+    registry
+}
@@ -3,23 +3,41 @@
 //! Compares old and new configurations to determine the minimum work needed
 //! when the user saves settings, avoiding unnecessary HTTP API calls.
 
+use std::collections::BTreeMap;
 use std::collections::HashSet;
 use storekeeper_core::AppConfig;
 use storekeeper_core::GameId;
 use storekeeper_core::SecretsConfig;
 
 /// Describes what changed between two configurations.
-pub(crate) struct ConfigDiff {
+#[expect(
+    clippy::struct_excessive_bools,
+    reason = "independent change flags, each acted on separately"
+)]
+pub struct ConfigDiff {
     /// Whether the locale/language setting changed (requires tray rebuild).
     pub locale_changed: bool,
 
     /// Whether the autostart setting changed (requires OS sync).
     pub autostart_changed: bool,
 
+    /// Whether the HTTP API settings or token changed (requires a server
+    /// restart).
+    pub http_api_changed: bool,
+
+    /// Whether the MQTT settings or broker login changed (requires a
+    /// reconnect).
+    pub mqtt_changed: bool,
+
+    /// Whether notification channel settings or secrets changed (applied by
+    /// [`AppState::apply_config`](crate::state::AppState::apply_config)).
+    pub notification_channels_changed: bool,
+
     /// Whether game client registries need to be rebuilt.
     ///
-    /// True when any game's client-relevant fields (enabled, uid, region,
-    /// tracked_resources) or provider credentials changed.
+    /// True when any account's client-relevant fields (enabled, uid, region,
+    /// credentials, tracked_resources), provider credentials or rate limits
+    /// changed.
     pub needs_registry_rebuild: bool,
 
     /// Games whose resources should be re-fetched from API.
@@ -31,9 +49,13 @@ pub(crate) struct ConfigDiff {
 
 impl ConfigDiff {
     /// Returns true if nothing changed (no work needed).
+    #[must_use]
     pub fn is_empty(&self) -> bool {
         !self.locale_changed
             && !self.autostart_changed
+            && !self.http_api_changed
+            && !self.mqtt_changed
+            && !self.notification_channels_changed
             && !self.needs_registry_rebuild
             && self.games_to_refresh.is_empty()
             && self.games_to_reset_notifications.is_empty()
@@ -41,7 +63,8 @@ impl ConfigDiff {
 }
 
 /// Computes the diff between old and new config/secrets.
-pub(crate) fn compute(
+#[must_use]
+pub fn compute(
     old_config: &AppConfig,
     new_config: &AppConfig,
     old_secrets: &SecretsConfig,
@@ -49,8 +72,14 @@ pub(crate) fn compute(
 ) -> ConfigDiff {
     let locale_changed = old_config.general.language != new_config.general.language;
     let autostart_changed = old_config.general.autostart != new_config.general.autostart;
-
-    let mut needs_registry_rebuild = false;
+    let http_api_changed =
+        old_config.http_api != new_config.http_api || old_secrets.http_api != new_secrets.http_api;
+    let mqtt_changed = old_config.mqtt != new_config.mqtt || old_secrets.mqtt != new_secrets.mqtt;
+    let notification_channels_changed = old_config.notification_channels
+        != new_config.notification_channels
+        || old_secrets.notification_channels != new_secrets.notification_channels;
+
+    let mut needs_registry_rebuild = old_config.rate_limits != new_config.rate_limits;
     let mut games_to_refresh = HashSet::new();
     let mut games_to_reset_notifications = HashSet::new();
 
@@ -83,7 +112,9 @@ pub(crate) fn compute(
     }
 
     // Check secrets changes — affects all games of the corresponding provider
-    if old_secrets.hoyolab != new_secrets.hoyolab {
+    if old_secrets.hoyolab != new_secrets.hoyolab
+        || old_secrets.hoyolab_accounts != new_secrets.hoyolab_accounts
+    {
         needs_registry_rebuild = true;
         for &game_id in &[
             GameId::GenshinImpact,
@@ -96,7 +127,9 @@ pub(crate) fn compute(
         }
     }
 
-    if old_secrets.kuro != new_secrets.kuro {
+    if old_secrets.kuro != new_secrets.kuro
+        || old_secrets.kuro_accounts != new_secrets.kuro_accounts
+    {
         needs_registry_rebuild = true;
         if new_config.games.is_enabled(GameId::WutheringWaves) {
             games_to_refresh.insert(GameId::WutheringWaves);
@@ -106,6 +139,9 @@ pub(crate) fn compute(
     ConfigDiff {
         locale_changed,
         autostart_changed,
+        http_api_changed,
+        mqtt_changed,
+        notification_channels_changed,
         needs_registry_rebuild,
         games_to_refresh,
         games_to_reset_notifications,
@@ -130,6 +166,32 @@ struct ClientIdentity<'a> {
     uid: &'a str,
     region: Option<&'a storekeeper_core::region::Region>,
     tracked_resources_hash: u64,
+    accounts: Vec<AccountIdentity<'a>>,
+}
+
+/// Identity fields of a named account that determine its HTTP client.
+#[derive(PartialEq, Eq)]
+struct AccountIdentity<'a> {
+    name: &'a str,
+    enabled: bool,
+    uid: &'a str,
+    region: Option<&'a storekeeper_core::region::Region>,
+    credentials: Option<&'a str>,
+    tracked_resources_hash: u64,
+}
+
+/// Returns true if the notification settings of any named account changed,
+/// or an account with notification settings was added or removed.
+fn account_notifications_changed<A, N: PartialEq>(
+    old: &BTreeMap<String, A>,
+    new: &BTreeMap<String, A>,
+    notifications: impl Fn(&A) -> &N,
+) -> bool {
+    !old.iter()
+        .map(|(name, account)| (name, notifications(account)))
+        .eq(new
+            .iter()
+            .map(|(name, account)| (name, notifications(account))))
 }
 
 fn hash_vec<T: std::hash::Hash>(items: &[T]) -> u64 {
@@ -150,10 +212,23 @@ impl ClientFields for storekeeper_core::GenshinConfig {
             uid: &self.uid,
             region: self.region.as_ref(),
             tracked_resources_hash: hash_vec(&self.tracked_resources),
+            accounts: self
+                .accounts
+                .iter()
+                .map(|(name, account)| AccountIdentity {
+                    name,
+                    enabled: account.enabled,
+                    uid: &account.uid,
+                    region: account.region.as_ref(),
+                    credentials: account.credentials.as_deref(),
+                    tracked_resources_hash: hash_vec(&account.tracked_resources),
+                })
+                .collect(),
         }
     }
     fn notification_changed(&self, other: &Self) -> bool {
         self.notifications != other.notifications
+            || account_notifications_changed(&self.accounts, &other.accounts, |a| &a.notifications)
     }
 }
 
@@ -167,10 +242,23 @@ impl ClientFields for storekeeper_core::HsrConfig {
             uid: &self.uid,
             region: self.region.as_ref(),
             tracked_resources_hash: hash_vec(&self.tracked_resources),
+            accounts: self
+                .accounts
+                .iter()
+                .map(|(name, account)| AccountIdentity {
+                    name,
+                    enabled: account.enabled,
+                    uid: &account.uid,
+                    region: account.region.as_ref(),
+                    credentials: account.credentials.as_deref(),
+                    tracked_resources_hash: hash_vec(&account.tracked_resources),
+                })
+                .collect(),
         }
     }
     fn notification_changed(&self, other: &Self) -> bool {
         self.notifications != other.notifications
+            || account_notifications_changed(&self.accounts, &other.accounts, |a| &a.notifications)
     }
 }
 
@@ -184,10 +272,23 @@ impl ClientFields for storekeeper_core::ZzzConfig {
             uid: &self.uid,
             region: self.region.as_ref(),
             tracked_resources_hash: hash_vec(&self.tracked_resources),
+            accounts: self
+                .accounts
+                .iter()
+                .map(|(name, account)| AccountIdentity {
+                    name,
+                    enabled: account.enabled,
+                    uid: &account.uid,
+                    region: account.region.as_ref(),
+                    credentials: account.credentials.as_deref(),
+                    tracked_resources_hash: hash_vec(&account.tracked_resources),
+                })
+                .collect(),
         }
     }
     fn notification_changed(&self, other: &Self) -> bool {
         self.notifications != other.notifications
+            || account_notifications_changed(&self.accounts, &other.accounts, |a| &a.notifications)
     }
 }
 
@@ -201,10 +302,23 @@ impl ClientFields for storekeeper_core::WuwaConfig {
             uid: &self.uid,
             region: self.region.as_ref(),
             tracked_resources_hash: hash_vec(&self.tracked_resources),
+            accounts: self
+                .accounts
+                .iter()
+                .map(|(name, account)| AccountIdentity {
+                    name,
+                    enabled: account.enabled,
+                    uid: &account.uid,
+                    region: account.region.as_ref(),
+                    credentials: account.credentials.as_deref(),
+                    tracked_resources_hash: hash_vec(&account.tracked_resources),
+                })
+                .collect(),
         }
     }
     fn notification_changed(&self, other: &Self) -> bool {
         self.notifications != other.notifications
+            || account_notifications_changed(&self.accounts, &other.accounts, |a| &a.notifications)
     }
 }
 
@@ -268,6 +382,7 @@ mod tests {
     use super::*;
     use storekeeper_core::AppConfig;
     use storekeeper_core::GamesConfig;
+    use storekeeper_core::GenshinAccountConfig;
     use storekeeper_core::GenshinConfig;
     use storekeeper_core::SecretsConfig;
     use storekeeper_core::WuwaConfig;
@@ -283,6 +398,11 @@ mod tests {
             auto_claim_daily_rewards: false,
             auto_claim_time: None,
             notifications: std::collections::HashMap::new(),
+            quiet_hours: None,
+            poll_interval_secs: None,
+            min_poll_interval_secs: None,
+            max_poll_interval_secs: None,
+            accounts: BTreeMap::new(),
         }
     }
 
@@ -293,6 +413,11 @@ mod tests {
             region: None,
             tracked_resources: storekeeper_core::WuwaResourceType::all().to_vec(),
             notifications: std::collections::HashMap::new(),
+            quiet_hours: None,
+            poll_interval_secs: None,
+            min_poll_interval_secs: None,
+            max_poll_interval_secs: None,
+            accounts: BTreeMap::new(),
         }
     }
 
@@ -355,6 +480,70 @@ mod tests {
         assert!(diff.games_to_refresh.is_empty());
     }
 
+    #[test]
+    fn http_api_token_change_only() {
+        let config = AppConfig::default();
+        let old_secrets = SecretsConfig::default();
+        let mut new_secrets = old_secrets.clone();
+        new_secrets.http_api.token = "s3cret".to_string();
+
+        let diff = compute(&config, &config, &old_secrets, &new_secrets);
+
+        assert!(diff.http_api_changed);
+        assert!(!diff.needs_registry_rebuild);
+        assert!(diff.games_to_refresh.is_empty());
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn mqtt_login_change_only() {
+        let config = AppConfig::default();
+        let old_secrets = SecretsConfig::default();
+        let mut new_secrets = old_secrets.clone();
+        new_secrets.mqtt.username = "storekeeper".to_string();
+
+        let diff = compute(&config, &config, &old_secrets, &new_secrets);
+
+        assert!(diff.mqtt_changed);
+        assert!(!diff.http_api_changed);
+        assert!(!diff.needs_registry_rebuild);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn notification_channel_url_change_only() {
+        let config = AppConfig::default();
+        let old_secrets = SecretsConfig::default();
+        let mut new_secrets = old_secrets.clone();
+        new_secrets.notification_channels.insert(
+            "phone".to_string(),
+            storekeeper_core::NotificationChannelSecrets {
+                url: "https://example.com/hook".to_string(),
+                ..Default::default()
+            },
+        );
+
+        let diff = compute(&config, &config, &old_secrets, &new_secrets);
+
+        assert!(diff.notification_channels_changed);
+        assert!(!diff.http_api_changed);
+        assert!(!diff.needs_registry_rebuild);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn rate_limit_change_only_rebuilds() {
+        let old = AppConfig::default();
+        let mut new = AppConfig::default();
+        new.rate_limits.hoyolab_burst = 1;
+        let secrets = SecretsConfig::default();
+
+        let diff = compute(&old, &new, &secrets, &secrets);
+        assert!(diff.needs_registry_rebuild);
+        assert!(diff.games_to_refresh.is_empty());
+        assert!(diff.games_to_reset_notifications.is_empty());
+    }
+
     #[test]
     fn game_uid_change_triggers_rebuild_and_refresh() {
         let old = config_with_genshin(default_genshin());
@@ -395,6 +584,10 @@ mod tests {
                 notify_minutes_before_full: Some(30),
                 notify_at_value: None,
                 cooldown_minutes: 10,
+                channels: Vec::new(),
+                template: None,
+                critical: false,
+                rules: Vec::new(),
             },
         );
         let new = config_with_genshin(new_genshin);
@@ -484,4 +677,91 @@ mod tests {
         let diff = compute(&config, &config, &secrets, &secrets);
         assert!(diff.is_empty());
     }
+
+    fn alt_genshin_account() -> GenshinAccountConfig {
+        GenshinAccountConfig {
+            enabled: true,
+            uid: "800000002".to_string(),
+            region: None,
+            credentials: None,
+            tracked_resources: storekeeper_core::GenshinResourceType::all().to_vec(),
+            auto_claim_daily_rewards: false,
+            auto_claim_time: None,
+            notifications: std::collections::HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn account_added_triggers_rebuild_and_refresh() {
+        let old = config_with_genshin(default_genshin());
+        let mut new_genshin = default_genshin();
+        new_genshin
+            .accounts
+            .insert("alt".to_string(), alt_genshin_account());
+        let new = config_with_genshin(new_genshin);
+
+        let secrets = SecretsConfig::default();
+        let diff = compute(&old, &new, &secrets, &secrets);
+
+        assert!(diff.needs_registry_rebuild);
+        assert!(diff.games_to_refresh.contains(&GameId::GenshinImpact));
+    }
+
+    #[test]
+    fn account_notification_change_only_resets_cooldowns() {
+        let mut old_genshin = default_genshin();
+        old_genshin
+            .accounts
+            .insert("alt".to_string(), alt_genshin_account());
+        let old = config_with_genshin(old_genshin.clone());
+
+        let mut alt = alt_genshin_account();
+        alt.notifications.insert(
+            storekeeper_core::resource_types::GenshinResourceType::Resin,
+            storekeeper_core::ResourceNotificationConfig {
+                enabled: true,
+                notify_minutes_before_full: Some(30),
+                notify_at_value: None,
+                cooldown_minutes: 10,
+                channels: Vec::new(),
+                template: None,
+                critical: false,
+                rules: Vec::new(),
+            },
+        );
+        old_genshin.accounts.insert("alt".to_string(), alt);
+        let new = config_with_genshin(old_genshin);
+
+        let secrets = SecretsConfig::default();
+        let diff = compute(&old, &new, &secrets, &secrets);
+
+        assert!(!diff.needs_registry_rebuild);
+        assert!(diff.games_to_refresh.is_empty());
+        assert!(
+            diff.games_to_reset_notifications
+                .contains(&GameId::GenshinImpact)
+        );
+    }
+
+    #[test]
+    fn named_hoyolab_secrets_change_triggers_rebuild() {
+        let old = config_with_genshin(default_genshin());
+        let new = old.clone();
+
+        let old_secrets = SecretsConfig::default();
+        let mut new_secrets = SecretsConfig::default();
+        new_secrets.hoyolab_accounts.insert(
+            "alt".to_string(),
+            HoyolabSecrets {
+                ltuid_v2: "alt_uid".to_string(),
+                ltoken_v2: "alt_token".to_string(),
+                ltmid_v2: String::new(),
+            },
+        );
+
+        let diff = compute(&old, &new, &old_secrets, &new_secrets);
+
+        assert!(diff.needs_registry_rebuild);
+        assert!(diff.games_to_refresh.contains(&GameId::GenshinImpact));
+    }
 }
@@ -4,15 +4,17 @@ use crate::provider_batch;
 use anyhow::Context;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use storekeeper_core::AccountKey;
 use storekeeper_core::DynDailyRewardClient;
 use storekeeper_core::GameId;
 
 /// Registry that holds type-erased daily reward clients.
 ///
 /// Similar to `GameClientRegistry`, this allows storing different game clients
-/// that implement daily reward functionality in a single collection.
+/// that implement daily reward functionality in a single collection, keyed by
+/// account.
 pub struct DailyRewardRegistry {
-    clients: HashMap<GameId, Box<dyn DynDailyRewardClient>>,
+    clients: HashMap<AccountKey, Box<dyn DynDailyRewardClient>>,
 }
 
 impl DailyRewardRegistry {
@@ -24,13 +26,13 @@ impl DailyRewardRegistry {
         }
     }
 
-    /// Registers a daily reward client in the registry.
+    /// Registers a daily reward client for the named account of its game.
     ///
-    /// If a client for the same game already exists, it will be replaced.
-    pub fn register(&mut self, client: Box<dyn DynDailyRewardClient>) {
-        let id = client.game_id();
-        tracing::debug!(game_id = ?id, "Registering daily reward client");
-        self.clients.insert(id, client);
+    /// If a client for the same account already exists, it will be replaced.
+    pub fn register(&mut self, account: &str, client: Box<dyn DynDailyRewardClient>) {
+        let key = AccountKey::new(client.game_id(), account);
+        tracing::debug!(account = %key, "Registering daily reward client");
+        self.clients.insert(key, client);
     }
 
     /// Returns the number of registered clients.
@@ -41,33 +43,38 @@ impl DailyRewardRegistry {
 
     /// Returns true if no clients are registered.
     #[must_use]
-    #[cfg_attr(
-        not(test),
-        expect(
-            dead_code,
-            reason = "companion to len() for len_without_is_empty; only exercised in tests"
-        )
-    )]
     pub fn is_empty(&self) -> bool {
         self.clients.is_empty()
     }
 
-    /// Returns true if a specific game is registered.
+    /// Returns true if a specific account is registered.
     #[must_use]
-    pub fn has_game(&self, game_id: GameId) -> bool {
-        self.clients.contains_key(&game_id)
+    pub fn has_account(&self, key: &AccountKey) -> bool {
+        self.clients.contains_key(key)
     }
 
-    /// Gets the reward status for a specific game.
+    /// Iterates over the registered clients and their account keys.
+    ///
+    /// Iteration order is unspecified.
+    pub fn clients(&self) -> impl Iterator<Item = (&AccountKey, &dyn DynDailyRewardClient)> {
+        self.clients
+            .iter()
+            .map(|(key, client)| (key, client.as_ref()))
+    }
+
+    /// Gets the reward status for a specific account.
     ///
     /// # Errors
     ///
-    /// Returns an error if the game is not registered or the fetch fails.
-    pub async fn get_status_for_game(&self, game_id: GameId) -> anyhow::Result<serde_json::Value> {
+    /// Returns an error if the account is not registered or the fetch fails.
+    pub async fn get_status_for_account(
+        &self,
+        key: &AccountKey,
+    ) -> anyhow::Result<serde_json::Value> {
         let client = self
             .clients
-            .get(&game_id)
-            .ok_or_else(|| anyhow::anyhow!("Game {game_id:?} not registered for daily rewards"))?;
+            .get(key)
+            .ok_or_else(|| anyhow::anyhow!("Account {key} not registered for daily rewards"))?;
 
         client
             .get_reward_status_json()
@@ -76,16 +83,16 @@ impl DailyRewardRegistry {
             .context("failed to fetch daily reward status")
     }
 
-    /// Claims daily reward for a specific game.
+    /// Claims daily reward for a specific account.
     ///
     /// # Errors
     ///
-    /// Returns an error if the game is not registered or the claim fails.
-    pub async fn claim_for_game(&self, game_id: GameId) -> anyhow::Result<serde_json::Value> {
+    /// Returns an error if the account is not registered or the claim fails.
+    pub async fn claim_for_account(&self, key: &AccountKey) -> anyhow::Result<serde_json::Value> {
         let client = self
             .clients
-            .get(&game_id)
-            .ok_or_else(|| anyhow::anyhow!("Game {game_id:?} not registered for daily rewards"))?;
+            .get(key)
+            .ok_or_else(|| anyhow::anyhow!("Account {key} not registered for daily rewards"))?;
 
         client
             .claim_daily_reward_json()
@@ -96,13 +103,14 @@ impl DailyRewardRegistry {
 
     /// Gets reward status from a subset of registered clients.
     ///
-    /// Same as `get_all_status` but only processes games in the given set.
+    /// Same as `get_all_status` but only processes accounts of games in the
+    /// given set.
     pub async fn get_status_for_games(
         &self,
         game_ids: &HashSet<GameId>,
-    ) -> HashMap<GameId, serde_json::Value> {
-        provider_batch::batch_by_provider(&self.clients, Some(game_ids), |game_id, client| {
-            Box::pin(async move { (game_id, client.get_reward_status_json().await) })
+    ) -> HashMap<AccountKey, serde_json::Value> {
+        provider_batch::batch_by_provider(&self.clients, Some(game_ids), |key, client| {
+            Box::pin(async move { (key, client.get_reward_status_json().await) })
         })
         .await
     }
@@ -110,10 +118,10 @@ impl DailyRewardRegistry {
     /// Gets reward status from all registered clients with rate limit
     /// awareness.
     ///
-    /// Returns a map from game ID to the JSON-serialized reward status.
-    pub async fn get_all_status(&self) -> HashMap<GameId, serde_json::Value> {
-        provider_batch::batch_by_provider(&self.clients, None, |game_id, client| {
-            Box::pin(async move { (game_id, client.get_reward_status_json().await) })
+    /// Returns a map from account key to the JSON-serialized reward status.
+    pub async fn get_all_status(&self) -> HashMap<AccountKey, serde_json::Value> {
+        provider_batch::batch_by_provider(&self.clients, None, |key, client| {
+            Box::pin(async move { (key, client.get_reward_status_json().await) })
         })
         .await
     }
@@ -131,6 +139,7 @@ mod tests {
     use std::future::Future;
     use std::pin::Pin;
     use storekeeper_core::ApiProvider;
+    use storekeeper_core::DEFAULT_ACCOUNT;
 
     type BoxError = Box<dyn std::error::Error + Send + Sync>;
     type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
@@ -207,58 +216,70 @@ mod tests {
     }
 
     #[test]
-    fn register_and_has_game() {
+    fn register_and_has_account() {
         let mut r = DailyRewardRegistry::new();
-        r.register(Box::new(MockDailyRewardClient::success(
-            GameId::GenshinImpact,
-        )));
+        r.register(
+            DEFAULT_ACCOUNT,
+            Box::new(MockDailyRewardClient::success(GameId::GenshinImpact)),
+        );
         assert_eq!(r.len(), 1);
         assert!(!r.is_empty());
-        assert!(r.has_game(GameId::GenshinImpact));
-        assert!(!r.has_game(GameId::HonkaiStarRail));
+        assert!(r.has_account(&AccountKey::default_for(GameId::GenshinImpact)));
+        assert!(!r.has_account(&AccountKey::default_for(GameId::HonkaiStarRail)));
+        assert!(!r.has_account(&AccountKey::new(GameId::GenshinImpact, "alt")));
     }
 
     #[test]
     fn duplicate_replaces() {
         let mut r = DailyRewardRegistry::new();
-        r.register(Box::new(MockDailyRewardClient::success(
-            GameId::GenshinImpact,
-        )));
-        r.register(Box::new(MockDailyRewardClient::failing(
-            GameId::GenshinImpact,
-        )));
+        r.register(
+            DEFAULT_ACCOUNT,
+            Box::new(MockDailyRewardClient::success(GameId::GenshinImpact)),
+        );
+        r.register(
+            DEFAULT_ACCOUNT,
+            Box::new(MockDailyRewardClient::failing(GameId::GenshinImpact)),
+        );
         assert_eq!(r.len(), 1);
     }
 
     // =========================================================================
-    // Async — get_status_for_game
+    // Async — get_status_for_account
     // =========================================================================
 
     #[tokio::test(start_paused = true)]
-    async fn status_for_game_success() {
+    async fn status_for_account_success() {
         let mut r = DailyRewardRegistry::new();
-        r.register(Box::new(MockDailyRewardClient::success(
-            GameId::GenshinImpact,
-        )));
-        let result = r.get_status_for_game(GameId::GenshinImpact).await;
+        r.register(
+            DEFAULT_ACCOUNT,
+            Box::new(MockDailyRewardClient::success(GameId::GenshinImpact)),
+        );
+        let result = r
+            .get_status_for_account(&AccountKey::default_for(GameId::GenshinImpact))
+            .await;
         result.expect("status should succeed");
     }
 
     #[tokio::test(start_paused = true)]
-    async fn status_for_game_not_registered() {
+    async fn status_for_account_not_registered() {
         let r = DailyRewardRegistry::new();
-        let result = r.get_status_for_game(GameId::GenshinImpact).await;
-        let err = result.expect_err("should fail for unregistered game");
+        let result = r
+            .get_status_for_account(&AccountKey::default_for(GameId::GenshinImpact))
+            .await;
+        let err = result.expect_err("should fail for unregistered account");
         assert!(err.to_string().contains("not registered"));
     }
 
     #[tokio::test(start_paused = true)]
-    async fn status_for_game_api_error() {
+    async fn status_for_account_api_error() {
         let mut r = DailyRewardRegistry::new();
-        r.register(Box::new(MockDailyRewardClient::failing(
-            GameId::GenshinImpact,
-        )));
-        let result = r.get_status_for_game(GameId::GenshinImpact).await;
+        r.register(
+            DEFAULT_ACCOUNT,
+            Box::new(MockDailyRewardClient::failing(GameId::GenshinImpact)),
+        );
+        let result = r
+            .get_status_for_account(&AccountKey::default_for(GameId::GenshinImpact))
+            .await;
         let err = result.expect_err("should fail for mock API error");
         assert!(
             format!("{err:#}").contains("mock status error"),
@@ -267,24 +288,29 @@ mod tests {
     }
 
     // =========================================================================
-    // Async — claim_for_game
+    // Async — claim_for_account
     // =========================================================================
 
     #[tokio::test(start_paused = true)]
-    async fn claim_for_game_success() {
+    async fn claim_for_account_success() {
         let mut r = DailyRewardRegistry::new();
-        r.register(Box::new(MockDailyRewardClient::success(
-            GameId::HonkaiStarRail,
-        )));
-        let result = r.claim_for_game(GameId::HonkaiStarRail).await;
+        r.register(
+            DEFAULT_ACCOUNT,
+            Box::new(MockDailyRewardClient::success(GameId::HonkaiStarRail)),
+        );
+        let result = r
+            .claim_for_account(&AccountKey::default_for(GameId::HonkaiStarRail))
+            .await;
         result.expect("claim should succeed");
     }
 
     #[tokio::test(start_paused = true)]
-    async fn claim_for_game_not_registered() {
+    async fn claim_for_account_not_registered() {
         let r = DailyRewardRegistry::new();
-        let result = r.claim_for_game(GameId::HonkaiStarRail).await;
-        result.expect_err("claim should fail for unregistered game");
+        let result = r
+            .claim_for_account(&AccountKey::default_for(GameId::HonkaiStarRail))
+            .await;
+        result.expect_err("claim should fail for unregistered account");
     }
 
     // =========================================================================
@@ -301,25 +327,45 @@ mod tests {
     #[tokio::test(start_paused = true)]
     async fn get_all_status_with_clients() {
         let mut r = DailyRewardRegistry::new();
-        r.register(Box::new(MockDailyRewardClient::success(
-            GameId::GenshinImpact,
-        )));
-        r.register(Box::new(MockDailyRewardClient::success(
-            GameId::HonkaiStarRail,
-        )));
+        r.register(
+            DEFAULT_ACCOUNT,
+            Box::new(MockDailyRewardClient::success(GameId::GenshinImpact)),
+        );
+        r.register(
+            DEFAULT_ACCOUNT,
+            Box::new(MockDailyRewardClient::success(GameId::HonkaiStarRail)),
+        );
+        let map = r.get_all_status().await;
+        assert_eq!(map.len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn get_all_status_per_account() {
+        let mut r = DailyRewardRegistry::new();
+        r.register(
+            DEFAULT_ACCOUNT,
+            Box::new(MockDailyRewardClient::success(GameId::GenshinImpact)),
+        );
+        r.register(
+            "alt",
+            Box::new(MockDailyRewardClient::success(GameId::GenshinImpact)),
+        );
         let map = r.get_all_status().await;
         assert_eq!(map.len(), 2);
+        assert!(map.contains_key(&AccountKey::new(GameId::GenshinImpact, "alt")));
     }
 
     #[tokio::test(start_paused = true)]
     async fn get_all_status_partial_failure() {
         let mut r = DailyRewardRegistry::new();
-        r.register(Box::new(MockDailyRewardClient::success(
-            GameId::GenshinImpact,
-        )));
-        r.register(Box::new(MockDailyRewardClient::failing(
-            GameId::HonkaiStarRail,
-        )));
+        r.register(
+            DEFAULT_ACCOUNT,
+            Box::new(MockDailyRewardClient::success(GameId::GenshinImpact)),
+        );
+        r.register(
+            DEFAULT_ACCOUNT,
+            Box::new(MockDailyRewardClient::failing(GameId::HonkaiStarRail)),
+        );
         let map = r.get_all_status().await;
         assert_eq!(map.len(), 1, "only successful status collected");
     }
@@ -0,0 +1,101 @@
+//! Classification of game client errors into [`ErrorKind`]s.
+//!
+//! Used to label fetch failure metrics and fetch statuses, to decide which
+//! failures to retry and, in the CLI, to pick exit codes.
+
+use storekeeper_client_core::ClientError;
+pub use storekeeper_client_core::ErrorKind;
+
+/// Classifies an error by walking its source chain for typed client errors.
+///
+/// Errors without a typed client error in their chain are
+/// [`ErrorKind::Other`].
+#[must_use]
+pub fn classify(error: &(dyn std::error::Error + 'static)) -> ErrorKind {
+    std::iter::successors(Some(error), |e| e.source())
+        .find_map(classify_one)
+        .unwrap_or(ErrorKind::Other)
+}
+
+/// Classifies an `anyhow` error like [`classify`], including one built from
+/// a type-erased client error, whose type `anyhow` hides from the source
+/// chain.
+#[must_use]
+pub fn classify_anyhow(error: &anyhow::Error) -> ErrorKind {
+    error
+        .downcast_ref::<Box<dyn std::error::Error + Send + Sync>>()
+        .map_or_else(
+            || classify(error.as_ref()),
+            |boxed| classify(boxed.as_ref()),
+        )
+}
+
+/// Classifies a single error in a chain, if its type is recognised.
+fn classify_one(error: &(dyn std::error::Error + 'static)) -> Option<ErrorKind> {
+    if let Some(e) = error.downcast_ref::<storekeeper_client_hoyolab::Error>() {
+        return Some(e.kind());
+    }
+    if let Some(e) = error.downcast_ref::<storekeeper_client_kuro::Error>() {
+        return Some(e.kind());
+    }
+    if let Some(e) = error.downcast_ref::<ClientError>() {
+        return Some(e.kind());
+    }
+    error
+        .downcast_ref::<reqwest::Error>()
+        .map(ErrorKind::from_reqwest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hoyolab_not_logged_in_is_auth() {
+        let err =
+            storekeeper_client_hoyolab::Error::Client(ClientError::api_error(-100, "Please login"));
+        assert_eq!(classify(&err), ErrorKind::Auth);
+    }
+
+    #[test]
+    fn hoyolab_rate_limit_is_rate_limited() {
+        let err = storekeeper_client_hoyolab::Error::RateLimited {
+            retry_after_secs: 30,
+        };
+        assert_eq!(classify(&err), ErrorKind::RateLimited);
+    }
+
+    #[test]
+    fn exhausted_middleware_retries_are_network() {
+        let err = storekeeper_client_kuro::Error::Client(ClientError::api_error(0, "timed out"));
+        assert_eq!(classify(&err), ErrorKind::Network);
+    }
+
+    #[test]
+    fn classification_follows_source_chain() {
+        let err = anyhow::Error::new(ClientError::auth_failed("expired"))
+            .context("failed to fetch resources");
+        assert_eq!(classify(err.as_ref()), ErrorKind::Auth);
+    }
+
+    #[test]
+    fn boxed_client_errors_keep_their_kind() {
+        let boxed: Box<dyn std::error::Error + Send + Sync> = Box::new(
+            storekeeper_client_hoyolab::Error::Client(ClientError::api_error(1034, "captcha")),
+        );
+        let err = anyhow::anyhow!(boxed).context("failed to claim daily reward");
+        assert_eq!(classify_anyhow(&err), ErrorKind::Captcha);
+    }
+
+    #[test]
+    fn anyhow_chain_is_classified() {
+        let err = anyhow::Error::new(ClientError::auth_failed("expired")).context("fetch");
+        assert_eq!(classify_anyhow(&err), ErrorKind::Auth);
+    }
+
+    #[test]
+    fn untyped_error_is_other() {
+        let err = std::io::Error::other("boom");
+        assert_eq!(classify(&err), ErrorKind::Other);
+    }
+}
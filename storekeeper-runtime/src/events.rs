@@ -0,0 +1,294 @@
+//! Centralized event definitions and the sink they are published to.
+//!
+//! The runtime never talks to a UI directly: it publishes [`AppEvent`]s with
+//! JSON payloads to an [`EventSink`]. The desktop app forwards them to the
+//! webview as Tauri events; the headless daemon logs them. Every event is
+//! also broadcast to in-process subscribers such as the HTTP API's event
+//! stream (see
+//! [`AppState::subscribe_events`](crate::state::AppState::subscribe_events)).
+
+use crate::fetch_status::FetchError;
+use crate::fetch_status::FetchStatus;
+use serde::Serialize;
+use std::sync::Arc;
+use storekeeper_core::GameId;
+use tokio::sync::broadcast;
+
+/// All events published by the runtime.
+#[derive(Debug, Clone, Copy)]
+pub enum AppEvent {
+    /// All resources have been fetched and updated.
+    ResourcesUpdated,
+    /// A manual refresh has started.
+    RefreshStarted,
+    /// A single game's resources have been fetched.
+    GameResourceUpdated,
+    /// Fetching a single game's resources failed.
+    GameFetchFailed,
+    /// Daily rewards have been claimed.
+    DailyRewardClaimed,
+    /// A Geetest challenge must be solved before a paused game resumes.
+    ChallengeRequired,
+}
+
+impl AppEvent {
+    /// Returns the event name string (also the Tauri event name).
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::ResourcesUpdated => "resources-updated",
+            Self::RefreshStarted => "refresh-started",
+            Self::GameResourceUpdated => "game-resource-updated",
+            Self::GameFetchFailed => "game-fetch-failed",
+            Self::DailyRewardClaimed => "daily-reward-claimed",
+            Self::ChallengeRequired => "challenge-required",
+        }
+    }
+}
+
+/// Receives events published by the runtime.
+///
+/// Implementations must not block; they are called from async tasks.
+pub trait EventSink: Send + Sync {
+    /// Publishes an event with its JSON payload.
+    fn emit(&self, event: AppEvent, payload: &serde_json::Value);
+}
+
+impl dyn EventSink {
+    /// Serializes `payload` and publishes it.
+    ///
+    /// Serialization failures are logged and the event is dropped.
+    pub fn publish<T: Serialize + ?Sized>(&self, event: AppEvent, payload: &T) {
+        match serde_json::to_value(payload) {
+            Ok(payload) => self.emit(event, &payload),
+            Err(e) => {
+                tracing::warn!(event = event.as_str(), error = %e, "Failed to serialize event payload");
+            }
+        }
+    }
+}
+
+/// Event sink that writes events to the log.
+///
+/// Used by frontends without a UI to forward events to.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LogEventSink;
+
+impl EventSink for LogEventSink {
+    fn emit(&self, event: AppEvent, payload: &serde_json::Value) {
+        tracing::debug!(event = event.as_str(), %payload, "Event published");
+    }
+}
+
+/// An event as published, with its serialized payload.
+#[derive(Debug, Clone)]
+pub struct PublishedEvent {
+    /// The event that was published.
+    pub event: AppEvent,
+    /// The event's JSON payload.
+    pub payload: serde_json::Value,
+}
+
+/// Event sink that forwards to another sink and also broadcasts every event
+/// to in-process subscribers (e.g. the HTTP API's event stream).
+pub(crate) struct BroadcastEventSink {
+    inner: Arc<dyn EventSink>,
+    sender: broadcast::Sender<PublishedEvent>,
+}
+
+impl BroadcastEventSink {
+    /// Wraps `inner`, broadcasting events through `sender`.
+    pub(crate) fn new(
+        inner: Arc<dyn EventSink>,
+        sender: broadcast::Sender<PublishedEvent>,
+    ) -> Self {
+        Self { inner, sender }
+    }
+}
+
+impl EventSink for BroadcastEventSink {
+    fn emit(&self, event: AppEvent, payload: &serde_json::Value) {
+        self.inner.emit(event, payload);
+        if self.sender.receiver_count() == 0 {
+            return;
+        }
+        let published = PublishedEvent {
+            event,
+            payload: payload.clone(),
+        };
+        if self.sender.send(published).is_err() {
+            tracing::trace!(event = event.as_str(), "Event subscribers went away");
+        }
+    }
+}
+
+/// Payload for per-account resource update events.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameResourcePayload<'a> {
+    /// The game that was updated.
+    pub game_id: GameId,
+    /// The account of the game that was updated.
+    pub account: &'a str,
+    /// The resource data for this account.
+    pub data: &'a serde_json::Value,
+    /// The account's fetch status after this fetch.
+    pub status: &'a FetchStatus,
+}
+
+/// Payload for per-account fetch failure events.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GameFetchFailedPayload<'a> {
+    /// The game whose fetch failed.
+    pub game_id: GameId,
+    /// The account whose fetch failed.
+    pub account: &'a str,
+    /// Why the fetch failed.
+    pub error: &'a FetchError,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // =========================================================================
+    // AppEvent::as_str — verify each variant
+    // =========================================================================
+
+    #[test]
+    fn event_resources_updated() {
+        assert_eq!(AppEvent::ResourcesUpdated.as_str(), "resources-updated");
+    }
+
+    #[test]
+    fn event_refresh_started() {
+        assert_eq!(AppEvent::RefreshStarted.as_str(), "refresh-started");
+    }
+
+    #[test]
+    fn event_game_resource_updated() {
+        assert_eq!(
+            AppEvent::GameResourceUpdated.as_str(),
+            "game-resource-updated"
+        );
+    }
+
+    #[test]
+    fn event_game_fetch_failed() {
+        assert_eq!(AppEvent::GameFetchFailed.as_str(), "game-fetch-failed");
+    }
+
+    #[test]
+    fn event_challenge_required() {
+        assert_eq!(AppEvent::ChallengeRequired.as_str(), "challenge-required");
+    }
+
+    #[test]
+    fn event_daily_reward_claimed() {
+        assert_eq!(
+            AppEvent::DailyRewardClaimed.as_str(),
+            "daily-reward-claimed"
+        );
+    }
+
+    // =========================================================================
+    // AppEvent — all events use lowercase kebab-case
+    // =========================================================================
+
+    #[test]
+    fn all_events_are_kebab_case() {
+        let events = [
+            AppEvent::ResourcesUpdated,
+            AppEvent::RefreshStarted,
+            AppEvent::GameResourceUpdated,
+            AppEvent::GameFetchFailed,
+            AppEvent::DailyRewardClaimed,
+            AppEvent::ChallengeRequired,
+        ];
+        for event in events {
+            let s = event.as_str();
+            assert!(
+                !s.contains('_') && !s.contains(' ') && s == s.to_lowercase(),
+                "Event {s:?} should be lowercase kebab-case"
+            );
+        }
+    }
+
+    // =========================================================================
+    // EventSink::publish — serializes payloads
+    // =========================================================================
+
+    #[derive(Default)]
+    struct RecordingSink(std::sync::Mutex<Vec<(&'static str, serde_json::Value)>>);
+
+    impl EventSink for RecordingSink {
+        fn emit(&self, event: AppEvent, payload: &serde_json::Value) {
+            self.0
+                .lock()
+                .expect("lock")
+                .push((event.as_str(), payload.clone()));
+        }
+    }
+
+    #[test]
+    fn publish_serializes_payload() {
+        let sink = RecordingSink::default();
+        let data = serde_json::json!([]);
+        let payload = GameResourcePayload {
+            game_id: GameId::WutheringWaves,
+            account: "alt",
+            data: &data,
+            status: &FetchStatus::default(),
+        };
+        (&sink as &dyn EventSink).publish(AppEvent::GameResourceUpdated, &payload);
+
+        let events = sink.0.lock().expect("lock");
+        assert_eq!(events.len(), 1);
+        let (name, json) = events.first().expect("one event");
+        assert_eq!(*name, "game-resource-updated");
+        assert_eq!(json.get("account"), Some(&serde_json::json!("alt")));
+    }
+
+    #[test]
+    fn broadcast_sink_forwards_and_broadcasts() {
+        let inner = Arc::new(RecordingSink::default());
+        let (sender, mut receiver) = broadcast::channel(4);
+        let sink = BroadcastEventSink::new(inner.clone(), sender);
+
+        sink.emit(AppEvent::RefreshStarted, &serde_json::Value::Null);
+
+        assert_eq!(inner.0.lock().expect("lock").len(), 1);
+        let published = receiver.try_recv().expect("event should be broadcast");
+        assert_eq!(published.event.as_str(), "refresh-started");
+        assert_eq!(published.payload, serde_json::Value::Null);
+    }
+
+    // =========================================================================
+    // GameResourcePayload serde — camelCase field names
+    // =========================================================================
+
+    #[test]
+    fn payload_serializes_camel_case() {
+        let data = serde_json::json!({"stamina": 160});
+        let payload = GameResourcePayload {
+            game_id: GameId::GenshinImpact,
+            account: "default",
+            data: &data,
+            status: &FetchStatus::default(),
+        };
+        let json = serde_json::to_value(&payload).expect("should serialize");
+        assert!(
+            json.get("gameId").is_some(),
+            "field should be camelCase `gameId`"
+        );
+        assert!(
+            json.get("game_id").is_none(),
+            "field should NOT be snake_case `game_id`"
+        );
+        assert_eq!(
+            json.get("account").and_then(serde_json::Value::as_str),
+            Some("default")
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Outcome of the latest resource fetch of each account.
+//!
+//! Lets frontends tell an account whose fetch failed (and why) from one that
+//! isn't configured, and flag data that is only still shown because the last
+//! fetch failed.
+
+use crate::challenge::GeetestChallenge;
+use crate::challenge::find_challenge;
+use crate::error_kind::ErrorKind;
+use crate::error_kind::classify;
+use jiff::Timestamp;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Why a fetch failed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchError {
+    /// Category of the failure.
+    pub kind: ErrorKind,
+    /// Human-readable error message.
+    pub message: String,
+    /// The Geetest challenge to solve before the account can be fetched
+    /// again, if HoYoLab's risk control refused the fetch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub challenge: Option<GeetestChallenge>,
+}
+
+impl FetchError {
+    /// Describes a failed fetch.
+    #[must_use]
+    pub fn new(error: &(dyn std::error::Error + 'static)) -> Self {
+        Self {
+            kind: classify(error),
+            message: error.to_string(),
+            challenge: find_challenge(error).cloned(),
+        }
+    }
+}
+
+/// Fetch history of one account.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FetchStatus {
+    /// When the account was last fetched successfully.
+    pub last_success: Option<Timestamp>,
+    /// When a fetch of the account was last attempted.
+    pub last_attempt: Option<Timestamp>,
+    /// Why the latest attempt failed; `None` if it succeeded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<FetchError>,
+}
+
+impl FetchStatus {
+    /// Returns the status of an account fetched successfully at `at`.
+    #[must_use]
+    pub const fn succeeded(at: Timestamp) -> Self {
+        Self {
+            last_success: Some(at),
+            last_attempt: Some(at),
+            error: None,
+        }
+    }
+
+    /// Records a successful fetch at `at`.
+    pub fn record_success(&mut self, at: Timestamp) {
+        *self = Self::succeeded(at);
+    }
+
+    /// Records a failed fetch at `at`, keeping the last success.
+    pub fn record_failure(&mut self, error: FetchError, at: Timestamp) {
+        self.last_attempt = Some(at);
+        self.error = Some(error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use storekeeper_client_core::ClientError;
+
+    #[test]
+    fn failure_keeps_last_success() {
+        let succeeded_at = Timestamp::UNIX_EPOCH;
+        let failed_at = Timestamp::now();
+        let mut status = FetchStatus::succeeded(succeeded_at);
+
+        status.record_failure(
+            FetchError::new(&ClientError::auth_failed("cookie expired")),
+            failed_at,
+        );
+
+        assert_eq!(status.last_success, Some(succeeded_at));
+        assert_eq!(status.last_attempt, Some(failed_at));
+        assert_eq!(
+            status.error.as_ref().map(|error| error.kind),
+            Some(ErrorKind::Auth)
+        );
+
+        status.record_success(failed_at);
+        assert_eq!(status, FetchStatus::succeeded(failed_at));
+    }
+
+    #[test]
+    fn status_serializes_camel_case_with_snake_case_kind() {
+        let mut status = FetchStatus::default();
+        status.record_failure(
+            FetchError {
+                kind: ErrorKind::RateLimited,
+                message: "slow down".to_string(),
+                challenge: None,
+            },
+            Timestamp::UNIX_EPOCH,
+        );
+
+        let json = serde_json::to_value(&status).expect("should serialize");
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "lastSuccess": null,
+                "lastAttempt": "1970-01-01T00:00:00Z",
+                "error": {"kind": "rate_limited", "message": "slow down"},
+            })
+        );
+    }
+}
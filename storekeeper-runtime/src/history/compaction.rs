@@ -0,0 +1,135 @@
+//! Retention and compaction of recorded history.
+
+use super::HistoryRecord;
+use jiff::SignedDuration;
+use jiff::Timestamp;
+use std::collections::HashMap;
+use storekeeper_core::GameId;
+use storekeeper_core::config::GeneralConfig;
+
+/// Returns the timestamp `days` days before `now`, or `None` if it would be
+/// out of range.
+fn days_before(now: Timestamp, days: u32) -> Option<Timestamp> {
+    now.checked_sub(SignedDuration::from_hours(i64::from(days) * 24))
+        .ok()
+}
+
+/// Applies retention and compaction to a chronological list of records.
+///
+/// - Records older than `history_retention_days` are dropped (`0` keeps all).
+/// - Records older than `history_compact_after_days` keep only the latest
+///   sample per resource per `history_compact_interval_mins` bucket (`0`
+///   disables compaction).
+///
+/// The relative order of kept records is preserved.
+#[must_use]
+pub(super) fn compact_records(
+    records: Vec<HistoryRecord>,
+    now: Timestamp,
+    general: &GeneralConfig,
+) -> Vec<HistoryRecord> {
+    let retention_cutoff = (general.history_retention_days > 0)
+        .then(|| days_before(now, general.history_retention_days))
+        .flatten();
+    let bucket_ms = i64::from(general.history_compact_interval_mins) * 60_000;
+    let compact_cutoff = (bucket_ms > 0)
+        .then(|| days_before(now, general.history_compact_after_days))
+        .flatten();
+
+    let mut kept: Vec<HistoryRecord> = Vec::with_capacity(records.len());
+    let mut buckets: HashMap<(GameId, String, String, i64), usize> = HashMap::new();
+    for record in records {
+        if retention_cutoff.is_some_and(|cutoff| record.fetched_at < cutoff) {
+            continue;
+        }
+        if compact_cutoff.is_some_and(|cutoff| record.fetched_at < cutoff) {
+            let bucket = record.fetched_at.as_millisecond().div_euclid(bucket_ms);
+            let bucket_key = (
+                record.game_id,
+                record.account.clone(),
+                record.resource_type.clone(),
+                bucket,
+            );
+            if let Some(slot) = buckets.get(&bucket_key).and_then(|&i| kept.get_mut(i)) {
+                // Later samples in the same bucket replace earlier ones.
+                *slot = record;
+                continue;
+            }
+            buckets.insert(bucket_key, kept.len());
+        }
+        kept.push(record);
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HOUR: i64 = 3_600;
+    const DAY: i64 = 24 * HOUR;
+
+    fn record(second: i64, resource_type: &str, value: i64) -> HistoryRecord {
+        HistoryRecord {
+            fetched_at: Timestamp::from_second(second).expect("valid timestamp"),
+            game_id: GameId::HonkaiStarRail,
+            account: "default".to_string(),
+            resource_type: resource_type.to_string(),
+            data: serde_json::json!(value),
+        }
+    }
+
+    fn now() -> Timestamp {
+        Timestamp::from_second(100 * DAY).expect("valid timestamp")
+    }
+
+    #[test]
+    fn recent_records_are_untouched() {
+        let records = vec![
+            record(99 * DAY, "power", 1),
+            record(99 * DAY + 300, "power", 2),
+        ];
+        let kept = compact_records(records.clone(), now(), &GeneralConfig::default());
+        assert_eq!(kept, records);
+    }
+
+    #[test]
+    fn old_records_keep_latest_per_bucket_and_type() {
+        let base = 50 * DAY;
+        let records = vec![
+            record(base, "power", 1),
+            record(base, "reserve", 10),
+            record(base + 300, "power", 2),
+            record(base + HOUR, "power", 3),
+        ];
+        let kept = compact_records(records, now(), &GeneralConfig::default());
+        let values: Vec<_> = kept.iter().map(|r| r.data.clone()).collect();
+        assert_eq!(
+            values,
+            [
+                serde_json::json!(2),
+                serde_json::json!(10),
+                serde_json::json!(3)
+            ]
+        );
+    }
+
+    #[test]
+    fn retention_drops_expired_records() {
+        let records = vec![record(5 * DAY, "power", 1), record(99 * DAY, "power", 2)];
+        let kept = compact_records(records, now(), &GeneralConfig::default());
+        assert_eq!(kept.len(), 1, "default retention is 90 days");
+    }
+
+    #[test]
+    fn zero_settings_disable_retention_and_compaction() {
+        let general = GeneralConfig {
+            history_retention_days: 0,
+            history_compact_interval_mins: 0,
+            ..GeneralConfig::default()
+        };
+        let records = vec![record(0, "power", 1), record(60, "power", 2)];
+        let kept = compact_records(records.clone(), now(), &general);
+        assert_eq!(kept, records);
+    }
+}
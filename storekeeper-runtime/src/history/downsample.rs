@@ -0,0 +1,87 @@
+//! Downsampling of history series for charting.
+
+use super::HistoryPoint;
+use jiff::Timestamp;
+
+/// Reduces a chronological series to at most `max_points` samples.
+///
+/// The `[from, to]` range (defaulting to the first and last sample) is split
+/// into `max_points` equal buckets and the latest sample of each bucket is
+/// kept. Keeping a real sample rather than averaging works for any resource
+/// payload shape, and the latest sample is what a poll at that time would
+/// have shown.
+#[must_use]
+pub(super) fn downsample(
+    points: Vec<HistoryPoint>,
+    from: Option<Timestamp>,
+    to: Option<Timestamp>,
+    max_points: usize,
+) -> Vec<HistoryPoint> {
+    if max_points == 0 || points.len() <= max_points {
+        return points;
+    }
+    let (Some(first), Some(last)) = (points.first(), points.last()) else {
+        return points;
+    };
+
+    let start = from.unwrap_or(first.fetched_at).as_millisecond();
+    let end = to.unwrap_or(last.fetched_at).as_millisecond();
+    let span = u64::try_from(end.saturating_sub(start)).unwrap_or(0);
+    let buckets = u64::try_from(max_points).unwrap_or(u64::MAX);
+    let bucket_ms = span.div_ceil(buckets).max(1);
+
+    let mut sampled: Vec<HistoryPoint> = Vec::with_capacity(max_points);
+    let mut current_bucket = None;
+    for point in points {
+        let offset = point.fetched_at.as_millisecond().saturating_sub(start);
+        // The end of the range lands exactly on the last bucket boundary.
+        let bucket = (u64::try_from(offset).unwrap_or(0) / bucket_ms).min(buckets - 1);
+        if current_bucket == Some(bucket)
+            && let Some(slot) = sampled.last_mut()
+        {
+            *slot = point;
+        } else {
+            current_bucket = Some(bucket);
+            sampled.push(point);
+        }
+    }
+    sampled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(second: i64) -> HistoryPoint {
+        HistoryPoint {
+            fetched_at: Timestamp::from_second(second).expect("valid timestamp"),
+            data: serde_json::json!(second),
+        }
+    }
+
+    #[test]
+    fn short_series_is_unchanged() {
+        let points = vec![point(0), point(10)];
+        assert_eq!(downsample(points.clone(), None, None, 5), points);
+    }
+
+    #[test]
+    fn keeps_latest_sample_per_bucket() {
+        let points: Vec<_> = (0..100).map(point).collect();
+        let sampled = downsample(points, None, None, 10);
+        assert!(sampled.len() <= 10);
+        assert_eq!(sampled.last(), Some(&point(99)), "last sample is kept");
+        assert!(
+            sampled
+                .windows(2)
+                .all(|w| matches!(w, [a, b] if a.fetched_at < b.fetched_at)),
+            "samples stay chronological"
+        );
+    }
+
+    #[test]
+    fn zero_max_points_disables_downsampling() {
+        let points: Vec<_> = (0..10).map(point).collect();
+        assert_eq!(downsample(points.clone(), None, None, 0).len(), 10);
+    }
+}
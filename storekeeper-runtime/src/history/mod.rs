@@ -0,0 +1,262 @@
+//! Local time-series history of fetched resources.
+//!
+//! Every successful fetch appends one record per account and resource type to
+//! an append-only JSON Lines file in the config directory. Records keep the
+//! tagged `{type, data}` payload verbatim, so new resource types are recorded
+//! without any changes here. A background task periodically applies the
+//! retention and compaction settings from `GeneralConfig`.
+
+mod compaction;
+mod downsample;
+mod store;
+
+use crate::state::AppState;
+use crate::state::run_blocking;
+use jiff::Timestamp;
+use serde::Deserialize;
+use serde::Serialize;
+use std::time::Duration;
+pub use store::HistoryStore;
+use storekeeper_core::AccountKey;
+use storekeeper_core::GameId;
+use tokio_util::sync::CancellationToken;
+
+/// Maximum points per series returned when a query does not specify one.
+const DEFAULT_MAX_POINTS: usize = 500;
+
+/// How often retention and compaction are applied.
+const COMPACTION_INTERVAL: Duration = Duration::from_hours(24);
+
+/// One recorded sample of one resource.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryRecord {
+    /// When the resource was fetched.
+    pub fetched_at: Timestamp,
+    /// Game the resource belongs to.
+    pub game_id: GameId,
+    /// Account the resource belongs to.
+    pub account: String,
+    /// Resource type tag (e.g. `"resin"`).
+    #[serde(rename = "type")]
+    pub resource_type: String,
+    /// Resource payload as returned by the game client.
+    pub data: serde_json::Value,
+}
+
+impl HistoryRecord {
+    /// Splits an account's fetched resource array into one record per
+    /// resource type.
+    ///
+    /// Entries without a string `type` tag or a `data` field are skipped.
+    #[must_use]
+    pub fn from_fetch(
+        key: &AccountKey,
+        resources: &serde_json::Value,
+        fetched_at: Timestamp,
+    ) -> Vec<Self> {
+        resources
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|resource| {
+                let resource_type = resource.get("type")?.as_str()?;
+                let data = resource.get("data")?;
+                Some(Self {
+                    fetched_at,
+                    game_id: key.game_id,
+                    account: key.account.clone(),
+                    resource_type: resource_type.to_owned(),
+                    data: data.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Filter for a history query.
+///
+/// All fields are optional; omitted filters match everything.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryQuery {
+    /// Only return series for this game.
+    pub game_id: Option<GameId>,
+    /// Only return series for this account.
+    pub account: Option<String>,
+    /// Only return series for this resource type.
+    #[serde(rename = "type")]
+    pub resource_type: Option<String>,
+    /// Inclusive start of the time range.
+    pub from: Option<Timestamp>,
+    /// Inclusive end of the time range.
+    pub to: Option<Timestamp>,
+    /// Maximum points per series after downsampling.
+    pub max_points: Option<usize>,
+}
+
+impl HistoryQuery {
+    fn matches(&self, record: &HistoryRecord) -> bool {
+        self.game_id.is_none_or(|game_id| game_id == record.game_id)
+            && self
+                .account
+                .as_ref()
+                .is_none_or(|account| *account == record.account)
+            && self
+                .resource_type
+                .as_ref()
+                .is_none_or(|resource_type| *resource_type == record.resource_type)
+            && self.from.is_none_or(|from| record.fetched_at >= from)
+            && self.to.is_none_or(|to| record.fetched_at <= to)
+    }
+}
+
+/// One sample within a [`HistorySeries`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryPoint {
+    /// When the resource was fetched.
+    pub fetched_at: Timestamp,
+    /// Resource payload at that time.
+    pub data: serde_json::Value,
+}
+
+/// Time-ordered samples of one resource type of one account.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistorySeries {
+    /// Game the resource belongs to.
+    pub game_id: GameId,
+    /// Account the resource belongs to.
+    pub account: String,
+    /// Resource type tag.
+    #[serde(rename = "type")]
+    pub resource_type: String,
+    /// Samples in chronological order.
+    pub points: Vec<HistoryPoint>,
+}
+
+/// Runs the background history maintenance task until cancelled.
+///
+/// Applies retention and compaction once on startup and then every 24 hours,
+/// reading the current settings from config each time.
+pub async fn run_history_compaction(state: AppState, cancel_token: CancellationToken) {
+    tracing::info!("Starting history compaction task");
+
+    loop {
+        compact_now(&state).await;
+
+        tokio::select! {
+            () = cancel_token.cancelled() => {
+                tracing::info!("History compaction cancelled");
+                break;
+            }
+            () = tokio::time::sleep(COMPACTION_INTERVAL) => {}
+        }
+    }
+}
+
+/// Applies retention and compaction to the history store once.
+async fn compact_now(state: &AppState) {
+    let Some(history) = state.history() else {
+        return;
+    };
+    let general = {
+        let inner = state.inner.read().await;
+        inner.config.general.clone()
+    };
+
+    match run_blocking(move || history.compact(Timestamp::now(), &general)).await {
+        Ok((before, after)) => {
+            tracing::debug!(before, after, "History compaction complete");
+        }
+        Err(e) => tracing::warn!("History compaction failed: {e:#}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_fetch_splits_tagged_resources() {
+        let key = AccountKey::new(GameId::GenshinImpact, "alt");
+        let now = Timestamp::now();
+        let resources = serde_json::json!([
+            {"type": "resin", "data": {"current": 100, "max": 200}},
+            {"type": "realmCurrency", "data": {"current": 900}},
+            {"data": {"current": 1}},
+            {"type": "missingData"},
+        ]);
+
+        let records = HistoryRecord::from_fetch(&key, &resources, now);
+        let types: Vec<_> = records.iter().map(|r| r.resource_type.as_str()).collect();
+        assert_eq!(types, ["resin", "realmCurrency"]);
+        assert!(records.iter().all(|r| r.account == "alt"));
+        assert!(records.iter().all(|r| r.fetched_at == now));
+    }
+
+    #[test]
+    fn from_fetch_ignores_non_array_payload() {
+        let key = AccountKey::default_for(GameId::HonkaiStarRail);
+        let records = HistoryRecord::from_fetch(&key, &serde_json::json!({}), Timestamp::now());
+        assert!(records.is_empty());
+    }
+
+    #[test]
+    fn record_serializes_type_tag() {
+        let record = HistoryRecord {
+            fetched_at: Timestamp::UNIX_EPOCH,
+            game_id: GameId::ZenlessZoneZero,
+            account: "default".to_string(),
+            resource_type: "battery".to_string(),
+            data: serde_json::json!({"current": 1}),
+        };
+        let v = serde_json::to_value(&record).expect("serialize");
+        assert_eq!(v.get("type"), Some(&serde_json::json!("battery")));
+        assert_eq!(
+            v.get("gameId"),
+            Some(&serde_json::json!("ZENLESS_ZONE_ZERO"))
+        );
+        assert!(v.get("fetchedAt").is_some());
+    }
+
+    #[test]
+    fn query_filters_by_all_fields() {
+        let record = HistoryRecord {
+            fetched_at: Timestamp::from_second(1_000).expect("valid timestamp"),
+            game_id: GameId::WutheringWaves,
+            account: "default".to_string(),
+            resource_type: "waveplates".to_string(),
+            data: serde_json::Value::Null,
+        };
+
+        assert!(HistoryQuery::default().matches(&record));
+        assert!(
+            HistoryQuery {
+                game_id: Some(GameId::WutheringWaves),
+                account: Some("default".to_string()),
+                resource_type: Some("waveplates".to_string()),
+                from: Some(Timestamp::from_second(1_000).expect("valid timestamp")),
+                to: Some(Timestamp::from_second(1_000).expect("valid timestamp")),
+                max_points: None,
+            }
+            .matches(&record),
+            "range bounds are inclusive"
+        );
+        assert!(
+            !HistoryQuery {
+                account: Some("alt".to_string()),
+                ..HistoryQuery::default()
+            }
+            .matches(&record)
+        );
+        assert!(
+            !HistoryQuery {
+                from: Some(Timestamp::from_second(1_001).expect("valid timestamp")),
+                ..HistoryQuery::default()
+            }
+            .matches(&record)
+        );
+    }
+}
@@ -0,0 +1,308 @@
+//! Append-only JSON Lines file backing the resource history.
+
+use super::DEFAULT_MAX_POINTS;
+use super::HistoryPoint;
+use super::HistoryQuery;
+use super::HistoryRecord;
+use super::HistorySeries;
+use super::compaction::compact_records;
+use super::downsample::downsample;
+use camino::Utf8Path;
+use camino::Utf8PathBuf;
+use jiff::Timestamp;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::io::Write;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use storekeeper_core::AccountKey;
+use storekeeper_core::AppConfig;
+use storekeeper_core::GameId;
+use storekeeper_core::config::GeneralConfig;
+
+/// History file name inside the config directory.
+const HISTORY_FILE_NAME: &str = "history.jsonl";
+
+/// Resource history stored as one JSON record per line.
+///
+/// Appends, queries and compaction are serialized through an internal lock so
+/// a compaction rewrite never races an append.
+pub struct HistoryStore {
+    path: Utf8PathBuf,
+    lock: Mutex<()>,
+}
+
+impl HistoryStore {
+    /// Creates a store backed by the given file. The file is created on the
+    /// first append.
+    #[must_use]
+    pub fn new(path: Utf8PathBuf) -> Self {
+        Self {
+            path,
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Returns the default history file path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the config directory cannot be determined.
+    pub fn default_path() -> storekeeper_core::Result<Utf8PathBuf> {
+        Ok(AppConfig::config_dir()?.join(HISTORY_FILE_NAME))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ()> {
+        self.lock.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Appends one record per account and resource type from a fetch result.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history file cannot be written.
+    pub fn append(
+        &self,
+        fetched: &HashMap<AccountKey, serde_json::Value>,
+        fetched_at: Timestamp,
+    ) -> anyhow::Result<()> {
+        let mut lines = String::new();
+        for (key, resources) in fetched {
+            for record in HistoryRecord::from_fetch(key, resources, fetched_at) {
+                lines.push_str(&serde_json::to_string(&record)?);
+                lines.push('\n');
+            }
+        }
+        if lines.is_empty() {
+            return Ok(());
+        }
+
+        let _guard = self.lock();
+        if let Some(parent) = self.path.parent() {
+            fs_err::create_dir_all(parent)?;
+        }
+        let mut file = fs_err::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(lines.as_bytes())?;
+        Ok(())
+    }
+
+    /// Returns the series matching a query, downsampled to at most
+    /// `max_points` samples each.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history file exists but cannot be read.
+    pub fn query(&self, query: &HistoryQuery) -> anyhow::Result<Vec<HistorySeries>> {
+        let records = {
+            let _guard = self.lock();
+            read_records(&self.path)?
+        };
+
+        let mut grouped: BTreeMap<(GameId, String, String), Vec<HistoryPoint>> = BTreeMap::new();
+        for record in records.into_iter().filter(|record| query.matches(record)) {
+            grouped
+                .entry((record.game_id, record.account, record.resource_type))
+                .or_default()
+                .push(HistoryPoint {
+                    fetched_at: record.fetched_at,
+                    data: record.data,
+                });
+        }
+
+        let max_points = query.max_points.unwrap_or(DEFAULT_MAX_POINTS);
+        Ok(grouped
+            .into_iter()
+            .map(|((game_id, account, resource_type), mut points)| {
+                points.sort_by_key(|point| point.fetched_at);
+                HistorySeries {
+                    game_id,
+                    account,
+                    resource_type,
+                    points: downsample(points, query.from, query.to, max_points),
+                }
+            })
+            .collect())
+    }
+
+    /// Applies retention and compaction settings, rewriting the file.
+    ///
+    /// Returns the record counts before and after compaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the history file cannot be read or rewritten.
+    pub fn compact(
+        &self,
+        now: Timestamp,
+        general: &GeneralConfig,
+    ) -> anyhow::Result<(usize, usize)> {
+        let _guard = self.lock();
+        let records = read_records(&self.path)?;
+        let before = records.len();
+        let kept = compact_records(records, now, general);
+        let after = kept.len();
+        if after == before {
+            return Ok((before, after));
+        }
+
+        let mut content = String::new();
+        for record in &kept {
+            content.push_str(&serde_json::to_string(record)?);
+            content.push('\n');
+        }
+        let tmp_path = self.path.with_extension("jsonl.tmp");
+        fs_err::write(&tmp_path, content)?;
+        fs_err::rename(&tmp_path, &self.path)?;
+        Ok((before, after))
+    }
+}
+
+/// Reads every parseable record from the history file.
+///
+/// A missing file yields no records. Malformed lines (e.g. a partial line left
+/// by a crash mid-append) are skipped.
+fn read_records(path: &Utf8Path) -> anyhow::Result<Vec<HistoryRecord>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let file = fs_err::File::open(path)?;
+    let mut records = Vec::new();
+    for line in std::io::BufReader::new(file).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(record) => records.push(record),
+            Err(e) => tracing::debug!("Skipping malformed history line: {e}"),
+        }
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU32;
+    use std::sync::atomic::Ordering;
+
+    fn temp_store(tag: &str) -> HistoryStore {
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .expect("temp dir should be UTF-8")
+            .join(format!(
+                "storekeeper-history-{tag}-{}-{n}",
+                std::process::id()
+            ));
+        HistoryStore::new(dir.join(HISTORY_FILE_NAME))
+    }
+
+    fn cleanup(store: &HistoryStore) {
+        if let Some(dir) = store.path.parent()
+            && let Err(err) = fs_err::remove_dir_all(dir)
+        {
+            eprintln!("failed to clean up temp dir {dir}: {err}");
+        }
+    }
+
+    fn ts(second: i64) -> Timestamp {
+        Timestamp::from_second(second).expect("valid timestamp")
+    }
+
+    fn fetch(key: &AccountKey, current: u64) -> HashMap<AccountKey, serde_json::Value> {
+        HashMap::from([(
+            key.clone(),
+            serde_json::json!([
+                {"type": "resin", "data": {"current": current}},
+                {"type": "realmCurrency", "data": {"current": current * 10}},
+            ]),
+        )])
+    }
+
+    #[test]
+    fn query_missing_file_is_empty() {
+        let store = temp_store("missing");
+        let series = store
+            .query(&HistoryQuery::default())
+            .expect("query should succeed");
+        assert!(series.is_empty());
+    }
+
+    #[test]
+    fn append_and_query_groups_by_series() {
+        let store = temp_store("append");
+        let key = AccountKey::default_for(GameId::GenshinImpact);
+        store.append(&fetch(&key, 10), ts(100)).expect("append");
+        store.append(&fetch(&key, 20), ts(200)).expect("append");
+
+        let series = store
+            .query(&HistoryQuery {
+                resource_type: Some("resin".to_string()),
+                ..HistoryQuery::default()
+            })
+            .expect("query");
+        assert_eq!(series.len(), 1);
+        let resin = series.first().expect("resin series");
+        assert_eq!(resin.game_id, GameId::GenshinImpact);
+        let values: Vec<_> = resin.points.iter().map(|p| p.data.clone()).collect();
+        assert_eq!(
+            values,
+            [
+                serde_json::json!({"current": 10}),
+                serde_json::json!({"current": 20})
+            ]
+        );
+
+        let all = store.query(&HistoryQuery::default()).expect("query");
+        assert_eq!(all.len(), 2, "one series per resource type");
+        cleanup(&store);
+    }
+
+    #[test]
+    fn query_skips_malformed_lines() {
+        let store = temp_store("malformed");
+        let key = AccountKey::default_for(GameId::GenshinImpact);
+        store.append(&fetch(&key, 10), ts(100)).expect("append");
+        let mut file = fs_err::OpenOptions::new()
+            .append(true)
+            .open(&store.path)
+            .expect("open");
+        file.write_all(b"{\"fetchedAt\": trunc").expect("write");
+
+        let series = store.query(&HistoryQuery::default()).expect("query");
+        assert_eq!(series.iter().map(|s| s.points.len()).sum::<usize>(), 2);
+        cleanup(&store);
+    }
+
+    #[test]
+    fn compact_drops_expired_records() {
+        let store = temp_store("compact");
+        let key = AccountKey::default_for(GameId::GenshinImpact);
+        let day = 86_400;
+        store.append(&fetch(&key, 1), ts(0)).expect("append");
+        store.append(&fetch(&key, 2), ts(10 * day)).expect("append");
+
+        let general = GeneralConfig {
+            history_retention_days: 5,
+            ..GeneralConfig::default()
+        };
+        let (before, after) = store.compact(ts(12 * day), &general).expect("compact");
+        assert_eq!((before, after), (4, 2));
+
+        let series = store.query(&HistoryQuery::default()).expect("query");
+        assert!(
+            series
+                .iter()
+                .flat_map(|s| &s.points)
+                .all(|p| p.fetched_at == ts(10 * day))
+        );
+        cleanup(&store);
+    }
+}
@@ -0,0 +1,104 @@
+//! Opt-in local HTTP API.
+//!
+//! Serves cached resources, daily reward status, Prometheus metrics, refresh
+//! and claim actions, and a server-sent event stream mirroring every published
+//! [`AppEvent`](crate::events::AppEvent), on `127.0.0.1:<port>`. Requests must
+//! carry the bearer token from `secrets.toml`; without a token the server does
+//! not start.
+
+mod routes;
+
+use crate::state::AppState;
+use routes::ApiState;
+use std::net::Ipv4Addr;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+
+/// Runs the HTTP API until cancelled.
+///
+/// The server is (re)started from the current config on startup and every
+/// time [`AppState::restart_http_api`] is called; while disabled the task
+/// just waits for the next restart.
+pub async fn run_http_api(state: AppState, cancel_token: CancellationToken) {
+    let notify = state.http_api_notify();
+
+    loop {
+        let shutdown = cancel_token.child_token();
+        let server = tokio::spawn(serve_from_config(state.clone(), shutdown.clone()));
+
+        let restart = tokio::select! {
+            () = cancel_token.cancelled() => false,
+            () = notify.notified() => true,
+        };
+
+        shutdown.cancel();
+        if let Err(e) = server.await {
+            tracing::error!(error = %e, "HTTP API task failed");
+        }
+
+        if !restart {
+            tracing::info!("HTTP API stopped");
+            return;
+        }
+        tracing::info!("HTTP API settings changed, restarting");
+    }
+}
+
+/// Binds and serves the HTTP API if it is enabled and a token is set.
+async fn serve_from_config(state: AppState, shutdown: CancellationToken) {
+    let (config, token) = {
+        let inner = state.inner.read().await;
+        (
+            inner.config.http_api.clone(),
+            inner.secrets.http_api.token().map(Arc::<str>::from),
+        )
+    };
+
+    if !config.enabled {
+        tracing::debug!("HTTP API disabled");
+        return;
+    }
+    let Some(token) = token else {
+        tracing::warn!(
+            "HTTP API is enabled but secrets.toml has no [http_api] token; not starting"
+        );
+        return;
+    };
+
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, config.port));
+    let listener = match TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::error!(%addr, error = %e, "Failed to bind HTTP API");
+            return;
+        }
+    };
+
+    tracing::info!(%addr, "HTTP API listening");
+    serve(listener, state, token, shutdown).await;
+}
+
+/// Serves the HTTP API on `listener` until `shutdown` is cancelled.
+///
+/// Open event streams are closed on shutdown so it completes promptly.
+async fn serve(
+    listener: TcpListener,
+    state: AppState,
+    token: Arc<str>,
+    shutdown: CancellationToken,
+) {
+    let router = routes::router(ApiState {
+        app: state,
+        token,
+        shutdown: shutdown.clone(),
+    });
+
+    if let Err(e) = axum::serve(listener, router)
+        .with_graceful_shutdown(shutdown.cancelled_owned())
+        .await
+    {
+        tracing::error!(error = %e, "HTTP API server failed");
+    }
+}
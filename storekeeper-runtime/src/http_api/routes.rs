@@ -0,0 +1,370 @@
+//! Routes, authentication and error responses of the HTTP API.
+
+use crate::polling;
+use crate::state::AllDailyRewardStatus;
+use crate::state::AllResources;
+use crate::state::AppState;
+use axum::Json;
+use axum::Router;
+use axum::extract::Path;
+use axum::extract::Query;
+use axum::extract::Request;
+use axum::extract::State;
+use axum::http::HeaderValue;
+use axum::http::StatusCode;
+use axum::http::header;
+use axum::middleware;
+use axum::middleware::Next;
+use axum::response::IntoResponse;
+use axum::response::Response;
+use axum::response::sse::Event;
+use axum::response::sse::KeepAlive;
+use axum::response::sse::Sse;
+use axum::routing::get;
+use axum::routing::post;
+use futures::Stream;
+use futures::StreamExt;
+use serde::Deserialize;
+use std::convert::Infallible;
+use std::sync::Arc;
+use storekeeper_core::AccountKey;
+use storekeeper_core::GameId;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+
+/// State shared by all request handlers.
+#[derive(Clone)]
+pub(super) struct ApiState {
+    /// The application state the API reads from and acts on.
+    pub(super) app: AppState,
+    /// Bearer token every request must present.
+    pub(super) token: Arc<str>,
+    /// Cancelled when the server shuts down; ends open event streams.
+    pub(super) shutdown: CancellationToken,
+}
+
+/// Builds the API router. Every route requires the bearer token.
+pub(super) fn router(state: ApiState) -> Router {
+    Router::new()
+        .route("/resources", get(get_resources))
+        .route("/daily-rewards", get(get_daily_rewards))
+        .route("/refresh", post(refresh))
+        .route("/claim/{game}", post(claim))
+        .route("/events", get(events))
+        .route("/metrics", get(metrics))
+        .layer(middleware::from_fn_with_state(state.clone(), require_token))
+        .with_state(state)
+}
+
+/// An error response, serialized as `{"error": "<message>"}`.
+enum ApiError {
+    /// The bearer token is missing or wrong.
+    Unauthorized,
+    /// The requested account is not configured.
+    NotFound(String),
+    /// The request conflicts with work already in progress.
+    Conflict(String),
+    /// The game API call behind the request failed.
+    Upstream(String),
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message) = match self {
+            Self::Unauthorized => (
+                StatusCode::UNAUTHORIZED,
+                "missing or invalid bearer token".to_string(),
+            ),
+            Self::NotFound(message) => (StatusCode::NOT_FOUND, message),
+            Self::Conflict(message) => (StatusCode::CONFLICT, message),
+            Self::Upstream(message) => (StatusCode::BAD_GATEWAY, message),
+        };
+
+        let mut response = (status, Json(serde_json::json!({ "error": message }))).into_response();
+        if status == StatusCode::UNAUTHORIZED {
+            response
+                .headers_mut()
+                .insert(header::WWW_AUTHENTICATE, HeaderValue::from_static("Bearer"));
+        }
+        response
+    }
+}
+
+/// Rejects requests without a matching `Authorization: Bearer` header.
+async fn require_token(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| constant_time_eq(token.as_bytes(), state.token.as_bytes()));
+
+    if authorized {
+        next.run(request).await
+    } else {
+        ApiError::Unauthorized.into_response()
+    }
+}
+
+/// Compares two byte strings in time independent of where they differ.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+/// `GET /resources`: the cached resources of every account.
+async fn get_resources(State(state): State<ApiState>) -> Json<AllResources> {
+    Json(state.app.get_resources().await)
+}
+
+/// `GET /daily-rewards`: the cached daily reward status of every account.
+async fn get_daily_rewards(State(state): State<ApiState>) -> Json<AllDailyRewardStatus> {
+    Json(state.app.get_daily_reward_status().await)
+}
+
+/// `POST /refresh`: fetches all resources now and returns them.
+async fn refresh(State(state): State<ApiState>) -> Result<Json<AllResources>, ApiError> {
+    polling::refresh_now(&state.app)
+        .await
+        .map(Json)
+        .map_err(ApiError::Conflict)
+}
+
+/// Query parameters of `POST /claim/{game}`.
+#[derive(Deserialize)]
+struct ClaimQuery {
+    /// Account name; the default account if omitted.
+    account: Option<String>,
+}
+
+/// `POST /claim/{game}`: claims the daily reward of one account and returns
+/// the claim result.
+async fn claim(
+    State(state): State<ApiState>,
+    Path(game_id): Path<GameId>,
+    Query(query): Query<ClaimQuery>,
+) -> Result<Json<serde_json::Value>, ApiError> {
+    let key = query.account.map_or_else(
+        || AccountKey::default_for(game_id),
+        |account| AccountKey::new(game_id, account),
+    );
+    if !state.app.has_daily_reward_account(&key).await {
+        return Err(ApiError::NotFound(format!(
+            "no daily reward account configured for {key}"
+        )));
+    }
+
+    tracing::info!(account = %key, "Daily reward claim requested over HTTP API");
+    state
+        .app
+        .claim_daily_reward_now(&key)
+        .await
+        .map(Json)
+        .map_err(|e| ApiError::Upstream(format!("{e:#}")))
+}
+
+/// `GET /events`: a server-sent event stream of every published event.
+///
+/// Each SSE event is named after the app event (e.g. `resources-updated`)
+/// and carries its JSON payload as data.
+async fn events(
+    State(state): State<ApiState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let stream = futures::stream::unfold(state.app.subscribe_events(), |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(published) => {
+                    let event = Event::default()
+                        .event(published.event.as_str())
+                        .data(published.payload.to_string());
+                    return Some((Ok(event), receiver));
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "HTTP API event stream fell behind, events dropped");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+    .take_until(state.shutdown.cancelled_owned());
+
+    Sse::new(stream).keep_alive(KeepAlive::default())
+}
+
+/// `GET /metrics`: resource gauges and fetch/claim counters in the
+/// Prometheus text exposition format.
+async fn metrics(State(state): State<ApiState>) -> impl IntoResponse {
+    (
+        [(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static("text/plain; version=0.0.4; charset=utf-8"),
+        )],
+        state.app.render_metrics().await,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::events::AppEvent;
+    use std::net::Ipv4Addr;
+    use tokio::net::TcpListener;
+
+    const TOKEN: &str = "test-token";
+
+    /// Serves the API on an ephemeral port; returns its base URL.
+    async fn spawn_server(app: AppState) -> (String, CancellationToken) {
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .expect("should bind");
+        let addr = listener.local_addr().expect("should have an address");
+        let shutdown = CancellationToken::new();
+        tokio::spawn(super::super::serve(
+            listener,
+            app,
+            Arc::from(TOKEN),
+            shutdown.clone(),
+        ));
+        (format!("http://{addr}"), shutdown)
+    }
+
+    fn client() -> reqwest::Client {
+        reqwest::Client::builder()
+            .no_proxy()
+            .build()
+            .expect("should build client")
+    }
+
+    #[test]
+    fn constant_time_eq_compares_contents_and_length() {
+        assert!(constant_time_eq(b"token", b"token"));
+        assert!(!constant_time_eq(b"token", b"tokem"));
+        assert!(!constant_time_eq(b"token", b"token2"));
+    }
+
+    #[tokio::test]
+    async fn requests_without_valid_token_are_rejected() {
+        let (url, shutdown) = spawn_server(AppState::new()).await;
+
+        let missing = client()
+            .get(format!("{url}/resources"))
+            .send()
+            .await
+            .expect("request should complete");
+        assert_eq!(missing.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let wrong = client()
+            .get(format!("{url}/resources"))
+            .bearer_auth("not-the-token")
+            .send()
+            .await
+            .expect("request should complete");
+        assert_eq!(wrong.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn resources_are_served_from_state() {
+        let app = AppState::new();
+        let mut resources = AllResources::default();
+        resources.games.insert(
+            AccountKey::default_for(GameId::GenshinImpact),
+            serde_json::json!([{"stamina": 160}]),
+        );
+        app.set_resources(resources).await;
+        let (url, shutdown) = spawn_server(app).await;
+
+        let body: serde_json::Value = client()
+            .get(format!("{url}/resources"))
+            .bearer_auth(TOKEN)
+            .send()
+            .await
+            .expect("request should complete")
+            .error_for_status()
+            .expect("request should succeed")
+            .json()
+            .await
+            .expect("body should be JSON");
+        assert_eq!(
+            body.pointer("/games/GENSHIN_IMPACT/default/0/stamina"),
+            Some(&serde_json::json!(160))
+        );
+
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn metrics_are_served_as_prometheus_text() {
+        let app = AppState::new();
+        app.metrics().record_fetch_success(GameId::ZenlessZoneZero);
+        let (url, shutdown) = spawn_server(app).await;
+
+        let response = client()
+            .get(format!("{url}/metrics"))
+            .bearer_auth(TOKEN)
+            .send()
+            .await
+            .expect("request should complete")
+            .error_for_status()
+            .expect("request should succeed");
+        assert!(
+            response
+                .headers()
+                .get(header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .is_some_and(|value| value.starts_with("text/plain; version=0.0.4"))
+        );
+        let body = response.text().await.expect("body should be text");
+        assert!(
+            body.contains("storekeeper_fetch_success_total{provider=\"hoyolab\"} 1\n"),
+            "{body}"
+        );
+
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn claim_for_unconfigured_account_is_not_found() {
+        let (url, shutdown) = spawn_server(AppState::new()).await;
+
+        let response = client()
+            .post(format!("{url}/claim/GENSHIN_IMPACT?account=alt"))
+            .bearer_auth(TOKEN)
+            .send()
+            .await
+            .expect("request should complete");
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+        shutdown.cancel();
+    }
+
+    #[tokio::test]
+    async fn event_stream_mirrors_published_events() {
+        let app = AppState::new();
+        let (url, shutdown) = spawn_server(app.clone()).await;
+
+        let mut response = client()
+            .get(format!("{url}/events"))
+            .bearer_auth(TOKEN)
+            .send()
+            .await
+            .expect("request should complete");
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+        app.publish(
+            AppEvent::DailyRewardClaimed,
+            &serde_json::json!({"ok": true}),
+        );
+
+        let chunk = response
+            .chunk()
+            .await
+            .expect("stream should yield")
+            .expect("stream should not end");
+        let text = String::from_utf8_lossy(&chunk);
+        assert!(text.contains("event: daily-reward-claimed"), "{text}");
+        assert!(text.contains(r#"data: {"ok":true}"#), "{text}");
+
+        shutdown.cancel();
+    }
+}
@@ -54,6 +54,32 @@ pub fn t_args(key: &str, args: &[(&str, Value)]) -> String {
     .unwrap_or_else(|| key.to_string())
 }
 
+/// Formats a caller-supplied template (e.g. a user's webhook message template)
+/// with the same syntax as [`t_args`].
+#[must_use]
+pub fn format_template(template: &str, args: &[(&str, Value)]) -> String {
+    with_messages(|m| {
+        let plural_rules = PluralRules::try_new_cardinal(m.locale.clone().into()).ok();
+        format_message(template, args, plural_rules.as_ref())
+    })
+    .unwrap_or_else(|| format_message(template, args, None))
+}
+
+/// Initializes i18n from the configured language, auto-detecting the system
+/// locale when none is set.
+///
+/// Falls back to English if the resolved locale fails to load; failures are
+/// logged rather than returned so startup always proceeds.
+pub fn init_from_config(language: Option<&str>) {
+    let effective_locale = resolve_locale(language);
+    if let Err(e) = init(effective_locale) {
+        tracing::warn!(error = %e, "Failed to initialize i18n, falling back to defaults");
+        if let Err(e) = init("en") {
+            tracing::error!(error = %e, "Failed to initialize i18n fallback locale");
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -95,6 +121,19 @@ mod tests {
         assert_eq!(result, "Genshin Impact - Original Resin");
     }
 
+    #[test]
+    fn test_format_template_substitutes_args() {
+        ensure_init();
+        let result = format_template(
+            "{title}: {body}",
+            &[
+                ("title", Value::from("Genshin Impact - Original Resin")),
+                ("body", Value::from("Full!")),
+            ],
+        );
+        assert_eq!(result, "Genshin Impact - Original Resin: Full!");
+    }
+
     #[test]
     fn test_stamina_full() {
         ensure_init();
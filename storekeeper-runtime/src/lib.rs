@@ -0,0 +1,53 @@
+//! Frontend-agnostic runtime for Storekeeper.
+//!
+//! Owns the application state, game client registries, background polling,
+//! scheduled daily reward claims, notification checks and resource history.
+//! Frontends (the Tauri desktop app, the headless daemon) plug in through
+//! [`events::EventSink`] for state-change events and
+//! [`notification::Notifier`] for user-facing notifications. An opt-in local
+//! HTTP API ([`http_api`]) exposes the same state to scripts, along with
+//! Prometheus [`metrics`], and [`mqtt`] publishes it to a broker for home
+//! automation.
+
+pub mod challenge;
+pub mod clients;
+pub mod config_diff;
+pub mod daily_reward_registry;
+pub mod error_kind;
+pub mod events;
+pub mod fetch_status;
+pub mod history;
+pub mod http_api;
+pub mod i18n;
+pub mod metrics;
+pub mod mqtt;
+pub mod notification;
+pub mod overcap;
+pub mod poll_schedule;
+pub mod polling;
+mod provider_batch;
+pub mod registry;
+pub mod retry_helpers;
+pub mod roles;
+pub mod scheduled_claim;
+mod snapshot;
+pub mod state;
+
+use state::AppState;
+use tokio_util::sync::CancellationToken;
+
+/// Runs every background task until `cancel_token` is cancelled.
+///
+/// Covers resource polling, scheduled daily reward claims, the notification
+/// checker, history compaction, the local HTTP API and MQTT publishing.
+/// Frontends spawn this on their async runtime once the state is set up.
+pub async fn run_background_tasks(state: AppState, cancel_token: CancellationToken) {
+    tokio::join!(
+        polling::run_polling(state.clone(), cancel_token.clone()),
+        scheduled_claim::run_scheduled_claims(state.clone(), cancel_token.clone()),
+        notification::run_notification_checker(state.clone(), cancel_token.clone()),
+        history::run_history_compaction(state.clone(), cancel_token.clone()),
+        http_api::run_http_api(state.clone(), cancel_token.clone()),
+        mqtt::run_mqtt(state, cancel_token),
+    );
+}
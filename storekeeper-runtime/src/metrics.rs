@@ -0,0 +1,420 @@
+//! Prometheus metrics for resources, fetch health and daily claims.
+//!
+//! Gauges are computed from the cached [`AllResources`] when rendered;
+//! counters accumulate in [`Metrics`] for the lifetime of the process.
+//! Rendered in the Prometheus text exposition format, which the HTTP API
+//! serves at `GET /metrics` and which can also be written to a file for
+//! node_exporter's textfile collector.
+
+use crate::error_kind::ErrorKind;
+use crate::notification::extract_resource_info;
+use crate::state::AllResources;
+use camino::Utf8Path;
+use jiff::Timestamp;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use storekeeper_core::AccountKey;
+use storekeeper_core::ApiProvider;
+use storekeeper_core::ClaimResult;
+use storekeeper_core::GameId;
+
+/// Outcome of a daily reward claim.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ClaimOutcome {
+    /// The reward was claimed.
+    Claimed,
+    /// The reward had already been claimed today.
+    AlreadyClaimed,
+    /// The claim request failed.
+    Failed,
+}
+
+impl ClaimOutcome {
+    /// Classifies the result of a claim request.
+    #[must_use]
+    pub fn of(result: &anyhow::Result<serde_json::Value>) -> Self {
+        match result {
+            Ok(value) => match serde_json::from_value::<ClaimResult>(value.clone()) {
+                Ok(claim) if !claim.success => Self::AlreadyClaimed,
+                _ => Self::Claimed,
+            },
+            Err(_) => Self::Failed,
+        }
+    }
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Claimed => "claimed",
+            Self::AlreadyClaimed => "already_claimed",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+/// Counter values, keyed by their label values.
+#[derive(Default)]
+struct Counters {
+    fetch_successes: BTreeMap<&'static str, u64>,
+    fetch_failures: BTreeMap<(&'static str, ErrorKind), u64>,
+    claims: BTreeMap<(AccountKey, ClaimOutcome), u64>,
+}
+
+/// Process-lifetime counters for resource fetches and daily claims.
+#[derive(Default)]
+pub struct Metrics {
+    counters: Mutex<Counters>,
+}
+
+impl Metrics {
+    /// Counts a successful resource fetch.
+    pub fn record_fetch_success(&self, game_id: GameId) {
+        *self
+            .lock()
+            .fetch_successes
+            .entry(provider_label(game_id))
+            .or_default() += 1;
+    }
+
+    /// Counts a failed resource fetch of the given error kind.
+    pub fn record_fetch_failure(&self, game_id: GameId, kind: ErrorKind) {
+        *self
+            .lock()
+            .fetch_failures
+            .entry((provider_label(game_id), kind))
+            .or_default() += 1;
+    }
+
+    /// Counts the outcome of a daily reward claim.
+    pub fn record_claim(&self, key: &AccountKey, outcome: ClaimOutcome) {
+        *self
+            .lock()
+            .claims
+            .entry((key.clone(), outcome))
+            .or_default() += 1;
+    }
+
+    /// Renders resource gauges and all counters in the Prometheus text
+    /// exposition format.
+    #[must_use]
+    pub fn render(&self, resources: &AllResources, now: Timestamp) -> String {
+        let mut families = resource_families(resources, now);
+        families.extend(self.counter_families());
+
+        families
+            .iter()
+            .filter(|family| !family.samples.is_empty())
+            .flat_map(Family::lines)
+            .map(|line| line + "\n")
+            .collect()
+    }
+
+    fn counter_families(&self) -> Vec<Family> {
+        let counters = self.lock();
+
+        let mut successes = Family::counter(
+            "storekeeper_fetch_success_total",
+            "Successful resource fetches.",
+        );
+        for (provider, count) in &counters.fetch_successes {
+            successes.push(&[("provider", provider)], *count);
+        }
+
+        let mut failures = Family::counter(
+            "storekeeper_fetch_failures_total",
+            "Failed resource fetches, by error kind.",
+        );
+        for ((provider, kind), count) in &counters.fetch_failures {
+            failures.push(&[("provider", provider), ("class", kind.as_str())], *count);
+        }
+
+        let mut claims = Family::counter(
+            "storekeeper_daily_claims_total",
+            "Daily reward claim attempts, by outcome.",
+        );
+        for ((key, outcome), count) in &counters.claims {
+            claims.push(
+                &[
+                    ("game", key.game_id.as_str()),
+                    ("account", &key.account),
+                    ("outcome", outcome.as_str()),
+                ],
+                *count,
+            );
+        }
+
+        vec![successes, failures, claims]
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Counters> {
+        // Counters stay consistent even if a holder panicked mid-update.
+        self.counters
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Writes rendered metrics to `path` for node_exporter's textfile collector.
+///
+/// Writes to a temporary file first and renames it into place, so the
+/// collector never reads a partial file.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written.
+pub fn write_textfile(path: &Utf8Path, contents: &str) -> anyhow::Result<()> {
+    let tmp = path.with_extension("prom.tmp");
+    fs_err::write(&tmp, contents)?;
+    fs_err::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Builds the per-resource gauge families from cached resources.
+fn resource_families(resources: &AllResources, now: Timestamp) -> Vec<Family> {
+    let mut current = Family::gauge(
+        "storekeeper_resource_current",
+        "Estimated current amount of a stamina resource.",
+    );
+    let mut max = Family::gauge(
+        "storekeeper_resource_max",
+        "Capacity of a stamina resource.",
+    );
+    let mut seconds_to_full = Family::gauge(
+        "storekeeper_resource_seconds_to_full",
+        "Seconds until a stamina resource is full; 0 when full.",
+    );
+    let mut ready = Family::gauge(
+        "storekeeper_resource_ready",
+        "Whether a cooldown or expedition resource is ready (1) or not (0).",
+    );
+    let mut seconds_to_ready = Family::gauge(
+        "storekeeper_resource_seconds_to_ready",
+        "Seconds until a cooldown or expedition resource is ready; 0 when ready.",
+    );
+    let mut fetched_at = Family::gauge(
+        "storekeeper_last_fetch_timestamp_seconds",
+        "Unix time of the last successful fetch of an account.",
+    );
+
+    let mut accounts: Vec<_> = resources.games.iter().collect();
+    accounts.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    for (key, data) in accounts {
+        if let Some(at) = resources.fetched_at.get(&key) {
+            fetched_at.push(&account_labels(&key), at.as_second());
+        }
+
+        for resource in data.as_array().into_iter().flatten() {
+            let Some(resource_type) = resource.get("type").and_then(serde_json::Value::as_str)
+            else {
+                continue;
+            };
+            let Some(info) = resource
+                .get("data")
+                .and_then(|data| extract_resource_info(resource_type, data))
+            else {
+                continue;
+            };
+
+            let labels = [
+                ("game", key.game_id.as_str()),
+                ("account", key.account.as_str()),
+                ("resource", resource_type),
+            ];
+            let remaining = if info.is_complete {
+                0
+            } else {
+                info.completion_at.duration_since(now).as_secs().max(0)
+            };
+
+            if let Some(resource_max) = info.max {
+                if let Some(value) = info.estimated_current(now) {
+                    current.push(&labels, value);
+                }
+                max.push(&labels, resource_max);
+                seconds_to_full.push(&labels, remaining);
+            } else {
+                ready.push(&labels, u8::from(remaining == 0));
+                seconds_to_ready.push(&labels, remaining);
+            }
+        }
+    }
+
+    vec![
+        current,
+        max,
+        seconds_to_full,
+        ready,
+        seconds_to_ready,
+        fetched_at,
+    ]
+}
+
+fn account_labels(key: &AccountKey) -> [(&'static str, &str); 2] {
+    [("game", key.game_id.as_str()), ("account", &key.account)]
+}
+
+/// Returns the `provider` label value for a game.
+const fn provider_label(game_id: GameId) -> &'static str {
+    match game_id.api_provider() {
+        ApiProvider::HoYoLab => "hoyolab",
+        ApiProvider::Kuro => "kuro",
+    }
+}
+
+/// One metric family: its metadata and samples.
+struct Family {
+    name: &'static str,
+    help: &'static str,
+    kind: &'static str,
+    /// Rendered `{labels} value` suffixes.
+    samples: Vec<String>,
+}
+
+impl Family {
+    const fn gauge(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            kind: "gauge",
+            samples: Vec::new(),
+        }
+    }
+
+    const fn counter(name: &'static str, help: &'static str) -> Self {
+        Self {
+            name,
+            help,
+            kind: "counter",
+            samples: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, labels: &[(&str, &str)], value: impl std::fmt::Display) {
+        let labels = labels
+            .iter()
+            .map(|(name, value)| format!("{name}=\"{}\"", escape_label_value(value)))
+            .collect::<Vec<_>>()
+            .join(",");
+        self.samples.push(format!("{{{labels}}} {value}"));
+    }
+
+    /// Returns the family's exposition lines, without trailing newlines.
+    fn lines(&self) -> impl Iterator<Item = String> + '_ {
+        let Self {
+            name,
+            help,
+            kind,
+            samples,
+        } = self;
+        [
+            format!("# HELP {name} {help}"),
+            format!("# TYPE {name} {kind}"),
+        ]
+        .into_iter()
+        .chain(samples.iter().map(move |sample| format!("{name}{sample}")))
+    }
+}
+
+/// Escapes a label value per the text exposition format.
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', r#"\""#)
+        .replace('\n', r"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::SignedDuration;
+
+    fn resources_at(now: Timestamp) -> AllResources {
+        let mut resources = AllResources::default();
+        resources.record(
+            AccountKey::default_for(GameId::GenshinImpact),
+            serde_json::json!([
+                {"type": "resin", "data": {
+                    "current": 100, "max": 200,
+                    "fullAt": (now + SignedDuration::from_secs(800)).to_string(),
+                    "regenRateSeconds": 8
+                }},
+                {"type": "parametric_transformer", "data": {
+                    "isReady": true, "readyAt": now.to_string()
+                }},
+            ]),
+            now,
+        );
+        resources
+    }
+
+    #[test]
+    fn renders_stamina_and_cooldown_gauges() {
+        let now = Timestamp::from_second(1_700_000_000).expect("valid timestamp");
+        let out = Metrics::default().render(&resources_at(now), now);
+
+        let labels = r#"game="GENSHIN_IMPACT",account="default""#;
+        assert!(out.contains("# TYPE storekeeper_resource_current gauge\n"));
+        assert!(out.contains(&format!(
+            "storekeeper_resource_current{{{labels},resource=\"resin\"}} 100\n"
+        )));
+        assert!(out.contains(&format!(
+            "storekeeper_resource_max{{{labels},resource=\"resin\"}} 200\n"
+        )));
+        assert!(out.contains(&format!(
+            "storekeeper_resource_seconds_to_full{{{labels},resource=\"resin\"}} 800\n"
+        )));
+        assert!(out.contains(&format!(
+            "storekeeper_resource_ready{{{labels},resource=\"parametric_transformer\"}} 1\n"
+        )));
+        assert!(out.contains(&format!(
+            "storekeeper_last_fetch_timestamp_seconds{{{labels}}} 1700000000\n"
+        )));
+        // No counters recorded yet, so their families are omitted.
+        assert!(!out.contains("storekeeper_fetch_success_total"));
+    }
+
+    #[test]
+    fn counts_fetches_by_provider_and_error_class() {
+        let metrics = Metrics::default();
+        metrics.record_fetch_success(GameId::GenshinImpact);
+        metrics.record_fetch_success(GameId::HonkaiStarRail);
+        metrics.record_fetch_failure(
+            GameId::WutheringWaves,
+            storekeeper_client_kuro::Error::RetryRequested.kind(),
+        );
+
+        let out = metrics.render(&AllResources::default(), Timestamp::now());
+        assert!(out.contains("# TYPE storekeeper_fetch_success_total counter\n"));
+        assert!(out.contains("storekeeper_fetch_success_total{provider=\"hoyolab\"} 2\n"));
+        assert!(out.contains(
+            "storekeeper_fetch_failures_total{provider=\"kuro\",class=\"rate_limited\"} 1\n"
+        ));
+    }
+
+    #[test]
+    fn counts_claim_outcomes() {
+        let metrics = Metrics::default();
+        let key = AccountKey::new(GameId::HonkaiStarRail, "alt");
+        let already = serde_json::json!({
+            "success": false, "reward": null, "message": "Already claimed",
+            "info": {"is_signed": true, "total_sign_day": 3}
+        });
+        metrics.record_claim(&key, ClaimOutcome::of(&Ok(already)));
+        metrics.record_claim(&key, ClaimOutcome::of(&Err(anyhow::anyhow!("offline"))));
+
+        let out = metrics.render(&AllResources::default(), Timestamp::now());
+        let labels = r#"game="HONKAI_STAR_RAIL",account="alt""#;
+        assert!(out.contains(&format!(
+            "storekeeper_daily_claims_total{{{labels},outcome=\"already_claimed\"}} 1\n"
+        )));
+        assert!(out.contains(&format!(
+            "storekeeper_daily_claims_total{{{labels},outcome=\"failed\"}} 1\n"
+        )));
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        assert_eq!(escape_label_value("a\"b\\c\nd"), r#"a\"b\\c\nd"#);
+    }
+}
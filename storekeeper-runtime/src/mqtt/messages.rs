@@ -0,0 +1,532 @@
+//! MQTT topics and payloads: resource states, Home Assistant discovery
+//! configs and the command topics of its buttons.
+
+use crate::notification::account_display_name;
+use crate::notification::extract_resource_info;
+use crate::state::AllResources;
+use jiff::Timestamp;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use storekeeper_core::AccountKey;
+use storekeeper_core::DisplayableResource;
+use storekeeper_core::GameId;
+use storekeeper_core::MqttConfig;
+use storekeeper_game_genshin::GenshinResource;
+use storekeeper_game_hsr::HsrResource;
+use storekeeper_game_wuwa::WuwaResource;
+use storekeeper_game_zzz::ZzzResource;
+
+/// Payload published to the availability topic while connected.
+pub(super) const ONLINE: &str = "online";
+
+/// Payload the broker publishes to the availability topic once the
+/// connection drops (the last will).
+pub(super) const OFFLINE: &str = "offline";
+
+/// A retained message to publish.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct Message {
+    pub topic: String,
+    pub payload: String,
+}
+
+/// An action requested over MQTT.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) enum Command {
+    /// Fetch all resources now.
+    Refresh,
+    /// Claim an account's daily reward.
+    Claim(AccountKey),
+}
+
+/// State of one resource, as published to its state topic.
+#[derive(Debug, Serialize)]
+struct ResourceState {
+    /// Estimated current amount (stamina resources only).
+    current: Option<u64>,
+    /// Capacity (stamina resources only).
+    max: Option<u64>,
+    /// When the resource is full or ready.
+    full_at: Timestamp,
+    /// Whether the resource is full or ready.
+    is_full: bool,
+}
+
+/// Availability topic, carrying [`ONLINE`] or [`OFFLINE`].
+pub(super) fn status_topic(config: &MqttConfig) -> String {
+    format!("{}/status", config.base_topic)
+}
+
+/// Topics to subscribe to for [`Command`]s.
+pub(super) fn command_filters(config: &MqttConfig) -> [String; 2] {
+    [
+        refresh_topic(config),
+        format!("{}/+/+/claim", config.base_topic),
+    ]
+}
+
+/// Parses a message on `topic` into a command.
+///
+/// Claims are only accepted for `claimable` accounts, since account names are
+/// sanitized in topics and cannot be recovered from them.
+pub(super) fn parse_command(
+    config: &MqttConfig,
+    topic: &str,
+    claimable: &[AccountKey],
+) -> Option<Command> {
+    if topic == refresh_topic(config) {
+        return Some(Command::Refresh);
+    }
+    claimable
+        .iter()
+        .find(|key| topic == claim_topic(config, key))
+        .map(|key| Command::Claim(key.clone()))
+}
+
+/// Builds the state message of every cached resource, projected to `now`.
+pub(super) fn state_messages(
+    config: &MqttConfig,
+    resources: &AllResources,
+    now: Timestamp,
+) -> Vec<Message> {
+    let mut messages = Vec::new();
+    for (key, data) in resources.games.iter() {
+        for resource in data.as_array().into_iter().flatten() {
+            let Some(resource_type) = resource.get("type").and_then(serde_json::Value::as_str)
+            else {
+                continue;
+            };
+            let Some(info) = resource
+                .get("data")
+                .and_then(|data| extract_resource_info(resource_type, data))
+            else {
+                continue;
+            };
+            let state = ResourceState {
+                current: info.estimated_current(now),
+                max: info.max,
+                full_at: info.completion_at,
+                // The cached flag is from fetch time; count resources that
+                // have filled since then as full.
+                is_full: info.is_complete || info.completion_at <= now,
+            };
+            messages.push(Message {
+                topic: state_topic(config, &key, resource_type),
+                payload: serde_json::json!(state).to_string(),
+            });
+        }
+    }
+    messages.sort_by(|a, b| a.topic.cmp(&b.topic));
+    messages
+}
+
+/// Builds the Home Assistant discovery configs for every cached resource,
+/// the refresh button, and a claim button per `claimable` account.
+///
+/// Stamina resources get a sensor for their current amount (with the full
+/// state as attributes), a timestamp sensor for when they are full and a
+/// binary sensor for whether they are; cooldowns and expeditions get the
+/// latter two.
+pub(super) fn discovery_messages(
+    config: &MqttConfig,
+    resources: &AllResources,
+    claimable: &[AccountKey],
+) -> Vec<Message> {
+    let refresh_id = format!("{}_refresh", segment(&config.client_id));
+    let mut messages = vec![discovery(
+        config,
+        "button",
+        &refresh_id,
+        &serde_json::json!({
+            "unique_id": refresh_id,
+            "name": "Refresh",
+            "icon": "mdi:refresh",
+            "command_topic": refresh_topic(config),
+            "availability_topic": status_topic(config),
+            "device": {
+                "identifiers": [segment(&config.client_id)],
+                "name": "Storekeeper",
+            },
+        }),
+    )];
+
+    for (key, data) in resources.games.iter() {
+        for resource in data.as_array().into_iter().flatten() {
+            messages.extend(resource_discovery(config, &key, resource));
+        }
+    }
+
+    for key in claimable {
+        let claim_id = format!("{}_claim", object_id(config, key));
+        messages.push(discovery(
+            config,
+            "button",
+            &claim_id,
+            &serde_json::json!({
+                "unique_id": claim_id,
+                "name": "Claim daily reward",
+                "icon": "mdi:gift",
+                "command_topic": claim_topic(config, key),
+                "availability_topic": status_topic(config),
+                "device": device(config, key),
+            }),
+        ));
+    }
+
+    messages.sort_by(|a, b| a.topic.cmp(&b.topic));
+    messages
+}
+
+/// Builds the discovery configs of one resource.
+fn resource_discovery(
+    config: &MqttConfig,
+    key: &AccountKey,
+    resource: &serde_json::Value,
+) -> Vec<Message> {
+    let Some(resource_type) = resource.get("type").and_then(serde_json::Value::as_str) else {
+        return Vec::new();
+    };
+    let Some(info) = resource
+        .get("data")
+        .and_then(|data| extract_resource_info(resource_type, data))
+    else {
+        return Vec::new();
+    };
+    let Some((name, icon)) = describe(key.game_id, resource) else {
+        return Vec::new();
+    };
+
+    let id = format!("{}_{}", object_id(config, key), segment(resource_type));
+    let entity = |suffix: &str, entity: serde_json::Value| {
+        let mut payload = serde_json::json!({
+            "unique_id": format!("{id}{suffix}"),
+            "state_topic": state_topic(config, key, resource_type),
+            "availability_topic": status_topic(config),
+            "device": device(config, key),
+        });
+        if let (Some(payload), serde_json::Value::Object(entity)) =
+            (payload.as_object_mut(), entity)
+        {
+            payload.extend(entity);
+        }
+        payload
+    };
+
+    let mut messages = Vec::new();
+    if info.max.is_some() {
+        messages.push(discovery(
+            config,
+            "sensor",
+            &id,
+            &entity(
+                "",
+                serde_json::json!({
+                    "name": name,
+                    "icon": mdi_icon(icon),
+                    "value_template": "{{ value_json.current }}",
+                    "json_attributes_topic": state_topic(config, key, resource_type),
+                    "state_class": "measurement",
+                }),
+            ),
+        ));
+    }
+    messages.push(discovery(
+        config,
+        "sensor",
+        &format!("{id}_full_at"),
+        &entity(
+            "_full_at",
+            serde_json::json!({
+                "name": format!("{name} full at"),
+                "icon": mdi_icon(icon),
+                "device_class": "timestamp",
+                "value_template": "{{ value_json.full_at }}",
+            }),
+        ),
+    ));
+    messages.push(discovery(
+        config,
+        "binary_sensor",
+        &format!("{id}_full"),
+        &entity(
+            "_full",
+            serde_json::json!({
+                "name": format!("{name} full"),
+                "icon": mdi_icon(icon),
+                "value_template": "{{ 'ON' if value_json.is_full else 'OFF' }}",
+            }),
+        ),
+    ));
+    messages
+}
+
+/// Returns the display name and icon identifier of a resource.
+fn describe(game_id: GameId, resource: &serde_json::Value) -> Option<(&'static str, &'static str)> {
+    fn parse<R: DeserializeOwned + DisplayableResource>(
+        resource: &serde_json::Value,
+    ) -> Option<(&'static str, &'static str)> {
+        serde_json::from_value::<R>(resource.clone())
+            .ok()
+            .map(|resource| (resource.display_name(), resource.icon()))
+    }
+
+    match game_id {
+        GameId::GenshinImpact => parse::<GenshinResource>(resource),
+        GameId::HonkaiStarRail => parse::<HsrResource>(resource),
+        GameId::ZenlessZoneZero => parse::<ZzzResource>(resource),
+        GameId::WutheringWaves => parse::<WuwaResource>(resource),
+    }
+}
+
+/// Maps a resource icon identifier to a Material Design icon.
+fn mdi_icon(icon: &str) -> &'static str {
+    match icon {
+        "resin" => "mdi:water",
+        "transformer" => "mdi:cog-transfer",
+        "realm" => "mdi:teapot",
+        "expedition" => "mdi:map-marker-path",
+        "power" => "mdi:lightning-bolt",
+        "battery" => "mdi:battery",
+        "waveplate" => "mdi:waves",
+        _ => "mdi:gauge",
+    }
+}
+
+/// Home Assistant device of an account.
+fn device(config: &MqttConfig, key: &AccountKey) -> serde_json::Value {
+    serde_json::json!({
+        "identifiers": [object_id(config, key)],
+        "name": account_display_name(key),
+        "manufacturer": "Storekeeper",
+        "model": key.game_id.display_name(),
+        "via_device": segment(&config.client_id),
+    })
+}
+
+/// Builds a discovery config message for a Home Assistant entity.
+fn discovery(
+    config: &MqttConfig,
+    component: &str,
+    object_id: &str,
+    payload: &serde_json::Value,
+) -> Message {
+    Message {
+        topic: format!("{}/{component}/{object_id}/config", config.discovery_prefix),
+        payload: payload.to_string(),
+    }
+}
+
+/// Topic of a resource's state.
+fn state_topic(config: &MqttConfig, key: &AccountKey, resource_type: &str) -> String {
+    format!(
+        "{}/{}/{}/{}/state",
+        config.base_topic,
+        key.game_id.config_key(),
+        segment(&key.account),
+        segment(resource_type)
+    )
+}
+
+/// Command topic of the refresh button.
+fn refresh_topic(config: &MqttConfig) -> String {
+    format!("{}/refresh", config.base_topic)
+}
+
+/// Command topic of an account's claim button.
+fn claim_topic(config: &MqttConfig, key: &AccountKey) -> String {
+    format!(
+        "{}/{}/{}/claim",
+        config.base_topic,
+        key.game_id.config_key(),
+        segment(&key.account)
+    )
+}
+
+/// Unique ID prefix of an account's entities.
+fn object_id(config: &MqttConfig, key: &AccountKey) -> String {
+    format!(
+        "{}_{}_{}",
+        segment(&config.client_id),
+        key.game_id.config_key(),
+        segment(&key.account)
+    )
+}
+
+/// Replaces characters that are not allowed in discovery object IDs (and
+/// MQTT wildcards and separators) with `_`.
+fn segment(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::SignedDuration;
+    use storekeeper_core::CooldownResource;
+    use storekeeper_core::StaminaResource;
+
+    fn ensure_init() {
+        #[expect(
+            clippy::let_underscore_must_use,
+            reason = "test setup may run after i18n is already initialized"
+        )]
+        let _ = crate::i18n::init("en");
+    }
+
+    fn genshin(now: Timestamp) -> AllResources {
+        let mut resources = AllResources::default();
+        resources.record(
+            AccountKey::default_for(GameId::GenshinImpact),
+            serde_json::json!([
+                {
+                    "type": "resin",
+                    "data": StaminaResource::new(
+                        100,
+                        200,
+                        now + SignedDuration::from_mins(80),
+                        480,
+                    ),
+                },
+                {
+                    "type": "parametric_transformer",
+                    "data": CooldownResource::new(true, now - SignedDuration::from_hours(1)),
+                },
+            ]),
+            now,
+        );
+        resources
+    }
+
+    fn payload(message: Option<&Message>) -> serde_json::Value {
+        let message = message.expect("message should exist");
+        serde_json::from_str(&message.payload).expect("payload should be JSON")
+    }
+
+    #[test]
+    fn states_are_projected_to_now() {
+        let now = Timestamp::now();
+        let config = MqttConfig::default();
+
+        let messages = state_messages(&config, &genshin(now), now);
+
+        let topics: Vec<_> = messages.iter().map(|m| m.topic.as_str()).collect();
+        assert_eq!(
+            topics,
+            [
+                "storekeeper/genshin_impact/default/parametric_transformer/state",
+                "storekeeper/genshin_impact/default/resin/state",
+            ]
+        );
+        assert_eq!(
+            payload(messages.first()),
+            serde_json::json!({
+                "current": null,
+                "max": null,
+                "full_at": now - SignedDuration::from_hours(1),
+                "is_full": true,
+            })
+        );
+        assert_eq!(
+            payload(messages.get(1)),
+            serde_json::json!({
+                "current": 190,
+                "max": 200,
+                "full_at": now + SignedDuration::from_mins(80),
+                "is_full": false,
+            })
+        );
+    }
+
+    #[test]
+    fn discovery_names_resources_and_links_state_topics() {
+        ensure_init();
+        let now = Timestamp::now();
+        let config = MqttConfig::default();
+        let claimable = [AccountKey::default_for(GameId::GenshinImpact)];
+
+        let messages = discovery_messages(&config, &genshin(now), &claimable);
+
+        let topics: Vec<_> = messages.iter().map(|m| m.topic.as_str()).collect();
+        assert_eq!(
+            topics,
+            [
+                "homeassistant/binary_sensor/storekeeper_genshin_impact_default_parametric_transformer_full/config",
+                "homeassistant/binary_sensor/storekeeper_genshin_impact_default_resin_full/config",
+                "homeassistant/button/storekeeper_genshin_impact_default_claim/config",
+                "homeassistant/button/storekeeper_refresh/config",
+                "homeassistant/sensor/storekeeper_genshin_impact_default_parametric_transformer_full_at/config",
+                "homeassistant/sensor/storekeeper_genshin_impact_default_resin/config",
+                "homeassistant/sensor/storekeeper_genshin_impact_default_resin_full_at/config",
+            ]
+        );
+
+        let device = serde_json::json!({
+            "identifiers": ["storekeeper_genshin_impact_default"],
+            "name": "Genshin Impact",
+            "manufacturer": "Storekeeper",
+            "model": "Genshin Impact",
+            "via_device": "storekeeper",
+        });
+        assert_eq!(
+            payload(messages.get(5)),
+            serde_json::json!({
+                "unique_id": "storekeeper_genshin_impact_default_resin",
+                "name": "Original Resin",
+                "icon": "mdi:water",
+                "state_topic": "storekeeper/genshin_impact/default/resin/state",
+                "json_attributes_topic": "storekeeper/genshin_impact/default/resin/state",
+                "value_template": "{{ value_json.current }}",
+                "state_class": "measurement",
+                "availability_topic": "storekeeper/status",
+                "device": device,
+            })
+        );
+        assert_eq!(
+            payload(messages.get(2)),
+            serde_json::json!({
+                "unique_id": "storekeeper_genshin_impact_default_claim",
+                "name": "Claim daily reward",
+                "icon": "mdi:gift",
+                "command_topic": "storekeeper/genshin_impact/default/claim",
+                "availability_topic": "storekeeper/status",
+                "device": device,
+            })
+        );
+    }
+
+    #[test]
+    fn commands_are_parsed_from_button_topics() {
+        let config = MqttConfig::default();
+        let alt = AccountKey::new(GameId::HonkaiStarRail, "my alt");
+        let claimable = [alt.clone()];
+
+        assert_eq!(
+            parse_command(&config, "storekeeper/refresh", &claimable),
+            Some(Command::Refresh)
+        );
+        assert_eq!(
+            parse_command(
+                &config,
+                "storekeeper/honkai_star_rail/my_alt/claim",
+                &claimable
+            ),
+            Some(Command::Claim(alt))
+        );
+        assert_eq!(
+            parse_command(
+                &config,
+                "storekeeper/genshin_impact/default/claim",
+                &claimable
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,279 @@
+//! Opt-in MQTT publishing with Home Assistant discovery.
+//!
+//! Publishes each account's resources (current, max, full_at, is_full) as
+//! retained JSON to `<base_topic>/<game>/<account>/<resource>/state`, and
+//! Home Assistant discovery configs so they show up as sensors along with
+//! "Refresh" and "Claim daily reward" buttons. Button presses run
+//! [`polling::refresh_now`] and [`AppState::claim_daily_reward_now`].
+
+mod messages;
+
+use crate::events::AppEvent;
+use crate::events::PublishedEvent;
+use crate::polling;
+use crate::state::AppState;
+use jiff::Timestamp;
+use messages::Command;
+use messages::Message;
+use rumqttc::AsyncClient;
+use rumqttc::Event;
+use rumqttc::EventLoop;
+use rumqttc::LastWill;
+use rumqttc::MqttOptions;
+use rumqttc::Outgoing;
+use rumqttc::Packet;
+use rumqttc::Publish;
+use rumqttc::QoS;
+use rumqttc::TlsConfiguration;
+use rumqttc::Transport;
+use std::time::Duration;
+use storekeeper_core::MqttConfig;
+use tokio::sync::Notify;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+/// Keep-alive interval of the broker connection.
+const KEEP_ALIVE: Duration = Duration::from_secs(30);
+
+/// How often states are republished so estimated amounts stay current.
+const REPUBLISH_INTERVAL: Duration = Duration::from_mins(1);
+
+/// Delay before reconnecting after the connection fails.
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// How long to wait for the broker to take the "offline" status on shutdown.
+const DISCONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Capacity of the client's outgoing request queue.
+const REQUEST_CAPACITY: usize = 64;
+
+/// Runs the MQTT connection until cancelled.
+///
+/// The connection is (re)established from the current config on startup and
+/// every time [`AppState::restart_mqtt`] is called; while disabled the task
+/// just waits for the next restart.
+pub async fn run_mqtt(state: AppState, cancel_token: CancellationToken) {
+    let notify = state.mqtt_notify();
+
+    loop {
+        let shutdown = cancel_token.child_token();
+        let connection = tokio::spawn(connect_from_config(state.clone(), shutdown.clone()));
+
+        let restart = tokio::select! {
+            () = cancel_token.cancelled() => false,
+            () = notify.notified() => true,
+        };
+
+        shutdown.cancel();
+        if let Err(e) = connection.await {
+            tracing::error!(error = %e, "MQTT task failed");
+        }
+
+        if !restart {
+            tracing::info!("MQTT stopped");
+            return;
+        }
+        tracing::info!("MQTT settings changed, reconnecting");
+    }
+}
+
+/// Connects to the broker if MQTT is enabled and publishes until `shutdown`
+/// is cancelled.
+async fn connect_from_config(state: AppState, shutdown: CancellationToken) {
+    let (config, login) = {
+        let inner = state.inner.read().await;
+        (
+            inner.config.mqtt.clone(),
+            inner
+                .secrets
+                .mqtt
+                .login()
+                .map(|(username, password)| (username.to_string(), password.to_string())),
+        )
+    };
+
+    if !config.enabled {
+        tracing::debug!("MQTT disabled");
+        return;
+    }
+
+    let mut options = MqttOptions::new(&config.client_id, &config.host, config.port);
+    options
+        .set_keep_alive(KEEP_ALIVE)
+        .set_last_will(LastWill::new(
+            messages::status_topic(&config),
+            messages::OFFLINE,
+            QoS::AtLeastOnce,
+            true,
+        ));
+    if let Some((username, password)) = login {
+        options.set_credentials(username, password);
+    }
+    if config.tls {
+        options.set_transport(Transport::tls_with_config(TlsConfiguration::Native));
+    }
+    let (client, mut eventloop) = AsyncClient::new(options, REQUEST_CAPACITY);
+
+    tracing::info!(host = %config.host, port = config.port, "Connecting to MQTT broker");
+    let connected = Notify::new();
+    tokio::select! {
+        () = shutdown.cancelled() => {}
+        () = handle_events(&mut eventloop, &client, &state, &config, &connected) => {}
+        () = publish_updates(&client, &state, &config, &connected) => {}
+    }
+
+    disconnect(&mut eventloop, &client, &config).await;
+}
+
+/// Drives the connection: subscribes to the command topics on every
+/// (re)connect and runs the commands received.
+async fn handle_events(
+    eventloop: &mut EventLoop,
+    client: &AsyncClient,
+    state: &AppState,
+    config: &MqttConfig,
+    connected: &Notify,
+) {
+    loop {
+        match eventloop.poll().await {
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                tracing::info!("Connected to MQTT broker");
+                for filter in messages::command_filters(config) {
+                    if let Err(e) = client.try_subscribe(filter, QoS::AtLeastOnce) {
+                        tracing::warn!(error = %e, "Failed to subscribe to MQTT commands");
+                    }
+                }
+                connected.notify_one();
+            }
+            Ok(Event::Incoming(Packet::Publish(publish))) => {
+                handle_command(state, config, &publish).await;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                tracing::warn!(error = %e, "MQTT connection failed, retrying");
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        }
+    }
+}
+
+/// Runs the command a button press published, in the background.
+async fn handle_command(state: &AppState, config: &MqttConfig, publish: &Publish) {
+    // A retained press would re-run on every reconnect.
+    if publish.retain {
+        tracing::debug!(topic = %publish.topic, "Ignoring retained MQTT command");
+        return;
+    }
+
+    let claimable = state.daily_reward_accounts().await;
+    let state = state.clone();
+    match messages::parse_command(config, &publish.topic, &claimable) {
+        Some(Command::Refresh) => {
+            tokio::spawn(async move {
+                if let Err(e) = polling::refresh_now(&state).await {
+                    tracing::warn!(error = %e, "Refresh requested over MQTT failed");
+                }
+            });
+        }
+        Some(Command::Claim(key)) => {
+            tracing::info!(account = %key, "Daily reward claim requested over MQTT");
+            tokio::spawn(async move {
+                if let Err(e) = state.claim_daily_reward_now(&key).await {
+                    tracing::warn!(account = %key, error = %e, "Daily reward claim requested over MQTT failed");
+                }
+            });
+        }
+        None => tracing::debug!(topic = %publish.topic, "Ignoring unknown MQTT command"),
+    }
+}
+
+/// Publishes availability and discovery configs on every connect and after
+/// each full refresh, and resource states on every update and periodically.
+async fn publish_updates(
+    client: &AsyncClient,
+    state: &AppState,
+    config: &MqttConfig,
+    connected: &Notify,
+) {
+    let mut events = state.subscribe_events();
+    let mut republish =
+        tokio::time::interval_at(Instant::now() + REPUBLISH_INTERVAL, REPUBLISH_INTERVAL);
+
+    loop {
+        let announce = tokio::select! {
+            () = connected.notified() => true,
+            event = events.recv() => match event {
+                Ok(PublishedEvent { event: AppEvent::ResourcesUpdated, .. })
+                | Err(RecvError::Lagged(_)) => true,
+                Ok(PublishedEvent { event: AppEvent::GameResourceUpdated, .. }) => false,
+                Ok(_) => continue,
+                Err(RecvError::Closed) => return,
+            },
+            _ = republish.tick() => false,
+        };
+        publish(client, state, config, announce).await;
+    }
+}
+
+/// Queues the current resource states, preceded by the availability and
+/// discovery configs if `announce` is set.
+async fn publish(client: &AsyncClient, state: &AppState, config: &MqttConfig, announce: bool) {
+    let resources = state.get_resources().await;
+    let mut queued = Vec::new();
+    if announce {
+        queued.push(Message {
+            topic: messages::status_topic(config),
+            payload: messages::ONLINE.to_string(),
+        });
+        if config.discovery {
+            let claimable = state.daily_reward_accounts().await;
+            queued.extend(messages::discovery_messages(config, &resources, &claimable));
+        }
+    }
+    queued.extend(messages::state_messages(
+        config,
+        &resources,
+        Timestamp::now(),
+    ));
+
+    for message in queued {
+        if let Err(e) = client
+            .publish(message.topic, QoS::AtLeastOnce, true, message.payload)
+            .await
+        {
+            tracing::warn!(error = %e, "Failed to queue MQTT message");
+            return;
+        }
+    }
+}
+
+/// Marks the service offline and disconnects cleanly, since the broker only
+/// publishes the last will when the connection drops.
+async fn disconnect(eventloop: &mut EventLoop, client: &AsyncClient, config: &MqttConfig) {
+    if client
+        .try_publish(
+            messages::status_topic(config),
+            QoS::AtLeastOnce,
+            true,
+            messages::OFFLINE,
+        )
+        .and_then(|()| client.try_disconnect())
+        .is_err()
+    {
+        return;
+    }
+
+    let flushed = tokio::time::timeout(DISCONNECT_TIMEOUT, async {
+        loop {
+            match eventloop.poll().await {
+                Ok(Event::Outgoing(Outgoing::Disconnect)) | Err(_) => return,
+                Ok(_) => {}
+            }
+        }
+    })
+    .await;
+    if flushed.is_err() {
+        tracing::debug!("Timed out disconnecting from MQTT broker");
+    }
+}
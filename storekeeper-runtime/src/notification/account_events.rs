@@ -0,0 +1,125 @@
+//! Notifications for account events (daily reward claims, rejected logins).
+//!
+//! These only go to hook channels; see [`AppState::notify_hooks`].
+//!
+//! [`AppState::notify_hooks`]: crate::state::AppState::notify_hooks
+
+use super::message_builder::account_display_name;
+use super::notifier::Notification;
+use super::notifier::NotificationEvent;
+use super::notifier::Urgency;
+use crate::i18n;
+use crate::metrics::ClaimOutcome;
+use storekeeper_core::AccountKey;
+use storekeeper_core::HookEvent;
+
+/// Builds the notification for a daily reward claim attempt.
+///
+/// Returns `None` if the reward had already been claimed.
+#[must_use]
+pub fn claim_notification(
+    account: &AccountKey,
+    result: &anyhow::Result<serde_json::Value>,
+) -> Option<Notification> {
+    let (kind, body, data, urgency) = match (ClaimOutcome::of(result), result) {
+        (ClaimOutcome::Claimed, Ok(claim)) => (
+            HookEvent::ClaimSucceeded,
+            i18n::t("notification_claim_succeeded"),
+            claim.clone(),
+            Urgency::Normal,
+        ),
+        (ClaimOutcome::Failed, Err(e)) => (
+            HookEvent::ClaimFailed,
+            i18n::t_args(
+                "notification_claim_failed",
+                &[("error", i18n::Value::from(e.to_string()))],
+            ),
+            serde_json::json!({ "error": format!("{e:#}") }),
+            Urgency::High,
+        ),
+        _ => return None,
+    };
+    Some(account_notification(account, kind, body, data, urgency))
+}
+
+/// Builds the notification for an account whose login was rejected.
+#[must_use]
+pub fn auth_expired_notification(account: &AccountKey) -> Notification {
+    account_notification(
+        account,
+        HookEvent::AuthExpired,
+        i18n::t("notification_auth_expired"),
+        serde_json::Value::Null,
+        Urgency::High,
+    )
+}
+
+fn account_notification(
+    account: &AccountKey,
+    kind: HookEvent,
+    body: String,
+    data: serde_json::Value,
+    urgency: Urgency,
+) -> Notification {
+    Notification {
+        title: account_display_name(account),
+        body,
+        game_id: Some(account.game_id),
+        urgency,
+        event: Some(NotificationEvent {
+            kind,
+            account: account.clone(),
+            resource: None,
+            data,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use storekeeper_core::GameId;
+
+    fn ensure_init() {
+        #[expect(
+            clippy::let_underscore_must_use,
+            reason = "test setup may run after i18n is already initialized"
+        )]
+        let _ = crate::i18n::init("en");
+    }
+
+    fn kind(notification: Option<&Notification>) -> Option<HookEvent> {
+        notification
+            .and_then(|notification| notification.event.as_ref())
+            .map(|event| event.kind)
+    }
+
+    #[test]
+    fn claim_results_map_to_events() {
+        ensure_init();
+        let account = AccountKey::default_for(GameId::GenshinImpact);
+        let claim = |success: bool| {
+            serde_json::json!({
+                "success": success, "reward": null, "message": null,
+                "info": {"is_signed": true, "total_sign_day": 3}
+            })
+        };
+        let claimed = Ok(claim(true));
+        let already = Ok(claim(false));
+        let failed = Err(anyhow::anyhow!("offline"));
+
+        assert_eq!(
+            kind(claim_notification(&account, &claimed).as_ref()),
+            Some(HookEvent::ClaimSucceeded)
+        );
+        assert_eq!(claim_notification(&account, &already), None);
+
+        let failure = claim_notification(&account, &failed).expect("failed claims notify");
+        assert_eq!(kind(Some(&failure)), Some(HookEvent::ClaimFailed));
+        assert_eq!(failure.title, "Genshin Impact");
+        assert_eq!(
+            failure.event.map(|event| event.data),
+            Some(serde_json::json!({"error": "offline"}))
+        );
+    }
+}
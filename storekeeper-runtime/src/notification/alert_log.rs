@@ -0,0 +1,157 @@
+//! Rolling log of sent notifications, for the "recent alerts" view.
+
+use super::notifier::Delivery;
+use super::notifier::Notification;
+use jiff::Timestamp;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::VecDeque;
+use storekeeper_core::GameId;
+
+/// Number of entries the alert log keeps.
+pub const ALERT_LOG_CAPACITY: usize = 200;
+
+/// One notification sent to one channel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlertLogEntry {
+    /// When the notification was sent.
+    pub sent_at: Timestamp,
+    /// Channel it was sent to.
+    pub channel: String,
+    /// Notification title.
+    pub title: String,
+    /// Notification body.
+    pub body: String,
+    /// Game the notification is about, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub game_id: Option<GameId>,
+    /// Account the notification is about, for event notifications.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+    /// Resource type tag, for resource notifications.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource: Option<String>,
+    /// Id of the notification rule that fired, for resource notifications.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
+    /// Whether the channel delivered it.
+    pub delivered: bool,
+    /// Why delivery failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl AlertLogEntry {
+    /// Describes the delivery of `notification` to one channel.
+    #[must_use]
+    pub fn new(notification: &Notification, delivery: &Delivery, sent_at: Timestamp) -> Self {
+        let event = notification.event.as_ref();
+        Self {
+            sent_at,
+            channel: delivery.channel.clone(),
+            title: notification.title.clone(),
+            body: notification.body.clone(),
+            game_id: notification.game_id,
+            account: event.map(|event| event.account.account.clone()),
+            resource: event.and_then(|event| event.resource.clone()),
+            rule: event
+                .and_then(|event| event.data.get("rule"))
+                .and_then(serde_json::Value::as_str)
+                .map(str::to_string),
+            delivered: delivery.result.is_ok(),
+            error: delivery.result.as_ref().err().map(|e| format!("{e:#}")),
+        }
+    }
+}
+
+/// The most recent [`ALERT_LOG_CAPACITY`] sent notifications, oldest first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AlertLog {
+    entries: VecDeque<AlertLogEntry>,
+}
+
+impl AlertLog {
+    /// Appends an entry, dropping the oldest once the log is full.
+    pub fn push(&mut self, entry: AlertLogEntry) {
+        while self.entries.len() >= ALERT_LOG_CAPACITY {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    /// Returns up to `limit` entries, newest first.
+    #[must_use]
+    pub fn recent(&self, limit: usize) -> Vec<AlertLogEntry> {
+        self.entries.iter().rev().take(limit).cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::notifier::NotificationEvent;
+    use super::*;
+    use storekeeper_core::AccountKey;
+    use storekeeper_core::HookEvent;
+
+    #[test]
+    fn entry_describes_resource_notification_and_failure() {
+        let notification = Notification {
+            game_id: Some(GameId::GenshinImpact),
+            event: Some(NotificationEvent {
+                kind: HookEvent::ResourceFull,
+                account: AccountKey::new(GameId::GenshinImpact, "alt"),
+                resource: Some("resin".to_string()),
+                data: serde_json::json!({"rule": "while_full"}),
+            }),
+            ..Notification::new("Genshin Impact (alt) - Original Resin", "Full!")
+        };
+        let delivery = Delivery {
+            channel: "phone".to_string(),
+            result: Err(anyhow::anyhow!("HTTP 500")),
+        };
+        let sent_at = Timestamp::now();
+
+        assert_eq!(
+            AlertLogEntry::new(&notification, &delivery, sent_at),
+            AlertLogEntry {
+                sent_at,
+                channel: "phone".to_string(),
+                title: "Genshin Impact (alt) - Original Resin".to_string(),
+                body: "Full!".to_string(),
+                game_id: Some(GameId::GenshinImpact),
+                account: Some("alt".to_string()),
+                resource: Some("resin".to_string()),
+                rule: Some("while_full".to_string()),
+                delivered: false,
+                error: Some("HTTP 500".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn log_keeps_the_most_recent_entries() {
+        let mut log = AlertLog::default();
+        let delivery = Delivery {
+            channel: "os".to_string(),
+            result: Ok(()),
+        };
+        for i in 0..=ALERT_LOG_CAPACITY {
+            log.push(AlertLogEntry::new(
+                &Notification::new(i.to_string(), ""),
+                &delivery,
+                Timestamp::now(),
+            ));
+        }
+
+        let recent = log.recent(ALERT_LOG_CAPACITY + 1);
+        assert_eq!(recent.len(), ALERT_LOG_CAPACITY);
+        assert_eq!(
+            recent.first().map(|entry| entry.title.as_str()),
+            Some(ALERT_LOG_CAPACITY.to_string().as_str())
+        );
+        assert_eq!(recent.last().map(|entry| entry.title.as_str()), Some("1"));
+        assert_eq!(log.recent(2).len(), 2);
+    }
+}
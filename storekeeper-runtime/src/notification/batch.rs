@@ -0,0 +1,224 @@
+//! Grouping of resource notifications that fire close together.
+
+use super::digest::summary_line;
+use super::notifier::Notification;
+use super::notifier::Urgency;
+use crate::i18n;
+use jiff::SignedDuration;
+use jiff::Timestamp;
+use std::collections::BTreeMap;
+
+/// Resource notifications collected to be sent as one notification per
+/// channel.
+#[derive(Debug, Default)]
+pub struct NotificationBatch {
+    started: Option<Timestamp>,
+    pending: Vec<(Vec<String>, Notification)>,
+}
+
+impl NotificationBatch {
+    /// Adds a notification routed to `channels` (every channel when empty).
+    ///
+    /// The first notification added starts the batch's window.
+    pub fn add(&mut self, channels: Vec<String>, notification: Notification, now: Timestamp) {
+        self.started.get_or_insert(now);
+        self.pending.push((channels, notification));
+    }
+
+    /// Removes and returns the collected notifications once `window` has
+    /// passed since the first was added.
+    pub fn take_due(
+        &mut self,
+        window: SignedDuration,
+        now: Timestamp,
+    ) -> Vec<(Vec<String>, Notification)> {
+        match self.started {
+            Some(started) if now.duration_since(started) >= window => {
+                self.started = None;
+                std::mem::take(&mut self.pending)
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Sorts notifications by the channel they go to, expanding those routed to
+/// every channel to each of `channel_names`.
+#[must_use]
+pub fn group_by_channel(
+    notifications: Vec<(Vec<String>, Notification)>,
+    channel_names: &[String],
+) -> BTreeMap<String, Vec<Notification>> {
+    let mut by_channel: BTreeMap<String, Vec<Notification>> = BTreeMap::new();
+    for (channels, notification) in notifications {
+        let channels = if channels.is_empty() {
+            channel_names
+        } else {
+            channels.as_slice()
+        };
+        for channel in channels {
+            by_channel
+                .entry(channel.clone())
+                .or_default()
+                .push(notification.clone());
+        }
+    }
+    by_channel
+}
+
+/// Combines notifications into one titled with their count and listing each
+/// as "{title}: {body}". A single notification is returned unchanged.
+///
+/// The result is high urgency if any notification is, and names a game only
+/// if they are all about the same one.
+#[must_use]
+pub fn build_batch_notification(mut notifications: Vec<Notification>) -> Option<Notification> {
+    if notifications.len() <= 1 {
+        return notifications.pop();
+    }
+
+    let count = notifications.len();
+    let urgency = if notifications.iter().any(|n| n.urgency == Urgency::High) {
+        Urgency::High
+    } else {
+        Urgency::Normal
+    };
+    let game_id = notifications
+        .first()
+        .and_then(|first| first.game_id)
+        .filter(|&game_id| notifications.iter().all(|n| n.game_id == Some(game_id)));
+    let body = notifications
+        .into_iter()
+        .map(|n| summary_line(&n.title, n.body))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    Some(Notification {
+        game_id,
+        urgency,
+        ..Notification::new(
+            i18n::t_args(
+                "notification_batch_title",
+                &[(
+                    "count",
+                    i18n::Value::from(i64::try_from(count).unwrap_or(i64::MAX)),
+                )],
+            ),
+            body,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use storekeeper_core::GameId;
+
+    fn ensure_init() {
+        #[expect(
+            clippy::let_underscore_must_use,
+            reason = "test setup may run after i18n is already initialized"
+        )]
+        let _ = crate::i18n::init("en");
+    }
+
+    fn resource(game_id: GameId, title: &str, body: &str, urgency: Urgency) -> Notification {
+        Notification {
+            game_id: Some(game_id),
+            urgency,
+            ..Notification::new(title, body)
+        }
+    }
+
+    #[test]
+    fn batch_is_due_once_its_window_passed() {
+        let now = Timestamp::now();
+        let mut batch = NotificationBatch::default();
+        assert!(batch.take_due(SignedDuration::ZERO, now).is_empty());
+
+        batch.add(Vec::new(), Notification::new("a", "1"), now);
+        batch.add(
+            Vec::new(),
+            Notification::new("b", "2"),
+            now + SignedDuration::from_mins(1),
+        );
+        let window = SignedDuration::from_mins(5);
+        assert!(
+            batch
+                .take_due(window, now + SignedDuration::from_mins(4))
+                .is_empty()
+        );
+        assert_eq!(
+            batch
+                .take_due(window, now + SignedDuration::from_mins(5))
+                .len(),
+            2
+        );
+
+        // The next notification starts a new window.
+        batch.add(
+            Vec::new(),
+            Notification::new("c", "3"),
+            now + SignedDuration::from_mins(6),
+        );
+        assert!(
+            batch
+                .take_due(window, now + SignedDuration::from_mins(10))
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn notifications_for_every_channel_join_each_group() {
+        let everywhere = Notification::new("a", "1");
+        let phone_only = Notification::new("b", "2");
+        let grouped = group_by_channel(
+            vec![
+                (Vec::new(), everywhere.clone()),
+                (vec!["phone".to_string()], phone_only.clone()),
+            ],
+            &["os".to_string(), "phone".to_string()],
+        );
+
+        assert_eq!(
+            grouped,
+            BTreeMap::from([
+                ("os".to_string(), vec![everywhere.clone()]),
+                ("phone".to_string(), vec![everywhere, phone_only]),
+            ])
+        );
+    }
+
+    #[test]
+    fn batch_notification_lists_each_resource() {
+        ensure_init();
+        let single = resource(
+            GameId::GenshinImpact,
+            "Genshin Impact - Original Resin",
+            "Full!",
+            Urgency::High,
+        );
+        assert_eq!(
+            build_batch_notification(vec![single.clone()]),
+            Some(single.clone())
+        );
+
+        let batch = build_batch_notification(vec![
+            single,
+            resource(
+                GameId::WutheringWaves,
+                "Wuthering Waves - Waveplates",
+                "200/240",
+                Urgency::Normal,
+            ),
+        ])
+        .expect("two notifications combine into one");
+        assert_eq!(batch.title, "2 resources need attention");
+        assert_eq!(
+            batch.body,
+            "Genshin Impact - Original Resin: Full!\nWuthering Waves - Waveplates: 200/240"
+        );
+        assert_eq!(batch.urgency, Urgency::High);
+        assert_eq!(batch.game_id, None);
+    }
+}
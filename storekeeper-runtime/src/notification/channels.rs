@@ -0,0 +1,216 @@
+//! Notification channels configured in `[notification_channels]`.
+
+use super::email::EmailDigest;
+use super::email::EmailNotifier;
+use super::hook::HookNotifier;
+use super::notifier::Notifier;
+use super::push::GotifyNotifier;
+use super::push::NtfyNotifier;
+use super::webhook::WebhookNotifier;
+use jiff::Zoned;
+use storekeeper_client_core::RetryConfig;
+use storekeeper_core::AppConfig;
+use storekeeper_core::NotificationChannelConfig;
+use storekeeper_core::NotificationChannelSecrets;
+use storekeeper_core::SecretsConfig;
+
+/// Creates a notifier for every configured notification channel.
+///
+/// Channels that are missing their secrets or fail to initialize are logged
+/// and skipped.
+#[must_use]
+pub fn create_notification_channels(
+    config: &AppConfig,
+    secrets: &SecretsConfig,
+) -> Vec<Box<dyn Notifier>> {
+    let mut channels: Vec<Box<dyn Notifier>> = Vec::new();
+
+    for (name, channel) in &config.notification_channels {
+        let channel_secrets = secrets.notification_channels.get(name);
+        let retry = RetryConfig::default();
+        let notifier: anyhow::Result<Box<dyn Notifier>> = match channel {
+            NotificationChannelConfig::Webhook(webhook) => {
+                let Some(url) = channel_secrets.and_then(|s| s.url()) else {
+                    tracing::warn!(
+                        channel = %name,
+                        "Webhook channel has no url in secrets.toml, skipping"
+                    );
+                    continue;
+                };
+                WebhookNotifier::new(name, url, webhook, retry)
+                    .map(|notifier| Box::new(notifier) as Box<dyn Notifier>)
+            }
+            NotificationChannelConfig::Ntfy(ntfy) => {
+                let token = channel_secrets.and_then(|s| s.token());
+                NtfyNotifier::new(name, ntfy, token, retry)
+                    .map(|notifier| Box::new(notifier) as Box<dyn Notifier>)
+            }
+            NotificationChannelConfig::Email(email) => {
+                if !email.alerts {
+                    continue;
+                }
+                let login = channel_secrets.and_then(NotificationChannelSecrets::smtp_login);
+                EmailNotifier::new(name, email, login, retry)
+                    .map(|notifier| Box::new(notifier) as Box<dyn Notifier>)
+            }
+            NotificationChannelConfig::Gotify(gotify) => {
+                let Some(token) = channel_secrets.and_then(|s| s.token()) else {
+                    tracing::warn!(
+                        channel = %name,
+                        "Gotify channel has no token in secrets.toml, skipping"
+                    );
+                    continue;
+                };
+                GotifyNotifier::new(name, gotify, token, retry)
+                    .map(|notifier| Box::new(notifier) as Box<dyn Notifier>)
+            }
+            // Hooks also run on account events, so they are kept apart (see
+            // `create_hooks`).
+            NotificationChannelConfig::Hook(_) => continue,
+        };
+        match notifier {
+            Ok(notifier) => channels.push(notifier),
+            Err(e) => {
+                tracing::warn!(channel = %name, error = %e, "Failed to create notification channel");
+            }
+        }
+    }
+
+    tracing::debug!(count = channels.len(), "Created notification channels");
+    channels
+}
+
+/// Creates a notifier for every hook channel.
+#[must_use]
+pub fn create_hooks(config: &AppConfig) -> Vec<HookNotifier> {
+    config
+        .notification_channels
+        .iter()
+        .filter_map(|(name, channel)| match channel {
+            NotificationChannelConfig::Hook(hook) => Some(HookNotifier::new(name, hook)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Creates the daily digest schedule of every email channel with a
+/// `digest_time`.
+///
+/// Channels that fail to initialize are logged and skipped.
+#[must_use]
+pub fn create_email_digests(config: &AppConfig, secrets: &SecretsConfig) -> Vec<EmailDigest> {
+    let now = Zoned::now();
+    config
+        .notification_channels
+        .iter()
+        .filter_map(|(name, channel)| {
+            let NotificationChannelConfig::Email(email) = channel else {
+                return None;
+            };
+            let time = email.digest_time?;
+            let login = secrets
+                .notification_channels
+                .get(name)
+                .and_then(NotificationChannelSecrets::smtp_login);
+            EmailNotifier::new(name, email, login, RetryConfig::default())
+                .inspect_err(|e| {
+                    tracing::warn!(channel = %name, error = %e, "Failed to create email digest");
+                })
+                .ok()
+                .map(|mailer| EmailDigest::new(mailer, time, &now))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::civil::Time;
+    use storekeeper_core::EmailChannelConfig;
+    use storekeeper_core::GotifyChannelConfig;
+    use storekeeper_core::HookChannelConfig;
+    use storekeeper_core::HookEvent;
+    use storekeeper_core::PushPriority;
+    use storekeeper_core::SmtpSecurity;
+    use storekeeper_core::WebhookChannelConfig;
+
+    #[test]
+    fn webhook_without_url_is_skipped() {
+        let mut config = AppConfig::default();
+        for name in ["phone", "team"] {
+            config.notification_channels.insert(
+                name.to_string(),
+                NotificationChannelConfig::Webhook(WebhookChannelConfig::default()),
+            );
+        }
+        let mut secrets = SecretsConfig::default();
+        secrets.notification_channels.insert(
+            "team".to_string(),
+            NotificationChannelSecrets {
+                url: "https://example.com/hook".to_string(),
+                ..Default::default()
+            },
+        );
+
+        let channels = create_notification_channels(&config, &secrets);
+        let names: Vec<_> = channels.iter().map(|channel| channel.name()).collect();
+        assert_eq!(names, ["team"]);
+    }
+
+    #[test]
+    fn gotify_without_token_is_skipped() {
+        let mut config = AppConfig::default();
+        config.notification_channels.insert(
+            "gotify".to_string(),
+            NotificationChannelConfig::Gotify(GotifyChannelConfig {
+                server: "https://gotify.example.com".to_string(),
+                priority: PushPriority {
+                    full: 8,
+                    approaching: 5,
+                },
+            }),
+        );
+
+        assert!(create_notification_channels(&config, &SecretsConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn digest_only_email_is_not_an_alert_channel() {
+        let mut config = AppConfig::default();
+        config.notification_channels.insert(
+            "email".to_string(),
+            NotificationChannelConfig::Email(EmailChannelConfig {
+                host: "smtp.example.com".to_string(),
+                port: 587,
+                security: SmtpSecurity::StartTls,
+                from: "storekeeper@example.com".to_string(),
+                to: vec!["me@example.com".to_string()],
+                alerts: false,
+                digest_time: Some(Time::constant(8, 0, 0, 0)),
+            }),
+        );
+        let secrets = SecretsConfig::default();
+
+        assert!(create_notification_channels(&config, &secrets).is_empty());
+        assert_eq!(create_email_digests(&config, &secrets).len(), 1);
+    }
+
+    #[test]
+    fn hooks_are_not_alert_channels() {
+        let mut config = AppConfig::default();
+        config.notification_channels.insert(
+            "script".to_string(),
+            NotificationChannelConfig::Hook(HookChannelConfig {
+                command: "/usr/local/bin/on-storekeeper".to_string(),
+                args: Vec::new(),
+                timeout_secs: 10,
+                events: HookEvent::all().to_vec(),
+            }),
+        );
+
+        assert!(create_notification_channels(&config, &SecretsConfig::default()).is_empty());
+        let hooks = create_hooks(&config);
+        let names: Vec<_> = hooks.iter().map(Notifier::name).collect();
+        assert_eq!(names, ["script"]);
+    }
+}
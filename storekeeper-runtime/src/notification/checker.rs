@@ -0,0 +1,101 @@
+//! Single-resource notification formatting and send logic.
+
+use super::message_builder::account_display_name;
+use super::message_builder::build_notification_body;
+use super::message_builder::format_rule_body;
+use super::message_builder::resource_display_name;
+use super::notifier::Notification;
+use super::notifier::NotificationEvent;
+use super::notifier::Urgency;
+use super::resource_extractor::ResourceInfo;
+use crate::i18n;
+use crate::state::AppState;
+use jiff::Timestamp;
+use storekeeper_core::AccountKey;
+use storekeeper_core::HookEvent;
+use storekeeper_core::NotificationRule;
+
+/// Builds the notification of `rule` for a single resource, with the rule's
+/// body template.
+pub(crate) fn build_resource_notification(
+    account: &AccountKey,
+    resource_type: &str,
+    rule: &NotificationRule,
+    info: &ResourceInfo,
+    now: Timestamp,
+) -> Notification {
+    let game_name = account_display_name(account);
+    let resource_name = resource_display_name(resource_type);
+
+    let body = rule.template.as_deref().map_or_else(
+        || build_notification_body(info, now),
+        |template| format_rule_body(template, account, resource_type, info, now),
+    );
+
+    let title = i18n::t_args(
+        "notification_title",
+        &[
+            ("game_name", i18n::Value::from(game_name.as_str())),
+            ("resource_name", i18n::Value::from(resource_name.as_str())),
+        ],
+    );
+
+    let is_full = info.is_complete || info.completion_at <= now;
+    Notification {
+        title,
+        body,
+        game_id: Some(account.game_id),
+        urgency: if is_full {
+            Urgency::High
+        } else {
+            Urgency::Normal
+        },
+        event: Some(NotificationEvent {
+            kind: if is_full {
+                HookEvent::ResourceFull
+            } else {
+                HookEvent::ResourceApproaching
+            },
+            account: account.clone(),
+            resource: Some(resource_type.to_string()),
+            data: serde_json::json!({
+                "rule": rule.id,
+                "current": info.estimated_current(now),
+                "max": info.max,
+                "full_at": info.completion_at,
+                "is_full": is_full,
+            }),
+        }),
+    }
+}
+
+/// Sends the notification of `rule` for a single resource, through the rule's
+/// channels (every channel when empty).
+///
+/// Returns `true` if the notification was sent successfully.
+pub(crate) async fn send_resource_notification(
+    state: &AppState,
+    account: &AccountKey,
+    resource_type: &str,
+    rule: &NotificationRule,
+    info: &ResourceInfo,
+    now: Timestamp,
+) -> bool {
+    let notification = build_resource_notification(account, resource_type, rule, info, now);
+
+    tracing::info!(
+        account = %account,
+        resource = resource_type,
+        rule = rule.id,
+        body = %notification.body,
+        "Sending resource notification"
+    );
+
+    match state.notify_via(&notification, &rule.channels).await {
+        Ok(()) => true,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to send notification");
+            false
+        }
+    }
+}
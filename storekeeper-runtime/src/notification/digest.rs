@@ -0,0 +1,215 @@
+//! Daily digest of every tracked resource and daily reward.
+
+use super::message_builder::account_display_name;
+use super::message_builder::build_notification_body;
+use super::message_builder::resource_display_name;
+use super::notifier::Notification;
+use super::resource_extractor::ResourceInfo;
+use super::resource_extractor::extract_resource_info;
+use crate::i18n;
+use crate::state::AllDailyRewardStatus;
+use crate::state::AllResources;
+use jiff::Timestamp;
+use storekeeper_core::AccountKey;
+
+/// Builds the daily digest: one section per account listing each resource's
+/// projected value and time to full at `now`, followed by today's daily
+/// reward claim status.
+///
+/// Returns `None` if there is nothing to report.
+#[must_use]
+pub fn build_daily_digest(
+    resources: &AllResources,
+    rewards: &AllDailyRewardStatus,
+    now: Timestamp,
+) -> Option<Notification> {
+    let mut accounts: Vec<AccountKey> = resources
+        .games
+        .iter()
+        .chain(rewards.games.iter())
+        .map(|(account, _)| account)
+        .collect();
+    // Group by game, default account first.
+    accounts.sort_by(|a, b| {
+        a.game_id
+            .cmp(&b.game_id)
+            .then_with(|| b.is_default().cmp(&a.is_default()))
+            .then_with(|| a.account.cmp(&b.account))
+    });
+    accounts.dedup();
+
+    let sections: Vec<String> = accounts
+        .iter()
+        .filter_map(|account| {
+            let mut lines: Vec<String> = resources
+                .games
+                .get(account)
+                .and_then(serde_json::Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(|resource| resource_line(resource, now))
+                .collect();
+            lines.extend(rewards.games.get(account).and_then(reward_line));
+            (!lines.is_empty()).then(|| {
+                std::iter::once(account_display_name(account))
+                    .chain(lines.into_iter().map(|line| format!("- {line}")))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            })
+        })
+        .collect();
+
+    (!sections.is_empty()).then(|| {
+        Notification::new(
+            i18n::t("notification_daily_digest_title"),
+            sections.join("\n\n"),
+        )
+    })
+}
+
+/// Formats a resource as "{name}: {status}", projecting it to `now`.
+fn resource_line(resource: &serde_json::Value, now: Timestamp) -> Option<String> {
+    let resource_type = resource.get("type")?.as_str()?;
+    let status = resource_status(resource_type, resource.get("data")?, now)?;
+    Some(summary_line(&resource_display_name(resource_type), status))
+}
+
+/// Formats a line of a summary notification as "{name}: {status}".
+pub(super) fn summary_line(name: &str, status: String) -> String {
+    i18n::t_args(
+        "notification_daily_digest_line",
+        &[
+            ("resource_name", i18n::Value::from(name)),
+            ("status", i18n::Value::from(status)),
+        ],
+    )
+}
+
+/// Describes a cached resource's status projected to `now`.
+pub(super) fn resource_status(
+    resource_type: &str,
+    data: &serde_json::Value,
+    now: Timestamp,
+) -> Option<String> {
+    let info = extract_resource_info(resource_type, data)?;
+    // The cached flag is from fetch time; count resources that have filled
+    // since then as full.
+    let info = ResourceInfo {
+        is_complete: info.is_complete || info.completion_at <= now,
+        ..info
+    };
+    Some(build_notification_body(&info, now))
+}
+
+/// Formats an account's daily reward status, if it has one.
+fn reward_line(status: &serde_json::Value) -> Option<String> {
+    let is_signed = status.pointer("/info/is_signed")?.as_bool()?;
+    Some(i18n::t(if is_signed {
+        "notification_daily_reward_claimed"
+    } else {
+        "notification_daily_reward_unclaimed"
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::SignedDuration;
+    use storekeeper_core::DailyRewardInfo;
+    use storekeeper_core::DailyRewardStatus;
+    use storekeeper_core::GameId;
+    use storekeeper_core::StaminaResource;
+
+    fn ensure_init() {
+        #[expect(
+            clippy::let_underscore_must_use,
+            reason = "test setup may run after i18n is already initialized"
+        )]
+        let _ = crate::i18n::init("en");
+    }
+
+    fn resin(current: u32, full_at: Timestamp) -> serde_json::Value {
+        serde_json::json!([{
+            "type": "resin",
+            "data": StaminaResource::new(current, 200, full_at, 480),
+        }])
+    }
+
+    fn reward(is_signed: bool) -> serde_json::Value {
+        serde_json::to_value(DailyRewardStatus::new(
+            DailyRewardInfo::new(is_signed, 3),
+            None,
+            Vec::new(),
+        ))
+        .expect("should serialize")
+    }
+
+    #[test]
+    fn digest_lists_resources_and_reward_status() {
+        ensure_init();
+        let now = Timestamp::now();
+        let genshin = AccountKey::default_for(GameId::GenshinImpact);
+        let alt = AccountKey::new(GameId::GenshinImpact, "alt");
+        let mut resources = AllResources::default();
+        resources.record(
+            genshin.clone(),
+            resin(200, now - SignedDuration::from_hours(1)),
+            now,
+        );
+        resources.record(
+            alt.clone(),
+            resin(100, now + SignedDuration::from_hours(10)),
+            now,
+        );
+        let mut rewards = AllDailyRewardStatus::default();
+        rewards.games.insert(genshin, reward(true));
+        rewards.games.insert(alt, reward(false));
+
+        let digest = build_daily_digest(&resources, &rewards, now).expect("should build digest");
+
+        assert_eq!(digest.title, "Daily summary");
+        let sections: Vec<_> = digest.body.split("\n\n").collect();
+        assert_eq!(
+            sections.first().copied(),
+            Some("Genshin Impact\n- Original Resin: Full!\n- Daily reward: claimed")
+        );
+        let alt_section = sections.get(1).expect("alt account section");
+        assert!(
+            alt_section.starts_with("Genshin Impact (alt)\n- Original Resin: 125/200"),
+            "{alt_section}"
+        );
+        assert!(
+            alt_section.ends_with("\n- Daily reward: not claimed yet"),
+            "{alt_section}"
+        );
+    }
+
+    #[test]
+    fn stale_full_flag_is_projected() {
+        ensure_init();
+        let now = Timestamp::now();
+        let mut resources = AllResources::default();
+        resources.record(
+            AccountKey::default_for(GameId::GenshinImpact),
+            resin(150, now - SignedDuration::from_mins(1)),
+            now - SignedDuration::from_hours(7),
+        );
+
+        let digest = build_daily_digest(&resources, &AllDailyRewardStatus::default(), now)
+            .expect("should build digest");
+
+        assert_eq!(digest.body, "Genshin Impact\n- Original Resin: Full!");
+    }
+
+    #[test]
+    fn empty_digest_is_skipped() {
+        assert!(
+            build_daily_digest(
+                &AllResources::default(),
+                &AllDailyRewardStatus::default(),
+                Timestamp::now()
+            )
+            .is_none()
+        );
+    }
+}
@@ -0,0 +1,332 @@
+//! Email notification channel (SMTP) and its daily digest schedule.
+
+use super::digest::build_daily_digest;
+use super::notifier::BoxFuture;
+use super::notifier::Notification;
+use super::notifier::Notifier;
+use crate::state::AppState;
+use jiff::Timestamp;
+use jiff::Zoned;
+use jiff::civil::Date;
+use jiff::civil::Time;
+use jiff::tz::TimeZone;
+use lettre::AsyncSmtpTransport;
+use lettre::AsyncTransport;
+use lettre::Message;
+use lettre::Tokio1Executor;
+use lettre::message::Mailbox;
+use lettre::message::header::ContentType;
+use lettre::transport::smtp::authentication::Credentials;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use storekeeper_client_core::RetryConfig;
+use storekeeper_client_core::retry_with_backoff;
+use storekeeper_core::EmailChannelConfig;
+use storekeeper_core::SmtpSecurity;
+
+/// Sends notifications as plain-text emails over SMTP.
+pub struct EmailNotifier {
+    name: String,
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+    to: Vec<Mailbox>,
+    retry: RetryConfig,
+}
+
+impl EmailNotifier {
+    /// Creates an email channel named `name`, logging in with `login`
+    /// (username, password) if the server requires it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an address is invalid or the TLS settings for the
+    /// host cannot be built.
+    pub fn new(
+        name: impl Into<String>,
+        config: &EmailChannelConfig,
+        login: Option<(&str, &str)>,
+        retry: RetryConfig,
+    ) -> anyhow::Result<Self> {
+        let from = config.from.parse()?;
+        let to = config
+            .to
+            .iter()
+            .map(|address| address.parse())
+            .collect::<Result<Vec<Mailbox>, _>>()?;
+        anyhow::ensure!(!to.is_empty(), "no recipients configured");
+
+        let builder = match config.security {
+            SmtpSecurity::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)?
+            }
+            SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)?,
+            SmtpSecurity::None => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)
+            }
+        }
+        .port(config.port);
+        let builder = match login {
+            Some((username, password)) => {
+                builder.credentials(Credentials::new(username.to_string(), password.to_string()))
+            }
+            None => builder,
+        };
+
+        Ok(Self {
+            name: name.into(),
+            transport: builder.build(),
+            from,
+            to,
+            retry,
+        })
+    }
+
+    /// Emails `body` with the given subject to every recipient.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message cannot be built or the server rejects
+    /// it after the retries.
+    pub async fn send_mail(&self, subject: &str, body: &str) -> anyhow::Result<()> {
+        let message = self
+            .to
+            .iter()
+            .fold(Message::builder().from(self.from.clone()), |builder, to| {
+                builder.to(to.clone())
+            })
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN)
+            .body(body.to_string())?;
+        retry_with_backoff(
+            &self.retry,
+            || self.transport.send(message.clone()),
+            |e: &lettre::transport::smtp::Error| e.is_transient() || e.is_timeout(),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+impl Notifier for EmailNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(self.send_mail(&notification.title, &notification.body))
+    }
+}
+
+/// Daily digest schedule of an email channel.
+pub struct EmailDigest {
+    mailer: EmailNotifier,
+    time: Time,
+    /// Local date the digest was last sent (or skipped) on.
+    last_sent: Mutex<Option<Date>>,
+}
+
+impl EmailDigest {
+    /// Creates a digest sent by `mailer` every day at the local `time`.
+    ///
+    /// If `time` has already passed today, the first digest goes out
+    /// tomorrow, so restarting or reloading the config does not resend it.
+    #[must_use]
+    pub fn new(mailer: EmailNotifier, time: Time, now: &Zoned) -> Self {
+        let sent_today = now.time() >= time;
+        Self {
+            mailer,
+            time,
+            last_sent: Mutex::new(sent_today.then(|| now.date())),
+        }
+    }
+
+    /// Returns whether the digest is due at `now`, marking it as sent today.
+    fn take_due(&self, now: &Zoned) -> bool {
+        let today = now.date();
+        let mut last_sent = self
+            .last_sent
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if now.time() < self.time || *last_sent == Some(today) {
+            return false;
+        }
+        *last_sent = Some(today);
+        true
+    }
+}
+
+/// Emails the daily digest through every email channel whose digest is due.
+pub(crate) async fn send_digests_if_due(state: &AppState) {
+    let digests = {
+        let inner = state.inner.read().await;
+        Arc::clone(&inner.email_digests)
+    };
+    let now = Timestamp::now().to_zoned(TimeZone::system());
+    let due: Vec<_> = digests
+        .iter()
+        .filter(|digest| digest.take_due(&now))
+        .collect();
+    if due.is_empty() {
+        return;
+    }
+
+    let resources = state.get_resources().await;
+    let rewards = state.get_daily_reward_status().await;
+    let Some(digest) = build_daily_digest(&resources, &rewards, now.timestamp()) else {
+        tracing::debug!("Skipping daily digest - nothing tracked");
+        return;
+    };
+    for email in due {
+        tracing::info!(channel = %email.mailer.name, "Sending daily digest");
+        if let Err(e) = email.mailer.send_mail(&digest.title, &digest.body).await {
+            tracing::warn!(channel = %email.mailer.name, error = %e, "Failed to send daily digest");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+    use tokio::io::AsyncBufReadExt;
+    use tokio::io::AsyncWriteExt;
+    use tokio::io::BufReader;
+    use tokio::net::TcpListener;
+
+    /// Minimal SMTP server: accepts every command and records each message's
+    /// envelope recipients and `DATA`.
+    async fn spawn_smtp() -> (u16, Arc<Mutex<Vec<(Vec<String>, String)>>>) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .expect("should bind");
+        let port = listener
+            .local_addr()
+            .expect("should have an address")
+            .port();
+        let sink = Arc::clone(&received);
+        tokio::spawn(async move {
+            loop {
+                let Ok((stream, _)) = listener.accept().await else {
+                    return;
+                };
+                let sink = Arc::clone(&sink);
+                tokio::spawn(async move {
+                    let (read, mut write) = stream.into_split();
+                    let mut lines = BufReader::new(read).lines();
+                    write
+                        .write_all(b"220 localhost ESMTP\r\n")
+                        .await
+                        .expect("write");
+                    let mut recipients = Vec::new();
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        let command = line.to_ascii_uppercase();
+                        let reply: &[u8] = if command.starts_with("EHLO") {
+                            b"250 localhost\r\n"
+                        } else if let Some(("RCPT TO", recipient)) = line.split_once(':') {
+                            recipients.push(recipient.to_string());
+                            b"250 OK\r\n"
+                        } else if command == "DATA" {
+                            write.write_all(b"354 Go ahead\r\n").await.expect("write");
+                            let mut data = Vec::new();
+                            while let Ok(Some(line)) = lines.next_line().await {
+                                if line == "." {
+                                    break;
+                                }
+                                data.push(line);
+                            }
+                            sink.lock()
+                                .expect("lock")
+                                .push((std::mem::take(&mut recipients), data.join("\n")));
+                            b"250 Queued\r\n"
+                        } else if command == "QUIT" {
+                            write.write_all(b"221 Bye\r\n").await.expect("write");
+                            return;
+                        } else {
+                            b"250 OK\r\n"
+                        };
+                        write.write_all(reply).await.expect("write");
+                    }
+                });
+            }
+        });
+        (port, received)
+    }
+
+    fn config(port: u16) -> EmailChannelConfig {
+        EmailChannelConfig {
+            host: "127.0.0.1".to_string(),
+            port,
+            security: SmtpSecurity::None,
+            from: "Storekeeper <storekeeper@example.com>".to_string(),
+            to: vec!["me@example.com".to_string(), "you@example.com".to_string()],
+            alerts: true,
+            digest_time: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn alerts_are_emailed_to_every_recipient() {
+        let (port, received) = spawn_smtp().await;
+        let notifier = EmailNotifier::new("email", &config(port), None, RetryConfig::default())
+            .expect("should build notifier");
+
+        notifier
+            .send(&Notification::new(
+                "Genshin Impact - Original Resin",
+                "Full!",
+            ))
+            .await
+            .expect("should deliver");
+
+        let received = received.lock().expect("lock");
+        let (recipients, data) = received.first().expect("one message");
+        assert_eq!(recipients, &["<me@example.com>", "<you@example.com>"]);
+        assert!(
+            data.contains("Subject: Genshin Impact - Original Resin"),
+            "{data}"
+        );
+        assert!(data.ends_with("\nFull!"), "{data}");
+    }
+
+    #[test]
+    fn invalid_address_is_rejected() {
+        let mut config = config(25);
+        config.to = vec!["not an address".to_string()];
+
+        assert!(EmailNotifier::new("email", &config, None, RetryConfig::default()).is_err());
+    }
+
+    #[test]
+    fn digest_is_due_once_per_day_after_its_time() {
+        let notifier = EmailNotifier::new("email", &config(25), None, RetryConfig::default())
+            .expect("should build notifier");
+        let at = |day: i8, hour: i8| {
+            jiff::civil::date(2026, 3, day)
+                .at(hour, 0, 0, 0)
+                .to_zoned(TimeZone::UTC)
+                .expect("valid time")
+        };
+        let digest = EmailDigest::new(notifier, Time::constant(8, 0, 0, 0), &at(1, 7));
+
+        assert!(!digest.take_due(&at(1, 7)));
+        assert!(digest.take_due(&at(1, 8)));
+        assert!(!digest.take_due(&at(1, 20)));
+        assert!(!digest.take_due(&at(2, 7)));
+        assert!(digest.take_due(&at(2, 9)));
+    }
+
+    #[test]
+    fn digest_created_after_its_time_waits_for_tomorrow() {
+        let notifier = EmailNotifier::new("email", &config(25), None, RetryConfig::default())
+            .expect("should build notifier");
+        let now = jiff::civil::date(2026, 3, 1)
+            .at(9, 0, 0, 0)
+            .to_zoned(TimeZone::UTC)
+            .expect("valid time");
+        let digest = EmailDigest::new(notifier, Time::constant(8, 0, 0, 0), &now);
+
+        assert!(!digest.take_due(&now));
+    }
+}
@@ -0,0 +1,237 @@
+//! Hook channel: runs a user-configured executable for each event.
+
+use super::notifier::BoxFuture;
+use super::notifier::Notification;
+use super::notifier::NotificationEvent;
+use super::notifier::Notifier;
+use anyhow::Context;
+use std::io;
+use std::process::Stdio;
+use std::time::Duration;
+use storekeeper_core::HookChannelConfig;
+use storekeeper_core::HookEvent;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+/// Runs an executable with the event as JSON on stdin.
+///
+/// Only notifications describing one of the configured events run the
+/// executable; the rest are skipped as delivered.
+pub struct HookNotifier {
+    name: String,
+    command: String,
+    args: Vec<String>,
+    timeout: Duration,
+    events: Vec<HookEvent>,
+}
+
+impl HookNotifier {
+    /// Creates a hook channel named `name`.
+    #[must_use]
+    pub fn new(name: impl Into<String>, config: &HookChannelConfig) -> Self {
+        Self {
+            name: name.into(),
+            command: config.command.clone(),
+            args: config.args.clone(),
+            timeout: Duration::from_secs(config.timeout_secs),
+            events: config.events.clone(),
+        }
+    }
+
+    /// Runs the executable for `event`, logging its output.
+    async fn run(
+        &self,
+        event: &NotificationEvent,
+        notification: &Notification,
+    ) -> anyhow::Result<()> {
+        let input = serde_json::to_vec(&payload(event, notification))?;
+        let mut child = Command::new(&self.command)
+            .args(&self.args)
+            .envs(env(event, notification))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("failed to run {}", self.command))?;
+
+        tracing::debug!(channel = %self.name, event = event.kind.as_str(), "Running hook");
+        // Dropping the child on timeout kills it.
+        let output = tokio::time::timeout(self.timeout, async move {
+            if let Some(mut stdin) = child.stdin.take() {
+                match stdin.write_all(&input).await {
+                    // The executable does not have to read its input.
+                    Err(e) if e.kind() != io::ErrorKind::BrokenPipe => return Err(e),
+                    _ => {}
+                }
+            }
+            child.wait_with_output().await
+        })
+        .await
+        .with_context(|| format!("timed out after {}s", self.timeout.as_secs()))??;
+
+        for (stream, bytes) in [("stdout", &output.stdout), ("stderr", &output.stderr)] {
+            let text = String::from_utf8_lossy(bytes);
+            let text = text.trim();
+            if !text.is_empty() {
+                tracing::info!(channel = %self.name, stream, output = %text, "Hook output");
+            }
+        }
+        anyhow::ensure!(output.status.success(), "exited with {}", output.status);
+        Ok(())
+    }
+}
+
+impl Notifier for HookNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            match &notification.event {
+                Some(event) if self.events.contains(&event.kind) => {
+                    self.run(event, notification).await
+                }
+                _ => Ok(()),
+            }
+        })
+    }
+
+    fn handles(&self, notification: &Notification) -> bool {
+        notification
+            .event
+            .as_ref()
+            .is_some_and(|event| self.events.contains(&event.kind))
+    }
+}
+
+/// Builds the JSON written to the executable's stdin.
+fn payload(event: &NotificationEvent, notification: &Notification) -> serde_json::Value {
+    serde_json::json!({
+        "event": event.kind,
+        "game": event.account.game_id.config_key(),
+        "account": event.account.account,
+        "resource": event.resource,
+        "title": notification.title,
+        "body": notification.body,
+        "data": event.data,
+    })
+}
+
+/// Builds the `STOREKEEPER_*` environment variables summarizing the event.
+fn env<'a>(
+    event: &'a NotificationEvent,
+    notification: &'a Notification,
+) -> impl Iterator<Item = (&'static str, &'a str)> {
+    [
+        ("STOREKEEPER_EVENT", event.kind.as_str()),
+        ("STOREKEEPER_GAME", event.account.game_id.config_key()),
+        ("STOREKEEPER_ACCOUNT", event.account.account.as_str()),
+        ("STOREKEEPER_TITLE", notification.title.as_str()),
+        ("STOREKEEPER_BODY", notification.body.as_str()),
+    ]
+    .into_iter()
+    .chain(
+        event
+            .resource
+            .as_deref()
+            .map(|resource| ("STOREKEEPER_RESOURCE", resource)),
+    )
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use storekeeper_core::AccountKey;
+    use storekeeper_core::GameId;
+
+    fn hook(script: &str, timeout_secs: u64, events: Vec<HookEvent>) -> HookNotifier {
+        HookNotifier::new(
+            "script",
+            &HookChannelConfig {
+                command: "sh".to_string(),
+                args: vec!["-c".to_string(), script.to_string()],
+                timeout_secs,
+                events,
+            },
+        )
+    }
+
+    fn resin_full() -> Notification {
+        Notification {
+            event: Some(NotificationEvent {
+                kind: HookEvent::ResourceFull,
+                account: AccountKey::default_for(GameId::GenshinImpact),
+                resource: Some("resin".to_string()),
+                data: serde_json::json!({"current": 200, "max": 200}),
+            }),
+            ..Notification::new("Genshin Impact - Original Resin", "Full!")
+        }
+    }
+
+    #[tokio::test]
+    async fn hook_gets_payload_on_stdin_and_summary_in_env() {
+        let out = std::env::temp_dir().join(format!("storekeeper-hook-{}", std::process::id()));
+        let script = format!(
+            r#"cat > "{0}.json"; echo "$STOREKEEPER_EVENT $STOREKEEPER_GAME $STOREKEEPER_RESOURCE" > "{0}.env""#,
+            out.display()
+        );
+
+        hook(&script, 10, HookEvent::all().to_vec())
+            .send(&resin_full())
+            .await
+            .expect("hook should succeed");
+
+        let input = std::fs::read_to_string(out.with_extension("json")).expect("stdin written");
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&input).expect("valid json"),
+            serde_json::json!({
+                "event": "resource_full",
+                "game": "genshin_impact",
+                "account": "default",
+                "resource": "resin",
+                "title": "Genshin Impact - Original Resin",
+                "body": "Full!",
+                "data": {"current": 200, "max": 200},
+            })
+        );
+        assert_eq!(
+            std::fs::read_to_string(out.with_extension("env")).expect("env written"),
+            "resource_full genshin_impact resin\n"
+        );
+        for extension in ["json", "env"] {
+            if let Err(err) = std::fs::remove_file(out.with_extension(extension)) {
+                eprintln!("failed to clean up hook output: {err}");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn unsubscribed_events_do_not_run() {
+        assert!(!hook("exit 1", 10, vec![HookEvent::AuthExpired]).handles(&resin_full()));
+        hook("exit 1", 10, vec![HookEvent::AuthExpired])
+            .send(&resin_full())
+            .await
+            .expect("skipped events succeed");
+        hook("exit 1", 10, HookEvent::all().to_vec())
+            .send(&Notification::new("Daily summary", "..."))
+            .await
+            .expect("notifications without an event are skipped");
+    }
+
+    #[tokio::test]
+    async fn failing_or_slow_hooks_are_errors() {
+        let err = hook("exit 3", 10, HookEvent::all().to_vec())
+            .send(&resin_full())
+            .await
+            .expect_err("non-zero exit fails");
+        assert!(err.to_string().contains("exited with"), "{err}");
+
+        let err = hook("sleep 5", 0, HookEvent::all().to_vec())
+            .send(&resin_full())
+            .await
+            .expect_err("slow hook times out");
+        assert!(err.to_string().contains("timed out"), "{err}");
+    }
+}
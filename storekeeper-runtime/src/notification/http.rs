@@ -0,0 +1,74 @@
+//! Shared request handling for HTTP notification channels.
+
+use reqwest::RequestBuilder;
+use reqwest::StatusCode;
+use storekeeper_client_core::RetryConfig;
+use storekeeper_client_core::is_transient_reqwest_error;
+use storekeeper_client_core::retry_with_backoff;
+
+/// Sends the request built by `request`, retrying transient failures.
+///
+/// `request` is called once per attempt since a request cannot be resent.
+///
+/// # Errors
+///
+/// Returns an error if the request still fails after the retries, or fails
+/// with a non-retryable error.
+pub(super) async fn send_with_retry(
+    retry: &RetryConfig,
+    request: impl Fn() -> RequestBuilder,
+) -> anyhow::Result<()> {
+    retry_with_backoff(retry, || send(request()), PostError::is_retryable).await?;
+    Ok(())
+}
+
+async fn send(request: RequestBuilder) -> Result<(), PostError> {
+    let response = request.send().await.map_err(PostError::Request)?;
+    let status = response.status();
+    if status.is_success() {
+        Ok(())
+    } else {
+        Err(PostError::Status(status))
+    }
+}
+
+/// Failure of a single notification request.
+#[derive(Debug)]
+enum PostError {
+    /// The request could not be sent.
+    Request(reqwest::Error),
+    /// The endpoint answered with a non-success status.
+    Status(StatusCode),
+}
+
+impl PostError {
+    /// Timeouts, connection failures, rate limiting and server errors are
+    /// worth retrying; other client errors (bad URL, bad payload, bad token)
+    /// are not.
+    fn is_retryable(&self) -> bool {
+        match self {
+            Self::Request(e) => is_transient_reqwest_error(e),
+            Self::Status(status) => {
+                *status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for PostError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Request(e) => write!(f, "notification request failed: {e}"),
+            Self::Status(status) => write!(f, "notification endpoint returned {status}"),
+        }
+    }
+}
+
+impl std::error::Error for PostError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Request(e) => Some(e),
+            Self::Status(_) => None,
+        }
+    }
+}
@@ -4,10 +4,12 @@ use super::resource_extractor::ResourceInfo;
 use crate::i18n;
 use jiff::Timestamp;
 use jiff::tz::TimeZone;
+use storekeeper_core::AccountKey;
 use storekeeper_core::GameId;
 
 /// Maps resource type tags to localized display names via i18n lookup.
-pub(crate) fn resource_display_name(resource_type: &str) -> String {
+#[must_use]
+pub fn resource_display_name(resource_type: &str) -> String {
     let key = format!("resource_{resource_type}");
     let result = i18n::t(&key);
     if result == key {
@@ -18,11 +20,31 @@ pub(crate) fn resource_display_name(resource_type: &str) -> String {
 }
 
 /// Returns the localized game display name via i18n lookup.
-pub(crate) fn game_display_name(game_id: GameId) -> String {
+#[must_use]
+pub fn game_display_name(game_id: GameId) -> String {
     let key = format!("game_{}_name", game_id.short_id());
     i18n::t(&key)
 }
 
+/// Returns the localized display name for an account of a game.
+///
+/// The default account uses the plain game name; named accounts append the
+/// account name so notifications for several accounts can be told apart.
+#[must_use]
+pub fn account_display_name(key: &AccountKey) -> String {
+    let game_name = game_display_name(key.game_id);
+    if key.is_default() {
+        return game_name;
+    }
+    i18n::t_args(
+        "notification_account_game_name",
+        &[
+            ("game_name", i18n::Value::from(game_name)),
+            ("account", i18n::Value::from(key.account.as_str())),
+        ],
+    )
+}
+
 /// Builds the notification body text for a resource.
 ///
 /// Differentiates between stamina resources (have `max`) and
@@ -32,7 +54,8 @@ pub(crate) fn game_display_name(game_id: GameId) -> String {
 ///
 /// The resource name is intentionally omitted — the notification title already
 /// contains both the game name and resource name.
-pub(crate) fn build_notification_body(info: &ResourceInfo, now: Timestamp) -> String {
+#[must_use]
+pub fn build_notification_body(info: &ResourceInfo, now: Timestamp) -> String {
     let is_stamina = info.max.is_some();
 
     if is_stamina {
@@ -40,21 +63,12 @@ pub(crate) fn build_notification_body(info: &ResourceInfo, now: Timestamp) -> St
             return i18n::t("notification_stamina_full");
         }
 
-        let current = info
-            .estimated_current(now)
-            .map_or_else(|| "?".to_string(), |v| v.to_string());
-        let max = info.max.map_or_else(|| "?".to_string(), |v| v.to_string());
-        let mins_remaining = info.completion_at.duration_since(now).as_mins();
-        let duration = i18n::format_duration(mins_remaining);
-        let completion_local = info.completion_at.to_zoned(TimeZone::system());
-        let now_local = now.to_zoned(TimeZone::system());
-        let local_time = i18n::format_time(&completion_local, &now_local);
-
+        let (duration, local_time) = time_remaining(info, now);
         i18n::t_args(
             "notification_stamina_progress",
             &[
-                ("current", i18n::Value::from(current)),
-                ("max", i18n::Value::from(max)),
+                ("current", i18n::Value::from(estimated_current(info, now))),
+                ("max", i18n::Value::from(max_value(info))),
                 ("duration", i18n::Value::from(duration)),
                 ("local_time", i18n::Value::from(local_time)),
             ],
@@ -64,12 +78,7 @@ pub(crate) fn build_notification_body(info: &ResourceInfo, now: Timestamp) -> St
             return i18n::t("notification_cooldown_complete");
         }
 
-        let mins_remaining = info.completion_at.duration_since(now).as_mins();
-        let duration = i18n::format_duration(mins_remaining);
-        let completion_local = info.completion_at.to_zoned(TimeZone::system());
-        let now_local = now.to_zoned(TimeZone::system());
-        let local_time = i18n::format_time(&completion_local, &now_local);
-
+        let (duration, local_time) = time_remaining(info, now);
         i18n::t_args(
             "notification_cooldown_remaining",
             &[
@@ -80,6 +89,62 @@ pub(crate) fn build_notification_body(info: &ResourceInfo, now: Timestamp) -> St
     }
 }
 
+/// Formats a notification rule's body `template` for a resource.
+///
+/// Besides `{body}` (the default body from [`build_notification_body`]), the
+/// template can use `{game_name}`, `{resource_name}`, `{current}`, `{max}`,
+/// `{duration}` and `{local_time}`; values unknown for the resource are `?`.
+#[must_use]
+pub fn format_rule_body(
+    template: &str,
+    account: &AccountKey,
+    resource_type: &str,
+    info: &ResourceInfo,
+    now: Timestamp,
+) -> String {
+    let (duration, local_time) = time_remaining(info, now);
+    i18n::format_template(
+        template,
+        &[
+            (
+                "game_name",
+                i18n::Value::from(account_display_name(account)),
+            ),
+            (
+                "resource_name",
+                i18n::Value::from(resource_display_name(resource_type)),
+            ),
+            ("current", i18n::Value::from(estimated_current(info, now))),
+            ("max", i18n::Value::from(max_value(info))),
+            ("duration", i18n::Value::from(duration)),
+            ("local_time", i18n::Value::from(local_time)),
+            (
+                "body",
+                i18n::Value::from(build_notification_body(info, now)),
+            ),
+        ],
+    )
+}
+
+fn estimated_current(info: &ResourceInfo, now: Timestamp) -> String {
+    info.estimated_current(now)
+        .map_or_else(|| "?".to_string(), |v| v.to_string())
+}
+
+fn max_value(info: &ResourceInfo) -> String {
+    info.max.map_or_else(|| "?".to_string(), |v| v.to_string())
+}
+
+/// Returns the formatted time until completion and the local clock time of
+/// completion.
+fn time_remaining(info: &ResourceInfo, now: Timestamp) -> (String, String) {
+    let mins_remaining = info.completion_at.duration_since(now).as_mins();
+    let duration = i18n::format_duration(mins_remaining);
+    let completion_local = info.completion_at.to_zoned(TimeZone::system());
+    let now_local = now.to_zoned(TimeZone::system());
+    (duration, i18n::format_time(&completion_local, &now_local))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -117,6 +182,19 @@ mod tests {
         assert_eq!(resource_display_name("waveplates"), "Waveplates");
     }
 
+    #[test]
+    fn test_account_display_name() {
+        ensure_init();
+        assert_eq!(
+            account_display_name(&AccountKey::default_for(GameId::GenshinImpact)),
+            "Genshin Impact"
+        );
+        assert_eq!(
+            account_display_name(&AccountKey::new(GameId::GenshinImpact, "alt")),
+            "Genshin Impact (alt)"
+        );
+    }
+
     #[test]
     fn test_display_name_unknown_fallback() {
         ensure_init();
@@ -159,6 +237,30 @@ mod tests {
         assert!(body.contains("/160"));
     }
 
+    #[test]
+    fn test_rule_template_fills_placeholders() {
+        ensure_init();
+        let now = Timestamp::now();
+        let info = ResourceInfo {
+            completion_at: now,
+            is_complete: true,
+            current: Some(160),
+            max: Some(160),
+            regen_rate_seconds: Some(480),
+        };
+        let body = format_rule_body(
+            "{game_name}: {resource_name} at {current}/{max}. {body}",
+            &AccountKey::new(GameId::GenshinImpact, "alt"),
+            "resin",
+            &info,
+            now,
+        );
+        assert_eq!(
+            body,
+            "Genshin Impact (alt): Original Resin at 160/160. Full!"
+        );
+    }
+
     // =========================================================================
     // body text tests — cooldown resources
     // =========================================================================
@@ -0,0 +1,353 @@
+//! Background notification checker for resource completion alerts.
+//!
+//! Runs on a 60-second timer, reads cached resources from state, and sends
+//! notifications through the configured [`Notifier`]s when resources are
+//! approaching full or have been full. Besides the frontend's built-in
+//! notifier, notifications go to the channels configured in
+//! `[notification_channels]` (see [`create_notification_channels`]), routed per
+//! resource by `channels`. Email channels can also send a daily digest of
+//! every resource (see [`create_email_digests`]). Hook channels run an
+//! executable on resource notifications and on account events such as daily
+//! reward claims and rejected logins (see [`create_hooks`]).
+//!
+//! During quiet hours (global `[[quiet_hours]]`, or a game's own), resource
+//! notifications that are not `critical` are deferred and sent as one summary
+//! once the game's quiet hours end (see [`DeferredNotifications`]). With
+//! `[notification_batch]` enabled, the others are grouped into one
+//! notification per channel (see [`NotificationBatch`]).
+//!
+//! Cooldowns and a log of recently sent notifications are persisted next to
+//! the config (see [`PersistedNotifications`]), so a restart does not repeat
+//! reminders that were already sent.
+
+mod account_events;
+mod alert_log;
+mod batch;
+mod channels;
+mod checker;
+mod digest;
+mod email;
+mod hook;
+mod http;
+mod message_builder;
+mod notifier;
+mod persisted;
+mod push;
+mod quiet_hours;
+mod resource_extractor;
+mod tracker;
+mod webhook;
+
+// Re-export public items so they remain accessible at `notification::*`.
+use crate::overcap;
+use crate::state::AllResources;
+use crate::state::AppState;
+pub use account_events::auth_expired_notification;
+pub use account_events::claim_notification;
+pub use alert_log::ALERT_LOG_CAPACITY;
+pub use alert_log::AlertLog;
+pub use alert_log::AlertLogEntry;
+pub use batch::NotificationBatch;
+use batch::build_batch_notification;
+use batch::group_by_channel;
+pub use channels::create_email_digests;
+pub use channels::create_hooks;
+pub use channels::create_notification_channels;
+pub use digest::build_daily_digest;
+pub use email::EmailDigest;
+pub use email::EmailNotifier;
+pub use hook::HookNotifier;
+use jiff::SignedDuration;
+use jiff::Timestamp;
+use jiff::tz::TimeZone;
+pub use message_builder::account_display_name;
+pub use message_builder::build_notification_body;
+pub use message_builder::game_display_name;
+pub use message_builder::resource_display_name;
+pub use notifier::Delivery;
+pub use notifier::LogNotifier;
+pub use notifier::Notification;
+pub use notifier::NotificationEvent;
+pub use notifier::Notifier;
+pub use notifier::Urgency;
+pub use notifier::deliver;
+pub use notifier::deliver_each;
+pub use notifier::delivery_result;
+pub use persisted::PersistedNotifications;
+pub use push::GotifyNotifier;
+pub use push::NtfyNotifier;
+pub use quiet_hours::DeferredEntry;
+pub use quiet_hours::DeferredNotifications;
+pub use quiet_hours::build_quiet_hours_summaries;
+pub use resource_extractor::ResourceInfo;
+pub use resource_extractor::extract_resource_info;
+use std::collections::HashSet;
+use storekeeper_core::AccountKey;
+use storekeeper_core::AppConfig;
+use storekeeper_core::GameId;
+use storekeeper_core::NotificationBatchConfig;
+use storekeeper_core::config::GamesConfig;
+use storekeeper_core::config::ResourceNotificationConfig;
+use tokio_util::sync::CancellationToken;
+pub use tracker::CooldownEntry;
+pub use tracker::NotificationTracker;
+use tracker::NotifyAction;
+use tracker::NotifyKey;
+pub use tracker::window_start;
+pub use webhook::WebhookNotifier;
+
+/// Resolves a resource JSON object into its notification config and extracted
+/// timing info, returning `None` if the resource is missing fields, has no
+/// config, or notifications are disabled.
+pub(crate) fn resolve_notifiable_resource<'a>(
+    resource_obj: &'a serde_json::Value,
+    games_config: &'a GamesConfig,
+    account: &AccountKey,
+) -> Option<(&'a str, &'a ResourceNotificationConfig, ResourceInfo)> {
+    let type_tag = resource_obj
+        .get("type")
+        .and_then(serde_json::Value::as_str)?;
+    let config = games_config.notification_config(account, type_tag)?;
+    if !config.enabled {
+        return None;
+    }
+    let data = resource_obj.get("data")?;
+    let resource_info = extract_resource_info(type_tag, data)?;
+    Some((type_tag, config, resource_info))
+}
+
+/// Resolves every cached resource that has notifications enabled.
+fn notifiable_resources<'a>(
+    resources: &'a AllResources,
+    games_config: &'a GamesConfig,
+) -> Vec<(
+    AccountKey,
+    &'a str,
+    &'a ResourceNotificationConfig,
+    ResourceInfo,
+)> {
+    let mut candidates = Vec::new();
+    for (account, resources_json) in resources.games.iter() {
+        if !games_config.has_notification_configs(&account) {
+            continue;
+        }
+        let Some(resource_array) = resources_json.as_array() else {
+            continue;
+        };
+        for resource_obj in resource_array {
+            let Some((type_tag, config, resource_info)) =
+                resolve_notifiable_resource(resource_obj, games_config, &account)
+            else {
+                continue;
+            };
+            candidates.push((account.clone(), type_tag, config, resource_info));
+        }
+    }
+    candidates
+}
+
+/// Runs the background notification checker until cancelled.
+///
+/// Checks once on startup, then every 60 seconds, comparing cached resources
+/// against per-account notification thresholds. Does not make API calls — reads
+/// state only.
+pub async fn run_notification_checker(state: AppState, cancel_token: CancellationToken) {
+    tracing::info!("Starting notification checker task");
+
+    // Check right away so reminders based on snapshot data loaded at
+    // startup don't wait for the first poll (which may fail offline).
+    check_and_notify(&state).await;
+
+    loop {
+        tokio::select! {
+            () = cancel_token.cancelled() => {
+                tracing::info!("Notification checker cancelled");
+                break;
+            }
+            () = tokio::time::sleep(std::time::Duration::from_mins(1)) => {
+                check_and_notify(&state).await;
+                overcap::send_weekly_digest_if_due(&state).await;
+                email::send_digests_if_due(&state).await;
+            }
+        }
+    }
+}
+
+/// Checks all cached resources against notification thresholds.
+///
+/// Notifications of games in quiet hours are deferred unless `critical`, and
+/// those of games whose quiet hours ended are sent as a summary. With
+/// `[notification_batch]` enabled, the rest are collected and sent grouped per
+/// channel once the batch window passes.
+pub(crate) async fn check_and_notify(state: &AppState) {
+    let now = Timestamp::now();
+    let resources = state.get_resources().await;
+
+    // Snapshot configs so the checker loop does not hold state locks while
+    // formatting/sending.
+    let (games_config, quiet_games, batch_config) = {
+        let inner = state.inner.read().await;
+        (
+            inner.config.games.clone(),
+            quiet_games(&inner.config, now),
+            inner.config.notification_batch.clone(),
+        )
+    };
+
+    // Step 1: Resolve all notifiable resources (no lock needed).
+    let candidates = notifiable_resources(&resources, &games_config);
+
+    // Step 2: Batch should_notify checks for every rule (single write lock).
+    let mut to_notify = Vec::new();
+    let cooldowns_cleared = {
+        let mut inner = state.inner.write().await;
+        let before = inner.notification_tracker.len();
+        for (i, (account, type_tag, config, resource_info)) in candidates.iter().enumerate() {
+            for rule in config.effective_rules().iter() {
+                if let NotifyAction::Notify(key) = inner.notification_tracker.should_notify(
+                    account,
+                    type_tag,
+                    rule,
+                    resource_info,
+                    now,
+                ) {
+                    to_notify.push((key, i, rule.clone()));
+                }
+            }
+        }
+        inner.notification_tracker.len() < before
+    };
+
+    // Step 3: Send notifications (no lock held), deferring those in quiet
+    // hours and collecting them instead when batching.
+    let mut sent_keys = Vec::new();
+    let mut deferred = Vec::new();
+    let mut batched = Vec::new();
+    for (key, i, rule) in to_notify {
+        let Some((account, type_tag, config, resource_info)) = candidates.get(i) else {
+            continue;
+        };
+        if quiet_games.contains(&account.game_id) && !config.critical {
+            tracing::debug!(account = %account, resource = type_tag, rule = rule.id, "Deferring notification during quiet hours");
+            deferred.push((key, rule.channels));
+            continue;
+        }
+        if batch_config.enabled {
+            let notification =
+                checker::build_resource_notification(account, type_tag, &rule, resource_info, now);
+            batched.push((key, rule.channels, notification));
+            continue;
+        }
+        if checker::send_resource_notification(state, account, type_tag, &rule, resource_info, now)
+            .await
+        {
+            sent_keys.push(key);
+        }
+    }
+
+    // Step 4: Batch record sent, deferred and batched notifications, and take
+    // the deferred notifications of games whose quiet hours ended and the
+    // batch if its window passed (single write lock). Deferred and batched
+    // notifications count as sent so their cooldown applies.
+    let changed =
+        cooldowns_cleared || !(sent_keys.is_empty() && deferred.is_empty() && batched.is_empty());
+    let (released, due) = {
+        let mut inner = state.inner.write().await;
+        for key in sent_keys {
+            inner.notification_tracker.record(key, now);
+        }
+        for (key, channels) in deferred {
+            inner.notification_tracker.record(key.clone(), now);
+            inner.deferred_notifications.defer(key, channels);
+        }
+        for (key, channels, notification) in batched {
+            inner.notification_tracker.record(key, now);
+            inner.notification_batch.add(channels, notification, now);
+        }
+        let released = if inner.deferred_notifications.is_empty() {
+            Vec::new()
+        } else {
+            inner
+                .deferred_notifications
+                .take_released(|game_id| quiet_games.contains(&game_id))
+        };
+        let window = batch_window(&batch_config);
+        (released, inner.notification_batch.take_due(window, now))
+    };
+    if changed || !released.is_empty() {
+        state.save_notification_state().await;
+    }
+
+    // Step 5: Send the quiet hours summaries (no lock held).
+    if !released.is_empty() {
+        send_quiet_hours_summaries(state, released, &resources, now).await;
+    }
+
+    // Step 6: Send the batch, one notification per channel (no lock held).
+    if !due.is_empty() {
+        send_batch(state, due).await;
+    }
+}
+
+/// Returns the games whose quiet hours include `now`, in system local time.
+fn quiet_games(config: &AppConfig, now: Timestamp) -> HashSet<GameId> {
+    let local_now = now.to_zoned(TimeZone::system());
+    GameId::all()
+        .iter()
+        .copied()
+        .filter(|&game_id| {
+            storekeeper_core::in_quiet_hours(config.quiet_hours_for(game_id), &local_now)
+        })
+        .collect()
+}
+
+/// Returns how long a batch collects notifications before it is sent.
+///
+/// Zero while batching is disabled, so a batch left over from before it was
+/// disabled goes out right away.
+fn batch_window(config: &NotificationBatchConfig) -> SignedDuration {
+    if config.enabled {
+        SignedDuration::from_mins(i64::from(config.window_minutes))
+    } else {
+        SignedDuration::ZERO
+    }
+}
+
+/// Sends the summaries of notifications released from quiet hours.
+async fn send_quiet_hours_summaries(
+    state: &AppState,
+    released: Vec<(NotifyKey, Vec<String>)>,
+    resources: &AllResources,
+    now: Timestamp,
+) {
+    for (channels, summary) in build_quiet_hours_summaries(released, resources, now) {
+        tracing::info!(body = %summary.body, "Sending quiet hours summary");
+        if let Err(e) = state.notify_via(&summary, &channels).await {
+            tracing::warn!(error = %e, "Failed to send quiet hours summary");
+        }
+    }
+}
+
+/// Sends batched notifications grouped per channel.
+///
+/// Hook channels still get one notification per resource, since they run on
+/// each event.
+async fn send_batch(state: &AppState, due: Vec<(Vec<String>, Notification)>) {
+    let (channel_names, hooks) = state.channel_names().await;
+    for (channel, notifications) in group_by_channel(due, &channel_names) {
+        let channels = std::slice::from_ref(&channel);
+        let notifications = if hooks.contains(&channel) {
+            notifications
+        } else {
+            build_batch_notification(notifications)
+                .into_iter()
+                .collect()
+        };
+        for notification in notifications {
+            tracing::info!(channel = %channel, title = %notification.title, "Sending batched notification");
+            if let Err(e) = state.notify_via(&notification, channels).await {
+                tracing::warn!(channel = %channel, error = %e, "Failed to send batched notification");
+            }
+        }
+    }
+}
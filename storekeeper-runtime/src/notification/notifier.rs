@@ -0,0 +1,264 @@
+//! Delivery backends for user-facing notifications.
+
+use std::future::Future;
+use std::pin::Pin;
+use storekeeper_core::AccountKey;
+use storekeeper_core::GameId;
+use storekeeper_core::HookEvent;
+
+pub(super) type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A localized notification ready to be delivered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Notification {
+    /// Short title (e.g. "Genshin Impact - Original Resin").
+    pub title: String,
+    /// Body text; may span several lines.
+    pub body: String,
+    /// Game the notification is about, if any.
+    pub game_id: Option<GameId>,
+    /// How pressing the notification is.
+    pub urgency: Urgency,
+    /// Event the notification reports, for hook channels.
+    pub event: Option<NotificationEvent>,
+}
+
+impl Notification {
+    /// Creates a normal-urgency notification not tied to a game.
+    #[must_use]
+    pub fn new(title: impl Into<String>, body: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            body: body.into(),
+            game_id: None,
+            urgency: Urgency::Normal,
+            event: None,
+        }
+    }
+}
+
+/// Structured description of the event behind a notification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotificationEvent {
+    /// What happened.
+    pub kind: HookEvent,
+    /// Account it happened to.
+    pub account: AccountKey,
+    /// Resource type tag, for resource events.
+    pub resource: Option<String>,
+    /// Event details (resource amounts, claim result, ...).
+    pub data: serde_json::Value,
+}
+
+/// Urgency of a notification, mapped to a priority by push channels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Urgency {
+    /// A resource is approaching its threshold, or a general message.
+    #[default]
+    Normal,
+    /// A resource is full or ready.
+    High,
+}
+
+/// Delivers notifications to the user (OS toast, log, webhook, ...).
+///
+/// Uses boxed futures (rather than `async fn`) so notifiers can be stored as
+/// trait objects.
+pub trait Notifier: Send + Sync {
+    /// Channel name used for routing and in logs (e.g. `"os"`).
+    fn name(&self) -> &str;
+
+    /// Delivers a notification.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the notification could not be delivered.
+    fn send<'a>(&'a self, notification: &'a Notification) -> BoxFuture<'a, anyhow::Result<()>>;
+
+    /// Whether the notifier acts on `notification`; those it ignores are not
+    /// sent to it. Defaults to every notification.
+    fn handles(&self, _notification: &Notification) -> bool {
+        true
+    }
+}
+
+/// Notifier that writes notifications to the log.
+///
+/// Always succeeds, so cooldowns advance exactly as they would with a real
+/// notification backend.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct LogNotifier;
+
+impl Notifier for LogNotifier {
+    fn name(&self) -> &'static str {
+        "log"
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            tracing::info!(
+                title = %notification.title,
+                body = %notification.body,
+                "Notification"
+            );
+            Ok(())
+        })
+    }
+}
+
+/// Sends a notification through the notifiers named in `channels`, or every
+/// notifier when `channels` is empty.
+///
+/// Returns `Ok` if at least one notifier delivered it, or none of the routed
+/// notifiers handles it; otherwise the last error. Fails when no notifier
+/// matches.
+///
+/// # Errors
+///
+/// Returns an error if no notifier delivered the notification.
+pub async fn deliver<'a>(
+    notifiers: impl IntoIterator<Item = &'a dyn Notifier>,
+    channels: &[String],
+    notification: &Notification,
+) -> anyhow::Result<()> {
+    delivery_result(deliver_each(notifiers, channels, notification).await?)
+}
+
+/// Result of sending a notification to one notifier.
+#[derive(Debug)]
+pub struct Delivery {
+    /// Name of the notifier.
+    pub channel: String,
+    /// Whether it delivered the notification.
+    pub result: anyhow::Result<()>,
+}
+
+/// Sends a notification through each notifier routed to by `channels` (every
+/// notifier when empty) that handles it, returning every notifier's result.
+///
+/// # Errors
+///
+/// Returns an error if no notifier is routed to.
+pub async fn deliver_each<'a>(
+    notifiers: impl IntoIterator<Item = &'a dyn Notifier>,
+    channels: &[String],
+    notification: &Notification,
+) -> anyhow::Result<Vec<Delivery>> {
+    let selected: Vec<_> = notifiers
+        .into_iter()
+        .filter(|notifier| channels.is_empty() || channels.iter().any(|c| c == notifier.name()))
+        .collect();
+    for channel in channels {
+        if !selected.iter().any(|notifier| notifier.name() == channel) {
+            tracing::warn!(channel = %channel, "Notification routed to unknown channel");
+        }
+    }
+    anyhow::ensure!(!selected.is_empty(), "no notifiers configured");
+
+    let mut deliveries = Vec::new();
+    for notifier in selected {
+        if !notifier.handles(notification) {
+            continue;
+        }
+        let result = notifier.send(notification).await;
+        if let Err(e) = &result {
+            tracing::warn!(notifier = notifier.name(), error = %e, "Failed to send notification");
+        }
+        deliveries.push(Delivery {
+            channel: notifier.name().to_string(),
+            result,
+        });
+    }
+    Ok(deliveries)
+}
+
+/// Returns `Ok` if any notifier delivered, or none was sent to; otherwise the
+/// last error.
+///
+/// # Errors
+///
+/// Returns the last error if every notifier failed.
+pub fn delivery_result(deliveries: Vec<Delivery>) -> anyhow::Result<()> {
+    let mut last_error = None;
+    for delivery in deliveries {
+        match delivery.result {
+            Ok(()) => return Ok(()),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    last_error.map_or(Ok(()), Err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FailingNotifier;
+
+    impl Notifier for FailingNotifier {
+        fn name(&self) -> &'static str {
+            "failing"
+        }
+
+        fn send<'a>(
+            &'a self,
+            _notification: &'a Notification,
+        ) -> BoxFuture<'a, anyhow::Result<()>> {
+            Box::pin(async { Err(anyhow::anyhow!("unreachable backend")) })
+        }
+    }
+
+    fn notification() -> Notification {
+        Notification::new("title", "body")
+    }
+
+    #[tokio::test]
+    async fn deliver_succeeds_if_any_notifier_succeeds() {
+        let notifiers: [&dyn Notifier; 2] = [&FailingNotifier, &LogNotifier];
+        deliver(notifiers, &[], &notification())
+            .await
+            .expect("log notifier delivers");
+    }
+
+    #[tokio::test]
+    async fn deliver_fails_when_all_notifiers_fail() {
+        let notifiers: [&dyn Notifier; 1] = [&FailingNotifier];
+        let err = deliver(notifiers, &[], &notification())
+            .await
+            .expect_err("all notifiers failed");
+        assert!(err.to_string().contains("unreachable backend"));
+    }
+
+    #[tokio::test]
+    async fn deliver_fails_without_notifiers() {
+        deliver([], &[], &notification())
+            .await
+            .expect_err("nothing to deliver to");
+    }
+
+    #[tokio::test]
+    async fn deliver_each_reports_every_notifier() {
+        let notifiers: [&dyn Notifier; 2] = [&FailingNotifier, &LogNotifier];
+        let deliveries = deliver_each(notifiers, &[], &notification())
+            .await
+            .expect("notifiers are routed to");
+        let results: Vec<_> = deliveries
+            .iter()
+            .map(|delivery| (delivery.channel.as_str(), delivery.result.is_ok()))
+            .collect();
+        assert_eq!(results, [("failing", false), ("log", true)]);
+        delivery_result(deliveries).expect("log notifier delivered");
+        delivery_result(Vec::new()).expect("nothing needed delivering");
+    }
+
+    #[tokio::test]
+    async fn deliver_only_uses_routed_channels() {
+        let notifiers: [&dyn Notifier; 2] = [&FailingNotifier, &LogNotifier];
+        deliver(notifiers, &["failing".to_string()], &notification())
+            .await
+            .expect_err("only the failing channel is routed to");
+        deliver(notifiers, &["log".to_string()], &notification())
+            .await
+            .expect("log channel delivers");
+    }
+}
@@ -0,0 +1,160 @@
+//! On-disk copy of the notification cooldowns, alert log and notifications
+//! deferred by quiet hours.
+//!
+//! Saved whenever any of them changes and loaded on startup, so resources that
+//! are already full don't notify again after a restart, and notifications held
+//! back by quiet hours are still summarized once they end.
+
+use super::alert_log::AlertLog;
+use super::quiet_hours::DeferredNotifications;
+use super::tracker::NotificationTracker;
+use camino::Utf8Path;
+use camino::Utf8PathBuf;
+use serde::Deserialize;
+use serde::Serialize;
+use storekeeper_core::AppConfig;
+
+/// Notification state file name inside the config directory.
+const NOTIFICATION_STATE_FILE_NAME: &str = "notification_state.json";
+
+/// Persisted notification cooldowns, alert log and deferred notifications.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistedNotifications {
+    /// When each notification rule last fired.
+    #[serde(default)]
+    pub cooldowns: NotificationTracker,
+
+    /// Recently sent notifications.
+    #[serde(default)]
+    pub alerts: AlertLog,
+
+    /// Notifications held back until their game's quiet hours end.
+    #[serde(default)]
+    pub deferred: DeferredNotifications,
+}
+
+impl PersistedNotifications {
+    /// Returns the default notification state file path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the config directory cannot be determined.
+    pub fn default_path() -> storekeeper_core::Result<Utf8PathBuf> {
+        Ok(AppConfig::config_dir()?.join(NOTIFICATION_STATE_FILE_NAME))
+    }
+
+    /// Loads the notification state, returning `None` if the file does not
+    /// exist.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn load_from_path(path: &Utf8Path) -> anyhow::Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs_err::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// Writes the notification state to a path, through a temporary file so a
+    /// crash mid-write never leaves a truncated file behind.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be serialized or written.
+    pub fn save_to_path(&self, path: &Utf8Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs_err::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string(self)?;
+        let tmp_path = path.with_extension("json.tmp");
+        fs_err::write(&tmp_path, content)?;
+        fs_err::rename(&tmp_path, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::alert_log::AlertLogEntry;
+    use super::super::notifier::Delivery;
+    use super::super::notifier::Notification;
+    use super::*;
+    use jiff::Timestamp;
+    use storekeeper_core::AccountKey;
+    use storekeeper_core::DEFAULT_RULE_ID;
+    use storekeeper_core::GameId;
+
+    fn temp_path(name: &str) -> Utf8PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "storekeeper-notifications-{name}-{}",
+            std::process::id()
+        ));
+        Utf8PathBuf::from_path_buf(dir)
+            .expect("temp dir should be UTF-8")
+            .join(NOTIFICATION_STATE_FILE_NAME)
+    }
+
+    #[test]
+    fn load_missing_file_returns_none() {
+        let loaded = PersistedNotifications::load_from_path(&temp_path("missing"))
+            .expect("should not error");
+        assert!(loaded.is_none());
+    }
+
+    #[test]
+    fn state_without_deferred_notifications_loads() {
+        let loaded: PersistedNotifications =
+            serde_json::from_str(r#"{"cooldowns":[],"alerts":[]}"#).expect("should parse");
+        assert!(loaded.deferred.is_empty());
+    }
+
+    #[test]
+    fn save_and_load_roundtrip() {
+        let path = temp_path("roundtrip");
+        let now = Timestamp::now();
+        let mut state = PersistedNotifications::default();
+        state.cooldowns.record(
+            (
+                AccountKey::default_for(GameId::WutheringWaves),
+                "waveplates".to_string(),
+                DEFAULT_RULE_ID.to_string(),
+            ),
+            now,
+        );
+        state.deferred.defer(
+            (
+                AccountKey::default_for(GameId::GenshinImpact),
+                "resin".to_string(),
+                DEFAULT_RULE_ID.to_string(),
+            ),
+            vec!["phone".to_string()],
+        );
+        state.alerts.push(AlertLogEntry::new(
+            &Notification::new("title", "body"),
+            &Delivery {
+                channel: "os".to_string(),
+                result: Ok(()),
+            },
+            now,
+        ));
+
+        state.save_to_path(&path).expect("should save");
+        let loaded = PersistedNotifications::load_from_path(&path)
+            .expect("should load")
+            .expect("file exists");
+
+        assert_eq!(
+            serde_json::to_value(&loaded).expect("serialize"),
+            serde_json::to_value(&state).expect("serialize")
+        );
+        assert!(!loaded.deferred.is_empty());
+        if let Some(dir) = path.parent()
+            && let Err(err) = fs_err::remove_dir_all(dir)
+        {
+            eprintln!("failed to clean up temp dir {dir}: {err}");
+        }
+    }
+}
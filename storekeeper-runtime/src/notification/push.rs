@@ -0,0 +1,300 @@
+//! Self-hosted push notification channels (ntfy and Gotify).
+
+use super::http::send_with_retry;
+use super::notifier::BoxFuture;
+use super::notifier::Notification;
+use super::notifier::Notifier;
+use super::notifier::Urgency;
+use std::collections::BTreeMap;
+use storekeeper_client_core::HttpClientBuilder;
+use storekeeper_client_core::RetryConfig;
+use storekeeper_core::GotifyChannelConfig;
+use storekeeper_core::NtfyChannelConfig;
+use storekeeper_core::PushPriority;
+
+/// Returns the priority a notification is sent with.
+const fn priority(priorities: PushPriority, notification: &Notification) -> u8 {
+    match notification.urgency {
+        Urgency::High => priorities.full,
+        Urgency::Normal => priorities.approaching,
+    }
+}
+
+/// Publishes notifications to a topic on an ntfy server.
+pub struct NtfyNotifier {
+    name: String,
+    server: String,
+    topic: String,
+    token: Option<String>,
+    priority: PushPriority,
+    tags: BTreeMap<String, Vec<String>>,
+    client: reqwest::Client,
+    retry: RetryConfig,
+}
+
+impl NtfyNotifier {
+    /// Creates an ntfy channel named `name`, authenticating with `token` if
+    /// the topic is protected.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built.
+    pub fn new(
+        name: impl Into<String>,
+        config: &NtfyChannelConfig,
+        token: Option<&str>,
+        retry: RetryConfig,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            name: name.into(),
+            server: config.server.trim_end_matches('/').to_string(),
+            topic: config.topic.clone(),
+            token: token.map(str::to_string),
+            priority: config.priority,
+            tags: config.tags.clone(),
+            client: HttpClientBuilder::new().build()?,
+            retry,
+        })
+    }
+
+    /// Builds the JSON publish payload for a notification.
+    fn payload(&self, notification: &Notification) -> serde_json::Value {
+        let tags = notification
+            .game_id
+            .and_then(|game_id| self.tags.get(game_id.config_key()))
+            .map_or(&[][..], Vec::as_slice);
+        serde_json::json!({
+            "topic": self.topic,
+            "title": notification.title,
+            "message": notification.body,
+            "priority": priority(self.priority, notification),
+            "tags": tags,
+        })
+    }
+}
+
+impl Notifier for NtfyNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            let payload = self.payload(notification);
+            send_with_retry(&self.retry, || {
+                let request = self.client.post(&self.server).json(&payload);
+                match &self.token {
+                    Some(token) => request.bearer_auth(token),
+                    None => request,
+                }
+            })
+            .await
+        })
+    }
+}
+
+/// Sends notifications as messages to a Gotify server.
+pub struct GotifyNotifier {
+    name: String,
+    url: String,
+    token: String,
+    priority: PushPriority,
+    client: reqwest::Client,
+    retry: RetryConfig,
+}
+
+impl GotifyNotifier {
+    /// Creates a Gotify channel named `name` sending with the application
+    /// `token`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built.
+    pub fn new(
+        name: impl Into<String>,
+        config: &GotifyChannelConfig,
+        token: impl Into<String>,
+        retry: RetryConfig,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            name: name.into(),
+            url: format!("{}/message", config.server.trim_end_matches('/')),
+            token: token.into(),
+            priority: config.priority,
+            client: HttpClientBuilder::new().build()?,
+            retry,
+        })
+    }
+
+    /// Builds the JSON message payload for a notification.
+    fn payload(&self, notification: &Notification) -> serde_json::Value {
+        serde_json::json!({
+            "title": notification.title,
+            "message": notification.body,
+            "priority": priority(self.priority, notification),
+        })
+    }
+}
+
+impl Notifier for GotifyNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            let payload = self.payload(notification);
+            send_with_retry(&self.retry, || {
+                self.client
+                    .post(&self.url)
+                    .header("X-Gotify-Key", &self.token)
+                    .json(&payload)
+            })
+            .await
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Json;
+    use axum::Router;
+    use axum::extract::State;
+    use axum::http::HeaderMap;
+    use axum::routing::post;
+    use reqwest::StatusCode;
+    use std::net::Ipv4Addr;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use storekeeper_core::GameId;
+    use tokio::net::TcpListener;
+
+    /// Authorization header and body of each request the test server received.
+    type Received = Arc<Mutex<Vec<(Option<String>, serde_json::Value)>>>;
+
+    async fn record(
+        State(received): State<Received>,
+        headers: HeaderMap,
+        Json(body): Json<serde_json::Value>,
+    ) -> StatusCode {
+        let auth = headers
+            .get("authorization")
+            .or_else(|| headers.get("x-gotify-key"))
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        received.lock().expect("lock").push((auth, body));
+        StatusCode::OK
+    }
+
+    /// Serves an endpoint at `path`, returning the server's base URL.
+    async fn spawn_server(path: &str) -> (String, Received) {
+        let received = Received::default();
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .expect("should bind");
+        let addr = listener.local_addr().expect("should have an address");
+        let router = Router::new()
+            .route(path, post(record))
+            .with_state(received.clone());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        (format!("http://{addr}"), received)
+    }
+
+    fn resource_notification(urgency: Urgency) -> Notification {
+        Notification {
+            title: "Genshin Impact - Original Resin".to_string(),
+            body: "Full!".to_string(),
+            game_id: Some(GameId::GenshinImpact),
+            urgency,
+            event: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn ntfy_publishes_priority_and_game_tags() {
+        let (server, received) = spawn_server("/").await;
+        let config = NtfyChannelConfig {
+            server: format!("{server}/"),
+            topic: "storekeeper".to_string(),
+            priority: PushPriority {
+                full: 5,
+                approaching: 2,
+            },
+            tags: BTreeMap::from([(
+                "genshin_impact".to_string(),
+                vec!["crystal_ball".to_string()],
+            )]),
+        };
+        let notifier =
+            NtfyNotifier::new("ntfy", &config, Some("tk_secret"), RetryConfig::default())
+                .expect("should build notifier");
+
+        notifier
+            .send(&resource_notification(Urgency::High))
+            .await
+            .expect("should deliver");
+        notifier
+            .send(&Notification::new("Digest", "Nothing wasted"))
+            .await
+            .expect("should deliver");
+
+        let received = received.lock().expect("lock");
+        assert_eq!(
+            *received,
+            vec![
+                (
+                    Some("Bearer tk_secret".to_string()),
+                    serde_json::json!({
+                        "topic": "storekeeper",
+                        "title": "Genshin Impact - Original Resin",
+                        "message": "Full!",
+                        "priority": 5,
+                        "tags": ["crystal_ball"],
+                    })
+                ),
+                (
+                    Some("Bearer tk_secret".to_string()),
+                    serde_json::json!({
+                        "topic": "storekeeper",
+                        "title": "Digest",
+                        "message": "Nothing wasted",
+                        "priority": 2,
+                        "tags": [],
+                    })
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn gotify_sends_message_with_app_token() {
+        let (server, received) = spawn_server("/message").await;
+        let config = GotifyChannelConfig {
+            server,
+            priority: PushPriority {
+                full: 8,
+                approaching: 5,
+            },
+        };
+        let notifier = GotifyNotifier::new("gotify", &config, "app-token", RetryConfig::default())
+            .expect("should build notifier");
+
+        notifier
+            .send(&resource_notification(Urgency::Normal))
+            .await
+            .expect("should deliver");
+
+        assert_eq!(
+            *received.lock().expect("lock"),
+            vec![(
+                Some("app-token".to_string()),
+                serde_json::json!({
+                    "title": "Genshin Impact - Original Resin",
+                    "message": "Full!",
+                    "priority": 5,
+                })
+            )]
+        );
+    }
+}
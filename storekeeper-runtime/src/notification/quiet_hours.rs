@@ -0,0 +1,270 @@
+//! Resource notifications held back during quiet hours.
+
+use super::digest::resource_status;
+use super::digest::summary_line;
+use super::message_builder::account_display_name;
+use super::message_builder::resource_display_name;
+use super::notifier::Notification;
+use super::tracker::NotifyKey;
+use crate::i18n;
+use crate::state::AllResources;
+use jiff::Timestamp;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use storekeeper_core::AccountKey;
+use storekeeper_core::GameId;
+
+/// Resource notifications deferred until their game's quiet hours end.
+///
+/// Holds one entry per (account, resource, rule), so repeated reminders during
+/// a window collapse into a single line of the summary, as do several rules of
+/// one resource routed to the same channels.
+///
+/// Serializes as a list of [`DeferredEntry`] so it can be persisted across
+/// restarts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "Vec<DeferredEntry>", into = "Vec<DeferredEntry>")]
+pub struct DeferredNotifications {
+    pending: BTreeMap<NotifyKey, Vec<String>>,
+}
+
+/// A notification rule of a resource held back until quiet hours end.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeferredEntry {
+    /// Game of the account.
+    pub game_id: GameId,
+    /// Account name.
+    pub account: String,
+    /// Resource type tag.
+    pub resource: String,
+    /// Rule id.
+    pub rule: String,
+    /// Channels the notification is routed to.
+    #[serde(default)]
+    pub channels: Vec<String>,
+}
+
+impl From<Vec<DeferredEntry>> for DeferredNotifications {
+    fn from(entries: Vec<DeferredEntry>) -> Self {
+        let pending = entries
+            .into_iter()
+            .map(|entry| {
+                let account = AccountKey::new(entry.game_id, entry.account);
+                ((account, entry.resource, entry.rule), entry.channels)
+            })
+            .collect();
+        Self { pending }
+    }
+}
+
+impl From<DeferredNotifications> for Vec<DeferredEntry> {
+    fn from(deferred: DeferredNotifications) -> Self {
+        deferred
+            .pending
+            .into_iter()
+            .map(|((account, resource, rule), channels)| DeferredEntry {
+                game_id: account.game_id,
+                account: account.account,
+                resource,
+                rule,
+                channels,
+            })
+            .collect()
+    }
+}
+
+impl DeferredNotifications {
+    /// Defers the notification for `key`, routed to `channels`.
+    pub fn defer(&mut self, key: NotifyKey, channels: Vec<String>) {
+        self.pending.insert(key, channels);
+    }
+
+    /// Returns true if nothing is deferred.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Drops the deferred notifications of every account of `game_id`.
+    pub fn clear_for_game(&mut self, game_id: GameId) {
+        self.pending
+            .retain(|(account, _, _), _| account.game_id != game_id);
+    }
+
+    /// Removes and returns the notifications of every game `is_quiet` no
+    /// longer holds back, with the channels they were routed to.
+    pub fn take_released(
+        &mut self,
+        is_quiet: impl Fn(GameId) -> bool,
+    ) -> Vec<(NotifyKey, Vec<String>)> {
+        let (released, held): (BTreeMap<_, _>, _) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|((account, _, _), _)| !is_quiet(account.game_id));
+        self.pending = held;
+        released.into_iter().collect()
+    }
+}
+
+/// Builds the summary of released notifications, one per set of channels
+/// they were routed to, listing each resource's status at `now`.
+///
+/// Resources that are no longer cached are left out.
+#[must_use]
+pub fn build_quiet_hours_summaries(
+    released: Vec<(NotifyKey, Vec<String>)>,
+    resources: &AllResources,
+    now: Timestamp,
+) -> Vec<(Vec<String>, Notification)> {
+    let mut lines_by_channels: BTreeMap<Vec<String>, Vec<String>> = BTreeMap::new();
+    for ((account, resource_type, _), channels) in released {
+        let Some(status) = resources
+            .games
+            .get(&account)
+            .and_then(serde_json::Value::as_array)
+            .into_iter()
+            .flatten()
+            .find(|resource| {
+                resource.get("type").and_then(serde_json::Value::as_str)
+                    == Some(resource_type.as_str())
+            })
+            .and_then(|resource| resource_status(&resource_type, resource.get("data")?, now))
+        else {
+            continue;
+        };
+        let name = i18n::t_args(
+            "notification_title",
+            &[
+                (
+                    "game_name",
+                    i18n::Value::from(account_display_name(&account)),
+                ),
+                (
+                    "resource_name",
+                    i18n::Value::from(resource_display_name(&resource_type)),
+                ),
+            ],
+        );
+        let line = summary_line(&name, status);
+        let lines = lines_by_channels.entry(channels).or_default();
+        if !lines.contains(&line) {
+            lines.push(line);
+        }
+    }
+
+    lines_by_channels
+        .into_iter()
+        .map(|(channels, lines)| {
+            let notification =
+                Notification::new(i18n::t("notification_quiet_hours_title"), lines.join("\n"));
+            (channels, notification)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::SignedDuration;
+    use storekeeper_core::AccountKey;
+    use storekeeper_core::DEFAULT_RULE_ID;
+    use storekeeper_core::StaminaResource;
+
+    fn ensure_init() {
+        #[expect(
+            clippy::let_underscore_must_use,
+            reason = "test setup may run after i18n is already initialized"
+        )]
+        let _ = crate::i18n::init("en");
+    }
+
+    fn key(game_id: GameId, resource: &str) -> NotifyKey {
+        rule_key(game_id, resource, DEFAULT_RULE_ID)
+    }
+
+    fn rule_key(game_id: GameId, resource: &str, rule: &str) -> NotifyKey {
+        (
+            AccountKey::default_for(game_id),
+            resource.to_string(),
+            rule.to_string(),
+        )
+    }
+
+    #[test]
+    fn notifications_are_released_per_game() {
+        let mut deferred = DeferredNotifications::default();
+        deferred.defer(key(GameId::GenshinImpact, "resin"), Vec::new());
+        deferred.defer(
+            key(GameId::GenshinImpact, "resin"),
+            vec!["phone".to_string()],
+        );
+        deferred.defer(key(GameId::WutheringWaves, "waveplates"), Vec::new());
+
+        let released = deferred.take_released(|game_id| game_id == GameId::WutheringWaves);
+
+        assert_eq!(
+            released,
+            vec![(
+                key(GameId::GenshinImpact, "resin"),
+                vec!["phone".to_string()]
+            )]
+        );
+        assert!(!deferred.is_empty());
+        assert_eq!(deferred.take_released(|_| false).len(), 1);
+        assert!(deferred.is_empty());
+    }
+
+    #[test]
+    fn clearing_a_game_drops_only_its_notifications() {
+        let mut deferred = DeferredNotifications::default();
+        deferred.defer(key(GameId::GenshinImpact, "resin"), Vec::new());
+        deferred.defer(key(GameId::WutheringWaves, "waveplates"), Vec::new());
+
+        deferred.clear_for_game(GameId::GenshinImpact);
+
+        assert_eq!(
+            deferred.take_released(|_| false),
+            vec![(key(GameId::WutheringWaves, "waveplates"), Vec::new())]
+        );
+    }
+
+    #[test]
+    fn summary_lists_current_status_per_channel_set() {
+        ensure_init();
+        let now = Timestamp::now();
+        let mut resources = AllResources::default();
+        resources.record(
+            AccountKey::default_for(GameId::GenshinImpact),
+            serde_json::json!([{
+                "type": "resin",
+                "data": StaminaResource::new(200, 200, now - SignedDuration::from_hours(1), 480),
+            }]),
+            now,
+        );
+
+        let summaries = build_quiet_hours_summaries(
+            vec![
+                (key(GameId::GenshinImpact, "resin"), Vec::new()),
+                (
+                    rule_key(GameId::GenshinImpact, "resin", "at_120"),
+                    Vec::new(),
+                ),
+                (key(GameId::HonkaiStarRail, "trailblaze_power"), Vec::new()),
+            ],
+            &resources,
+            now,
+        );
+
+        assert_eq!(
+            summaries,
+            vec![(
+                Vec::new(),
+                Notification::new(
+                    "Held during quiet hours",
+                    "Genshin Impact - Original Resin: Full!"
+                )
+            )]
+        );
+    }
+}
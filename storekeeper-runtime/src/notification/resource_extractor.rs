@@ -6,17 +6,17 @@ use storekeeper_core::ExpeditionResource;
 use storekeeper_core::StaminaResource;
 
 /// Extracted timing info from a resource JSON object.
-pub(crate) struct ResourceInfo {
+pub struct ResourceInfo {
     /// When the resource will be complete/full/ready.
-    pub(crate) completion_at: Timestamp,
+    pub completion_at: Timestamp,
     /// Whether the resource is already complete.
-    pub(crate) is_complete: bool,
+    pub is_complete: bool,
     /// Current resource value (stamina resources only).
-    pub(crate) current: Option<u64>,
+    pub current: Option<u64>,
     /// Maximum resource value (stamina resources only).
-    pub(crate) max: Option<u64>,
+    pub max: Option<u64>,
     /// Seconds per unit of regeneration (stamina resources only).
-    pub(crate) regen_rate_seconds: Option<u64>,
+    pub regen_rate_seconds: Option<u64>,
 }
 
 impl ResourceInfo {
@@ -26,7 +26,8 @@ impl ResourceInfo {
     /// The cached `current` field can be stale (set at API-fetch time), so this
     /// computes the value from elapsed time instead.  Falls back to the cached
     /// `current` when max/rate are unavailable.
-    pub(crate) fn estimated_current(&self, now: Timestamp) -> Option<u64> {
+    #[must_use]
+    pub fn estimated_current(&self, now: Timestamp) -> Option<u64> {
         let (max, rate) = match (self.max, self.regen_rate_seconds) {
             (Some(m), Some(r)) if r > 0 => (m, r),
             _ => return self.current,
@@ -53,7 +54,8 @@ impl ResourceInfo {
 /// Extracts completion timing from a resource data object.
 ///
 /// Uses `resource_type` to deserialize into exactly one expected shape.
-pub(crate) fn extract_resource_info(
+#[must_use]
+pub fn extract_resource_info(
     resource_type: &str,
     data: &serde_json::Value,
 ) -> Option<ResourceInfo> {
@@ -0,0 +1,789 @@
+//! Notification cooldown tracker for (account, resource, rule) triples.
+
+use super::resource_extractor::ResourceInfo;
+use jiff::SignedDuration;
+use jiff::Timestamp;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use storekeeper_core::AccountKey;
+use storekeeper_core::GameId;
+use storekeeper_core::NotificationRule;
+
+/// Pre-built HashMap key for an (account, resource, rule id) triple.
+pub type NotifyKey = (AccountKey, String, String);
+
+/// Result of a `should_notify` check.
+pub enum NotifyAction {
+    /// Do not send a notification.
+    Skip,
+    /// Send a notification; includes the pre-built key for `record`.
+    Notify(NotifyKey),
+}
+
+#[cfg(test)]
+impl NotifyAction {
+    fn is_notify(&self) -> bool {
+        matches!(self, Self::Notify(_))
+    }
+}
+
+/// Returns when a resource enters `rule`'s notification window.
+///
+/// Value thresholds are converted to a time before full via the regen rate;
+/// without one (or without a max) the window can't be predicted and `None` is
+/// returned. A rule without a threshold opens at completion.
+#[must_use]
+pub fn window_start(rule: &NotificationRule, info: &ResourceInfo) -> Option<Timestamp> {
+    let window = match (rule.notify_at_value, rule.notify_minutes_before_full) {
+        (Some(threshold), _) => {
+            let (Some(max), Some(rate)) = (info.max, info.regen_rate_seconds) else {
+                return None;
+            };
+            let units_remaining = max.saturating_sub(threshold);
+            let effective_minutes = i64::try_from(
+                units_remaining
+                    .checked_mul(rate)
+                    .map_or(u64::MAX, |v| v / 60),
+            )
+            .unwrap_or(i64::MAX);
+            // Build the window in seconds with saturating arithmetic so an
+            // extreme `effective_minutes` can't overflow the duration.
+            SignedDuration::from_secs(effective_minutes.saturating_mul(60))
+        }
+        (None, Some(minutes)) => SignedDuration::from_mins(i64::from(minutes)),
+        (None, None) => SignedDuration::ZERO,
+    };
+    Some(
+        info.completion_at
+            .checked_sub(window)
+            .unwrap_or(Timestamp::MIN),
+    )
+}
+
+/// Tracks notification cooldown state per (account, resource, rule) triple.
+///
+/// Serializes as a list of [`CooldownEntry`] so it can be persisted across
+/// restarts.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "Vec<CooldownEntry>", into = "Vec<CooldownEntry>")]
+pub struct NotificationTracker {
+    cooldowns: HashMap<NotifyKey, Timestamp>,
+}
+
+/// When a rule of a resource last notified.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CooldownEntry {
+    /// Game of the account.
+    pub game_id: GameId,
+    /// Account name.
+    pub account: String,
+    /// Resource type tag.
+    pub resource: String,
+    /// Rule id.
+    pub rule: String,
+    /// When the rule last notified.
+    pub notified_at: Timestamp,
+}
+
+impl From<Vec<CooldownEntry>> for NotificationTracker {
+    fn from(entries: Vec<CooldownEntry>) -> Self {
+        let cooldowns = entries
+            .into_iter()
+            .map(|entry| {
+                let account = AccountKey::new(entry.game_id, entry.account);
+                ((account, entry.resource, entry.rule), entry.notified_at)
+            })
+            .collect();
+        Self { cooldowns }
+    }
+}
+
+impl From<NotificationTracker> for Vec<CooldownEntry> {
+    fn from(tracker: NotificationTracker) -> Self {
+        tracker
+            .cooldowns
+            .into_iter()
+            .map(|((account, resource, rule), notified_at)| CooldownEntry {
+                game_id: account.game_id,
+                account: account.account,
+                resource,
+                rule,
+                notified_at,
+            })
+            .collect()
+    }
+}
+
+impl NotificationTracker {
+    /// Decides whether `rule` should fire for this resource.
+    ///
+    /// Returns `Skip` (and clears cooldown) when the resource is outside the
+    /// notification window. Returns `Skip` when still within cooldown. Returns
+    /// `Notify(key)` when the resource is in-window/full and cooldown has
+    /// expired or no prior notification exists.
+    ///
+    /// When `cooldown_minutes` is 0, only one notification fires per window
+    /// entry — no recurring reminders until the resource leaves and re-enters.
+    pub fn should_notify(
+        &mut self,
+        account: &AccountKey,
+        resource_type: &str,
+        rule: &NotificationRule,
+        info: &ResourceInfo,
+        now: Timestamp,
+    ) -> NotifyAction {
+        let in_window = info.is_complete
+            || match (rule.notify_at_value, window_start(rule, info)) {
+                // Neither threshold set: notify only when full/ready
+                (None, _) if rule.notify_minutes_before_full.is_none() => false,
+                (_, Some(start)) => now >= start,
+                // Value threshold without a regen rate: direct value comparison
+                (threshold, None) => threshold
+                    .zip(info.current)
+                    .is_some_and(|(threshold, current)| current >= threshold),
+            };
+
+        let key = (account.clone(), resource_type.to_string(), rule.id.clone());
+
+        // Not in notification window yet — reset cooldown tracking
+        if !in_window {
+            self.cooldowns.remove(&key);
+            return NotifyAction::Skip;
+        }
+
+        // In window or already full — check cooldown
+        if let Some(last_notified) = self.cooldowns.get(&key).copied() {
+            // cooldown_minutes == 0 means "notify once, don't repeat"
+            if rule.cooldown_minutes == 0 {
+                return NotifyAction::Skip;
+            }
+            let cooldown = SignedDuration::from_mins(i64::from(rule.cooldown_minutes));
+            if now.duration_since(last_notified) < cooldown {
+                return NotifyAction::Skip;
+            }
+        }
+
+        NotifyAction::Notify(key)
+    }
+
+    /// Records that a notification was sent for the given key.
+    pub fn record(&mut self, key: NotifyKey, now: Timestamp) {
+        self.cooldowns.insert(key, now);
+    }
+
+    /// Clears cooldown entries for a specific game.
+    ///
+    /// Removes all (account, resource, rule) cooldowns for every account of
+    /// the given game.
+    pub fn clear_for_game(&mut self, game_id: GameId) {
+        self.cooldowns
+            .retain(|(account, _, _), _| account.game_id != game_id);
+    }
+
+    /// Clears all cooldown entries.
+    pub fn clear_all(&mut self) {
+        self.cooldowns.clear();
+    }
+
+    /// Returns the number of active cooldowns.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cooldowns.len()
+    }
+
+    /// Returns true if no cooldown is active.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cooldowns.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use storekeeper_core::DEFAULT_RULE_ID;
+
+    fn stub_config(threshold_min: u32, cooldown_min: u32) -> NotificationRule {
+        NotificationRule {
+            id: DEFAULT_RULE_ID.to_string(),
+            notify_minutes_before_full: if threshold_min > 0 {
+                Some(threshold_min)
+            } else {
+                None
+            },
+            notify_at_value: None,
+            cooldown_minutes: cooldown_min,
+            channels: Vec::new(),
+            template: None,
+        }
+    }
+
+    fn stub_info(completion_at: Timestamp, is_complete: bool) -> ResourceInfo {
+        ResourceInfo {
+            completion_at,
+            is_complete,
+            current: None,
+            max: None,
+            regen_rate_seconds: None,
+        }
+    }
+
+    fn key(account: &AccountKey, resource_type: &str) -> NotifyKey {
+        (
+            account.clone(),
+            resource_type.to_string(),
+            DEFAULT_RULE_ID.to_string(),
+        )
+    }
+
+    #[test]
+    fn test_not_in_window_clears_and_returns_false() {
+        let mut tracker = NotificationTracker::default();
+        let now = Timestamp::now();
+        let game = AccountKey::default_for(GameId::GenshinImpact);
+        let config = stub_config(60, 10);
+
+        // Seed a prior cooldown entry
+        tracker.record(key(&game, "resin"), now - SignedDuration::from_hours(1));
+
+        let info = stub_info(now + SignedDuration::from_hours(2), false);
+        assert!(
+            !tracker
+                .should_notify(&game, "resin", &config, &info, now)
+                .is_notify()
+        );
+
+        // Internal state was cleared — next in-window check should return true
+        let in_window_info = stub_info(now + SignedDuration::from_mins(30), false);
+        assert!(
+            tracker
+                .should_notify(&game, "resin", &config, &in_window_info, now)
+                .is_notify()
+        );
+    }
+
+    #[test]
+    fn test_in_window_first_time_returns_true() {
+        let mut tracker = NotificationTracker::default();
+        let now = Timestamp::now();
+        let config = stub_config(60, 10);
+        let info = stub_info(now + SignedDuration::from_mins(30), false);
+
+        assert!(
+            tracker
+                .should_notify(
+                    &AccountKey::default_for(GameId::GenshinImpact),
+                    "resin",
+                    &config,
+                    &info,
+                    now
+                )
+                .is_notify()
+        );
+    }
+
+    #[test]
+    fn test_in_window_within_cooldown_returns_false() {
+        let mut tracker = NotificationTracker::default();
+        let now = Timestamp::now();
+        let game = AccountKey::default_for(GameId::GenshinImpact);
+        let config = stub_config(60, 10);
+
+        tracker.record(key(&game, "resin"), now);
+
+        let info = stub_info(now + SignedDuration::from_mins(30), false);
+        // 5 minutes later, still within 10-minute cooldown
+        let later = now + SignedDuration::from_mins(5);
+        assert!(
+            !tracker
+                .should_notify(&game, "resin", &config, &info, later)
+                .is_notify()
+        );
+    }
+
+    #[test]
+    fn test_in_window_after_cooldown_expired_returns_true() {
+        let mut tracker = NotificationTracker::default();
+        let now = Timestamp::now();
+        let game = AccountKey::default_for(GameId::HonkaiStarRail);
+        let config = stub_config(60, 10);
+
+        tracker.record(key(&game, "trailblaze_power"), now);
+
+        let info = stub_info(now + SignedDuration::from_mins(30), false);
+        // 11 minutes later, past 10-minute cooldown
+        let later = now + SignedDuration::from_mins(11);
+        assert!(
+            tracker
+                .should_notify(&game, "trailblaze_power", &config, &info, later)
+                .is_notify()
+        );
+    }
+
+    #[test]
+    fn test_at_full_returns_true() {
+        let mut tracker = NotificationTracker::default();
+        let now = Timestamp::now();
+        let config = stub_config(60, 10);
+        let info = stub_info(now - SignedDuration::from_secs(1), true);
+
+        assert!(
+            tracker
+                .should_notify(
+                    &AccountKey::default_for(GameId::ZenlessZoneZero),
+                    "battery",
+                    &config,
+                    &info,
+                    now
+                )
+                .is_notify()
+        );
+    }
+
+    #[test]
+    fn test_clear_resets_state() {
+        let mut tracker = NotificationTracker::default();
+        let now = Timestamp::now();
+        let game = AccountKey::default_for(GameId::WutheringWaves);
+        let config = stub_config(60, 10);
+
+        tracker.record(key(&game, "waveplates"), now);
+
+        let info = stub_info(now + SignedDuration::from_mins(30), false);
+        // Within cooldown — should be false
+        assert!(
+            !tracker
+                .should_notify(&game, "waveplates", &config, &info, now)
+                .is_notify()
+        );
+
+        // Manually clear — next check should return true
+        tracker.cooldowns.remove(&key(&game, "waveplates"));
+        assert!(
+            tracker
+                .should_notify(&game, "waveplates", &config, &info, now)
+                .is_notify()
+        );
+    }
+
+    #[test]
+    fn test_zero_cooldown_notifies_once_then_stops() {
+        let mut tracker = NotificationTracker::default();
+        let now = Timestamp::now();
+        let game = AccountKey::default_for(GameId::GenshinImpact);
+        let config = stub_config(60, 0); // cooldown_minutes = 0
+
+        let info = stub_info(now + SignedDuration::from_mins(30), false);
+
+        // First check — no prior notification, should fire
+        assert!(
+            tracker
+                .should_notify(&game, "resin", &config, &info, now)
+                .is_notify()
+        );
+        tracker.record(key(&game, "resin"), now);
+
+        // Subsequent checks — never re-notifies regardless of time elapsed
+        let much_later = now + SignedDuration::from_hours(24);
+        assert!(
+            !tracker
+                .should_notify(&game, "resin", &config, &info, much_later)
+                .is_notify()
+        );
+    }
+
+    #[test]
+    fn test_zero_cooldown_resets_on_leaving_window() {
+        let mut tracker = NotificationTracker::default();
+        let now = Timestamp::now();
+        let game = AccountKey::default_for(GameId::GenshinImpact);
+        let config = stub_config(60, 0);
+
+        // In window — notifies
+        let in_window = stub_info(now + SignedDuration::from_mins(30), false);
+        assert!(
+            tracker
+                .should_notify(&game, "resin", &config, &in_window, now)
+                .is_notify()
+        );
+        tracker.record(key(&game, "resin"), now);
+
+        // Leaves window (resource consumed) — clears state
+        let out_of_window = stub_info(now + SignedDuration::from_hours(5), false);
+        assert!(
+            !tracker
+                .should_notify(&game, "resin", &config, &out_of_window, now)
+                .is_notify()
+        );
+
+        // Re-enters window — should notify again (one-shot reset)
+        assert!(
+            tracker
+                .should_notify(&game, "resin", &config, &in_window, now)
+                .is_notify()
+        );
+    }
+
+    // =========================================================================
+    // Value-threshold mode tests
+    // =========================================================================
+
+    #[test]
+    fn test_value_threshold_with_regen_rate() {
+        let mut tracker = NotificationTracker::default();
+        let now = Timestamp::now();
+        let config = NotificationRule {
+            id: DEFAULT_RULE_ID.to_string(),
+            notify_minutes_before_full: None,
+            notify_at_value: Some(140),
+            cooldown_minutes: 10,
+            channels: Vec::new(),
+            template: None,
+        };
+
+        // Resin: max=160, rate=480s/unit. threshold=140, remaining=20 units,
+        // 20*480/60=160 min
+        let info = ResourceInfo {
+            completion_at: now + SignedDuration::from_mins(100), // within 160 min window
+            is_complete: false,
+            current: Some(120),
+            max: Some(160),
+            regen_rate_seconds: Some(480),
+        };
+
+        assert!(
+            tracker
+                .should_notify(
+                    &AccountKey::default_for(GameId::GenshinImpact),
+                    "resin",
+                    &config,
+                    &info,
+                    now
+                )
+                .is_notify()
+        );
+    }
+
+    #[test]
+    fn test_value_threshold_not_in_window() {
+        let mut tracker = NotificationTracker::default();
+        let now = Timestamp::now();
+        let config = NotificationRule {
+            id: DEFAULT_RULE_ID.to_string(),
+            notify_minutes_before_full: None,
+            notify_at_value: Some(140),
+            cooldown_minutes: 10,
+            channels: Vec::new(),
+            template: None,
+        };
+
+        // threshold=140, remaining=20 units, 20*480/60=160 min. time_to_full=200 > 160
+        let info = ResourceInfo {
+            completion_at: now + SignedDuration::from_mins(200),
+            is_complete: false,
+            current: Some(100),
+            max: Some(160),
+            regen_rate_seconds: Some(480),
+        };
+
+        assert!(
+            !tracker
+                .should_notify(
+                    &AccountKey::default_for(GameId::GenshinImpact),
+                    "resin",
+                    &config,
+                    &info,
+                    now
+                )
+                .is_notify()
+        );
+    }
+
+    #[test]
+    fn test_value_threshold_regen_rate_math_boundary() {
+        // Verify exact math: threshold=140, max=160, rate=480s/unit
+        // units_remaining = 160-140 = 20, effective_minutes = 20*480/60 = 160
+        let mut tracker = NotificationTracker::default();
+        let now = Timestamp::now();
+        let config = NotificationRule {
+            id: DEFAULT_RULE_ID.to_string(),
+            notify_minutes_before_full: None,
+            notify_at_value: Some(140),
+            cooldown_minutes: 10,
+            channels: Vec::new(),
+            template: None,
+        };
+
+        // Exactly at boundary (160 min to full) — should notify (<=)
+        let at_boundary = ResourceInfo {
+            completion_at: now + SignedDuration::from_mins(160),
+            is_complete: false,
+            current: Some(120),
+            max: Some(160),
+            regen_rate_seconds: Some(480),
+        };
+        assert!(
+            tracker
+                .should_notify(
+                    &AccountKey::default_for(GameId::GenshinImpact),
+                    "resin",
+                    &config,
+                    &at_boundary,
+                    now
+                )
+                .is_notify()
+        );
+
+        tracker.cooldowns.remove(&key(
+            &AccountKey::default_for(GameId::GenshinImpact),
+            "resin",
+        ));
+
+        // Just outside boundary (161 min to full) — should NOT notify
+        let outside_boundary = ResourceInfo {
+            completion_at: now + SignedDuration::from_mins(161),
+            is_complete: false,
+            current: Some(119),
+            max: Some(160),
+            regen_rate_seconds: Some(480),
+        };
+        assert!(
+            !tracker
+                .should_notify(
+                    &AccountKey::default_for(GameId::GenshinImpact),
+                    "resin",
+                    &config,
+                    &outside_boundary,
+                    now
+                )
+                .is_notify()
+        );
+    }
+
+    #[test]
+    fn test_value_threshold_fallback_direct_comparison() {
+        let mut tracker = NotificationTracker::default();
+        let now = Timestamp::now();
+        let config = NotificationRule {
+            id: DEFAULT_RULE_ID.to_string(),
+            notify_minutes_before_full: None,
+            notify_at_value: Some(140),
+            cooldown_minutes: 10,
+            channels: Vec::new(),
+            template: None,
+        };
+
+        // No regen rate — falls back to direct comparison
+        let info = ResourceInfo {
+            completion_at: now + SignedDuration::from_hours(1),
+            is_complete: false,
+            current: Some(145),
+            max: Some(160),
+            regen_rate_seconds: None,
+        };
+
+        assert!(
+            tracker
+                .should_notify(
+                    &AccountKey::default_for(GameId::GenshinImpact),
+                    "resin",
+                    &config,
+                    &info,
+                    now
+                )
+                .is_notify()
+        );
+    }
+
+    #[test]
+    fn test_value_threshold_fallback_below_threshold() {
+        let mut tracker = NotificationTracker::default();
+        let now = Timestamp::now();
+        let config = NotificationRule {
+            id: DEFAULT_RULE_ID.to_string(),
+            notify_minutes_before_full: None,
+            notify_at_value: Some(140),
+            cooldown_minutes: 10,
+            channels: Vec::new(),
+            template: None,
+        };
+
+        // No regen rate, current < threshold
+        let info = ResourceInfo {
+            completion_at: now + SignedDuration::from_hours(1),
+            is_complete: false,
+            current: Some(100),
+            max: Some(160),
+            regen_rate_seconds: None,
+        };
+
+        assert!(
+            !tracker
+                .should_notify(
+                    &AccountKey::default_for(GameId::GenshinImpact),
+                    "resin",
+                    &config,
+                    &info,
+                    now
+                )
+                .is_notify()
+        );
+    }
+
+    #[test]
+    fn test_neither_threshold_only_notifies_when_full() {
+        let mut tracker = NotificationTracker::default();
+        let now = Timestamp::now();
+        let config = NotificationRule {
+            id: DEFAULT_RULE_ID.to_string(),
+            notify_minutes_before_full: None,
+            notify_at_value: None,
+            cooldown_minutes: 10,
+            channels: Vec::new(),
+            template: None,
+        };
+
+        // Not full — should NOT notify
+        let info = stub_info(now + SignedDuration::from_mins(5), false);
+        assert!(
+            !tracker
+                .should_notify(
+                    &AccountKey::default_for(GameId::GenshinImpact),
+                    "resin",
+                    &config,
+                    &info,
+                    now
+                )
+                .is_notify()
+        );
+
+        // Full — should notify
+        let full_info = stub_info(now - SignedDuration::from_secs(1), true);
+        assert!(
+            tracker
+                .should_notify(
+                    &AccountKey::default_for(GameId::GenshinImpact),
+                    "resin",
+                    &config,
+                    &full_info,
+                    now
+                )
+                .is_notify()
+        );
+    }
+
+    #[test]
+    fn test_accounts_have_independent_cooldowns() {
+        let mut tracker = NotificationTracker::default();
+        let now = Timestamp::now();
+        let main = AccountKey::default_for(GameId::GenshinImpact);
+        let alt = AccountKey::new(GameId::GenshinImpact, "alt");
+        let config = stub_config(60, 10);
+        let info = stub_info(now + SignedDuration::from_mins(30), false);
+
+        tracker.record(key(&main, "resin"), now);
+
+        assert!(
+            !tracker
+                .should_notify(&main, "resin", &config, &info, now)
+                .is_notify()
+        );
+        assert!(
+            tracker
+                .should_notify(&alt, "resin", &config, &info, now)
+                .is_notify(),
+            "cooldown of one account must not suppress another"
+        );
+    }
+
+    #[test]
+    fn test_rules_have_independent_cooldowns() {
+        let mut tracker = NotificationTracker::default();
+        let now = Timestamp::now();
+        let game = AccountKey::default_for(GameId::GenshinImpact);
+        let early = NotificationRule {
+            id: "early".to_string(),
+            ..stub_config(60, 10)
+        };
+        let late = NotificationRule {
+            id: "late".to_string(),
+            ..stub_config(30, 10)
+        };
+        let info = stub_info(now + SignedDuration::from_mins(20), false);
+
+        assert!(
+            tracker
+                .should_notify(&game, "resin", &early, &info, now)
+                .is_notify()
+        );
+        tracker.record(
+            (game.clone(), "resin".to_string(), "early".to_string()),
+            now,
+        );
+
+        assert!(
+            !tracker
+                .should_notify(&game, "resin", &early, &info, now)
+                .is_notify()
+        );
+        assert!(
+            tracker
+                .should_notify(&game, "resin", &late, &info, now)
+                .is_notify(),
+            "cooldown of one rule must not suppress another"
+        );
+    }
+
+    #[test]
+    fn test_cooldowns_survive_serialization() {
+        let mut tracker = NotificationTracker::default();
+        let now = Timestamp::now();
+        let alt = AccountKey::new(GameId::GenshinImpact, "alt");
+        let config = stub_config(60, 10);
+        let info = stub_info(now + SignedDuration::from_mins(30), false);
+        tracker.record(key(&alt, "resin"), now);
+
+        let json = serde_json::to_string(&tracker).expect("serialize");
+        let mut restored: NotificationTracker = serde_json::from_str(&json).expect("deserialize");
+
+        assert!(
+            !restored
+                .should_notify(&alt, "resin", &config, &info, now)
+                .is_notify(),
+            "restored cooldown should still apply"
+        );
+    }
+
+    #[test]
+    fn test_clear_for_game_clears_all_accounts() {
+        let mut tracker = NotificationTracker::default();
+        let now = Timestamp::now();
+        tracker.record(
+            key(&AccountKey::default_for(GameId::GenshinImpact), "resin"),
+            now,
+        );
+        tracker.record(
+            key(&AccountKey::new(GameId::GenshinImpact, "alt"), "resin"),
+            now,
+        );
+        tracker.record(
+            key(
+                &AccountKey::default_for(GameId::HonkaiStarRail),
+                "trailblaze_power",
+            ),
+            now,
+        );
+
+        tracker.clear_for_game(GameId::GenshinImpact);
+
+        assert_eq!(tracker.cooldowns.len(), 1);
+        assert!(
+            tracker
+                .cooldowns
+                .keys()
+                .all(|(account, _, _)| account.game_id == GameId::HonkaiStarRail)
+        );
+    }
+}
@@ -0,0 +1,214 @@
+//! Webhook notification channel (Discord, Slack or plain JSON).
+
+use super::http::send_with_retry;
+use super::notifier::BoxFuture;
+use super::notifier::Notification;
+use super::notifier::Notifier;
+use crate::i18n;
+use storekeeper_client_core::HttpClientBuilder;
+use storekeeper_client_core::RetryConfig;
+use storekeeper_core::WebhookChannelConfig;
+use storekeeper_core::WebhookPreset;
+
+/// Delivers notifications as JSON `POST`s to a webhook URL.
+pub struct WebhookNotifier {
+    name: String,
+    url: String,
+    preset: WebhookPreset,
+    template: String,
+    client: reqwest::Client,
+    retry: RetryConfig,
+}
+
+impl WebhookNotifier {
+    /// Creates a webhook channel named `name` posting to `url`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built.
+    pub fn new(
+        name: impl Into<String>,
+        url: impl Into<String>,
+        config: &WebhookChannelConfig,
+        retry: RetryConfig,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            name: name.into(),
+            url: url.into(),
+            preset: config.preset,
+            template: config
+                .template
+                .clone()
+                .unwrap_or_else(|| default_template(config.preset).to_string()),
+            client: HttpClientBuilder::new().build()?,
+            retry,
+        })
+    }
+
+    /// Builds the JSON payload for a notification.
+    fn payload(&self, notification: &Notification) -> serde_json::Value {
+        let message = i18n::format_template(
+            &self.template,
+            &[
+                ("title", i18n::Value::from(notification.title.as_str())),
+                ("body", i18n::Value::from(notification.body.as_str())),
+            ],
+        );
+        match self.preset {
+            WebhookPreset::Discord => serde_json::json!({ "content": message }),
+            WebhookPreset::Slack => serde_json::json!({ "text": message }),
+            WebhookPreset::Json => serde_json::json!({
+                "title": notification.title,
+                "body": notification.body,
+                "message": message,
+            }),
+        }
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn send<'a>(&'a self, notification: &'a Notification) -> BoxFuture<'a, anyhow::Result<()>> {
+        Box::pin(async move {
+            let payload = self.payload(notification);
+            send_with_retry(&self.retry, || self.client.post(&self.url).json(&payload)).await
+        })
+    }
+}
+
+/// Default message template of each preset, using the services' markdown.
+const fn default_template(preset: WebhookPreset) -> &'static str {
+    match preset {
+        WebhookPreset::Discord => "**{title}**\n{body}",
+        WebhookPreset::Slack => "*{title}*\n{body}",
+        WebhookPreset::Json => "{title}\n{body}",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::Json;
+    use axum::Router;
+    use axum::extract::State;
+    use axum::routing::post;
+    use reqwest::StatusCode;
+    use std::net::Ipv4Addr;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use tokio::net::TcpListener;
+
+    /// Requests received by the test server, and how many to fail first.
+    #[derive(Clone, Default)]
+    struct Received {
+        bodies: Arc<Mutex<Vec<serde_json::Value>>>,
+        failures_left: Arc<Mutex<u32>>,
+    }
+
+    async fn hook(
+        State(received): State<Received>,
+        Json(body): Json<serde_json::Value>,
+    ) -> StatusCode {
+        received.bodies.lock().expect("lock").push(body);
+        let mut failures_left = received.failures_left.lock().expect("lock");
+        if *failures_left > 0 {
+            *failures_left -= 1;
+            StatusCode::SERVICE_UNAVAILABLE
+        } else {
+            StatusCode::NO_CONTENT
+        }
+    }
+
+    /// Serves a webhook endpoint that fails `failures` times before succeeding.
+    async fn spawn_hook(failures: u32) -> (String, Received) {
+        let received = Received::default();
+        *received.failures_left.lock().expect("lock") = failures;
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .expect("should bind");
+        let addr = listener.local_addr().expect("should have an address");
+        let router = Router::new()
+            .route("/hook", post(hook))
+            .with_state(received.clone());
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        (format!("http://{addr}/hook"), received)
+    }
+
+    fn notifier(url: &str, config: &WebhookChannelConfig) -> WebhookNotifier {
+        WebhookNotifier::new("test", url, config, RetryConfig::new(2, 1, 1))
+            .expect("should build notifier")
+    }
+
+    fn notification() -> Notification {
+        Notification::new("Genshin Impact - Original Resin", "Full!")
+    }
+
+    #[tokio::test]
+    async fn discord_preset_posts_content() {
+        let (url, received) = spawn_hook(0).await;
+        let config = WebhookChannelConfig {
+            preset: WebhookPreset::Discord,
+            template: None,
+        };
+
+        notifier(&url, &config)
+            .send(&notification())
+            .await
+            .expect("should deliver");
+
+        assert_eq!(
+            *received.bodies.lock().expect("lock"),
+            vec![serde_json::json!({"content": "**Genshin Impact - Original Resin**\nFull!"})]
+        );
+    }
+
+    #[tokio::test]
+    async fn json_preset_uses_custom_template() {
+        let (url, received) = spawn_hook(0).await;
+        let config = WebhookChannelConfig {
+            preset: WebhookPreset::Json,
+            template: Some("[{title}] {body}".to_string()),
+        };
+
+        notifier(&url, &config)
+            .send(&notification())
+            .await
+            .expect("should deliver");
+
+        let bodies = received.bodies.lock().expect("lock");
+        assert_eq!(
+            bodies.first().and_then(|body| body.get("message")),
+            Some(&serde_json::json!(
+                "[Genshin Impact - Original Resin] Full!"
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn server_errors_are_retried() {
+        let (url, received) = spawn_hook(2).await;
+
+        notifier(&url, &WebhookChannelConfig::default())
+            .send(&notification())
+            .await
+            .expect("should deliver after retries");
+
+        assert_eq!(received.bodies.lock().expect("lock").len(), 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let (url, received) = spawn_hook(5).await;
+
+        let err = notifier(&url, &WebhookChannelConfig::default())
+            .send(&notification())
+            .await
+            .expect_err("should fail");
+
+        assert!(err.to_string().contains("503"), "{err}");
+        assert_eq!(received.bodies.lock().expect("lock").len(), 3);
+    }
+}
@@ -0,0 +1,133 @@
+//! Overcap analytics: how much stamina was lost while resources sat at max.
+//!
+//! Each successful poll feeds stamina resources into an [`OvercapTracker`],
+//! which keeps "full since" intervals between polls. Wasted units are derived
+//! from the time spent at max and the resource's regen rate, summarized per
+//! local day, and optionally sent as a weekly digest notification.
+
+mod store;
+mod tracker;
+
+use crate::i18n;
+use crate::notification::Notification;
+use crate::notification::account_display_name;
+use crate::notification::resource_display_name;
+use crate::state::AppState;
+use crate::state::run_blocking;
+use jiff::Timestamp;
+pub use store::DIGEST_DAYS;
+pub use store::OvercapStore;
+pub use tracker::MAX_SUMMARY_DAYS;
+pub use tracker::OvercapSummary;
+
+/// Builds the weekly digest body, one line per resource that wasted units.
+///
+/// Returns `None` if nothing was wasted.
+pub(crate) fn build_digest_body(summary: &OvercapSummary) -> Option<String> {
+    let lines: Vec<String> = summary
+        .resources
+        .iter()
+        .filter(|resource| resource.wasted_units > 0)
+        .map(|resource| {
+            i18n::t_args(
+                "notification_overcap_digest_line",
+                &[
+                    (
+                        "game_name",
+                        i18n::Value::from(account_display_name(&resource.key.account_key())),
+                    ),
+                    (
+                        "resource_name",
+                        i18n::Value::from(resource_display_name(&resource.key.resource_type)),
+                    ),
+                    (
+                        "amount",
+                        i18n::Value::from(resource.wasted_units.to_string()),
+                    ),
+                ],
+            )
+        })
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Sends the weekly overcap digest if it is enabled and due.
+pub(crate) async fn send_weekly_digest_if_due(state: &AppState) {
+    let Some(overcap) = state.overcap() else {
+        return;
+    };
+    let enabled = {
+        let inner = state.inner.read().await;
+        inner.config.general.overcap_weekly_digest
+    };
+    if !enabled {
+        return;
+    }
+
+    let summary = match run_blocking(move || overcap.take_due_digest(Timestamp::now())).await {
+        Ok(Some(summary)) => summary,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!("Failed to update overcap digest state: {e:#}");
+            return;
+        }
+    };
+    let Some(body) = build_digest_body(&summary) else {
+        tracing::debug!("Skipping weekly overcap digest - nothing wasted");
+        return;
+    };
+
+    tracing::info!("Sending weekly overcap digest");
+    let notification = Notification::new(i18n::t("notification_overcap_digest_title"), body);
+    if let Err(e) = state.notify(&notification).await {
+        tracing::warn!(error = %e, "Failed to send overcap digest notification");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::overcap::tracker::OvercapTracker;
+    use crate::overcap::tracker::ResourceKey;
+    use jiff::tz::TimeZone;
+    use storekeeper_core::AccountKey;
+    use storekeeper_core::GameId;
+    use storekeeper_core::StaminaResource;
+
+    fn ensure_init() {
+        #[expect(
+            clippy::let_underscore_must_use,
+            reason = "test setup may run after i18n is already initialized"
+        )]
+        let _ = crate::i18n::init("en");
+    }
+
+    fn ts(second: i64) -> Timestamp {
+        Timestamp::from_second(second).expect("valid timestamp")
+    }
+
+    #[test]
+    fn digest_body_lists_wasted_resources() {
+        ensure_init();
+        let key = ResourceKey::new(&AccountKey::new(GameId::GenshinImpact, "alt"), "resin");
+        let mut tracker = OvercapTracker::default();
+        tracker.observe(key, &StaminaResource::new(200, 200, ts(0), 480), ts(0));
+        let summary = tracker.summarize(ts(4_800), 1, &TimeZone::UTC);
+
+        let body = build_digest_body(&summary).expect("resin was wasted");
+        assert!(
+            body.contains("10"),
+            "body should include wasted amount: {body}"
+        );
+        assert!(
+            body.contains("(alt)"),
+            "body should name the account: {body}"
+        );
+    }
+
+    #[test]
+    fn digest_body_is_none_without_waste() {
+        let summary = OvercapTracker::default().summarize(ts(0), 7, &TimeZone::UTC);
+        assert!(build_digest_body(&summary).is_none());
+    }
+}
@@ -0,0 +1,220 @@
+//! File-backed overcap tracker.
+
+use super::tracker::OvercapSummary;
+use super::tracker::OvercapTracker;
+use super::tracker::ResourceKey;
+use crate::history::HistoryRecord;
+use camino::Utf8Path;
+use camino::Utf8PathBuf;
+use jiff::SignedDuration;
+use jiff::Timestamp;
+use jiff::tz::TimeZone;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::PoisonError;
+use storekeeper_core::AccountKey;
+use storekeeper_core::AppConfig;
+use storekeeper_core::StaminaResource;
+
+/// Overcap state file name inside the config directory.
+const OVERCAP_FILE_NAME: &str = "overcap.json";
+
+/// Minimum time between weekly digests.
+const DIGEST_INTERVAL: SignedDuration = SignedDuration::from_hours(7 * 24);
+
+/// Days covered by the weekly digest.
+pub const DIGEST_DAYS: u32 = 7;
+
+/// Overcap tracker persisted to a JSON file after every update.
+pub struct OvercapStore {
+    path: Utf8PathBuf,
+    tracker: Mutex<OvercapTracker>,
+}
+
+impl OvercapStore {
+    /// Loads the tracker from a file, starting empty if the file is missing
+    /// or unreadable.
+    #[must_use]
+    pub fn load(path: Utf8PathBuf) -> Self {
+        let tracker = match read_tracker(&path) {
+            Ok(tracker) => tracker,
+            Err(e) => {
+                tracing::warn!(path = %path, "Failed to load overcap state, starting fresh: {e:#}");
+                OvercapTracker::default()
+            }
+        };
+        Self {
+            path,
+            tracker: Mutex::new(tracker),
+        }
+    }
+
+    /// Returns the default overcap state file path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the config directory cannot be determined.
+    pub fn default_path() -> storekeeper_core::Result<Utf8PathBuf> {
+        Ok(AppConfig::config_dir()?.join(OVERCAP_FILE_NAME))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, OvercapTracker> {
+        self.tracker.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Feeds every stamina resource of a fetch result into the tracker and
+    /// saves it.
+    ///
+    /// Resources that don't deserialize as [`StaminaResource`] are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state file cannot be written.
+    pub fn observe_fetch(
+        &self,
+        fetched: &HashMap<AccountKey, serde_json::Value>,
+        fetched_at: Timestamp,
+    ) -> anyhow::Result<()> {
+        let mut tracker = self.lock();
+        for (account, resources) in fetched {
+            for record in HistoryRecord::from_fetch(account, resources, fetched_at) {
+                let Ok(stamina) = serde_json::from_value::<StaminaResource>(record.data) else {
+                    continue;
+                };
+                let key = ResourceKey::new(account, &record.resource_type);
+                tracker.observe(key, &stamina, fetched_at);
+            }
+        }
+        tracker.prune(fetched_at);
+        write_tracker(&self.path, &tracker)
+    }
+
+    /// Summarizes the last `days` local days.
+    #[must_use]
+    pub fn summarize(&self, now: Timestamp, days: u32) -> OvercapSummary {
+        self.lock().summarize(now, days, &TimeZone::system())
+    }
+
+    /// Returns the weekly digest summary if one is due, and marks it sent.
+    ///
+    /// The first call only starts the weekly clock, so enabling digests never
+    /// sends one immediately.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the state file cannot be written.
+    pub fn take_due_digest(&self, now: Timestamp) -> anyhow::Result<Option<OvercapSummary>> {
+        let mut tracker = self.lock();
+        let due = match tracker.last_digest_at {
+            Some(last) if now.duration_since(last) < DIGEST_INTERVAL => return Ok(None),
+            Some(_) => true,
+            None => false,
+        };
+
+        tracker.last_digest_at = Some(now);
+        write_tracker(&self.path, &tracker)?;
+        Ok(due.then(|| tracker.summarize(now, DIGEST_DAYS, &TimeZone::system())))
+    }
+}
+
+fn read_tracker(path: &Utf8Path) -> anyhow::Result<OvercapTracker> {
+    if !path.exists() {
+        return Ok(OvercapTracker::default());
+    }
+    let content = fs_err::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+fn write_tracker(path: &Utf8Path, tracker: &OvercapTracker) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs_err::create_dir_all(parent)?;
+    }
+    let content = serde_json::to_string(tracker)?;
+    let tmp_path = path.with_extension("json.tmp");
+    fs_err::write(&tmp_path, content)?;
+    fs_err::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU32;
+    use std::sync::atomic::Ordering;
+    use storekeeper_core::GameId;
+
+    fn temp_path(tag: &str) -> Utf8PathBuf {
+        static COUNTER: AtomicU32 = AtomicU32::new(0);
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .expect("temp dir should be UTF-8")
+            .join(format!(
+                "storekeeper-overcap-{tag}-{}-{n}",
+                std::process::id()
+            ))
+            .join(OVERCAP_FILE_NAME)
+    }
+
+    fn cleanup(path: &Utf8Path) {
+        if let Some(dir) = path.parent()
+            && let Err(err) = fs_err::remove_dir_all(dir)
+        {
+            eprintln!("failed to clean up temp dir {dir}: {err}");
+        }
+    }
+
+    fn ts(second: i64) -> Timestamp {
+        Timestamp::from_second(second).expect("valid timestamp")
+    }
+
+    #[test]
+    fn observe_fetch_tracks_only_stamina_and_persists() {
+        let path = temp_path("observe");
+        let store = OvercapStore::load(path.clone());
+        let fetched = HashMap::from([(
+            AccountKey::default_for(GameId::HonkaiStarRail),
+            serde_json::json!([
+                {"type": "trailblazePower", "data": {
+                    "current": 240, "max": 240, "fullAt": ts(0).to_string(), "regenRateSeconds": 360
+                }},
+                {"type": "expeditions", "data": {
+                    "currentExpeditions": 4, "maxExpeditions": 4, "earliestFinishAt": ts(0).to_string()
+                }},
+            ]),
+        )]);
+        store.observe_fetch(&fetched, ts(0)).expect("observe");
+
+        let reloaded = OvercapStore::load(path.clone());
+        let summary = reloaded.summarize(ts(3_600), 1);
+        let types: Vec<_> = summary
+            .resources
+            .iter()
+            .map(|r| r.key.resource_type.as_str())
+            .collect();
+        assert_eq!(types, ["trailblazePower"]);
+        cleanup(&path);
+    }
+
+    #[test]
+    fn digest_is_due_weekly_after_first_call() {
+        let path = temp_path("digest");
+        let store = OvercapStore::load(path.clone());
+        assert!(store.take_due_digest(ts(0)).expect("digest").is_none());
+        assert!(store.take_due_digest(ts(86_400)).expect("digest").is_none());
+        assert!(
+            store
+                .take_due_digest(ts(7 * 86_400))
+                .expect("digest")
+                .is_some()
+        );
+        assert!(
+            store
+                .take_due_digest(ts(7 * 86_400 + 60))
+                .expect("digest")
+                .is_none(),
+            "digest clock restarts after sending"
+        );
+        cleanup(&path);
+    }
+}
@@ -0,0 +1,384 @@
+//! Tracking of intervals during which stamina resources sat at max.
+
+use jiff::SignedDuration;
+use jiff::Span;
+use jiff::Timestamp;
+use jiff::civil::Date;
+use jiff::tz::TimeZone;
+use serde::Deserialize;
+use serde::Serialize;
+use storekeeper_core::AccountKey;
+use storekeeper_core::GameId;
+use storekeeper_core::StaminaResource;
+
+/// Longest summary window, in days.
+pub const MAX_SUMMARY_DAYS: u32 = 8 * 7;
+
+/// How long closed intervals are kept (`MAX_SUMMARY_DAYS`).
+const INTERVAL_RETENTION: SignedDuration = SignedDuration::from_hours(8 * 7 * 24);
+
+/// Identifies one resource type of one account.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceKey {
+    /// Game the resource belongs to.
+    pub game_id: GameId,
+    /// Account the resource belongs to.
+    pub account: String,
+    /// Resource type tag (e.g. `"resin"`).
+    #[serde(rename = "type")]
+    pub resource_type: String,
+}
+
+impl ResourceKey {
+    /// Creates a key for a resource type of an account.
+    #[must_use]
+    pub fn new(account: &AccountKey, resource_type: &str) -> Self {
+        Self {
+            game_id: account.game_id,
+            account: account.account.clone(),
+            resource_type: resource_type.to_owned(),
+        }
+    }
+
+    /// Returns the account this resource belongs to.
+    #[must_use]
+    pub fn account_key(&self) -> AccountKey {
+        AccountKey::new(self.game_id, self.account.as_str())
+    }
+}
+
+/// Between-poll state of one stamina resource.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResourceState {
+    #[serde(flatten)]
+    key: ResourceKey,
+    /// Start of the open full interval, if the resource is currently full.
+    full_since: Option<Timestamp>,
+    /// Last poll that saw the resource full.
+    last_full_seen: Option<Timestamp>,
+    /// When the last non-full poll predicted the resource would fill.
+    expected_full_at: Option<Timestamp>,
+    /// Seconds per regenerated unit, from the latest poll.
+    regen_rate_seconds: u32,
+}
+
+/// A closed interval during which a resource sat at max.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FullInterval {
+    #[serde(flatten)]
+    key: ResourceKey,
+    start: Timestamp,
+    end: Timestamp,
+    regen_rate_seconds: u32,
+}
+
+impl FullInterval {
+    /// Returns the overlap with `[from, to)` in whole seconds.
+    fn overlap_secs(&self, from: Timestamp, to: Timestamp) -> u64 {
+        let start = self.start.max(from);
+        let end = self.end.min(to);
+        u64::try_from(end.duration_since(start).as_secs()).unwrap_or(0)
+    }
+
+    /// Returns the units regenerated (and lost) during the overlap with
+    /// `[from, to)`.
+    fn wasted_units(&self, from: Timestamp, to: Timestamp) -> u64 {
+        if self.regen_rate_seconds == 0 {
+            return 0;
+        }
+        self.overlap_secs(from, to) / u64::from(self.regen_rate_seconds)
+    }
+}
+
+/// Time at max and units wasted for one resource within one local day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyOvercap {
+    /// Local calendar date.
+    pub date: Date,
+    /// Seconds spent at max.
+    pub full_secs: u64,
+    /// Units that would have regenerated while at max.
+    pub wasted_units: u64,
+}
+
+/// Overcap totals for one resource over a summary window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceOvercap {
+    /// The resource these totals belong to.
+    #[serde(flatten)]
+    pub key: ResourceKey,
+    /// Seconds spent at max over the whole window.
+    pub full_secs: u64,
+    /// Units wasted over the whole window.
+    pub wasted_units: u64,
+    /// Per-day breakdown, oldest first.
+    pub daily: Vec<DailyOvercap>,
+}
+
+/// Overcap totals for every tracked resource over a window of local days.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OvercapSummary {
+    /// Start of the window (local midnight).
+    pub from: Timestamp,
+    /// End of the window (the time the summary was computed).
+    pub to: Timestamp,
+    /// Totals per resource, sorted by game, account and resource type.
+    pub resources: Vec<ResourceOvercap>,
+}
+
+/// Persistent overcap tracking state.
+///
+/// Fed one observation per stamina resource per successful poll. Because the
+/// app only sees the resource at poll times, intervals are conservative: an
+/// interval starts at the predicted fill time (or the first poll that saw the
+/// resource full) and ends at the last poll that still saw it full.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OvercapTracker {
+    #[serde(default)]
+    resources: Vec<ResourceState>,
+    #[serde(default)]
+    intervals: Vec<FullInterval>,
+    /// When the last weekly digest was sent (or digests were first enabled).
+    #[serde(default)]
+    pub last_digest_at: Option<Timestamp>,
+}
+
+impl OvercapTracker {
+    /// Records one poll result for a stamina resource.
+    pub fn observe(&mut self, key: ResourceKey, stamina: &StaminaResource, observed_at: Timestamp) {
+        let index = if let Some(index) = self.resources.iter().position(|state| state.key == key) {
+            index
+        } else {
+            self.resources.push(ResourceState {
+                key,
+                full_since: None,
+                last_full_seen: None,
+                expected_full_at: None,
+                regen_rate_seconds: stamina.regen_rate_seconds,
+            });
+            self.resources.len().saturating_sub(1)
+        };
+        let Some(state) = self.resources.get_mut(index) else {
+            return;
+        };
+        state.regen_rate_seconds = stamina.regen_rate_seconds;
+
+        if stamina.is_full() {
+            if state.full_since.is_none() {
+                // Prefer the fill time predicted by the previous poll; the API
+                // reports "now" as the fill time for an already-full resource.
+                let predicted = state
+                    .expected_full_at
+                    .unwrap_or(stamina.full_at)
+                    .min(observed_at);
+                state.full_since = Some(predicted);
+            }
+            state.last_full_seen = Some(observed_at);
+            state.expected_full_at = None;
+            return;
+        }
+
+        if let (Some(start), Some(end)) = (state.full_since.take(), state.last_full_seen.take())
+            && end > start
+        {
+            self.intervals.push(FullInterval {
+                key: state.key.clone(),
+                start,
+                end,
+                regen_rate_seconds: state.regen_rate_seconds,
+            });
+        }
+        state.expected_full_at = Some(stamina.full_at);
+    }
+
+    /// Drops closed intervals that ended before the retention window.
+    pub fn prune(&mut self, now: Timestamp) {
+        let Ok(cutoff) = now.checked_sub(INTERVAL_RETENTION) else {
+            return;
+        };
+        self.intervals.retain(|interval| interval.end >= cutoff);
+    }
+
+    /// Closed intervals plus open intervals extended up to `now`.
+    fn intervals_until(&self, now: Timestamp) -> Vec<FullInterval> {
+        let open = self.resources.iter().filter_map(|state| {
+            state.full_since.map(|start| FullInterval {
+                key: state.key.clone(),
+                start,
+                end: now,
+                regen_rate_seconds: state.regen_rate_seconds,
+            })
+        });
+        self.intervals.iter().cloned().chain(open).collect()
+    }
+
+    /// Summarizes time at max and wasted units over the last `days` local
+    /// days (including today).
+    #[must_use]
+    pub fn summarize(&self, now: Timestamp, days: u32, tz: &TimeZone) -> OvercapSummary {
+        let day_starts = local_day_starts(now, days.max(1), tz);
+        let from = day_starts.first().map_or(now, |(_, start)| *start);
+        let intervals = self.intervals_until(now);
+
+        let mut keys: Vec<&ResourceKey> = self
+            .resources
+            .iter()
+            .map(|state| &state.key)
+            .chain(intervals.iter().map(|interval| &interval.key))
+            .collect();
+        keys.sort();
+        keys.dedup();
+
+        let resources = keys
+            .into_iter()
+            .map(|key| {
+                let relevant: Vec<&FullInterval> =
+                    intervals.iter().filter(|i| &i.key == key).collect();
+                let daily: Vec<DailyOvercap> = day_starts
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (date, start))| {
+                        let end = day_starts.get(i + 1).map_or(now, |(_, next)| *next);
+                        DailyOvercap {
+                            date: *date,
+                            full_secs: relevant.iter().map(|r| r.overlap_secs(*start, end)).sum(),
+                            wasted_units: relevant
+                                .iter()
+                                .map(|r| r.wasted_units(*start, end))
+                                .sum(),
+                        }
+                    })
+                    .collect();
+                ResourceOvercap {
+                    key: key.clone(),
+                    full_secs: daily.iter().map(|d| d.full_secs).sum(),
+                    wasted_units: daily.iter().map(|d| d.wasted_units).sum(),
+                    daily,
+                }
+            })
+            .collect();
+
+        OvercapSummary {
+            from,
+            to: now,
+            resources,
+        }
+    }
+}
+
+/// Returns `(date, local midnight)` for the last `days` local days, oldest
+/// first.
+fn local_day_starts(now: Timestamp, days: u32, tz: &TimeZone) -> Vec<(Date, Timestamp)> {
+    let today = now.to_zoned(tz.clone()).date();
+    (0..days)
+        .rev()
+        .filter_map(|days_ago| {
+            let date = today.checked_sub(Span::new().days(days_ago)).ok()?;
+            let start = date.to_zoned(tz.clone()).ok()?.timestamp();
+            Some((date, start))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RATE: u32 = 480;
+
+    fn ts(second: i64) -> Timestamp {
+        Timestamp::from_second(second).expect("valid timestamp")
+    }
+
+    fn key() -> ResourceKey {
+        ResourceKey::new(&AccountKey::default_for(GameId::GenshinImpact), "resin")
+    }
+
+    fn stamina(current: u32, full_at: Timestamp) -> StaminaResource {
+        StaminaResource::new(current, 200, full_at, RATE)
+    }
+
+    #[test]
+    fn interval_starts_at_predicted_fill_time() {
+        let mut tracker = OvercapTracker::default();
+        // Filling, predicted full at t=1000
+        tracker.observe(key(), &stamina(190, ts(1_000)), ts(0));
+        // Seen full at t=2000 and t=5000, spent before t=6000
+        tracker.observe(key(), &stamina(200, ts(2_000)), ts(2_000));
+        tracker.observe(key(), &stamina(200, ts(5_000)), ts(5_000));
+        tracker.observe(key(), &stamina(40, ts(90_000)), ts(6_000));
+
+        assert_eq!(tracker.intervals.len(), 1);
+        let interval = tracker.intervals.first().expect("closed interval");
+        assert_eq!((interval.start, interval.end), (ts(1_000), ts(5_000)));
+        assert_eq!(interval.wasted_units(ts(0), ts(10_000)), 4_000 / 480);
+    }
+
+    #[test]
+    fn first_full_observation_without_prediction_starts_now() {
+        let mut tracker = OvercapTracker::default();
+        tracker.observe(key(), &stamina(200, ts(500)), ts(500));
+        let summary = tracker.summarize(ts(500 + 960), 1, &TimeZone::UTC);
+        let resin = summary.resources.first().expect("resin tracked");
+        assert_eq!(resin.full_secs, 960);
+        assert_eq!(resin.wasted_units, 2, "open interval counts up to now");
+    }
+
+    #[test]
+    fn never_full_resource_wastes_nothing() {
+        let mut tracker = OvercapTracker::default();
+        tracker.observe(key(), &stamina(10, ts(80_000)), ts(0));
+        tracker.observe(key(), &stamina(20, ts(80_000)), ts(4_800));
+        assert!(tracker.intervals.is_empty());
+        let summary = tracker.summarize(ts(5_000), 1, &TimeZone::UTC);
+        assert_eq!(summary.resources.first().map(|r| r.wasted_units), Some(0));
+    }
+
+    #[test]
+    fn summary_splits_intervals_across_local_days() {
+        let day = 86_400;
+        let mut tracker = OvercapTracker::default();
+        tracker.intervals.push(FullInterval {
+            key: key(),
+            start: ts(day - 4_800),
+            end: ts(day + 9_600),
+            regen_rate_seconds: RATE,
+        });
+
+        let summary = tracker.summarize(ts(2 * day), 3, &TimeZone::UTC);
+        assert_eq!(summary.from, ts(0));
+        let resin = summary.resources.first().expect("resin summarized");
+        let wasted: Vec<_> = resin.daily.iter().map(|d| d.wasted_units).collect();
+        assert_eq!(wasted, [10, 20, 0]);
+        assert_eq!(resin.wasted_units, 30);
+    }
+
+    #[test]
+    fn prune_drops_old_intervals() {
+        let mut tracker = OvercapTracker::default();
+        tracker.intervals.push(FullInterval {
+            key: key(),
+            start: ts(0),
+            end: ts(10),
+            regen_rate_seconds: RATE,
+        });
+        tracker.prune(ts(100 * 86_400));
+        assert!(tracker.intervals.is_empty());
+    }
+
+    #[test]
+    fn tracker_serde_roundtrip() {
+        let mut tracker = OvercapTracker::default();
+        tracker.observe(key(), &stamina(200, ts(0)), ts(0));
+        let json = serde_json::to_string(&tracker).expect("serialize");
+        let restored: OvercapTracker = serde_json::from_str(&json).expect("deserialize");
+        assert_eq!(restored, tracker);
+    }
+}
@@ -0,0 +1,382 @@
+//! Per-game poll scheduling.
+//!
+//! Each game is polled every `poll_interval_secs`, its own if set, counted
+//! from its last poll, so games are fetched independently of each other.
+//!
+//! With `[adaptive_polling]` enabled, each game is instead polled shortly
+//! before the next notification threshold one of its resources is predicted to
+//! cross, so the reading that triggers a notification is fresh, and only every
+//! `max_interval_secs` while nothing is close. Games sharing an API provider
+//! are polled together when they come due close to each other, so the provider
+//! is not hit once per game.
+
+use crate::notification::resolve_notifiable_resource;
+use crate::notification::window_start;
+use crate::state::AllResources;
+use jiff::SignedDuration;
+use jiff::Timestamp;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use storekeeper_core::AppConfig;
+use storekeeper_core::GameId;
+use storekeeper_core::NotificationRule;
+use storekeeper_core::config::GamesConfig;
+
+/// The next poll of one game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlannedPoll {
+    /// When the game should be polled.
+    pub at: Timestamp,
+    /// Earliest time the game may be polled, `min_interval_secs` after its
+    /// last poll.
+    pub earliest: Timestamp,
+    /// The game's minimum interval between polls.
+    pub min_interval: SignedDuration,
+}
+
+/// How long to wait before polling a game again after its poll failed.
+const RETRY_DELAY: SignedDuration = SignedDuration::from_secs(60);
+
+/// When each game was last polled.
+#[derive(Debug, Default)]
+pub struct PollSchedule {
+    last_polled: HashMap<GameId, Timestamp>,
+    /// When each game whose last poll failed is retried.
+    retry_at: HashMap<GameId, Timestamp>,
+}
+
+impl PollSchedule {
+    /// Records that `games` were polled at `now`.
+    pub fn record(&mut self, games: impl IntoIterator<Item = GameId>, now: Timestamp) {
+        for game_id in games {
+            self.last_polled.insert(game_id, now);
+            self.retry_at.remove(&game_id);
+        }
+    }
+
+    /// Records that polling `games` failed at `now`, so they are retried
+    /// shortly rather than a full interval later.
+    pub fn record_failure(&mut self, games: impl IntoIterator<Item = GameId>, now: Timestamp) {
+        for game_id in games {
+            self.retry_at.insert(game_id, add(now, RETRY_DELAY));
+        }
+    }
+
+    /// Plans the next poll of each of `games`, from the cached resources when
+    /// adaptive polling is enabled.
+    ///
+    /// A game that was never polled is due right away, and one whose last
+    /// poll failed once its retry delay has passed.
+    #[must_use]
+    pub fn plan(
+        &self,
+        config: &AppConfig,
+        resources: &AllResources,
+        games: &HashSet<GameId>,
+        now: Timestamp,
+    ) -> BTreeMap<GameId, PlannedPoll> {
+        games
+            .iter()
+            .map(|&game_id| {
+                let last = self
+                    .last_polled
+                    .get(&game_id)
+                    .copied()
+                    .unwrap_or(Timestamp::MIN);
+                let mut poll = if config.adaptive_polling.enabled {
+                    plan_adaptive(config, resources, game_id, last, now)
+                } else {
+                    let at = add(last, secs(config.poll_interval_for(game_id)));
+                    PlannedPoll {
+                        at,
+                        earliest: at,
+                        min_interval: SignedDuration::ZERO,
+                    }
+                };
+                if let Some(&retry_at) = self.retry_at.get(&game_id) {
+                    poll.at = poll.at.max(retry_at);
+                    poll.earliest = poll.earliest.max(retry_at);
+                }
+                (game_id, poll)
+            })
+            .collect()
+    }
+}
+
+/// Plans the next adaptive poll of a game last polled at `last`.
+fn plan_adaptive(
+    config: &AppConfig,
+    resources: &AllResources,
+    game_id: GameId,
+    last: Timestamp,
+    now: Timestamp,
+) -> PlannedPoll {
+    let lead = secs(config.adaptive_polling.lead_secs);
+    let (min, max) = config.adaptive_poll_bounds(game_id);
+    let (min, max) = (secs(min), secs(max));
+    let earliest = add(last, min);
+    let latest = add(last, max);
+    let at = next_crossing(&config.games, resources, game_id, now).map_or(latest, |crossing| {
+        crossing
+            .checked_sub(lead)
+            .unwrap_or(Timestamp::MIN)
+            .clamp(earliest, latest)
+    });
+    PlannedPoll {
+        at,
+        earliest,
+        min_interval: min,
+    }
+}
+
+/// Returns the games to poll at `now`: those due, and those sharing an API
+/// provider with a due game whose own poll would follow within its minimum
+/// interval anyway.
+#[must_use]
+pub fn due_games(plan: &BTreeMap<GameId, PlannedPoll>, now: Timestamp) -> HashSet<GameId> {
+    let due: HashSet<GameId> = plan
+        .iter()
+        .filter(|(_, poll)| poll.at <= now)
+        .map(|(&game_id, _)| game_id)
+        .collect();
+    let providers: HashSet<_> = due.iter().map(GameId::api_provider).collect();
+    plan.iter()
+        .filter(|&(game_id, poll)| {
+            due.contains(game_id)
+                || (providers.contains(&game_id.api_provider())
+                    && poll.earliest <= now
+                    && poll.at <= add(now, poll.min_interval))
+        })
+        .map(|(&game_id, _)| game_id)
+        .collect()
+}
+
+/// Returns when the next notification rule of a game's cached resources is
+/// predicted to come due.
+///
+/// That is the earliest window start still ahead of `now`, or the completion
+/// of a resource that isn't complete yet for a rule that only fires once it is,
+/// since only a poll can tell. Rules whose window is already open fire from
+/// the cached data and don't need a poll.
+#[must_use]
+pub fn next_crossing(
+    games_config: &GamesConfig,
+    resources: &AllResources,
+    game_id: GameId,
+    now: Timestamp,
+) -> Option<Timestamp> {
+    resources
+        .games
+        .iter()
+        .filter(|(account, _)| account.game_id == game_id)
+        .flat_map(|(account, resources_json)| {
+            resources_json
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(move |resource_obj| {
+                    let (_, config, info) =
+                        resolve_notifiable_resource(resource_obj, games_config, &account)?;
+                    if info.is_complete {
+                        return None;
+                    }
+                    config
+                        .effective_rules()
+                        .iter()
+                        .filter_map(|rule| {
+                            let start = window_start(rule, &info)?;
+                            (fires_only_when_complete(rule) || start > now).then_some(start)
+                        })
+                        .min()
+                })
+        })
+        .min()
+}
+
+fn fires_only_when_complete(rule: &NotificationRule) -> bool {
+    rule.notify_at_value.is_none() && rule.notify_minutes_before_full.is_none()
+}
+
+fn secs(secs: u64) -> SignedDuration {
+    SignedDuration::from_secs(i64::try_from(secs).unwrap_or(i64::MAX))
+}
+
+fn add(timestamp: Timestamp, duration: SignedDuration) -> Timestamp {
+    timestamp.checked_add(duration).unwrap_or(Timestamp::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use storekeeper_core::AccountKey;
+    use storekeeper_core::StaminaResource;
+
+    /// Adaptive polling with the default bounds but `min_interval_secs`, and a
+    /// Genshin resin notification at 180.
+    fn resin_at_180(min_interval_secs: u64) -> AppConfig {
+        serde_json::from_value(serde_json::json!({
+            "adaptive_polling": {
+                "enabled": true,
+                "min_interval_secs": min_interval_secs,
+            },
+            "games": {
+                "genshin_impact": {
+                    "uid": "800000001",
+                    "notifications": {"resin": {"notify_at_value": 180}},
+                },
+            },
+        }))
+        .expect("should parse config")
+    }
+
+    /// Genshin resin at `current`/200, regenerating one unit every 8 minutes.
+    fn resin(current: u32, now: Timestamp) -> AllResources {
+        let mut resources = AllResources::default();
+        let full_at = now + SignedDuration::from_mins(i64::from((200 - current) * 8));
+        resources.record(
+            AccountKey::default_for(GameId::GenshinImpact),
+            serde_json::json!([{
+                "type": "resin",
+                "data": StaminaResource::new(current, 200, full_at, 480),
+            }]),
+            now,
+        );
+        resources
+    }
+
+    #[test]
+    fn crossing_is_when_the_threshold_is_reached() {
+        let now = Timestamp::now();
+        let config = resin_at_180(60);
+
+        // 20 units at 8 minutes each.
+        assert_eq!(
+            next_crossing(&config.games, &resin(160, now), GameId::GenshinImpact, now),
+            Some(now + SignedDuration::from_mins(160))
+        );
+        // Already past the threshold: the window is open, nothing to predict.
+        assert_eq!(
+            next_crossing(&config.games, &resin(190, now), GameId::GenshinImpact, now),
+            None
+        );
+        assert_eq!(
+            next_crossing(&config.games, &resin(160, now), GameId::HonkaiStarRail, now),
+            None
+        );
+    }
+
+    #[test]
+    fn poll_comes_before_the_crossing_within_bounds() {
+        let now = Timestamp::now();
+        let config = resin_at_180(60);
+        let games = HashSet::from([GameId::GenshinImpact]);
+        let mut schedule = PollSchedule::default();
+
+        // Never polled: due right away.
+        let plan = schedule.plan(&config, &resin(160, now), &games, now);
+        assert!(
+            plan.get(&GameId::GenshinImpact)
+                .is_some_and(|poll| poll.at <= now)
+        );
+
+        schedule.record([GameId::GenshinImpact], now);
+        // Crossing in 160 minutes is past the 30 minute maximum.
+        let plan = schedule.plan(&config, &resin(160, now), &games, now);
+        assert_eq!(
+            plan.get(&GameId::GenshinImpact).map(|poll| poll.at),
+            Some(now + SignedDuration::from_mins(30))
+        );
+        // Crossing in 16 minutes: poll 2 minutes before it.
+        let plan = schedule.plan(&config, &resin(178, now), &games, now);
+        assert_eq!(
+            plan.get(&GameId::GenshinImpact).map(|poll| poll.at),
+            Some(now + SignedDuration::from_mins(14))
+        );
+        // Crossing in 8 minutes: no sooner than a 10 minute minimum.
+        let config = resin_at_180(600);
+        let plan = schedule.plan(&config, &resin(179, now), &games, now);
+        assert_eq!(
+            plan.get(&GameId::GenshinImpact).map(|poll| poll.at),
+            Some(now + SignedDuration::from_mins(10))
+        );
+    }
+
+    #[test]
+    fn fixed_polls_follow_each_games_interval() {
+        let now = Timestamp::now();
+        let config: AppConfig = serde_json::from_value(serde_json::json!({
+            "general": {"poll_interval_secs": 300},
+            "games": {
+                "wuthering_waves": {"uid": "500000001", "poll_interval_secs": 900},
+            },
+        }))
+        .expect("should parse config");
+        let games = HashSet::from([GameId::GenshinImpact, GameId::WutheringWaves]);
+        let mut schedule = PollSchedule::default();
+        schedule.record([GameId::GenshinImpact, GameId::WutheringWaves], now);
+
+        let plan = schedule.plan(&config, &resin(160, now), &games, now);
+        assert_eq!(
+            plan.get(&GameId::GenshinImpact).map(|poll| poll.at),
+            Some(now + SignedDuration::from_mins(5))
+        );
+        assert_eq!(
+            plan.get(&GameId::WutheringWaves).map(|poll| poll.at),
+            Some(now + SignedDuration::from_mins(15))
+        );
+        assert_eq!(
+            due_games(&plan, now + SignedDuration::from_mins(5)),
+            HashSet::from([GameId::GenshinImpact])
+        );
+    }
+
+    #[test]
+    fn failed_polls_are_retried_after_a_short_delay() {
+        let now = Timestamp::now();
+        let config = AppConfig::default();
+        let games = HashSet::from([GameId::GenshinImpact]);
+        let mut schedule = PollSchedule::default();
+        schedule.record([GameId::GenshinImpact], now);
+
+        let later = now + SignedDuration::from_hours(1);
+        schedule.record_failure([GameId::GenshinImpact], later);
+        let plan = schedule.plan(&config, &AllResources::default(), &games, later);
+        assert_eq!(
+            plan.get(&GameId::GenshinImpact).map(|poll| poll.at),
+            Some(later + RETRY_DELAY)
+        );
+
+        let retried = later + RETRY_DELAY;
+        schedule.record([GameId::GenshinImpact], retried);
+        let plan = schedule.plan(&config, &AllResources::default(), &games, retried);
+        assert_eq!(
+            plan.get(&GameId::GenshinImpact).map(|poll| poll.at),
+            Some(retried + secs(config.general.poll_interval_secs))
+        );
+    }
+
+    #[test]
+    fn games_of_one_provider_are_polled_together() {
+        let now = Timestamp::now();
+        let poll = |at_mins: i64, earliest_mins: i64| PlannedPoll {
+            at: now + SignedDuration::from_mins(at_mins),
+            earliest: now + SignedDuration::from_mins(earliest_mins),
+            min_interval: SignedDuration::from_mins(5),
+        };
+        let plan = BTreeMap::from([
+            (GameId::GenshinImpact, poll(0, -1)),
+            // Due within its minimum interval: joins Genshin.
+            (GameId::HonkaiStarRail, poll(4, -1)),
+            // Due later: waits for its own poll.
+            (GameId::ZenlessZoneZero, poll(20, -1)),
+            // Other provider.
+            (GameId::WutheringWaves, poll(1, -1)),
+        ]);
+
+        assert_eq!(
+            due_games(&plan, now),
+            HashSet::from([GameId::GenshinImpact, GameId::HonkaiStarRail])
+        );
+    }
+}
@@ -0,0 +1,490 @@
+//! Background polling for periodic resource updates.
+
+use crate::events::AppEvent;
+use crate::notification;
+use crate::poll_schedule;
+use crate::poll_schedule::PlannedPoll;
+use crate::poll_schedule::PollSchedule;
+use crate::registry::FetchOutcome;
+use crate::state::AllResources;
+use crate::state::AppState;
+use jiff::Timestamp;
+use std::collections::BTreeMap;
+use std::collections::HashSet;
+use std::time::Duration;
+use storekeeper_core::AccountKey;
+use storekeeper_core::GameId;
+use tokio_util::sync::CancellationToken;
+
+/// RAII guard that releases the refresh slots of its games on drop.
+struct RefreshGuard<'a> {
+    state: &'a AppState,
+    games: HashSet<GameId>,
+}
+
+impl Drop for RefreshGuard<'_> {
+    fn drop(&mut self) {
+        self.state.finish_refresh(&self.games);
+    }
+}
+
+/// Tries to acquire the refresh slot of every game in `games`, returning a
+/// guard on success.
+fn try_acquire_refresh(state: &AppState, games: HashSet<GameId>) -> Option<RefreshGuard<'_>> {
+    if state.try_start_refresh(&games) {
+        Some(RefreshGuard { state, games })
+    } else {
+        None
+    }
+}
+
+/// Tries to acquire the refresh slot of every game.
+fn try_acquire_refresh_all(state: &AppState) -> Option<RefreshGuard<'_>> {
+    try_acquire_refresh(state, GameId::all().iter().copied().collect())
+}
+
+/// Runs the background polling loop until cancelled.
+///
+/// Periodically fetches resources from the configured game APIs and publishes
+/// update events. Each game is polled as scheduled by [`PollSchedule`]: every
+/// `poll_interval_secs`, its own if set, or, with `[adaptive_polling]`
+/// enabled, ahead of its next notification threshold.
+pub async fn run_polling(state: AppState, cancel_token: CancellationToken) {
+    let games = state.configured_games().await;
+    {
+        let inner = state.inner.read().await;
+        let poll_intervals_secs: BTreeMap<GameId, u64> = games
+            .into_iter()
+            .map(|game_id| (game_id, inner.config.poll_interval_for(game_id)))
+            .collect();
+        tracing::info!(
+            ?poll_intervals_secs,
+            adaptive = inner.config.adaptive_polling.enabled,
+            "Starting background polling task"
+        );
+    }
+
+    // Initial fetch after short delay
+    tokio::time::sleep(Duration::from_secs(2)).await;
+
+    // Do an initial fetch on startup
+    tracing::debug!("Performing initial resource fetch");
+    let started = Timestamp::now();
+    try_refresh(&state).await;
+    let mut schedule = PollSchedule::default();
+    record_polls(
+        &state,
+        &mut schedule,
+        state.configured_games().await,
+        started,
+    )
+    .await;
+
+    loop {
+        let delay = poll_delay(&state, &schedule).await;
+        tokio::select! {
+            () = cancel_token.cancelled() => {
+                tracing::info!("Background polling cancelled");
+                break;
+            }
+            () = tokio::time::sleep(delay) => {
+                poll_due_games(&state, &mut schedule).await;
+            }
+        }
+    }
+}
+
+/// Plans the next poll of every configured game not paused by a Geetest
+/// challenge.
+async fn plan_polls(
+    state: &AppState,
+    schedule: &PollSchedule,
+    now: Timestamp,
+) -> BTreeMap<GameId, PlannedPoll> {
+    let paused = state.paused_games().await;
+    let games: HashSet<GameId> = state
+        .configured_games()
+        .await
+        .difference(&paused)
+        .copied()
+        .collect();
+    let resources = state.get_resources().await;
+    let inner = state.inner.read().await;
+    schedule.plan(&inner.config, &resources, &games, now)
+}
+
+/// Returns how long to sleep until the next poll is due.
+///
+/// Without any configured game, waits the global poll interval, or the
+/// global maximum interval of adaptive polling.
+async fn poll_delay(state: &AppState, schedule: &PollSchedule) -> Duration {
+    let now = Timestamp::now();
+    let next = plan_polls(state, schedule, now)
+        .await
+        .into_values()
+        .map(|poll| poll.at)
+        .min();
+    if let Some(at) = next {
+        Duration::try_from(at.duration_since(now)).unwrap_or(Duration::ZERO)
+    } else {
+        let inner = state.inner.read().await;
+        let adaptive = &inner.config.adaptive_polling;
+        Duration::from_secs(if adaptive.enabled {
+            adaptive.max_interval_secs
+        } else {
+            inner.config.general.poll_interval_secs
+        })
+    }
+}
+
+/// Polls the games whose poll is due, if any.
+///
+/// Due games that are already being refreshed are skipped but still recorded
+/// as polled, since that refresh fetches them anyway. Games whose fetch failed
+/// are retried after a short delay rather than a full interval.
+async fn poll_due_games(state: &AppState, schedule: &mut PollSchedule) {
+    let now = Timestamp::now();
+    let due = poll_schedule::due_games(&plan_polls(state, schedule, now).await, now);
+    if due.is_empty() {
+        return;
+    }
+    tracing::debug!(games = ?due, "Scheduled poll triggered");
+    let available: HashSet<GameId> = due
+        .iter()
+        .copied()
+        .filter(|&game_id| state.try_start_refresh(&HashSet::from([game_id])))
+        .collect();
+    if available.len() < due.len() {
+        tracing::debug!(
+            games = ?due.difference(&available).collect::<Vec<_>>(),
+            "Skipping poll of games already being refreshed"
+        );
+    }
+    if !available.is_empty() {
+        let refresh_guard = RefreshGuard {
+            state,
+            games: available,
+        };
+        merge_resources_for_games(state, &refresh_guard.games).await;
+    }
+    record_polls(state, schedule, due, now).await;
+}
+
+/// Records `games` as polled, except those with an account whose fetch failed
+/// since `started`, which are retried shortly instead.
+async fn record_polls(
+    state: &AppState,
+    schedule: &mut PollSchedule,
+    games: HashSet<GameId>,
+    started: Timestamp,
+) {
+    let resources = state.get_resources().await;
+    let failed: HashSet<GameId> = resources
+        .fetch_status
+        .iter()
+        .filter(|(key, status)| {
+            games.contains(&key.game_id)
+                && status.error.is_some()
+                && status.last_attempt.is_some_and(|at| at >= started)
+        })
+        .map(|(key, _)| key.game_id)
+        .collect();
+    let now = Timestamp::now();
+    if !failed.is_empty() {
+        tracing::debug!(games = ?failed, "Retrying failed polls shortly");
+        schedule.record_failure(failed.iter().copied(), now);
+    }
+    schedule.record(games.difference(&failed).copied(), now);
+}
+
+/// Attempts a refresh, skipping if already refreshing or no clients configured.
+async fn try_refresh(state: &AppState) {
+    let Some(_refresh_guard) = try_acquire_refresh_all(state) else {
+        tracing::debug!("Skipping poll - refresh already in progress");
+        return;
+    };
+
+    if !state.has_clients().await {
+        tracing::debug!("Skipping poll - no game clients configured");
+        return;
+    }
+
+    do_refresh(state).await;
+}
+
+/// Core refresh logic shared by polling and manual refresh.
+///
+/// Fetches resources from all game clients, updates state, publishes events,
+/// and checks notification thresholds. Returns the fetched resources.
+async fn do_refresh(state: &AppState) -> AllResources {
+    tracing::debug!("Fetching resources from all game clients");
+
+    let resources = state.fetch_all_resources().await;
+
+    state.set_resources(resources.clone()).await;
+
+    state.publish(AppEvent::ResourcesUpdated, &resources);
+
+    notification::check_and_notify(state).await;
+
+    resources
+}
+
+/// Manually triggers a resource refresh.
+///
+/// This is called by the refresh command and tray menu action.
+///
+/// # Errors
+///
+/// Returns an error if a refresh is already in progress.
+pub async fn refresh_now(state: &AppState) -> Result<AllResources, String> {
+    tracing::info!("Manual refresh requested");
+
+    // Check if already refreshing
+    let Some(_refresh_guard) = try_acquire_refresh_all(state) else {
+        tracing::debug!("Refresh already in progress, rejecting manual refresh");
+        return Err("Refresh already in progress".to_string());
+    };
+
+    // If no clients configured, just return empty resources with timestamp
+    if !state.has_clients().await {
+        tracing::debug!("No game clients configured, returning empty resources");
+        let mut resources = state.get_resources().await;
+        resources.last_updated = Some(Timestamp::now());
+        return Ok(resources);
+    }
+
+    state.publish(AppEvent::RefreshStarted, &());
+
+    let resources = do_refresh(state).await;
+
+    tracing::info!("Manual refresh completed");
+
+    Ok(resources)
+}
+
+/// Refreshes resources and daily reward status for a specific set of games.
+///
+/// Merges the fetched results into the existing cached state rather than
+/// replacing it entirely. This is used by the config reload path to only
+/// fetch games whose configuration actually changed, and by
+/// [`refresh_game`].
+///
+/// # Errors
+///
+/// Returns an error if a refresh of any of the games is already in progress.
+#[expect(
+    clippy::implicit_hasher,
+    reason = "game sets come from ConfigDiff, which uses the default hasher"
+)]
+pub async fn refresh_games(
+    state: &AppState,
+    game_ids: &HashSet<GameId>,
+) -> Result<AllResources, String> {
+    tracing::info!(games = ?game_ids, "Selective refresh requested");
+
+    let Some(_refresh_guard) = try_acquire_refresh(state, game_ids.clone()) else {
+        tracing::debug!("Refresh already in progress, skipping selective refresh");
+        return Err("Refresh already in progress".to_string());
+    };
+
+    state.publish(AppEvent::RefreshStarted, &());
+
+    // Fetch only the specified games, merging into the existing cached state
+    let resources = merge_resources_for_games(state, game_ids).await;
+
+    let new_daily_status = state.fetch_daily_reward_status_for_games(game_ids).await;
+    state
+        .merge_daily_reward_status(new_daily_status, Timestamp::now())
+        .await;
+
+    tracing::info!("Selective refresh completed");
+    Ok(resources)
+}
+
+/// Manually refreshes a single game, independently of refreshes of other
+/// games still in progress.
+///
+/// This is called by the per-game refresh command and tray menu actions.
+///
+/// # Errors
+///
+/// Returns an error if the game is not configured or is already being
+/// refreshed.
+pub async fn refresh_game(state: &AppState, game_id: GameId) -> Result<AllResources, String> {
+    if !state.configured_games().await.contains(&game_id) {
+        return Err(format!("{} is not configured", game_id.display_name()));
+    }
+    refresh_games(state, &HashSet::from([game_id])).await
+}
+
+/// Refreshes a single account, even while its game stays paused by a
+/// Geetest challenge of another account.
+///
+/// This replays a fetch once the account's own challenge is solved.
+///
+/// # Errors
+///
+/// Returns an error if the account's game is already being refreshed.
+pub async fn refresh_account(state: &AppState, key: &AccountKey) -> Result<AllResources, String> {
+    let Some(_refresh_guard) = try_acquire_refresh(state, HashSet::from([key.game_id])) else {
+        tracing::debug!(account = %key, "Refresh already in progress, skipping account refresh");
+        return Err("Refresh already in progress".to_string());
+    };
+
+    state.publish(AppEvent::RefreshStarted, &());
+    let outcome = state.fetch_resources_for_account(key).await;
+    Ok(merge_outcome(state, outcome).await)
+}
+
+/// Fetches resources for a subset of games and merges them into the cached
+/// state, then publishes the full snapshot and checks notification thresholds.
+async fn merge_resources_for_games(state: &AppState, game_ids: &HashSet<GameId>) -> AllResources {
+    let outcome = state.fetch_resources_for_games(game_ids).await;
+    merge_outcome(state, outcome).await
+}
+
+/// Merges fetched resources into the cached state, then publishes the full
+/// snapshot and checks notification thresholds.
+async fn merge_outcome(state: &AppState, outcome: FetchOutcome) -> AllResources {
+    let resources = state.merge_resources(outcome, Timestamp::now()).await;
+
+    state.publish(AppEvent::ResourcesUpdated, &resources);
+    notification::check_and_notify(state).await;
+
+    resources
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::challenge::BlockedOperation;
+    use crate::challenge::GeetestChallenge;
+    use crate::registry::GameClientRegistry;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+    use storekeeper_core::DEFAULT_ACCOUNT;
+    use storekeeper_core::DynGameClient;
+
+    type BoxError = Box<dyn std::error::Error + Send + Sync>;
+    type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+    /// Game client counting its fetches.
+    struct CountingGameClient {
+        id: GameId,
+        fetches: Arc<AtomicUsize>,
+    }
+
+    impl DynGameClient for CountingGameClient {
+        fn game_id(&self) -> GameId {
+            self.id
+        }
+
+        fn game_name(&self) -> &'static str {
+            "Counting Game"
+        }
+
+        fn fetch_resources_json(&self) -> BoxFuture<'_, Result<serde_json::Value, BoxError>> {
+            self.fetches.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async { Ok(serde_json::json!([])) })
+        }
+
+        fn is_authenticated_dyn(&self) -> BoxFuture<'_, Result<bool, BoxError>> {
+            Box::pin(async { Ok(true) })
+        }
+    }
+
+    #[tokio::test]
+    async fn refresh_now_leaves_paused_games_untouched() {
+        let state = AppState::new();
+        let paused = AccountKey::default_for(GameId::HonkaiStarRail);
+        let cached = serde_json::json!([{"type": "trailblaze_power"}]);
+        let fetches = Arc::new(AtomicUsize::new(0));
+        {
+            let mut data = state.inner.write().await;
+            let mut registry = GameClientRegistry::new();
+            for id in [GameId::GenshinImpact, GameId::HonkaiStarRail] {
+                registry.register(
+                    DEFAULT_ACCOUNT,
+                    Box::new(CountingGameClient {
+                        id,
+                        fetches: Arc::clone(&fetches),
+                    }),
+                );
+            }
+            data.registry = Arc::new(registry);
+            data.resources
+                .record(paused.clone(), cached.clone(), Timestamp::UNIX_EPOCH);
+        }
+        let challenge = GeetestChallenge {
+            gt: "gt1".to_string(),
+            challenge: "c1".to_string(),
+        };
+        state
+            .raise_challenge(&paused, challenge, BlockedOperation::Fetch)
+            .await;
+
+        let resources = refresh_now(&state).await.expect("refresh should run");
+
+        // Only Genshin was fetched.
+        assert_eq!(fetches.load(Ordering::SeqCst), 1);
+        assert_eq!(resources.games.get(&paused), Some(&cached));
+        assert_eq!(
+            resources.fetched_at.get(&paused),
+            Some(&Timestamp::UNIX_EPOCH)
+        );
+        assert_eq!(
+            state.paused_games().await,
+            HashSet::from([GameId::HonkaiStarRail])
+        );
+    }
+
+    #[tokio::test]
+    async fn refresh_account_replays_one_of_two_paused_accounts() {
+        let state = AppState::new();
+        let main = AccountKey::default_for(GameId::GenshinImpact);
+        let alt = AccountKey::new(GameId::GenshinImpact, "alt");
+        let main_fetches = Arc::new(AtomicUsize::new(0));
+        let alt_fetches = Arc::new(AtomicUsize::new(0));
+        {
+            let mut data = state.inner.write().await;
+            let mut registry = GameClientRegistry::new();
+            for (key, fetches) in [(&main, &main_fetches), (&alt, &alt_fetches)] {
+                registry.register(
+                    &key.account,
+                    Box::new(CountingGameClient {
+                        id: key.game_id,
+                        fetches: Arc::clone(fetches),
+                    }),
+                );
+            }
+            data.registry = Arc::new(registry);
+        }
+        for (key, challenge) in [(&main, "c1"), (&alt, "c2")] {
+            let challenge = GeetestChallenge {
+                gt: "gt1".to_string(),
+                challenge: challenge.to_string(),
+            };
+            state
+                .raise_challenge(key, challenge, BlockedOperation::Fetch)
+                .await;
+        }
+        assert!(state.take_challenge(&main, "c1").await.is_some());
+
+        let resources = refresh_account(&state, &main)
+            .await
+            .expect("refresh should run");
+
+        assert_eq!(main_fetches.load(Ordering::SeqCst), 1);
+        assert_eq!(alt_fetches.load(Ordering::SeqCst), 0);
+        assert!(resources.games.get(&main).is_some());
+        // The other account's challenge keeps the game paused.
+        assert_eq!(
+            state.paused_games().await,
+            HashSet::from([GameId::GenshinImpact])
+        );
+    }
+}
@@ -9,11 +9,12 @@ use std::collections::HashMap;
 use std::collections::HashSet;
 use std::future::Future;
 use std::pin::Pin;
+use storekeeper_core::AccountKey;
 use storekeeper_core::GameId;
 
-/// Type alias for the result of a per-game operation.
+/// Type alias for the result of a per-account operation.
 type OperationResult = (
-    GameId,
+    AccountKey,
     Result<serde_json::Value, Box<dyn std::error::Error + Send + Sync>>,
 );
 
@@ -22,21 +23,21 @@ type OperationResult = (
 /// Clients sharing an API provider are processed sequentially (to avoid rate
 /// limits). Different providers are processed in parallel.
 ///
-/// An optional `game_filter` limits which games are processed. When `None`, all
-/// clients are processed.
+/// An optional `game_filter` limits which games are processed; every account
+/// of a matching game is included. When `None`, all clients are processed.
 ///
-/// The `operation` closure receives the game ID and client reference, and
-/// returns a pinned future yielding the game ID paired with the result. The
-/// closure is responsible for any side effects like event emission or
+/// The `operation` closure receives the account key and client reference, and
+/// returns a pinned future yielding the account key paired with the result.
+/// The closure is responsible for any side effects like event emission or
 /// inter-operation delays.
 pub async fn batch_by_provider<C, F>(
-    clients: &HashMap<GameId, Box<C>>,
+    clients: &HashMap<AccountKey, Box<C>>,
     game_filter: Option<&HashSet<GameId>>,
     operation: F,
-) -> HashMap<GameId, serde_json::Value>
+) -> HashMap<AccountKey, serde_json::Value>
 where
     C: ?Sized + Sync,
-    F: Fn(GameId, &C) -> Pin<Box<dyn Future<Output = OperationResult> + Send + '_>>,
+    F: Fn(AccountKey, &C) -> Pin<Box<dyn Future<Output = OperationResult> + Send + '_>>,
 {
     if clients.is_empty() {
         return HashMap::new();
@@ -44,7 +45,7 @@ where
 
     let active_clients: Vec<_> = clients
         .iter()
-        .filter(|(id, _)| game_filter.is_none_or(|f| f.contains(id)))
+        .filter(|(key, _)| game_filter.is_none_or(|f| f.contains(&key.game_id)))
         .collect();
 
     if active_clients.is_empty() {
@@ -53,7 +54,7 @@ where
 
     let providers: HashSet<_> = active_clients
         .iter()
-        .map(|(id, _)| id.api_provider())
+        .map(|(key, _)| key.game_id.api_provider())
         .collect();
 
     let provider_futures: Vec<_> = providers
@@ -61,13 +62,13 @@ where
         .map(|provider| {
             let provider_clients: Vec<_> = active_clients
                 .iter()
-                .filter(|(id, _)| id.api_provider() == provider)
+                .filter(|(key, _)| key.game_id.api_provider() == provider)
                 .collect();
 
             async {
                 let mut results = Vec::new();
-                for (game_id, client) in provider_clients {
-                    results.push(operation(**game_id, client.as_ref()).await);
+                for (key, client) in provider_clients {
+                    results.push(operation(AccountKey::clone(key), client.as_ref()).await);
                 }
                 results
             }
@@ -79,17 +80,19 @@ where
 }
 
 /// Collects results from provider batches into a single map, logging failures.
-fn collect_results(all_results: Vec<Vec<OperationResult>>) -> HashMap<GameId, serde_json::Value> {
+fn collect_results(
+    all_results: Vec<Vec<OperationResult>>,
+) -> HashMap<AccountKey, serde_json::Value> {
     let mut map = HashMap::new();
     for provider_results in all_results {
-        for (game_id, result) in provider_results {
+        for (key, result) in provider_results {
             match result {
                 Ok(data) => {
-                    tracing::debug!(game_id = ?game_id, "Batch operation succeeded");
-                    map.insert(game_id, data);
+                    tracing::debug!(account = %key, "Batch operation succeeded");
+                    map.insert(key, data);
                 }
                 Err(e) => {
-                    tracing::warn!(game_id = ?game_id, error = %e, "Batch operation failed");
+                    tracing::warn!(account = %key, error = %e, "Batch operation failed");
                 }
             }
         }
@@ -129,12 +132,12 @@ mod tests {
         }
     }
 
-    fn make_clients(specs: &[(GameId, bool)]) -> HashMap<GameId, Box<dyn MockClient>> {
+    fn make_clients(specs: &[(GameId, bool)]) -> HashMap<AccountKey, Box<dyn MockClient>> {
         specs
             .iter()
             .map(|(id, fail)| {
                 (
-                    *id,
+                    AccountKey::default_for(*id),
                     Box::new(TestClient {
                         id: *id,
                         should_fail: *fail,
@@ -146,7 +149,7 @@ mod tests {
 
     #[tokio::test(start_paused = true)]
     async fn empty_clients() {
-        let clients: HashMap<GameId, Box<dyn MockClient>> = HashMap::new();
+        let clients: HashMap<AccountKey, Box<dyn MockClient>> = HashMap::new();
         let result = batch_by_provider(&clients, None, |id, c| {
             Box::pin(async move { (id, c.do_work().await) })
         })
@@ -179,7 +182,7 @@ mod tests {
         })
         .await;
         assert_eq!(result.len(), 1);
-        assert!(result.contains_key(&GameId::GenshinImpact));
+        assert!(result.contains_key(&AccountKey::default_for(GameId::GenshinImpact)));
     }
 
     #[tokio::test(start_paused = true)]
@@ -197,4 +200,48 @@ mod tests {
         assert_eq!(result.len(), 1);
         assert_eq!(counter.load(Ordering::Relaxed), 1);
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn multiple_accounts_of_same_game() {
+        let mut clients = make_clients(&[(GameId::GenshinImpact, false)]);
+        clients.insert(
+            AccountKey::new(GameId::GenshinImpact, "alt"),
+            Box::new(TestClient {
+                id: GameId::GenshinImpact,
+                should_fail: false,
+            }),
+        );
+        let result = batch_by_provider(&clients, None, |id, c| {
+            Box::pin(async move { (id, c.do_work().await) })
+        })
+        .await;
+        assert_eq!(result.len(), 2, "each account is fetched separately");
+        assert!(result.contains_key(&AccountKey::new(GameId::GenshinImpact, "alt")));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn game_filter_includes_all_accounts_of_game() {
+        let mut clients = make_clients(&[
+            (GameId::GenshinImpact, false),
+            (GameId::WutheringWaves, false),
+        ]);
+        clients.insert(
+            AccountKey::new(GameId::GenshinImpact, "alt"),
+            Box::new(TestClient {
+                id: GameId::GenshinImpact,
+                should_fail: false,
+            }),
+        );
+        let filter = HashSet::from([GameId::GenshinImpact]);
+        let result = batch_by_provider(&clients, Some(&filter), |id, c| {
+            Box::pin(async move { (id, c.do_work().await) })
+        })
+        .await;
+        assert_eq!(result.len(), 2);
+        assert!(
+            result
+                .keys()
+                .all(|key| key.game_id == GameId::GenshinImpact)
+        );
+    }
 }
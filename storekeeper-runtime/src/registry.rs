@@ -0,0 +1,385 @@
+//! Game client registry for dynamic client management.
+
+use crate::events::AppEvent;
+use crate::events::EventSink;
+use crate::events::GameFetchFailedPayload;
+use crate::events::GameResourcePayload;
+use crate::fetch_status::FetchError;
+use crate::fetch_status::FetchStatus;
+use crate::metrics::Metrics;
+use crate::provider_batch;
+use jiff::Timestamp;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use storekeeper_core::AccountKey;
+use storekeeper_core::DynGameClient;
+use storekeeper_core::GameId;
+
+/// Results of fetching resources from several clients.
+#[derive(Debug, Default)]
+pub struct FetchOutcome {
+    /// JSON-serialized resources of each account fetched successfully.
+    pub resources: HashMap<AccountKey, serde_json::Value>,
+    /// Why each account whose fetch failed did so.
+    pub failures: HashMap<AccountKey, FetchError>,
+}
+
+/// Registry that holds type-erased game clients.
+///
+/// This allows storing different game client types in a single collection,
+/// enabling dynamic iteration and fetching without explicit per-game fields.
+/// Clients are keyed by account so a game may have several registered clients.
+pub struct GameClientRegistry {
+    clients: HashMap<AccountKey, Box<dyn DynGameClient>>,
+}
+
+impl GameClientRegistry {
+    /// Creates a new empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            clients: HashMap::new(),
+        }
+    }
+
+    /// Registers a game client for the named account of its game.
+    ///
+    /// If a client for the same account already exists, it will be replaced.
+    pub fn register(&mut self, account: &str, client: Box<dyn DynGameClient>) {
+        let key = AccountKey::new(client.game_id(), account);
+        tracing::debug!(account = %key, "Registering game client");
+        self.clients.insert(key, client);
+    }
+
+    /// Returns the number of registered clients.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Returns true if a client is registered for the account.
+    #[must_use]
+    pub fn contains(&self, key: &AccountKey) -> bool {
+        self.clients.contains_key(key)
+    }
+
+    /// Returns true if no clients are registered.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+
+    /// Returns true if any clients are registered.
+    #[must_use]
+    pub fn has_any(&self) -> bool {
+        !self.is_empty()
+    }
+
+    /// Iterates over the registered clients and their account keys.
+    ///
+    /// Iteration order is unspecified.
+    pub fn clients(&self) -> impl Iterator<Item = (&AccountKey, &dyn DynGameClient)> {
+        self.clients
+            .iter()
+            .map(|(key, client)| (key, client.as_ref()))
+    }
+
+    /// Fetches resources from all registered clients with rate limit awareness.
+    ///
+    /// Games are grouped by API provider:
+    /// - Games using the same provider are fetched sequentially to avoid rate
+    ///   limits
+    /// - Different providers are fetched in parallel for efficiency
+    ///
+    /// Publishes a per-account event after each fetch, successful or not, and
+    /// counts every outcome in `metrics`.
+    /// Returns the JSON-serialized resources of each account fetched, and why
+    /// each account that failed did so.
+    pub async fn fetch_all(
+        &self,
+        events: &Arc<dyn EventSink>,
+        metrics: &Arc<Metrics>,
+    ) -> FetchOutcome {
+        self.fetch_filtered(None, events, metrics).await
+    }
+
+    /// Fetches resources from a subset of registered clients.
+    ///
+    /// Same as `fetch_all` but only processes accounts of games in the given
+    /// set.
+    pub async fn fetch_for_games(
+        &self,
+        game_ids: &HashSet<GameId>,
+        events: &Arc<dyn EventSink>,
+        metrics: &Arc<Metrics>,
+    ) -> FetchOutcome {
+        self.fetch_filtered(Some(game_ids), events, metrics).await
+    }
+
+    /// Fetches resources of a single account, if a client is registered for
+    /// it.
+    ///
+    /// Publishes its update or failure event and counts the outcome like
+    /// `fetch_all`.
+    pub async fn fetch_account(
+        &self,
+        key: &AccountKey,
+        events: &Arc<dyn EventSink>,
+        metrics: &Arc<Metrics>,
+    ) -> FetchOutcome {
+        let mut outcome = FetchOutcome::default();
+        let Some(client) = self.clients.get(key) else {
+            return outcome;
+        };
+        let result = client.fetch_resources_json().await;
+        if let Some(error) = publish_outcome(key, &result, events, metrics) {
+            outcome.failures.insert(key.clone(), error);
+        }
+        if let Ok(data) = result {
+            outcome.resources.insert(key.clone(), data);
+        }
+        outcome
+    }
+
+    async fn fetch_filtered(
+        &self,
+        game_ids: Option<&HashSet<GameId>>,
+        events: &Arc<dyn EventSink>,
+        metrics: &Arc<Metrics>,
+    ) -> FetchOutcome {
+        let failures = Arc::new(Mutex::new(HashMap::new()));
+        let resources =
+            provider_batch::batch_by_provider(&self.clients, game_ids, |key, client| {
+                let events = Arc::clone(events);
+                let metrics = Arc::clone(metrics);
+                let failures = Arc::clone(&failures);
+                Box::pin(async move {
+                    let result = client.fetch_resources_json().await;
+                    if let Some(error) = publish_outcome(&key, &result, &events, &metrics) {
+                        failures
+                            .lock()
+                            .unwrap_or_else(PoisonError::into_inner)
+                            .insert(key.clone(), error);
+                    }
+                    (key, result)
+                })
+            })
+            .await;
+
+        let failures =
+            std::mem::take(&mut *failures.lock().unwrap_or_else(PoisonError::into_inner));
+        FetchOutcome {
+            resources,
+            failures,
+        }
+    }
+}
+
+/// Counts an account's fetch outcome in `metrics` and publishes its update or
+/// failure event.
+///
+/// Returns why the fetch failed, if it did.
+fn publish_outcome(
+    key: &AccountKey,
+    result: &Result<serde_json::Value, Box<dyn std::error::Error + Send + Sync>>,
+    events: &Arc<dyn EventSink>,
+    metrics: &Metrics,
+) -> Option<FetchError> {
+    match result {
+        Ok(resources) => {
+            metrics.record_fetch_success(key.game_id);
+            let payload = GameResourcePayload {
+                game_id: key.game_id,
+                account: &key.account,
+                data: resources,
+                status: &FetchStatus::succeeded(Timestamp::now()),
+            };
+            events.publish(AppEvent::GameResourceUpdated, &payload);
+            None
+        }
+        Err(e) => {
+            let error = FetchError::new(e.as_ref());
+            metrics.record_fetch_failure(key.game_id, error.kind);
+            let payload = GameFetchFailedPayload {
+                game_id: key.game_id,
+                account: &key.account,
+                error: &error,
+            };
+            events.publish(AppEvent::GameFetchFailed, &payload);
+            Some(error)
+        }
+    }
+}
+
+impl Default for GameClientRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::Future;
+    use std::pin::Pin;
+    use storekeeper_core::DEFAULT_ACCOUNT;
+
+    type BoxError = Box<dyn std::error::Error + Send + Sync>;
+    type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+    struct MockGameClient {
+        id: GameId,
+    }
+
+    impl DynGameClient for MockGameClient {
+        fn game_id(&self) -> GameId {
+            self.id
+        }
+
+        fn game_name(&self) -> &'static str {
+            "Mock Game"
+        }
+
+        fn fetch_resources_json(&self) -> BoxFuture<'_, Result<serde_json::Value, BoxError>> {
+            Box::pin(async { Ok(serde_json::json!({"mock": true})) })
+        }
+
+        fn is_authenticated_dyn(&self) -> BoxFuture<'_, Result<bool, BoxError>> {
+            Box::pin(async { Ok(true) })
+        }
+    }
+
+    // =========================================================================
+    // Construction
+    // =========================================================================
+
+    #[test]
+    fn new_registry_is_empty() {
+        let r = GameClientRegistry::new();
+        assert_eq!(r.len(), 0);
+        assert!(!r.has_any());
+    }
+
+    #[test]
+    fn default_registry_is_empty() {
+        let r = GameClientRegistry::default();
+        assert_eq!(r.len(), 0);
+        assert!(!r.has_any());
+    }
+
+    // =========================================================================
+    // Registration
+    // =========================================================================
+
+    #[test]
+    fn register_single_client() {
+        let mut r = GameClientRegistry::new();
+        r.register(
+            DEFAULT_ACCOUNT,
+            Box::new(MockGameClient {
+                id: GameId::GenshinImpact,
+            }),
+        );
+        assert_eq!(r.len(), 1);
+        assert!(r.has_any());
+    }
+
+    #[test]
+    fn register_multiple_clients() {
+        let mut r = GameClientRegistry::new();
+        r.register(
+            DEFAULT_ACCOUNT,
+            Box::new(MockGameClient {
+                id: GameId::GenshinImpact,
+            }),
+        );
+        r.register(
+            DEFAULT_ACCOUNT,
+            Box::new(MockGameClient {
+                id: GameId::HonkaiStarRail,
+            }),
+        );
+        r.register(
+            DEFAULT_ACCOUNT,
+            Box::new(MockGameClient {
+                id: GameId::WutheringWaves,
+            }),
+        );
+        assert_eq!(r.len(), 3);
+    }
+
+    #[test]
+    fn duplicate_game_id_replaces() {
+        let mut r = GameClientRegistry::new();
+        r.register(
+            DEFAULT_ACCOUNT,
+            Box::new(MockGameClient {
+                id: GameId::GenshinImpact,
+            }),
+        );
+        r.register(
+            DEFAULT_ACCOUNT,
+            Box::new(MockGameClient {
+                id: GameId::GenshinImpact,
+            }),
+        );
+        assert_eq!(r.len(), 1, "duplicate should replace, not add");
+    }
+
+    #[test]
+    fn accounts_of_same_game_are_separate() {
+        let mut r = GameClientRegistry::new();
+        r.register(
+            DEFAULT_ACCOUNT,
+            Box::new(MockGameClient {
+                id: GameId::GenshinImpact,
+            }),
+        );
+        r.register(
+            "alt",
+            Box::new(MockGameClient {
+                id: GameId::GenshinImpact,
+            }),
+        );
+        assert_eq!(r.len(), 2, "named accounts should not replace the default");
+    }
+
+    #[test]
+    fn register_all_four_games() {
+        let mut r = GameClientRegistry::new();
+        for &id in GameId::all() {
+            r.register(DEFAULT_ACCOUNT, Box::new(MockGameClient { id }));
+        }
+        assert_eq!(r.len(), 4);
+    }
+
+    #[test]
+    fn clients_yields_registered_accounts() {
+        let mut r = GameClientRegistry::new();
+        r.register(
+            DEFAULT_ACCOUNT,
+            Box::new(MockGameClient {
+                id: GameId::GenshinImpact,
+            }),
+        );
+        r.register(
+            "alt",
+            Box::new(MockGameClient {
+                id: GameId::GenshinImpact,
+            }),
+        );
+
+        let mut keys: Vec<_> = r.clients().map(|(key, _)| key.clone()).collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                AccountKey::new(GameId::GenshinImpact, "alt"),
+                AccountKey::default_for(GameId::GenshinImpact),
+            ]
+        );
+    }
+}
@@ -3,6 +3,7 @@
 //! Provides exponential-backoff retry for network calls that may fail
 //! due to transient issues (timeouts, DNS, connection resets, etc.).
 
+use crate::error_kind::classify_anyhow;
 use std::future::Future;
 use storekeeper_client_core::retry::RetryConfig;
 
@@ -11,6 +12,11 @@ use storekeeper_client_core::retry::RetryConfig;
 /// Only retries on transient network errors (see [`is_retryable_error`]).
 /// Non-retryable errors (auth failures, rate limits, etc.) propagate
 /// immediately.
+///
+/// # Errors
+///
+/// Returns the last error once retries are exhausted, or the first
+/// non-retryable error.
 pub async fn retry_with_backoff<F, Fut>(operation: F) -> anyhow::Result<serde_json::Value>
 where
     F: FnMut() -> Fut,
@@ -20,97 +26,60 @@ where
     storekeeper_client_core::retry::retry_with_backoff(&config, operation, is_retryable_error).await
 }
 
-/// Determines if an error is retryable by inspecting the error chain.
-///
-/// Walks the `anyhow` error chain looking for `reqwest` errors that indicate
-/// transient network issues (timeouts, connection errors, etc.).
+/// Determines if an error is retryable from the kind of the typed client
+/// error in its chain.
 fn is_retryable_error(error: &anyhow::Error) -> bool {
-    // Walk the error chain for typed reqwest errors
-    for cause in error.chain() {
-        if let Some(reqwest_err) = cause.downcast_ref::<reqwest::Error>()
-            && storekeeper_client_core::is_transient_reqwest_error(reqwest_err)
-        {
-            return true;
-        }
-    }
-
-    // Fallback: pattern-match on the display string for errors that don't
-    // preserve typed info through the chain
-    let msg = error.to_string().to_lowercase();
-    msg.contains("timeout")
-        || msg.contains("connection")
-        || msg.contains("network")
-        || msg.contains("refused")
-        || msg.contains("dns")
-        || msg.contains("reset")
-        || msg.contains("unreachable")
+    classify_anyhow(error).is_transient()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use storekeeper_client_core::ClientError;
 
     // =========================================================================
     // is_retryable_error tests
     // =========================================================================
 
-    #[test]
-    fn retryable_dns_in_message() {
-        let err = anyhow::anyhow!("dns resolution failed");
-        assert!(is_retryable_error(&err));
-    }
-
-    #[test]
-    fn retryable_reset_in_message() {
-        let err = anyhow::anyhow!("connection reset by peer");
-        assert!(is_retryable_error(&err));
-    }
-
-    #[test]
-    fn retryable_unreachable_in_message() {
-        let err = anyhow::anyhow!("host unreachable");
-        assert!(is_retryable_error(&err));
-    }
-
-    #[test]
-    fn retryable_timeout_in_message() {
-        let err = anyhow::anyhow!("request timeout");
-        assert!(is_retryable_error(&err));
+    /// A HoYoLab error as the daily reward registry returns it.
+    fn hoyolab_error(code: i32) -> anyhow::Error {
+        let boxed: Box<dyn std::error::Error + Send + Sync> = Box::new(
+            storekeeper_client_hoyolab::Error::Client(ClientError::api_error(code, "test")),
+        );
+        anyhow::anyhow!(boxed).context("failed to claim daily reward")
     }
 
     #[test]
-    fn retryable_connection_in_message() {
-        let err = anyhow::anyhow!("connection dropped");
-        assert!(is_retryable_error(&err));
+    fn retryable_server_error() {
+        assert!(is_retryable_error(&hoyolab_error(502)));
     }
 
     #[test]
-    fn retryable_network_in_message() {
-        let err = anyhow::anyhow!("temporary network issue");
-        assert!(is_retryable_error(&err));
+    fn retryable_exhausted_middleware_retries() {
+        assert!(is_retryable_error(&hoyolab_error(0)));
     }
 
     #[test]
-    fn retryable_refused_in_message() {
-        let err = anyhow::anyhow!("connection refused by host");
-        assert!(is_retryable_error(&err));
+    fn not_retryable_auth_error() {
+        assert!(!is_retryable_error(&hoyolab_error(-100)));
     }
 
     #[test]
-    fn not_retryable_auth_error() {
-        let err = anyhow::anyhow!("authentication failed: invalid token");
+    fn not_retryable_rate_limit() {
+        let err = anyhow::Error::new(storekeeper_client_hoyolab::Error::RateLimited {
+            retry_after_secs: 30,
+        });
         assert!(!is_retryable_error(&err));
     }
 
     #[test]
-    fn not_retryable_rate_limit() {
-        let err = anyhow::anyhow!("rate limit exceeded");
-        assert!(!is_retryable_error(&err));
+    fn not_retryable_captcha() {
+        assert!(!is_retryable_error(&hoyolab_error(1034)));
     }
 
     #[test]
-    fn not_retryable_empty() {
-        let err = anyhow::anyhow!("");
+    fn not_retryable_untyped_error() {
+        let err = anyhow::anyhow!("connection timeout");
         assert!(!is_retryable_error(&err));
     }
 
@@ -129,7 +98,7 @@ mod tests {
         let mut calls = 0u32;
         let result = retry_with_backoff(|| {
             calls += 1;
-            async { Err(anyhow::anyhow!("authentication failed")) }
+            async { Err(hoyolab_error(-100)) }
         })
         .await;
         result.expect_err("non-retryable error should fail immediately");
@@ -143,7 +112,7 @@ mod tests {
             calls += 1;
             async move {
                 if calls <= 1 {
-                    Err(anyhow::anyhow!("connection timeout"))
+                    Err(hoyolab_error(0))
                 } else {
                     Ok(serde_json::json!({"ok": true}))
                 }
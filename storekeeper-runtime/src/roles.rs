@@ -0,0 +1,72 @@
+//! Discovery of the game accounts bound to HoYoLab logins.
+//!
+//! Lets settings offer the UIDs of a login to pick from, with the region of
+//! each taken from the server HoYoLab reports instead of guessed from the
+//! UID.
+
+use crate::clients::create_hoyolab_client;
+use crate::error_kind::ErrorKind;
+use crate::state::AppState;
+use anyhow::Context as _;
+pub use storekeeper_client_hoyolab::GameRole;
+use storekeeper_client_hoyolab::Platform;
+use storekeeper_client_hoyolab::fetch_game_roles;
+
+/// Lists the Genshin Impact, Honkai: Star Rail and Zenless Zone Zero roles
+/// bound to a HoYoLab login of the applied secrets.
+///
+/// A login belongs to either HoYoLab or Miyoushe, so Miyoushe is only asked
+/// when HoYoLab rejects it.
+///
+/// # Errors
+///
+/// Returns an error if the login is not configured or neither platform
+/// returns its roles.
+pub async fn discover_game_roles(
+    state: &AppState,
+    credentials: &str,
+) -> anyhow::Result<Vec<GameRole>> {
+    let (secrets, rate_limiters) = {
+        let data = state.inner.read().await;
+        (data.secrets.clone(), data.rate_limiters.clone())
+    };
+    let client = |platform| {
+        create_hoyolab_client(
+            &secrets,
+            &rate_limiters.hoyolab,
+            state.challenges(),
+            credentials,
+            platform,
+        )
+        .with_context(|| format!("HoYoLab login \"{credentials}\" is not configured"))
+    };
+
+    match fetch_game_roles(&client(Platform::Hoyolab)?).await {
+        Err(e) if e.kind() == ErrorKind::Auth => {
+            tracing::debug!(credentials = %credentials, "HoYoLab rejected the login, trying Miyoushe");
+            fetch_game_roles(&client(Platform::Miyoushe)?)
+                .await
+                .with_context(|| format!("failed to list the game roles of \"{credentials}\""))
+        }
+        result => {
+            result.with_context(|| format!("failed to list the game roles of \"{credentials}\""))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn unconfigured_login_is_an_error() {
+        let state = AppState::new();
+        let result = discover_game_roles(&state, "missing").await;
+        assert!(
+            result
+                .as_ref()
+                .is_err_and(|e| e.to_string().contains("not configured")),
+            "expected a missing-login error, got: {result:?}"
+        );
+    }
+}
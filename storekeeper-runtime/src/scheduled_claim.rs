@@ -1,21 +1,19 @@
 //! Scheduled auto-claim for daily rewards.
 
 use crate::events::AppEvent;
+use crate::metrics::ClaimOutcome;
 use crate::retry_helpers::retry_with_backoff;
+use crate::state::AccountValues;
 use crate::state::AppState;
 use anyhow::Context;
 use jiff::Timestamp;
-use std::collections::HashMap;
 use std::ops::ControlFlow;
 use std::sync::Arc;
 use std::time::Duration;
+use storekeeper_core::AccountKey;
 use storekeeper_core::ClaimTime;
 use storekeeper_core::DailyRewardStatus;
-use storekeeper_core::GameId;
 use storekeeper_core::next_claim_datetime_utc;
-use tauri::AppHandle;
-use tauri::Emitter;
-use tauri::Manager;
 use tokio::sync::Notify;
 use tokio_util::sync::CancellationToken;
 
@@ -24,7 +22,7 @@ use tokio_util::sync::CancellationToken;
 /// Prevents long `tokio::time::sleep` calls that can drift during OS suspend.
 const MAX_SLEEP_CHUNK: Duration = Duration::from_mins(15);
 
-/// Short sleep used when no games are configured or no claims are pending.
+/// Short sleep used when no accounts are configured or no claims are pending.
 const IDLE_SLEEP: Duration = Duration::from_mins(15);
 
 /// Why the scheduler woke up from a sleep.
@@ -60,77 +58,72 @@ fn handle_wake_reason(reason: &WakeReason) -> ControlFlow<(), PostWake> {
     }
 }
 
-/// Starts the scheduled daily claim task.
+/// Runs the scheduled daily claim task until cancelled.
 ///
-/// This spawns a tokio task that:
-/// 1. Runs startup claims for any games that haven't been claimed today
+/// The task:
+/// 1. Runs startup claims for any accounts that haven't been claimed today
 /// 2. Enters the main scheduling loop to claim at configured times
 ///
 /// The scheduler wakes on three events:
 /// - Timer expiry (wall-clock-bounded in 15 min chunks to survive OS suspend)
 /// - Config change notification via `AppState::wake_scheduler()`
 /// - Cancellation token (app shutdown)
-pub fn start_scheduled_claims(app_handle: AppHandle, cancel_token: CancellationToken) {
-    tauri::async_runtime::spawn(async move {
-        tracing::info!("Starting scheduled daily reward claim task");
-
-        let state = app_handle.state::<AppState>();
-        let notify = state.scheduler_notify();
-
-        // Run startup claims before entering the main loop
-        run_startup_claims(&state, &app_handle).await;
-
-        // Main scheduling loop
-        loop {
-            // Get games that have auto-claim enabled
-            let auto_claim_games = state.get_auto_claim_games().await;
-
-            if auto_claim_games.is_empty() {
-                tracing::debug!("No games with auto-claim enabled, idle sleeping");
-                match idle_wait(&cancel_token, &notify, &state, &app_handle).await {
-                    ControlFlow::Break(()) => break,
-                    ControlFlow::Continue(()) => continue,
-                }
-            }
+pub async fn run_scheduled_claims(state: AppState, cancel_token: CancellationToken) {
+    tracing::info!("Starting scheduled daily reward claim task");
 
-            // Find the earliest next claim time across all games
-            let Some((target, games_to_claim)) =
-                calculate_next_claim(&auto_claim_games, &state).await
-            else {
-                // No games need claiming right now, idle sleep
-                tracing::debug!("No games need claiming, idle sleeping");
-                match idle_wait(&cancel_token, &notify, &state, &app_handle).await {
-                    ControlFlow::Break(()) => break,
-                    ControlFlow::Continue(()) => continue,
-                }
-            };
+    let notify = state.scheduler_notify();
 
-            // Clamp to zero for display: a target in the past sleeps for no time.
-            let until_claim_secs = target.duration_since(Timestamp::now()).as_secs().max(0);
+    // Run startup claims before entering the main loop
+    run_startup_claims(&state).await;
 
-            tracing::info!(
-                sleep_secs = until_claim_secs,
-                target = %target,
-                games = ?games_to_claim,
-                "Waiting until next scheduled claim time"
-            );
+    // Main scheduling loop
+    loop {
+        // Get accounts that have auto-claim enabled
+        let auto_claim_accounts = state.get_auto_claim_accounts().await;
 
-            // Wait until claim time, config change, or cancellation
-            match handle_wake_reason(&sleep_until(target, &cancel_token, &notify).await) {
+        if auto_claim_accounts.is_empty() {
+            tracing::debug!("No accounts with auto-claim enabled, idle sleeping");
+            match idle_wait(&cancel_token, &notify, &state).await {
                 ControlFlow::Break(()) => break,
-                ControlFlow::Continue(PostWake::Rerun) => {
-                    tracing::info!(
-                        "Config changed while waiting for claim, re-running startup claims"
-                    );
-                    run_startup_claims(&state, &app_handle).await;
-                }
-                ControlFlow::Continue(PostWake::Resume) => {
-                    // Claim rewards for all games that are due
-                    claim_games_and_emit(&state, &app_handle, &games_to_claim).await;
-                }
+                ControlFlow::Continue(()) => continue,
             }
         }
-    });
+
+        // Find the earliest next claim time across all accounts
+        let Some((target, accounts_to_claim)) =
+            calculate_next_claim(&auto_claim_accounts, &state).await
+        else {
+            // No accounts need claiming right now, idle sleep
+            tracing::debug!("No accounts need claiming, idle sleeping");
+            match idle_wait(&cancel_token, &notify, &state).await {
+                ControlFlow::Break(()) => break,
+                ControlFlow::Continue(()) => continue,
+            }
+        };
+
+        // Clamp to zero for display: a target in the past sleeps for no time.
+        let until_claim_secs = target.duration_since(Timestamp::now()).as_secs().max(0);
+
+        tracing::info!(
+            sleep_secs = until_claim_secs,
+            target = %target,
+            accounts = ?accounts_to_claim,
+            "Waiting until next scheduled claim time"
+        );
+
+        // Wait until claim time, config change, or cancellation
+        match handle_wake_reason(&sleep_until(target, &cancel_token, &notify).await) {
+            ControlFlow::Break(()) => break,
+            ControlFlow::Continue(PostWake::Rerun) => {
+                tracing::info!("Config changed while waiting for claim, re-running startup claims");
+                run_startup_claims(&state).await;
+            }
+            ControlFlow::Continue(PostWake::Resume) => {
+                // Claim rewards for all accounts that are due
+                claim_accounts_and_emit(&state, &accounts_to_claim).await;
+            }
+        }
+    }
 }
 
 /// Sleeps idly and processes the resulting wake.
@@ -142,66 +135,73 @@ async fn idle_wait(
     cancel_token: &CancellationToken,
     notify: &Arc<Notify>,
     state: &AppState,
-    app_handle: &AppHandle,
 ) -> ControlFlow<()> {
     match handle_wake_reason(&sleep_short(cancel_token, notify).await) {
         ControlFlow::Break(()) => ControlFlow::Break(()),
         ControlFlow::Continue(post) => {
             if post == PostWake::Rerun {
                 tracing::info!("Config changed while idle, re-running startup claims");
-                run_startup_claims(state, app_handle).await;
+                run_startup_claims(state).await;
             }
             ControlFlow::Continue(())
         }
     }
 }
 
-/// Runs startup claims for games that have auto-claim enabled.
+/// Runs startup claims for accounts that have auto-claim enabled.
 ///
-/// For each game, checks the API status first - if not claimed today,
+/// For each account, checks the API status first - if not claimed today,
 /// attempts to claim with retry on network failures.
-async fn run_startup_claims(state: &AppState, app_handle: &AppHandle) {
+async fn run_startup_claims(state: &AppState) {
     tracing::info!("Running startup auto-claim check");
 
-    let auto_claim_games = state.get_auto_claim_games().await;
+    let auto_claim_accounts = state.get_auto_claim_accounts().await;
 
-    if auto_claim_games.is_empty() {
-        tracing::debug!("No games with auto-claim enabled");
+    if auto_claim_accounts.is_empty() {
+        tracing::debug!("No accounts with auto-claim enabled");
         return;
     }
 
-    let game_ids: Vec<GameId> = auto_claim_games.into_iter().map(|(id, _)| id).collect();
-    claim_games_and_emit(state, app_handle, &game_ids).await;
+    let keys: Vec<AccountKey> = auto_claim_accounts
+        .into_iter()
+        .map(|(key, _)| key)
+        .collect();
+    claim_accounts_and_emit(state, &keys).await;
 }
 
-/// Claims rewards for the given games and emits results to the frontend.
-async fn claim_games_and_emit(state: &AppState, app_handle: &AppHandle, game_ids: &[GameId]) {
-    let mut results = HashMap::new();
+/// Claims rewards for the given accounts and publishes the results.
+async fn claim_accounts_and_emit(state: &AppState, keys: &[AccountKey]) {
+    let mut results = AccountValues::default();
+    let paused = state.paused_games().await;
 
-    for &game_id in game_ids {
-        if !state.should_auto_claim_game(game_id).await {
-            tracing::debug!(game_id = ?game_id, "Skipping auto-claim (disabled in config)");
+    for key in keys {
+        if !state.should_auto_claim_account(key).await {
+            tracing::debug!(account = %key, "Skipping auto-claim (disabled in config)");
+            continue;
+        }
+        if paused.contains(&key.game_id) {
+            tracing::debug!(account = %key, "Skipping auto-claim (waiting on a Geetest challenge)");
             continue;
         }
 
-        tracing::info!(game_id = ?game_id, "Auto-claiming daily reward");
+        tracing::info!(account = %key, "Auto-claiming daily reward");
 
-        match claim_with_status_check(state, game_id).await {
+        match claim_with_status_check(state, key).await {
             Ok(true) => {
-                tracing::info!(game_id = ?game_id, "Auto-claim successful");
-                if let Ok(status) = state.get_daily_reward_status_for_game(game_id).await {
-                    results.insert(game_id, status);
+                tracing::info!(account = %key, "Auto-claim successful");
+                if let Ok(status) = state.get_daily_reward_status_for_account(key).await {
+                    results.insert(key.clone(), status);
                 }
             }
             Ok(false) => {
-                tracing::debug!(game_id = ?game_id, "Already claimed today (per API)");
+                tracing::debug!(account = %key, "Already claimed today (per API)");
             }
             Err(e) => {
-                tracing::error!(game_id = ?game_id, error = %e, "Auto-claim failed");
+                tracing::error!(account = %key, error = %e, "Auto-claim failed");
             }
         }
 
-        // Small delay between games to avoid rate limiting
+        // Small delay between accounts to avoid rate limiting
         tokio::time::sleep(Duration::from_millis(500)).await;
     }
 
@@ -209,11 +209,9 @@ async fn claim_games_and_emit(state: &AppState, app_handle: &AppHandle, game_ids
         let status = state.fetch_all_daily_reward_status().await;
         state.set_daily_reward_status(status).await;
 
-        if let Err(e) = app_handle.emit(AppEvent::DailyRewardClaimed.as_str(), &results) {
-            tracing::warn!(error = %e, "Failed to emit daily reward claimed event");
-        }
+        state.publish(AppEvent::DailyRewardClaimed, &results);
 
-        tracing::info!(games_claimed = results.len(), "Auto-claim complete");
+        tracing::info!(accounts_claimed = results.len(), "Auto-claim complete");
     }
 }
 
@@ -255,7 +253,7 @@ async fn sleep_until(
 
 /// Short idle sleep with cancel/notify support.
 ///
-/// Used when no games are configured or no claims are pending.
+/// Used when no accounts are configured or no claims are pending.
 async fn sleep_short(cancel_token: &CancellationToken, notify: &Arc<Notify>) -> WakeReason {
     tokio::select! {
         () = cancel_token.cancelled() => {
@@ -275,20 +273,23 @@ async fn sleep_short(cancel_token: &CancellationToken, notify: &Arc<Notify>) ->
 ///
 /// Returns `Ok(true)` if claimed, `Ok(false)` if already claimed, `Err` on
 /// failure.
-async fn claim_with_status_check(state: &AppState, game_id: GameId) -> anyhow::Result<bool> {
+async fn claim_with_status_check(state: &AppState, key: &AccountKey) -> anyhow::Result<bool> {
     // Step 1: Check status first
-    let status = fetch_status_with_retry(state, game_id).await?;
+    let status = fetch_status_with_retry(state, key).await?;
 
     // Step 2: Check if already claimed via typed deserialization
     let reward_status: DailyRewardStatus =
         serde_json::from_value(status).context("failed to deserialize daily reward status")?;
 
     if reward_status.info.is_signed {
+        state
+            .metrics()
+            .record_claim(key, ClaimOutcome::AlreadyClaimed);
         return Ok(false); // Already claimed
     }
 
     // Step 3: Attempt to claim with retry
-    claim_reward_with_retry(state, game_id).await?;
+    claim_reward_with_retry(state, key).await?;
 
     Ok(true)
 }
@@ -296,42 +297,44 @@ async fn claim_with_status_check(state: &AppState, game_id: GameId) -> anyhow::R
 /// Fetches daily reward status with retry on network failures.
 async fn fetch_status_with_retry(
     state: &AppState,
-    game_id: GameId,
+    key: &AccountKey,
 ) -> anyhow::Result<serde_json::Value> {
-    retry_with_backoff(|| state.get_daily_reward_status_for_game(game_id)).await
+    retry_with_backoff(|| state.get_daily_reward_status_for_account(key)).await
 }
 
 /// Claims daily reward with retry on network failures.
 async fn claim_reward_with_retry(
     state: &AppState,
-    game_id: GameId,
+    key: &AccountKey,
 ) -> anyhow::Result<serde_json::Value> {
-    retry_with_backoff(|| state.claim_daily_reward_for_game(game_id)).await
+    let result = retry_with_backoff(|| state.claim_daily_reward_for_account(key)).await;
+    state.record_claim(key, &result).await;
+    result
 }
 
-/// Calculates the next claim time and which games to claim.
+/// Calculates the next claim time and which accounts to claim.
 ///
-/// Returns the target wall-clock datetime and the list of games to claim at
-/// that time. Returns `None` if no games need claiming.
+/// Returns the target wall-clock datetime and the list of accounts to claim at
+/// that time. Returns `None` if no accounts need claiming.
 async fn calculate_next_claim(
-    auto_claim_games: &[(GameId, Option<ClaimTime>)],
+    auto_claim_accounts: &[(AccountKey, Option<ClaimTime>)],
     state: &AppState,
-) -> Option<(Timestamp, Vec<GameId>)> {
+) -> Option<(Timestamp, Vec<AccountKey>)> {
     let mut earliest_time = None;
-    let mut games_at_earliest: Vec<GameId> = Vec::new();
+    let mut accounts_at_earliest: Vec<AccountKey> = Vec::new();
 
-    for (game_id, claim_time) in auto_claim_games {
-        // Check if this game has auto-claim enabled
-        if !state.should_auto_claim_game(*game_id).await {
+    for (key, claim_time) in auto_claim_accounts {
+        // Check if this account has auto-claim enabled
+        if !state.should_auto_claim_account(key).await {
             continue;
         }
 
-        // Calculate the next claim time for this game
+        // Calculate the next claim time for this account
         let next_claim = match next_claim_datetime_utc(*claim_time) {
             Ok(dt) => dt,
             Err(e) => {
                 tracing::error!(
-                    game_id = ?game_id,
+                    account = %key,
                     error = %e,
                     "Failed to calculate next claim time"
                 );
@@ -342,14 +345,14 @@ async fn calculate_next_claim(
         match earliest_time {
             None => {
                 earliest_time = Some(next_claim);
-                games_at_earliest = vec![*game_id];
+                accounts_at_earliest = vec![key.clone()];
             }
             Some(earliest) => {
                 if next_claim < earliest {
                     earliest_time = Some(next_claim);
-                    games_at_earliest = vec![*game_id];
+                    accounts_at_earliest = vec![key.clone()];
                 } else if next_claim == earliest {
-                    games_at_earliest.push(*game_id);
+                    accounts_at_earliest.push(key.clone());
                 }
             }
         }
@@ -358,7 +361,7 @@ async fn calculate_next_claim(
     let earliest = earliest_time?;
 
     // If the target is in the past, `sleep_until` will return immediately
-    Some((earliest, games_at_earliest))
+    Some((earliest, accounts_at_earliest))
 }
 
 #[cfg(test)]
@@ -0,0 +1,154 @@
+//! On-disk cache of the last successfully fetched resources.
+//!
+//! The snapshot is rewritten whenever cached resources or daily reward status
+//! change, and loaded on startup so the UI and notification checker have data
+//! to work with before (or without) the first successful poll.
+
+use crate::state::AllDailyRewardStatus;
+use crate::state::AllResources;
+use camino::Utf8Path;
+use camino::Utf8PathBuf;
+use serde::Deserialize;
+use serde::Serialize;
+use storekeeper_core::AppConfig;
+
+/// Snapshot file name inside the config directory.
+const SNAPSHOT_FILE_NAME: &str = "snapshot.json";
+
+/// Persisted copy of the cached application state.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snapshot {
+    /// Last known resources, with per-account fetch timestamps.
+    #[serde(default)]
+    pub resources: AllResources,
+
+    /// Last known daily reward status.
+    #[serde(default)]
+    pub daily_reward_status: AllDailyRewardStatus,
+}
+
+impl Snapshot {
+    /// Returns the default snapshot file path.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the config directory cannot be determined.
+    pub fn default_path() -> storekeeper_core::Result<Utf8PathBuf> {
+        Ok(AppConfig::config_dir()?.join(SNAPSHOT_FILE_NAME))
+    }
+
+    /// Loads a snapshot, returning `None` if the file does not exist.
+    ///
+    /// Loaded data is marked stale until replaced by a fresh fetch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn load_from_path(path: &Utf8Path) -> anyhow::Result<Option<Self>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let content = fs_err::read_to_string(path)?;
+        let mut snapshot: Self = serde_json::from_str(&content)?;
+        snapshot.resources.stale = true;
+        snapshot.daily_reward_status.stale = true;
+        Ok(Some(snapshot))
+    }
+
+    /// Writes the snapshot to a path.
+    ///
+    /// Writes to a temporary file first and renames it into place so a crash
+    /// mid-write never leaves a truncated snapshot behind.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be serialized or written.
+    pub fn save_to_path(&self, path: &Utf8Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs_err::create_dir_all(parent)?;
+        }
+
+        let content = serde_json::to_string(self)?;
+        let tmp_path = path.with_extension("json.tmp");
+        fs_err::write(&tmp_path, content)?;
+        fs_err::rename(&tmp_path, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jiff::Timestamp;
+    use storekeeper_core::AccountKey;
+    use storekeeper_core::GameId;
+
+    fn temp_path(name: &str) -> Utf8PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "storekeeper-snapshot-{name}-{}",
+            std::process::id()
+        ));
+        Utf8PathBuf::from_path_buf(dir)
+            .expect("temp dir should be UTF-8")
+            .join(SNAPSHOT_FILE_NAME)
+    }
+
+    #[test]
+    fn load_missing_file_returns_none() {
+        let path = temp_path("missing");
+        let loaded = Snapshot::load_from_path(&path).expect("should not error");
+        assert!(loaded.is_none());
+    }
+
+    #[test]
+    fn save_and_load_roundtrip_marks_stale() {
+        let path = temp_path("roundtrip");
+        let key = AccountKey::new(GameId::HonkaiStarRail, "alt");
+        let fetched_at = Timestamp::now();
+
+        let mut snapshot = Snapshot::default();
+        snapshot.resources.record(
+            key.clone(),
+            serde_json::json!([{"type": "trailblazePower"}]),
+            fetched_at,
+        );
+        snapshot.resources.last_updated = Some(fetched_at);
+        snapshot.daily_reward_status.games.insert(
+            key.clone(),
+            serde_json::json!({"info": {"is_signed": true}}),
+        );
+        snapshot.save_to_path(&path).expect("should save");
+
+        let loaded = Snapshot::load_from_path(&path)
+            .expect("should load")
+            .expect("snapshot should exist");
+        assert!(loaded.resources.stale);
+        assert!(loaded.daily_reward_status.stale);
+        assert_eq!(loaded.resources.games.len(), 1);
+        assert_eq!(loaded.resources.fetched_at.get(&key), Some(&fetched_at));
+        assert_eq!(loaded.daily_reward_status.games.len(), 1);
+
+        if let Some(parent) = path.parent() {
+            fs_err::remove_dir_all(parent).expect("should clean up");
+        }
+    }
+
+    #[test]
+    fn load_rejects_corrupt_file() {
+        let path = temp_path("corrupt");
+        if let Some(parent) = path.parent() {
+            fs_err::create_dir_all(parent).expect("should create dir");
+        }
+        fs_err::write(&path, "{ not json").expect("should write");
+
+        let err =
+            Snapshot::load_from_path(&path).expect_err("corrupt snapshot should fail to load");
+        assert!(!err.to_string().is_empty());
+
+        if let Some(parent) = path.parent() {
+            fs_err::remove_dir_all(parent).expect("should clean up");
+        }
+    }
+}
@@ -0,0 +1,1684 @@
+//! Application state management.
+
+use crate::challenge::BlockedOperation;
+use crate::challenge::ChallengeRequiredPayload;
+use crate::challenge::Challenges;
+use crate::challenge::GeetestChallenge;
+use crate::challenge::PendingChallenge;
+use crate::challenge::find_challenge_anyhow;
+use crate::clients::RateLimiters;
+use crate::clients::create_daily_reward_registry;
+use crate::clients::create_registry;
+use crate::daily_reward_registry::DailyRewardRegistry;
+use crate::error_kind::ErrorKind;
+use crate::events::AppEvent;
+use crate::events::BroadcastEventSink;
+use crate::events::EventSink;
+use crate::events::LogEventSink;
+use crate::events::PublishedEvent;
+use crate::fetch_status::FetchError;
+use crate::fetch_status::FetchStatus;
+use crate::history::HistoryStore;
+use crate::metrics;
+use crate::metrics::ClaimOutcome;
+use crate::metrics::Metrics;
+use crate::notification::AlertLog;
+use crate::notification::AlertLogEntry;
+use crate::notification::DeferredNotifications;
+use crate::notification::Delivery;
+use crate::notification::EmailDigest;
+use crate::notification::HookNotifier;
+use crate::notification::Notification;
+use crate::notification::NotificationBatch;
+use crate::notification::NotificationTracker;
+use crate::notification::Notifier;
+use crate::notification::PersistedNotifications;
+use crate::notification::auth_expired_notification;
+use crate::notification::claim_notification;
+use crate::notification::create_email_digests;
+use crate::notification::create_hooks;
+use crate::notification::create_notification_channels;
+use crate::overcap::OvercapStore;
+use crate::registry::FetchOutcome;
+use crate::registry::GameClientRegistry;
+use crate::retry_helpers::retry_with_backoff;
+use crate::snapshot::Snapshot;
+use camino::Utf8PathBuf;
+use jiff::Timestamp;
+use serde::Deserialize;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+use storekeeper_core::AccountKey;
+use storekeeper_core::AppConfig;
+use storekeeper_core::ClaimTime;
+use storekeeper_core::GameId;
+use storekeeper_core::SecretsConfig;
+use storekeeper_core::ensure_configs_exist;
+use tokio::sync::Notify;
+use tokio::sync::RwLock;
+use tokio::sync::broadcast;
+
+/// Events buffered per event subscriber before the slowest one starts
+/// missing events.
+const EVENT_CHANNEL_CAPACITY: usize = 64;
+
+/// Values keyed by game ID, then by account name (JSON values by default).
+///
+/// Serializes as `{ "<GAME_ID>": { "<account>": <value> } }` so the frontend
+/// can look up a game's accounts without knowing how many are configured.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AccountValues<T = serde_json::Value>(HashMap<GameId, HashMap<String, T>>);
+
+impl<T> Default for AccountValues<T> {
+    fn default() -> Self {
+        Self(HashMap::new())
+    }
+}
+
+impl<T> AccountValues<T> {
+    /// Returns the value stored for an account.
+    #[must_use]
+    pub fn get(&self, key: &AccountKey) -> Option<&T> {
+        self.0.get(&key.game_id)?.get(&key.account)
+    }
+
+    /// Stores the value for an account, replacing any previous value.
+    pub fn insert(&mut self, key: AccountKey, value: T) {
+        self.0
+            .entry(key.game_id)
+            .or_default()
+            .insert(key.account, value);
+    }
+
+    /// Returns the value stored for an account, storing the default first if
+    /// there is none.
+    pub fn get_or_insert_default(&mut self, key: AccountKey) -> &mut T
+    where
+        T: Default,
+    {
+        self.0
+            .entry(key.game_id)
+            .or_default()
+            .entry(key.account)
+            .or_default()
+    }
+
+    /// Iterates over all stored values with their account keys.
+    pub fn iter(&self) -> impl Iterator<Item = (AccountKey, &T)> {
+        self.0.iter().flat_map(|(game_id, accounts)| {
+            accounts
+                .iter()
+                .map(|(account, value)| (AccountKey::new(*game_id, account.as_str()), value))
+        })
+    }
+
+    /// Returns the number of stored account values.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.values().map(HashMap::len).sum()
+    }
+
+    /// Returns true if no values are stored.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.values().all(HashMap::is_empty)
+    }
+}
+
+impl<T> FromIterator<(AccountKey, T)> for AccountValues<T> {
+    fn from_iter<I: IntoIterator<Item = (AccountKey, T)>>(iter: I) -> Self {
+        let mut values = Self::default();
+        for (key, value) in iter {
+            values.insert(key, value);
+        }
+        values
+    }
+}
+
+/// All resources from all games.
+///
+/// Resources are stored as a map from game ID and account name to JSON value,
+/// allowing for dynamic game support without explicit per-game fields.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllResources {
+    /// Resources keyed by game ID and account name.
+    ///
+    /// Each value is a JSON array of the account's resource types.
+    #[serde(default, skip_serializing_if = "AccountValues::is_empty")]
+    pub games: AccountValues,
+
+    /// When each account's resources were last fetched successfully.
+    #[serde(default, skip_serializing_if = "AccountValues::is_empty")]
+    pub fetched_at: AccountValues<Timestamp>,
+
+    /// Outcome of each account's latest fetch, including why it failed.
+    ///
+    /// Accounts without a status were never fetched, e.g. because they are
+    /// not configured.
+    #[serde(default, skip_serializing_if = "AccountValues::is_empty")]
+    pub fetch_status: AccountValues<FetchStatus>,
+
+    /// Last update timestamp.
+    pub last_updated: Option<Timestamp>,
+
+    /// True while the data comes from the on-disk snapshot rather than a
+    /// fetch made by this process.
+    #[serde(default)]
+    pub stale: bool,
+}
+
+impl AllResources {
+    /// Stores freshly fetched resources for an account.
+    pub fn record(&mut self, key: AccountKey, data: serde_json::Value, fetched_at: Timestamp) {
+        self.fetched_at.insert(key.clone(), fetched_at);
+        self.fetch_status
+            .insert(key.clone(), FetchStatus::succeeded(fetched_at));
+        self.games.insert(key, data);
+    }
+
+    /// Records that fetching an account's resources failed, keeping any
+    /// resources already stored for it.
+    pub fn record_failure(&mut self, key: AccountKey, error: FetchError, attempted_at: Timestamp) {
+        self.fetch_status
+            .get_or_insert_default(key)
+            .record_failure(error, attempted_at);
+    }
+
+    /// Copies an account's last known resources and fetch status from a
+    /// previous snapshot.
+    ///
+    /// Used to keep showing (and notifying on) an account whose latest fetch
+    /// failed. Does nothing if `previous` has nothing for the account.
+    pub fn carry_over(&mut self, previous: &Self, key: &AccountKey) {
+        if let Some(status) = previous.fetch_status.get(key) {
+            self.fetch_status.insert(key.clone(), status.clone());
+        }
+        let Some(data) = previous.games.get(key) else {
+            return;
+        };
+        self.games.insert(key.clone(), data.clone());
+        if let Some(fetched_at) = previous.fetched_at.get(key) {
+            self.fetched_at.insert(key.clone(), *fetched_at);
+        }
+    }
+}
+
+/// All daily reward status from all games.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AllDailyRewardStatus {
+    /// Reward status keyed by game ID and account name.
+    #[serde(default, skip_serializing_if = "AccountValues::is_empty")]
+    pub games: AccountValues,
+
+    /// Last check timestamp.
+    pub last_checked: Option<Timestamp>,
+
+    /// True while the status comes from the on-disk snapshot rather than a
+    /// check made by this process.
+    #[serde(default)]
+    pub stale: bool,
+}
+
+/// Inner state data protected by RwLock.
+#[derive(Default)]
+pub struct StateData {
+    /// Cached resources from all games.
+    pub resources: AllResources,
+
+    /// Registry-based game clients.
+    pub registry: Arc<GameClientRegistry>,
+
+    /// Daily reward client registry.
+    pub daily_reward_registry: Arc<DailyRewardRegistry>,
+
+    /// Rate limiters of the clients in both registries, kept across registry
+    /// rebuilds so a rebuild neither refills the budgets nor ends a cooldown.
+    pub rate_limiters: RateLimiters,
+
+    /// Cached daily reward status.
+    pub daily_reward_status: AllDailyRewardStatus,
+
+    /// Application configuration.
+    pub config: AppConfig,
+
+    /// Secrets configuration (kept in memory for diff detection on reload).
+    pub secrets: SecretsConfig,
+
+    /// Notification cooldown tracker.
+    pub notification_tracker: NotificationTracker,
+
+    /// Recently sent notifications.
+    pub alert_log: AlertLog,
+
+    /// Resource notifications held back until quiet hours end.
+    pub deferred_notifications: DeferredNotifications,
+
+    /// Resource notifications collected to be sent grouped.
+    pub notification_batch: NotificationBatch,
+
+    /// Notifiers for the channels in `[notification_channels]`.
+    pub notification_channels: Arc<[Box<dyn Notifier>]>,
+
+    /// Daily digest schedules of the email channels.
+    pub email_digests: Arc<[EmailDigest]>,
+
+    /// Hook channels, which also run on account events.
+    pub hooks: Arc<[HookNotifier]>,
+
+    /// Accounts whose last fetch failed because their login was rejected.
+    pub auth_failures: HashSet<AccountKey>,
+
+    /// Geetest challenges accounts are waiting on; their games are paused.
+    pub pending_challenges: HashMap<AccountKey, PendingChallenge>,
+}
+
+/// Application state wrapper.
+#[derive(Clone)]
+pub struct AppState {
+    /// Inner state protected by async RwLock.
+    pub inner: Arc<RwLock<StateData>>,
+    /// Games with a refresh in progress.
+    refreshing: Arc<Mutex<HashSet<GameId>>>,
+    /// Notifier to wake the scheduler when config changes.
+    scheduler_notify: Arc<Notify>,
+    /// Notifier to restart the HTTP API when its settings change.
+    http_api_notify: Arc<Notify>,
+    /// Notifier to reconnect to the MQTT broker when its settings change.
+    mqtt_notify: Arc<Notify>,
+    /// Where cached resources are persisted, if anywhere.
+    snapshot_path: Option<Arc<Utf8PathBuf>>,
+    /// Where notification cooldowns, the alert log and deferred notifications
+    /// are persisted, if anywhere.
+    notification_state_path: Option<Arc<Utf8PathBuf>>,
+    /// Resource history store, if available.
+    history: Option<Arc<HistoryStore>>,
+    /// Overcap tracker, if available.
+    overcap: Option<Arc<OvercapStore>>,
+    /// Fetch and claim counters exposed as metrics.
+    metrics: Arc<Metrics>,
+    /// Geetest challenges shared by every HoYoLab client, kept across
+    /// registry rebuilds.
+    challenges: Challenges,
+    /// Where state-change events are published.
+    events: Arc<dyn EventSink>,
+    /// Broadcasts every published event to in-process subscribers.
+    event_sender: broadcast::Sender<PublishedEvent>,
+    /// Where user-facing notifications are delivered.
+    notifiers: Arc<[Box<dyn Notifier>]>,
+}
+
+impl AppState {
+    /// Creates a new application state with default values.
+    ///
+    /// Events are logged and notifications are dropped.
+    #[must_use]
+    pub fn new() -> Self {
+        let (event_sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self {
+            inner: Arc::new(RwLock::new(StateData::default())),
+            refreshing: Arc::new(Mutex::new(HashSet::new())),
+            scheduler_notify: Arc::new(Notify::new()),
+            http_api_notify: Arc::new(Notify::new()),
+            mqtt_notify: Arc::new(Notify::new()),
+            snapshot_path: None,
+            notification_state_path: None,
+            history: None,
+            overcap: None,
+            metrics: Arc::new(Metrics::default()),
+            challenges: Challenges::default(),
+            events: Arc::new(BroadcastEventSink::new(
+                Arc::new(LogEventSink),
+                event_sender.clone(),
+            )),
+            event_sender,
+            notifiers: Arc::from(Vec::new()),
+        }
+    }
+
+    /// Creates a new application state with initialized clients from config.
+    ///
+    /// Attempts to load configuration and secrets files. If they don't exist,
+    /// creates default config files first, then loads them. Cached resources
+    /// are seeded from the on-disk snapshot, if present, and marked stale, and
+    /// notification cooldowns, the alert log and deferred notifications from
+    /// their own file.
+    ///
+    /// Events are published to `events` and notifications are sent through
+    /// every notifier in `notifiers`.
+    #[must_use]
+    pub fn with_config(events: Arc<dyn EventSink>, notifiers: Vec<Box<dyn Notifier>>) -> Self {
+        // Ensure config files exist, creating defaults if needed
+        if let Err(e) = ensure_configs_exist() {
+            tracing::warn!("Failed to ensure config files exist: {e}");
+        }
+
+        let config = AppConfig::load().unwrap_or_else(|e| {
+            tracing::warn!("Failed to load config, using defaults: {e}");
+            AppConfig::default()
+        });
+        let secrets = SecretsConfig::load().unwrap_or_else(|e| {
+            tracing::warn!("Failed to load secrets, using defaults: {e}");
+            SecretsConfig::default()
+        });
+
+        let rate_limiters = RateLimiters::new(&config.rate_limits);
+        let challenges = Challenges::default();
+        let registry = create_registry(&config, &secrets, &rate_limiters, &challenges);
+        let daily_reward_registry =
+            create_daily_reward_registry(&config, &secrets, &rate_limiters, &challenges);
+        let notification_channels = create_notification_channels(&config, &secrets);
+        let email_digests = create_email_digests(&config, &secrets);
+        let hooks = create_hooks(&config);
+
+        let snapshot_path = Snapshot::default_path()
+            .inspect_err(|e| tracing::warn!("Failed to resolve snapshot path: {e}"))
+            .ok();
+        let snapshot = snapshot_path
+            .as_deref()
+            .and_then(|path| match Snapshot::load_from_path(path) {
+                Ok(snapshot) => snapshot,
+                Err(e) => {
+                    tracing::warn!(path = %path, "Failed to load resource snapshot: {e}");
+                    None
+                }
+            })
+            .unwrap_or_default();
+        let notification_state_path = PersistedNotifications::default_path()
+            .inspect_err(|e| tracing::warn!("Failed to resolve notification state path: {e}"))
+            .ok();
+        let notification_state = notification_state_path
+            .as_deref()
+            .and_then(|path| match PersistedNotifications::load_from_path(path) {
+                Ok(state) => state,
+                Err(e) => {
+                    tracing::warn!(path = %path, "Failed to load notification state: {e}");
+                    None
+                }
+            })
+            .unwrap_or_default();
+        let history = HistoryStore::default_path()
+            .inspect_err(|e| tracing::warn!("Failed to resolve history path: {e}"))
+            .ok()
+            .map(|path| Arc::new(HistoryStore::new(path)));
+        let overcap = OvercapStore::default_path()
+            .inspect_err(|e| tracing::warn!("Failed to resolve overcap state path: {e}"))
+            .ok()
+            .map(|path| Arc::new(OvercapStore::load(path)));
+
+        if !snapshot.resources.games.is_empty() {
+            tracing::info!(
+                accounts = snapshot.resources.games.len(),
+                "Loaded stale resources from snapshot"
+            );
+        }
+
+        let (event_sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+
+        Self {
+            inner: Arc::new(RwLock::new(StateData {
+                resources: snapshot.resources,
+                registry: Arc::new(registry),
+                daily_reward_registry: Arc::new(daily_reward_registry),
+                rate_limiters,
+                daily_reward_status: snapshot.daily_reward_status,
+                config,
+                secrets,
+                notification_tracker: notification_state.cooldowns,
+                alert_log: notification_state.alerts,
+                deferred_notifications: notification_state.deferred,
+                notification_batch: NotificationBatch::default(),
+                notification_channels: Arc::from(notification_channels),
+                email_digests: Arc::from(email_digests),
+                hooks: Arc::from(hooks),
+                auth_failures: HashSet::new(),
+                pending_challenges: HashMap::new(),
+            })),
+            refreshing: Arc::new(Mutex::new(HashSet::new())),
+            scheduler_notify: Arc::new(Notify::new()),
+            http_api_notify: Arc::new(Notify::new()),
+            mqtt_notify: Arc::new(Notify::new()),
+            snapshot_path: snapshot_path.map(Arc::new),
+            notification_state_path: notification_state_path.map(Arc::new),
+            history,
+            overcap,
+            metrics: Arc::new(Metrics::default()),
+            challenges,
+            events: Arc::new(BroadcastEventSink::new(events, event_sender.clone())),
+            event_sender,
+            notifiers: Arc::from(notifiers),
+        }
+    }
+
+    /// Publishes an event to the frontend's event sink.
+    pub fn publish<T: Serialize + ?Sized>(&self, event: AppEvent, payload: &T) {
+        self.events.publish(event, payload);
+    }
+
+    /// Subscribes to every event published from now on.
+    ///
+    /// A subscriber that falls more than a few dozen events behind misses the
+    /// oldest ones (see [`broadcast::error::RecvError::Lagged`]).
+    #[must_use]
+    pub fn subscribe_events(&self) -> broadcast::Receiver<PublishedEvent> {
+        self.event_sender.subscribe()
+    }
+
+    /// Sends a notification through the built-in notifiers and every
+    /// configured notification channel.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no notifier delivered the notification.
+    pub async fn notify(&self, notification: &Notification) -> anyhow::Result<()> {
+        self.notify_via(notification, &[]).await
+    }
+
+    /// Sends a notification through the notifiers named in `channels`, or all
+    /// of them when `channels` is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no notifier delivered the notification.
+    pub async fn notify_via(
+        &self,
+        notification: &Notification,
+        channels: &[String],
+    ) -> anyhow::Result<()> {
+        let (configured, hooks) = {
+            let state = self.inner.read().await;
+            (
+                Arc::clone(&state.notification_channels),
+                Arc::clone(&state.hooks),
+            )
+        };
+        let notifiers = self
+            .notifiers
+            .iter()
+            .chain(configured.iter())
+            .map(AsRef::as_ref)
+            .chain(hooks.iter().map(|hook| hook as &dyn Notifier));
+        let deliveries =
+            crate::notification::deliver_each(notifiers, channels, notification).await?;
+        self.record_alerts(notification, &deliveries).await;
+        crate::notification::delivery_result(deliveries)
+    }
+
+    /// Appends each delivery of `notification` to the alert log.
+    async fn record_alerts(&self, notification: &Notification, deliveries: &[Delivery]) {
+        if deliveries.is_empty() {
+            return;
+        }
+        let now = Timestamp::now();
+        {
+            let mut state = self.inner.write().await;
+            for delivery in deliveries {
+                state
+                    .alert_log
+                    .push(AlertLogEntry::new(notification, delivery, now));
+            }
+        }
+        self.save_notification_state().await;
+    }
+
+    /// Returns up to `limit` recently sent notifications, newest first.
+    pub async fn recent_alerts(&self, limit: usize) -> Vec<AlertLogEntry> {
+        let state = self.inner.read().await;
+        state.alert_log.recent(limit)
+    }
+
+    /// Clears the notification cooldowns of `games`, so their resources
+    /// notify again as soon as they reach a threshold, and drops their
+    /// notifications deferred by quiet hours.
+    pub async fn reset_notifications(&self, games: &HashSet<GameId>) {
+        {
+            let mut state = self.inner.write().await;
+            for &game_id in games {
+                state.notification_tracker.clear_for_game(game_id);
+                state.deferred_notifications.clear_for_game(game_id);
+            }
+        }
+        self.save_notification_state().await;
+    }
+
+    /// Returns the name of every notifier `notify_via` delivers to, and which
+    /// of them are hook channels.
+    pub async fn channel_names(&self) -> (Vec<String>, HashSet<String>) {
+        let state = self.inner.read().await;
+        let hooks: HashSet<String> = state
+            .hooks
+            .iter()
+            .map(|hook| hook.name().to_string())
+            .collect();
+        let names = self
+            .notifiers
+            .iter()
+            .chain(state.notification_channels.iter())
+            .map(|notifier| notifier.name().to_string())
+            .chain(hooks.iter().cloned())
+            .collect();
+        (names, hooks)
+    }
+
+    /// Runs every hook channel on `notification` in the background.
+    ///
+    /// Used for account events, which only hooks are notified of.
+    pub async fn notify_hooks(&self, notification: Notification) {
+        let hooks = {
+            let state = self.inner.read().await;
+            Arc::clone(&state.hooks)
+        };
+        if hooks.is_empty() {
+            return;
+        }
+        let state = self.clone();
+        tokio::spawn(async move {
+            let mut deliveries = Vec::new();
+            for hook in hooks.iter().filter(|hook| hook.handles(&notification)) {
+                let result = hook.send(&notification).await;
+                if let Err(e) = &result {
+                    tracing::warn!(notifier = hook.name(), error = %e, "Failed to run hook");
+                }
+                deliveries.push(Delivery {
+                    channel: hook.name().to_string(),
+                    result,
+                });
+            }
+            state.record_alerts(&notification, &deliveries).await;
+        });
+    }
+
+    /// Gets a clone of the current resources.
+    pub async fn get_resources(&self) -> AllResources {
+        let state = self.inner.read().await;
+        state.resources.clone()
+    }
+
+    /// Updates the resources and persists them to the snapshot file and, if
+    /// configured, the metrics textfile.
+    pub async fn set_resources(&self, resources: AllResources) {
+        {
+            let mut state = self.inner.write().await;
+            state.resources = resources;
+        }
+        self.save_snapshot().await;
+        self.write_metrics_textfile().await;
+    }
+
+    /// Merges freshly fetched resources into the cached ones, replacing only
+    /// the fetched accounts and recording the failures, and persists the
+    /// result like [`Self::set_resources`].
+    ///
+    /// Returns the merged resources.
+    pub async fn merge_resources(&self, fetched: FetchOutcome, now: Timestamp) -> AllResources {
+        let resources = {
+            let mut state = self.inner.write().await;
+            if !fetched.resources.is_empty() {
+                state.resources.last_updated = Some(now);
+                state.resources.stale = false;
+            }
+            for (key, data) in fetched.resources {
+                state.resources.record(key, data, now);
+            }
+            for (key, error) in fetched.failures {
+                state.resources.record_failure(key, error, now);
+            }
+            state.resources.clone()
+        };
+        self.save_snapshot().await;
+        self.write_metrics_textfile().await;
+        resources
+    }
+
+    /// Returns the fetch and claim counters.
+    #[must_use]
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format.
+    pub async fn render_metrics(&self) -> String {
+        let state = self.inner.read().await;
+        self.metrics.render(&state.resources, Timestamp::now())
+    }
+
+    /// Writes the metrics to `general.metrics_textfile`, if set.
+    ///
+    /// Failures are logged and otherwise ignored.
+    async fn write_metrics_textfile(&self) {
+        let (path, contents) = {
+            let state = self.inner.read().await;
+            let Some(path) = state.config.general.metrics_textfile.clone() else {
+                return;
+            };
+            (
+                Utf8PathBuf::from(path),
+                self.metrics.render(&state.resources, Timestamp::now()),
+            )
+        };
+        let write = {
+            let path = path.clone();
+            move || metrics::write_textfile(&path, &contents)
+        };
+        if let Err(e) = run_blocking(write).await {
+            tracing::warn!(path = %path, "Failed to write metrics textfile: {e:#}");
+        }
+    }
+
+    /// Writes the cached resources and daily reward status to disk.
+    ///
+    /// Failures are logged and otherwise ignored; the snapshot is only a
+    /// best-effort cache.
+    async fn save_snapshot(&self) {
+        let Some(path) = &self.snapshot_path else {
+            return;
+        };
+        let snapshot = {
+            let state = self.inner.read().await;
+            Snapshot {
+                resources: state.resources.clone(),
+                daily_reward_status: state.daily_reward_status.clone(),
+            }
+        };
+        let write = {
+            let path = Arc::clone(path);
+            move || snapshot.save_to_path(&path)
+        };
+        if let Err(e) = run_blocking(write).await {
+            tracing::warn!(path = %path, "Failed to save resource snapshot: {e}");
+        }
+    }
+
+    /// Writes the notification cooldowns, alert log and deferred notifications
+    /// to disk.
+    ///
+    /// Failures are logged and otherwise ignored.
+    pub(crate) async fn save_notification_state(&self) {
+        let Some(path) = &self.notification_state_path else {
+            return;
+        };
+        let persisted = {
+            let state = self.inner.read().await;
+            PersistedNotifications {
+                cooldowns: state.notification_tracker.clone(),
+                alerts: state.alert_log.clone(),
+                deferred: state.deferred_notifications.clone(),
+            }
+        };
+        let write = {
+            let path = Arc::clone(path);
+            move || persisted.save_to_path(&path)
+        };
+        if let Err(e) = run_blocking(write).await {
+            tracing::warn!(path = %path, "Failed to save notification state: {e}");
+        }
+    }
+
+    /// Attempts to mark a refresh of `games` as started.
+    ///
+    /// Returns `true` if none of them was already being refreshed, in which
+    /// case this call acquired the refresh slot of each.
+    #[must_use]
+    pub fn try_start_refresh(&self, games: &HashSet<GameId>) -> bool {
+        let mut refreshing = self
+            .refreshing
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if !refreshing.is_disjoint(games) {
+            return false;
+        }
+        refreshing.extend(games);
+        true
+    }
+
+    /// Marks the refresh of `games` as finished.
+    pub fn finish_refresh(&self, games: &HashSet<GameId>) {
+        let mut refreshing = self
+            .refreshing
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        refreshing.retain(|game_id| !games.contains(game_id));
+    }
+
+    /// Wakes the scheduler to re-evaluate config (e.g. after auto-claim
+    /// changes).
+    pub fn wake_scheduler(&self) {
+        self.scheduler_notify.notify_one();
+    }
+
+    /// Returns the scheduler notify handle for use in the scheduler task.
+    #[must_use]
+    pub fn scheduler_notify(&self) -> Arc<Notify> {
+        Arc::clone(&self.scheduler_notify)
+    }
+
+    /// Restarts the HTTP API so it picks up changed settings.
+    pub fn restart_http_api(&self) {
+        self.http_api_notify.notify_one();
+    }
+
+    /// Returns the HTTP API restart handle for use in the server task.
+    #[must_use]
+    pub fn http_api_notify(&self) -> Arc<Notify> {
+        Arc::clone(&self.http_api_notify)
+    }
+
+    /// Reconnects to the MQTT broker so it picks up changed settings.
+    pub fn restart_mqtt(&self) {
+        self.mqtt_notify.notify_one();
+    }
+
+    /// Returns the MQTT restart handle for use in the MQTT task.
+    #[must_use]
+    pub fn mqtt_notify(&self) -> Arc<Notify> {
+        Arc::clone(&self.mqtt_notify)
+    }
+
+    /// Returns the resource history store, if available.
+    #[must_use]
+    pub fn history(&self) -> Option<Arc<HistoryStore>> {
+        self.history.clone()
+    }
+
+    /// Returns the overcap tracker, if available.
+    #[must_use]
+    pub fn overcap(&self) -> Option<Arc<OvercapStore>> {
+        self.overcap.clone()
+    }
+
+    /// Feeds freshly fetched resources to the overcap tracker and, when
+    /// history is enabled, the history store.
+    async fn record_fetch(
+        &self,
+        fetched: &HashMap<AccountKey, serde_json::Value>,
+        fetched_at: Timestamp,
+    ) {
+        let history_enabled = {
+            let state = self.inner.read().await;
+            state.config.general.history_enabled
+        };
+        let history = self.history.clone().filter(|_| history_enabled);
+        if self.overcap.is_none() && history.is_none() {
+            return;
+        }
+        let fetched = Arc::new(fetched.clone());
+
+        if let Some(overcap) = self.overcap.clone() {
+            let fetched = Arc::clone(&fetched);
+            if let Err(e) = run_blocking(move || overcap.observe_fetch(&fetched, fetched_at)).await
+            {
+                tracing::warn!("Failed to update overcap tracker: {e:#}");
+            }
+        }
+
+        if let Some(history) = history
+            && let Err(e) = run_blocking(move || history.append(&fetched, fetched_at)).await
+        {
+            tracing::warn!("Failed to record resource history: {e:#}");
+        }
+    }
+
+    /// Runs the hooks for accounts whose login was just rejected, and forgets
+    /// the rejection of accounts that fetched successfully again.
+    ///
+    /// Hooks run once per rejection rather than on every failed poll.
+    async fn track_auth_failures(&self, outcome: &FetchOutcome) {
+        let expired: Vec<AccountKey> = {
+            let mut state = self.inner.write().await;
+            state
+                .auth_failures
+                .retain(|key| !outcome.resources.contains_key(key));
+            outcome
+                .failures
+                .iter()
+                .filter(|&(key, error)| {
+                    error.kind == ErrorKind::Auth && state.auth_failures.insert(key.clone())
+                })
+                .map(|(key, _)| key.clone())
+                .collect()
+        };
+        for key in expired {
+            tracing::warn!(account = %key, "Login rejected");
+            self.notify_hooks(auth_expired_notification(&key)).await;
+        }
+    }
+
+    /// Pauses the games of accounts whose fetch raised a Geetest challenge,
+    /// and resumes those of accounts that fetched successfully again.
+    async fn track_challenges(&self, outcome: &FetchOutcome) {
+        {
+            let mut state = self.inner.write().await;
+            state.pending_challenges.retain(|key, pending| {
+                pending.operation != BlockedOperation::Fetch || !outcome.resources.contains_key(key)
+            });
+        }
+        for (key, error) in &outcome.failures {
+            if let Some(challenge) = &error.challenge {
+                self.raise_challenge(key, challenge.clone(), BlockedOperation::Fetch)
+                    .await;
+            }
+        }
+    }
+
+    /// Records that an account waits on a Geetest challenge, pausing its game,
+    /// and publishes [`AppEvent::ChallengeRequired`].
+    pub async fn raise_challenge(
+        &self,
+        key: &AccountKey,
+        challenge: GeetestChallenge,
+        operation: BlockedOperation,
+    ) {
+        tracing::warn!(account = %key, ?operation, "Pausing game until a Geetest challenge is solved");
+        let pending = PendingChallenge {
+            challenge,
+            operation,
+        };
+        {
+            let mut state = self.inner.write().await;
+            state
+                .pending_challenges
+                .insert(key.clone(), pending.clone());
+        }
+        let payload = ChallengeRequiredPayload {
+            game_id: key.game_id,
+            account: &key.account,
+            pending: &pending,
+        };
+        self.publish(AppEvent::ChallengeRequired, &payload);
+    }
+
+    /// Removes an account's pending challenge if `challenge` is its ID,
+    /// resuming its game.
+    pub async fn take_challenge(
+        &self,
+        key: &AccountKey,
+        challenge: &str,
+    ) -> Option<PendingChallenge> {
+        let mut state = self.inner.write().await;
+        if state
+            .pending_challenges
+            .get(key)
+            .is_none_or(|pending| pending.challenge.challenge != challenge)
+        {
+            return None;
+        }
+        state.pending_challenges.remove(key)
+    }
+
+    /// Returns the Geetest challenges accounts are waiting on.
+    pub async fn pending_challenges(&self) -> HashMap<AccountKey, PendingChallenge> {
+        let state = self.inner.read().await;
+        state.pending_challenges.clone()
+    }
+
+    /// Returns the games paused until a Geetest challenge is solved.
+    pub async fn paused_games(&self) -> HashSet<GameId> {
+        let state = self.inner.read().await;
+        state
+            .pending_challenges
+            .keys()
+            .map(|key| key.game_id)
+            .collect()
+    }
+
+    /// Returns the Geetest challenges shared by every HoYoLab client.
+    #[must_use]
+    pub fn challenges(&self) -> &Challenges {
+        &self.challenges
+    }
+
+    /// Fetches resources from all configured game clients using the registry.
+    ///
+    /// Publishes per-account update or failure events as each account
+    /// completes. Accounts whose fetch fails keep their last known resources
+    /// so notifications keep working while offline, and have the failure
+    /// recorded in their fetch status. Games paused by a Geetest challenge are
+    /// not fetched and keep their last known resources too.
+    pub async fn fetch_all_resources(&self) -> AllResources {
+        let paused = self.paused_games().await;
+        let (registry, previous) = {
+            let state = self.inner.read().await;
+            (Arc::clone(&state.registry), state.resources.clone())
+        };
+        let games: HashSet<GameId> = registry
+            .clients()
+            .map(|(key, _)| key.game_id)
+            .filter(|game_id| !paused.contains(game_id))
+            .collect();
+        let paused_accounts: Vec<AccountKey> = registry
+            .clients()
+            .map(|(key, _)| key)
+            .filter(|key| paused.contains(&key.game_id))
+            .cloned()
+            .collect();
+        let outcome = registry
+            .fetch_for_games(&games, &self.events, &self.metrics)
+            .await;
+        self.track_auth_failures(&outcome).await;
+        self.track_challenges(&outcome).await;
+        let FetchOutcome {
+            resources: fetched,
+            failures,
+        } = outcome;
+        let now = Timestamp::now();
+        self.record_fetch(&fetched, now).await;
+
+        // When every account failed, everything shown is carried over, so it
+        // stays stale and keeps its last update time.
+        let mut resources = AllResources {
+            last_updated: if fetched.is_empty() {
+                previous.last_updated
+            } else {
+                Some(now)
+            },
+            stale: !failures.is_empty() && fetched.is_empty(),
+            ..AllResources::default()
+        };
+        for (key, data) in fetched {
+            resources.record(key, data, now);
+        }
+        for (key, error) in failures {
+            tracing::debug!(account = %key, "Keeping last known resources after failed fetch");
+            resources.carry_over(&previous, &key);
+            resources.record_failure(key, error, now);
+        }
+        for key in &paused_accounts {
+            resources.carry_over(&previous, key);
+        }
+        resources
+    }
+
+    /// Returns the games with at least one configured client.
+    pub async fn configured_games(&self) -> HashSet<GameId> {
+        let state = self.inner.read().await;
+        state
+            .registry
+            .clients()
+            .map(|(key, _)| key.game_id)
+            .collect()
+    }
+
+    /// Returns whether any game clients are configured.
+    pub async fn has_clients(&self) -> bool {
+        let registry = {
+            let state = self.inner.read().await;
+            Arc::clone(&state.registry)
+        };
+        registry.has_any()
+    }
+
+    // ========================================================================
+    // Daily Reward Methods
+    // ========================================================================
+
+    /// Gets the cached daily reward status.
+    pub async fn get_daily_reward_status(&self) -> AllDailyRewardStatus {
+        let state = self.inner.read().await;
+        state.daily_reward_status.clone()
+    }
+
+    /// Updates the cached daily reward status and persists it to the snapshot
+    /// file.
+    pub async fn set_daily_reward_status(&self, status: AllDailyRewardStatus) {
+        {
+            let mut state = self.inner.write().await;
+            state.daily_reward_status = status;
+        }
+        self.save_snapshot().await;
+    }
+
+    /// Merges freshly fetched daily reward status into the cached one,
+    /// replacing only the fetched accounts, and persists the result.
+    pub async fn merge_daily_reward_status(
+        &self,
+        fetched: HashMap<AccountKey, serde_json::Value>,
+        now: Timestamp,
+    ) {
+        {
+            let mut state = self.inner.write().await;
+            for (key, data) in fetched {
+                state.daily_reward_status.games.insert(key, data);
+            }
+            state.daily_reward_status.last_checked = Some(now);
+        }
+        self.save_snapshot().await;
+    }
+
+    /// Fetches daily reward status from all configured accounts.
+    pub async fn fetch_all_daily_reward_status(&self) -> AllDailyRewardStatus {
+        let daily_reward_registry = {
+            let state = self.inner.read().await;
+            Arc::clone(&state.daily_reward_registry)
+        };
+        let games = daily_reward_registry.get_all_status().await;
+        AllDailyRewardStatus {
+            games: games.into_iter().collect(),
+            last_checked: Some(Timestamp::now()),
+            stale: false,
+        }
+    }
+
+    /// Claims daily reward for a specific account.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the account is not configured or the claim fails.
+    pub async fn claim_daily_reward_for_account(
+        &self,
+        key: &AccountKey,
+    ) -> anyhow::Result<serde_json::Value> {
+        let daily_reward_registry = {
+            let state = self.inner.read().await;
+            Arc::clone(&state.daily_reward_registry)
+        };
+        daily_reward_registry.claim_for_account(key).await
+    }
+
+    /// Claims the daily reward for an account on user request.
+    ///
+    /// Retries network failures with backoff, refreshes the account's cached
+    /// reward status and publishes [`AppEvent::DailyRewardClaimed`] with the
+    /// claim result.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the account is not configured or the claim fails.
+    pub async fn claim_daily_reward_now(
+        &self,
+        key: &AccountKey,
+    ) -> anyhow::Result<serde_json::Value> {
+        let result = retry_with_backoff(|| self.claim_daily_reward_for_account(key)).await;
+        self.record_claim(key, &result).await;
+        let result = result?;
+
+        // Refresh status for this account after claiming
+        if let Ok(account_status) = self.get_daily_reward_status_for_account(key).await {
+            let mut current_status = self.get_daily_reward_status().await;
+            current_status.games.insert(key.clone(), account_status);
+            current_status.last_checked = Some(Timestamp::now());
+            self.set_daily_reward_status(current_status).await;
+        }
+
+        self.publish(AppEvent::DailyRewardClaimed, &result);
+
+        Ok(result)
+    }
+
+    /// Counts a daily reward claim attempt and runs the hooks on its outcome.
+    ///
+    /// A claim refused until a Geetest challenge is solved pauses the game.
+    pub async fn record_claim(&self, key: &AccountKey, result: &anyhow::Result<serde_json::Value>) {
+        self.metrics.record_claim(key, ClaimOutcome::of(result));
+        match result {
+            Ok(_) => {
+                let mut state = self.inner.write().await;
+                if state
+                    .pending_challenges
+                    .get(key)
+                    .is_some_and(|pending| pending.operation == BlockedOperation::Claim)
+                {
+                    state.pending_challenges.remove(key);
+                }
+            }
+            Err(e) => {
+                if let Some(challenge) = find_challenge_anyhow(e) {
+                    self.raise_challenge(key, challenge.clone(), BlockedOperation::Claim)
+                        .await;
+                }
+            }
+        }
+        if let Some(notification) = claim_notification(key, result) {
+            self.notify_hooks(notification).await;
+        }
+    }
+
+    /// Returns whether a daily reward client is registered for an account.
+    pub async fn has_daily_reward_account(&self, key: &AccountKey) -> bool {
+        let state = self.inner.read().await;
+        state.daily_reward_registry.has_account(key)
+    }
+
+    /// Returns every account with a daily reward client, sorted.
+    pub async fn daily_reward_accounts(&self) -> Vec<AccountKey> {
+        let state = self.inner.read().await;
+        let mut accounts: Vec<_> = state
+            .daily_reward_registry
+            .clients()
+            .map(|(key, _)| key.clone())
+            .collect();
+        accounts.sort();
+        accounts
+    }
+
+    /// Gets the daily reward status for a specific account.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the account is not configured or the fetch fails.
+    pub async fn get_daily_reward_status_for_account(
+        &self,
+        key: &AccountKey,
+    ) -> anyhow::Result<serde_json::Value> {
+        let daily_reward_registry = {
+            let state = self.inner.read().await;
+            Arc::clone(&state.daily_reward_registry)
+        };
+        daily_reward_registry.get_status_for_account(key).await
+    }
+
+    /// Gets the list of accounts that have auto-claim enabled.
+    ///
+    /// Returns a list of `(AccountKey, Option<ClaimTime>)` pairs.
+    pub async fn get_auto_claim_accounts(&self) -> Vec<(AccountKey, Option<ClaimTime>)> {
+        let state = self.inner.read().await;
+        state
+            .config
+            .games
+            .all_accounts()
+            .into_iter()
+            .filter(|account| account.enabled && account.auto_claim_daily_rewards)
+            .map(|account| (account.key(), account.auto_claim_time))
+            .collect()
+    }
+
+    /// Checks if auto-claim is enabled for a specific account.
+    ///
+    /// Returns true if auto-claim is enabled in config and the account is
+    /// registered in the daily reward registry. Does not check if already
+    /// claimed today - that is determined by fetching status from the API.
+    pub async fn should_auto_claim_account(&self, key: &AccountKey) -> bool {
+        let state = self.inner.read().await;
+        state.config.games.auto_claim_enabled(key) && state.daily_reward_registry.has_account(key)
+    }
+
+    // ========================================================================
+    // Selective Fetch Methods
+    // ========================================================================
+
+    /// Fetches resources from a subset of configured game clients, skipping
+    /// games paused by a Geetest challenge.
+    ///
+    /// Successful results are also recorded in the history store and the
+    /// overcap tracker.
+    pub async fn fetch_resources_for_games(&self, game_ids: &HashSet<GameId>) -> FetchOutcome {
+        let paused = self.paused_games().await;
+        if !paused.is_disjoint(game_ids) {
+            tracing::debug!(games = ?paused, "Skipping games waiting on a Geetest challenge");
+        }
+        let game_ids: HashSet<GameId> = game_ids.difference(&paused).copied().collect();
+        let registry = {
+            let state = self.inner.read().await;
+            Arc::clone(&state.registry)
+        };
+        let outcome = registry
+            .fetch_for_games(&game_ids, &self.events, &self.metrics)
+            .await;
+        self.track_fetch(&outcome).await;
+        outcome
+    }
+
+    /// Fetches resources of a single account, even while its game is paused
+    /// by a Geetest challenge, e.g. to replay a fetch once its challenge is
+    /// solved.
+    ///
+    /// Results are recorded like [`Self::fetch_resources_for_games`].
+    pub async fn fetch_resources_for_account(&self, key: &AccountKey) -> FetchOutcome {
+        let registry = {
+            let state = self.inner.read().await;
+            Arc::clone(&state.registry)
+        };
+        let outcome = registry
+            .fetch_account(key, &self.events, &self.metrics)
+            .await;
+        self.track_fetch(&outcome).await;
+        outcome
+    }
+
+    /// Tracks login rejections and challenges of a partial fetch, and records
+    /// its successful results.
+    async fn track_fetch(&self, outcome: &FetchOutcome) {
+        self.track_auth_failures(outcome).await;
+        self.track_challenges(outcome).await;
+        self.record_fetch(&outcome.resources, Timestamp::now())
+            .await;
+    }
+
+    /// Fetches daily reward status from a subset of configured games.
+    pub async fn fetch_daily_reward_status_for_games(
+        &self,
+        game_ids: &HashSet<GameId>,
+    ) -> HashMap<AccountKey, serde_json::Value> {
+        let daily_reward_registry = {
+            let state = self.inner.read().await;
+            Arc::clone(&state.daily_reward_registry)
+        };
+        daily_reward_registry.get_status_for_games(game_ids).await
+    }
+
+    // ========================================================================
+    // Config Reload Methods
+    // ========================================================================
+
+    /// Applies new config and secrets to state, optionally rebuilding
+    /// registries.
+    ///
+    /// When `rebuild_registries` is true, game client and daily reward
+    /// registries are recreated from the new config/secrets. This is only
+    /// needed when game-level settings (uid, region, enabled), credentials or
+    /// rate limits change. The rate limiters are only replaced when the rate
+    /// limits changed. Notification channels are recreated whenever their
+    /// settings or secrets changed.
+    pub async fn apply_config(
+        &self,
+        config: AppConfig,
+        secrets: SecretsConfig,
+        rebuild_registries: bool,
+    ) {
+        let (channels_changed, current_rate_limiters) = {
+            let state = self.inner.read().await;
+            (
+                state.config.notification_channels != config.notification_channels
+                    || state.secrets.notification_channels != secrets.notification_channels,
+                (state.config.rate_limits == config.rate_limits)
+                    .then(|| state.rate_limiters.clone()),
+            )
+        };
+        let notification_channels = channels_changed.then(|| {
+            (
+                create_notification_channels(&config, &secrets),
+                create_email_digests(&config, &secrets),
+                create_hooks(&config),
+            )
+        });
+        let registries = rebuild_registries.then(|| {
+            let rate_limiters =
+                current_rate_limiters.unwrap_or_else(|| RateLimiters::new(&config.rate_limits));
+            (
+                create_registry(&config, &secrets, &rate_limiters, &self.challenges),
+                create_daily_reward_registry(&config, &secrets, &rate_limiters, &self.challenges),
+                rate_limiters,
+            )
+        });
+
+        let mut state = self.inner.write().await;
+        state.config = config;
+        state.secrets = secrets;
+        if let Some((registry, daily_reward_registry, rate_limiters)) = registries {
+            state.registry = Arc::new(registry);
+            state.daily_reward_registry = Arc::new(daily_reward_registry);
+            state.rate_limiters = rate_limiters;
+        }
+        if let Some((channels, digests, hooks)) = notification_channels {
+            state.notification_channels = Arc::from(channels);
+            state.email_digests = Arc::from(digests);
+            state.hooks = Arc::from(hooks);
+        }
+    }
+}
+
+/// Runs blocking file I/O on the blocking thread pool, keeping it off the
+/// async runtime threads.
+pub(crate) async fn run_blocking<T: Send + 'static>(
+    io: impl FnOnce() -> anyhow::Result<T> + Send + 'static,
+) -> anyhow::Result<T> {
+    tokio::task::spawn_blocking(io).await?
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use storekeeper_core::DynGameClient;
+
+    // =========================================================================
+    // AllResources tests
+    // =========================================================================
+
+    #[test]
+    fn all_resources_default_is_empty() {
+        let r = AllResources::default();
+        assert!(r.games.is_empty());
+        assert!(r.last_updated.is_none());
+    }
+
+    #[test]
+    fn all_resources_serde_roundtrip_empty() {
+        let r = AllResources::default();
+        let json = serde_json::to_string(&r).expect("serialize");
+        let r2: AllResources = serde_json::from_str(&json).expect("deserialize");
+        assert!(r2.games.is_empty());
+        assert!(r2.last_updated.is_none());
+    }
+
+    #[test]
+    fn all_resources_serde_roundtrip_with_data() {
+        let mut r = AllResources::default();
+        r.games.insert(
+            AccountKey::default_for(GameId::GenshinImpact),
+            serde_json::json!([{"stamina": 160}]),
+        );
+        r.last_updated = Some(Timestamp::now());
+
+        let json = serde_json::to_string(&r).expect("serialize");
+        let r2: AllResources = serde_json::from_str(&json).expect("deserialize");
+        assert_eq!(r2.games.len(), 1);
+        assert!(r2.last_updated.is_some());
+    }
+
+    #[test]
+    fn all_resources_camel_case_keys() {
+        let r = AllResources {
+            last_updated: Some(Timestamp::now()),
+            ..AllResources::default()
+        };
+        let v = serde_json::to_value(&r).expect("serialize");
+        assert!(v.get("lastUpdated").is_some(), "should be camelCase");
+        assert!(v.get("last_updated").is_none(), "should NOT be snake_case");
+    }
+
+    #[test]
+    fn all_resources_empty_games_skipped() {
+        let r = AllResources::default();
+        let v = serde_json::to_value(&r).expect("serialize");
+        assert!(
+            v.get("games").is_none(),
+            "empty games map should be skipped"
+        );
+    }
+
+    #[test]
+    fn all_resources_nest_accounts_under_game() {
+        let mut r = AllResources::default();
+        r.games.insert(
+            AccountKey::default_for(GameId::GenshinImpact),
+            serde_json::json!([]),
+        );
+        r.games.insert(
+            AccountKey::new(GameId::GenshinImpact, "alt"),
+            serde_json::json!([{"stamina": 40}]),
+        );
+        assert_eq!(r.games.len(), 2);
+
+        let v = serde_json::to_value(&r).expect("serialize");
+        let alt = v
+            .get("games")
+            .and_then(|games| games.get("GENSHIN_IMPACT"))
+            .and_then(|accounts| accounts.get("alt"));
+        assert_eq!(alt, Some(&serde_json::json!([{"stamina": 40}])));
+    }
+
+    #[test]
+    fn all_resources_record_sets_fetched_at() {
+        let key = AccountKey::default_for(GameId::ZenlessZoneZero);
+        let now = Timestamp::now();
+        let mut r = AllResources::default();
+        r.record(key.clone(), serde_json::json!([]), now);
+        assert_eq!(r.games.get(&key), Some(&serde_json::json!([])));
+        assert_eq!(r.fetched_at.get(&key), Some(&now));
+
+        let v = serde_json::to_value(&r).expect("serialize");
+        assert!(
+            v.get("fetchedAt")
+                .and_then(|f| f.get("ZENLESS_ZONE_ZERO"))
+                .and_then(|a| a.get("default"))
+                .is_some(),
+            "fetchedAt should nest accounts under game"
+        );
+    }
+
+    #[test]
+    fn all_resources_carry_over_keeps_previous_timestamp() {
+        let key = AccountKey::new(GameId::GenshinImpact, "alt");
+        let earlier = Timestamp::from_second(1_700_000_000).expect("valid timestamp");
+        let mut previous = AllResources::default();
+        previous.record(key.clone(), serde_json::json!([{"stamina": 80}]), earlier);
+
+        let mut r = AllResources::default();
+        r.carry_over(&previous, &key);
+        r.carry_over(&previous, &AccountKey::default_for(GameId::GenshinImpact));
+        assert_eq!(r.games.len(), 1, "missing accounts are not carried over");
+        assert_eq!(r.fetched_at.get(&key), Some(&earlier));
+    }
+
+    #[test]
+    fn all_resources_failure_keeps_data_and_last_success() {
+        let key = AccountKey::new(GameId::HonkaiStarRail, "alt");
+        let earlier = Timestamp::from_second(1_700_000_000).expect("valid timestamp");
+        let now = Timestamp::now();
+        let mut previous = AllResources::default();
+        previous.record(key.clone(), serde_json::json!([{"stamina": 80}]), earlier);
+        let error = FetchError {
+            kind: ErrorKind::Auth,
+            message: "cookie expired".to_string(),
+            challenge: None,
+        };
+
+        let mut r = AllResources::default();
+        r.carry_over(&previous, &key);
+        r.record_failure(key.clone(), error.clone(), now);
+        let unfetched = AccountKey::default_for(GameId::GenshinImpact);
+        r.record_failure(unfetched.clone(), error.clone(), now);
+
+        assert_eq!(r.games.get(&key), previous.games.get(&key));
+        assert_eq!(
+            r.fetch_status.get(&key),
+            Some(&FetchStatus {
+                last_success: Some(earlier),
+                last_attempt: Some(now),
+                error: Some(error),
+            })
+        );
+        assert!(r.games.get(&unfetched).is_none());
+        assert_eq!(
+            r.fetch_status
+                .get(&unfetched)
+                .and_then(|status| status.last_success),
+            None
+        );
+    }
+
+    #[test]
+    fn all_resources_stale_defaults_to_false() {
+        let r: AllResources = serde_json::from_str("{}").expect("deserialize");
+        assert!(!r.stale);
+    }
+
+    // =========================================================================
+    // AccountValues tests
+    // =========================================================================
+
+    #[test]
+    fn account_values_get_and_iter() {
+        let alt = AccountKey::new(GameId::HonkaiStarRail, "alt");
+        let values: AccountValues = [
+            (
+                AccountKey::default_for(GameId::HonkaiStarRail),
+                serde_json::json!(1),
+            ),
+            (alt.clone(), serde_json::json!(2)),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(values.get(&alt), Some(&serde_json::json!(2)));
+        assert!(
+            values
+                .get(&AccountKey::default_for(GameId::GenshinImpact))
+                .is_none()
+        );
+        assert_eq!(values.iter().count(), 2);
+    }
+
+    #[test]
+    fn account_values_insert_replaces() {
+        let key = AccountKey::default_for(GameId::WutheringWaves);
+        let mut values = AccountValues::default();
+        values.insert(key.clone(), serde_json::json!(1));
+        values.insert(key.clone(), serde_json::json!(2));
+        assert_eq!(values.len(), 1);
+        assert_eq!(values.get(&key), Some(&serde_json::json!(2)));
+    }
+
+    // =========================================================================
+    // Fetch tests
+    // =========================================================================
+
+    type BoxError = Box<dyn std::error::Error + Send + Sync>;
+    type BoxFuture<'a, T> = std::pin::Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+    /// Game client whose fetches always fail.
+    struct FailingGameClient {
+        id: GameId,
+    }
+
+    impl DynGameClient for FailingGameClient {
+        fn game_id(&self) -> GameId {
+            self.id
+        }
+
+        fn game_name(&self) -> &'static str {
+            "Failing Game"
+        }
+
+        fn fetch_resources_json(&self) -> BoxFuture<'_, Result<serde_json::Value, BoxError>> {
+            Box::pin(async { Err("offline".into()) })
+        }
+
+        fn is_authenticated_dyn(&self) -> BoxFuture<'_, Result<bool, BoxError>> {
+            Box::pin(async { Ok(true) })
+        }
+    }
+
+    #[tokio::test]
+    async fn all_failed_fetch_keeps_resources_stale() {
+        let state = AppState::new();
+        let key = AccountKey::default_for(GameId::GenshinImpact);
+        let fetched_at = Timestamp::UNIX_EPOCH;
+        {
+            let mut data = state.inner.write().await;
+            let mut registry = GameClientRegistry::new();
+            registry.register(
+                &key.account,
+                Box::new(FailingGameClient { id: key.game_id }),
+            );
+            data.registry = Arc::new(registry);
+            data.resources
+                .record(key.clone(), serde_json::json!([]), fetched_at);
+            data.resources.last_updated = Some(fetched_at);
+            data.resources.stale = true;
+        }
+
+        let resources = state.fetch_all_resources().await;
+
+        assert!(resources.stale);
+        assert_eq!(resources.last_updated, Some(fetched_at));
+        assert_eq!(resources.games.get(&key), Some(&serde_json::json!([])));
+        assert!(
+            resources
+                .fetch_status
+                .get(&key)
+                .is_some_and(|status| status.error.is_some())
+        );
+    }
+
+    #[tokio::test]
+    async fn successful_merge_clears_stale_resources() {
+        let state = AppState::new();
+        state.inner.write().await.resources.stale = true;
+        let key = AccountKey::default_for(GameId::GenshinImpact);
+        let now = Timestamp::UNIX_EPOCH;
+
+        let resources = state
+            .merge_resources(
+                FetchOutcome {
+                    resources: HashMap::from([(key.clone(), serde_json::json!([]))]),
+                    failures: HashMap::new(),
+                },
+                now,
+            )
+            .await;
+
+        assert!(!resources.stale);
+        assert_eq!(resources.last_updated, Some(now));
+        assert_eq!(resources.games.get(&key), Some(&serde_json::json!([])));
+    }
+
+    // =========================================================================
+    // Challenge tests
+    // =========================================================================
+
+    #[tokio::test]
+    async fn challenge_pauses_game_until_taken() {
+        let state = AppState::new();
+        let key = AccountKey::new(GameId::HonkaiStarRail, "alt");
+        let challenge = GeetestChallenge {
+            gt: "gt1".to_string(),
+            challenge: "c1".to_string(),
+        };
+
+        state
+            .raise_challenge(&key, challenge.clone(), BlockedOperation::Claim)
+            .await;
+        assert_eq!(
+            state.paused_games().await,
+            HashSet::from([GameId::HonkaiStarRail])
+        );
+
+        assert_eq!(state.take_challenge(&key, "stale").await, None);
+        assert_eq!(
+            state.take_challenge(&key, "c1").await,
+            Some(PendingChallenge {
+                challenge,
+                operation: BlockedOperation::Claim,
+            })
+        );
+        assert!(state.paused_games().await.is_empty());
+    }
+
+    // =========================================================================
+    // AllDailyRewardStatus tests
+    // =========================================================================
+
+    #[test]
+    fn all_daily_reward_status_default_is_empty() {
+        let s = AllDailyRewardStatus::default();
+        assert!(s.games.is_empty());
+        assert!(s.last_checked.is_none());
+    }
+
+    #[test]
+    fn all_daily_reward_status_serde_roundtrip() {
+        let mut s = AllDailyRewardStatus::default();
+        s.games.insert(
+            AccountKey::default_for(GameId::HonkaiStarRail),
+            serde_json::json!({"is_signed": true}),
+        );
+        s.last_checked = Some(Timestamp::now());
+
+        let json = serde_json::to_string(&s).expect("serialize");
+        let s2: AllDailyRewardStatus = serde_json::from_str(&json).expect("deserialize");
+        assert_eq!(s2.games.len(), 1);
+        assert!(s2.last_checked.is_some());
+    }
+
+    #[test]
+    fn all_daily_reward_status_camel_case_keys() {
+        let s = AllDailyRewardStatus {
+            last_checked: Some(Timestamp::now()),
+            ..AllDailyRewardStatus::default()
+        };
+        let v = serde_json::to_value(&s).expect("serialize");
+        assert!(v.get("lastChecked").is_some(), "should be camelCase");
+        assert!(v.get("last_checked").is_none(), "should NOT be snake_case");
+    }
+}